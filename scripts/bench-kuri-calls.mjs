@@ -0,0 +1,170 @@
+#!/usr/bin/env node
+
+// Benchmark a 50-call workflow against a local stand-in for Kuri and Chrome's
+// CDP endpoint, comparing per-call setup with the shared paths src/kuri/client.ts uses:
+//
+//   http     50 GETs through fetch's shared keep-alive dispatcher vs a fresh
+//            connection per call (what building a client per call costs).
+//   cookies  50 Network.setCookie commands over one CDP WebSocket vs the old
+//            setCookies flow: re-list /json and open a new socket per cookie.
+//
+// Needs a global WebSocket: Bun, Node >= 22, or Node 20 with --experimental-websocket.
+//   bun scripts/bench-kuri-calls.mjs [calls] [rounds]
+
+import { createHash } from "node:crypto";
+import http from "node:http";
+
+const CALLS = Number(process.argv[2]) || 50;
+const ROUNDS = Number(process.argv[3]) || 7;
+const REQUEST_TIMEOUT_MS = 5000;
+
+if (typeof WebSocket !== "function") {
+  console.error("No global WebSocket — run with bun, Node >= 22, or node --experimental-websocket");
+  process.exit(1);
+}
+
+// --- Stand-in server: JSON HTTP endpoints plus a minimal CDP WebSocket ---
+
+function wsFrame(text) {
+  const payload = Buffer.from(text);
+  const head = payload.length < 126
+    ? Buffer.from([0x81, payload.length])
+    : Buffer.from([0x81, 126, payload.length >> 8, payload.length & 0xff]);
+  return Buffer.concat([head, payload]);
+}
+
+/** Answer every masked client text frame with `{ id, result: { success: true } }`. */
+function serveCdp(socket) {
+  let buffered = Buffer.alloc(0);
+  socket.on("data", (chunk) => {
+    buffered = Buffer.concat([buffered, chunk]);
+    while (buffered.length >= 6) {
+      const opcode = buffered[0] & 0x0f;
+      let length = buffered[1] & 0x7f;
+      let offset = 2;
+      if (length === 126) { length = buffered.readUInt16BE(2); offset = 4; }
+      if (buffered.length < offset + 4 + length) return;
+      const mask = buffered.subarray(offset, offset + 4);
+      const payload = Buffer.from(buffered.subarray(offset + 4, offset + 4 + length).map((b, i) => b ^ mask[i % 4]));
+      buffered = buffered.subarray(offset + 4 + length);
+      if (opcode === 0x8) {
+        socket.end(Buffer.from([0x88, 0]));
+        return;
+      }
+      if (opcode === 0x1) {
+        const { id } = JSON.parse(payload.toString());
+        socket.write(wsFrame(JSON.stringify({ id, result: { success: true } })));
+      }
+    }
+  });
+  socket.on("error", () => {});
+}
+
+const server = http.createServer((req, res) => {
+  const body = req.url === "/json"
+    ? [{ id: "tab-1", webSocketDebuggerUrl: `ws://127.0.0.1:${server.address().port}/devtools/page/tab-1` }]
+    : { ok: true, path: req.url };
+  res.writeHead(200, { "content-type": "application/json" });
+  res.end(JSON.stringify(body));
+});
+server.on("upgrade", (req, socket) => {
+  const accept = createHash("sha1")
+    .update(`${req.headers["sec-websocket-key"]}258EAFA5-E914-47DA-95CA-C5AB0DC85B11`)
+    .digest("base64");
+  socket.write(`HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: ${accept}\r\n\r\n`);
+  serveCdp(socket);
+});
+await new Promise((resolve) => server.listen(0, "127.0.0.1", resolve));
+const base = `http://127.0.0.1:${server.address().port}`;
+
+// --- HTTP: shared dispatcher vs fresh connection per call ---
+
+async function sharedGet(path) {
+  const res = await fetch(`${base}${path}`, { signal: AbortSignal.timeout(REQUEST_TIMEOUT_MS) });
+  return res.json();
+}
+
+function freshGet(path) {
+  return new Promise((resolve, reject) => {
+    http.get(`${base}${path}`, { agent: false, timeout: REQUEST_TIMEOUT_MS }, (res) => {
+      let text = "";
+      res.on("data", (chunk) => { text += chunk; });
+      res.on("end", () => resolve(JSON.parse(text)));
+    }).on("error", reject);
+  });
+}
+
+// --- CDP: one socket per cookie vs one socket per batch ---
+
+function openSocket(url) {
+  return new Promise((resolve, reject) => {
+    const ws = new WebSocket(url);
+    ws.onopen = () => resolve(ws);
+    ws.onerror = () => reject(new Error("CDP socket failed to open"));
+  });
+}
+
+function sendCommand(ws, id, method, params) {
+  return new Promise((resolve) => {
+    const onMessage = (event) => {
+      const msg = JSON.parse(String(event.data));
+      if (msg.id !== id) return;
+      ws.removeEventListener("message", onMessage);
+      resolve(msg.result);
+    };
+    ws.addEventListener("message", onMessage);
+    ws.send(JSON.stringify({ id, method, params }));
+  });
+}
+
+function cookie(i) {
+  return { name: `c${i}`, value: "v", domain: ".example.com", path: "/", secure: true, httpOnly: true, sameSite: "Lax" };
+}
+
+async function debuggerUrl() {
+  const pages = await sharedGet("/json");
+  return pages.find((p) => p.id === "tab-1").webSocketDebuggerUrl;
+}
+
+async function cookiesPerSocket() {
+  for (let i = 0; i < CALLS; i++) {
+    const ws = await openSocket(await debuggerUrl());
+    await sendCommand(ws, 1, "Network.setCookie", cookie(i));
+    ws.close();
+  }
+}
+
+async function cookiesSharedSocket() {
+  const ws = await openSocket(await debuggerUrl());
+  for (let i = 0; i < CALLS; i++) await sendCommand(ws, i + 1, "Network.setCookie", cookie(i));
+  ws.close();
+}
+
+// --- Runner ---
+
+async function time(run) {
+  const samples = [];
+  await run(); // warm-up
+  for (let r = 0; r < ROUNDS; r++) {
+    const started = performance.now();
+    await run();
+    samples.push(performance.now() - started);
+  }
+  samples.sort((a, b) => a - b);
+  return samples[Math.floor(samples.length / 2)];
+}
+
+const workflows = [
+  ["http: fresh connection per call", async () => { for (let i = 0; i < CALLS; i++) await freshGet(`/tab/${i}`); }],
+  ["http: shared fetch dispatcher", async () => { for (let i = 0; i < CALLS; i++) await sharedGet(`/tab/${i}`); }],
+  ["cookies: socket per cookie", cookiesPerSocket],
+  ["cookies: one socket per batch", cookiesSharedSocket],
+];
+
+console.log(`${CALLS} calls per workflow, median of ${ROUNDS} rounds\n`);
+for (const [name, run] of workflows) {
+  const ms = await time(run);
+  console.log(`${name.padEnd(34)} ${ms.toFixed(1).padStart(8)} ms  ${(ms / CALLS).toFixed(2).padStart(6)} ms/call`);
+}
+server.closeAllConnections();
+server.close();
@@ -1,10 +1,11 @@
 import { nanoid } from "nanoid";
 import { readFileSync } from "node:fs";
-import { extractEndpoints } from "../reverse-engineer/index.js";
+import { extractEndpoints, extractVersionHeaders } from "../reverse-engineer/index.js";
 import { buildSkillOperationGraph, inferEndpointSemantic } from "../graph/index.js";
-import type { KuriHarEntry } from "../kuri/client.js";
+import { harPostDataText, harResponseBody, type KuriHarEntry } from "../kuri/client.js";
 import type { EndpointDescriptor, SkillManifest } from "../types/index.js";
-import type { RawRequest } from "../capture/index.js";
+import { canonicalHeaders, sortRequestsByTime, truncateRequestBodies, type RawRequest } from "../capture/index.js";
+import { parseHarDateTime } from "../time.js";
 import { cachePublishedSkill, findExistingSkillForDomain } from "../client/index.js";
 import { mergeEndpoints } from "../marketplace/index.js";
 import { upsertDagEdgesFromOperationGraph } from "../orchestrator/dag-feedback.js";
@@ -40,12 +41,13 @@ export function harEntriesToRawRequests(entries: KuriHarEntry[], baseUrl?: strin
     .map((entry) => ({
       url: normalizeBrowseUrl(entry.request.url, baseUrl),
       method: entry.request.method,
-      request_headers: Object.fromEntries((entry.request.headers ?? []).map((header) => [header.name.toLowerCase(), header.value])),
-      request_body: entry.request.postData?.text,
+      request_headers: canonicalHeaders(entry.request.headers),
+      request_body: harPostDataText(entry.request.postData),
       response_status: entry.response.status,
-      response_headers: Object.fromEntries((entry.response.headers ?? []).map((header) => [header.name.toLowerCase(), header.value])),
-      response_body: entry.response.content?.text,
-      timestamp: entry.startedDateTime ?? new Date().toISOString(),
+      response_headers: canonicalHeaders(entry.response.headers),
+      ...harResponseBody(entry.response.content),
+      timestamp: parseHarDateTime(entry.startedDateTime),
+      ...(typeof entry.time === "number" && entry.time >= 0 ? { duration_ms: entry.time } : {}),
     }));
 }
 
@@ -57,7 +59,12 @@ export function buildBrowseRequestKey(request: RawRequest): string {
   ].join(":");
 }
 
-export function mergeBrowseRequests(intercepted: RawRequest[], harEntries: KuriHarEntry[], baseUrl?: string): RawRequest[] {
+export function mergeBrowseRequests(
+  intercepted: RawRequest[],
+  harEntries: KuriHarEntry[],
+  baseUrl?: string,
+  maxBodyBytes?: number,
+): RawRequest[] {
   const normalizedIntercepted = intercepted.map((request) => ({
     ...request,
     url: normalizeBrowseUrl(request.url, baseUrl),
@@ -82,7 +89,7 @@ export function mergeBrowseRequests(intercepted: RawRequest[], harEntries: KuriH
     }
   }
 
-  return allRequests;
+  return truncateRequestBodies(sortRequestsByTime(allRequests), maxBodyBytes);
 }
 
 export interface BrowseIndexResult {
@@ -127,6 +134,7 @@ export async function cacheBrowseRequests(params: {
       for (const endpoint of mergedEndpoints) {
         if (!endpoint.description) endpoint.description = generateLocalDescription(endpoint);
       }
+      const versionHeaders = { ...(existingSkill?.version_headers ?? {}), ...extractVersionHeaders(requests) };
 
       const quickSkill: SkillManifest = {
         skill_id: existingSkill?.skill_id ?? nanoid(),
@@ -144,6 +152,7 @@ export async function cacheBrowseRequests(params: {
         endpoints: mergedEndpoints,
         operation_graph: buildSkillOperationGraph(mergedEndpoints),
         intents: Array.from(new Set([...(existingSkill?.intents ?? []), `browse ${domain}`])),
+        ...(Object.keys(versionHeaders).length > 0 ? { version_headers: versionHeaders } : {}),
       };
 
       const cacheKey = buildResolveCacheKey(domain, `browse ${domain}`, sessionUrl);
@@ -0,0 +1,217 @@
+/**
+ * Append-only journal for browse sessions.
+ *
+ * Every navigation, action, and API call is appended to
+ * ~/.unbrowse/browse-journal/{session_id}.jsonl as it happens — intercepted
+ * calls are drained into it every few seconds, HAR-only ones at flush — so
+ * a Kuri/CDP crash mid-session no longer loses the whole recording. The in-memory
+ * BrowseSession stays authoritative while the session is live; the journal
+ * is only read back by recovery.
+ *
+ * The journal is plaintext on disk, so API calls are scrubbed before they
+ * are written: credential headers and cookies are dropped and secret-looking
+ * body fields redacted. Auth lives in the vault, not here. Journals older
+ * than UNBROWSE_BROWSE_JOURNAL_MAX_AGE_DAYS (default 7) are pruned whenever
+ * a session starts.
+ */
+
+import { existsSync, mkdirSync, appendFileSync, readFileSync, readdirSync, statSync, unlinkSync } from "node:fs";
+import { join } from "node:path";
+import { homedir } from "node:os";
+import { PERSISTED_MAX_BODY_BYTES, truncateRequestBodies, type RawRequest } from "../capture/index.js";
+import { log, logError, logWarn } from "../logger.js";
+import { redactSecrets } from "../indexer/index.js";
+import { isSensitiveHeader } from "../reverse-engineer/index.js";
+import { scrubAuthHeaders } from "../reverse-engineer/auth-detect.js";
+
+export type BrowseJournalEntry =
+  | { type: "start"; at: string; session_id: string; url: string; domain: string }
+  | { type: "navigation"; at: string; url: string; domain: string }
+  | { type: "action"; at: string; action: string; args?: Record<string, unknown> }
+  | { type: "api_call"; at: string; request: RawRequest }
+  | { type: "close"; at: string; reason?: string };
+
+export interface RecoveredBrowseSession {
+  session_id: string;
+  started_at?: string;
+  url: string;
+  domain: string;
+  closed: boolean;
+  navigations: string[];
+  actions: Array<{ action: string; at: string; args?: Record<string, unknown> }>;
+  requests: RawRequest[];
+}
+
+/** Allow tests to override the journal root via env var. */
+function getJournalRoot(): string {
+  return process.env.UNBROWSE_BROWSE_JOURNAL_DIR ?? join(homedir(), ".unbrowse", "browse-journal");
+}
+
+function journalFilePath(sessionId: string): string {
+  return join(getJournalRoot(), `${sessionId.replace(/[^a-zA-Z0-9_\-]/g, "_")}.jsonl`);
+}
+
+const DEFAULT_MAX_AGE_DAYS = 7;
+const CREDENTIAL_HEADERS = new Set(["authorization", "proxy-authorization", "cookie", "set-cookie"]);
+/** Body fields redacted whatever their value looks like (redactSecrets also catches token-shaped values) */
+const SECRET_FIELD = /(^|[-_])(pass(word|wd)?|secret|token|otp|pin|code[-_]?verifier|assertion|api[-_]?key|session[-_]?id|csrf)$/i;
+const REDACTED = "[REDACTED]";
+
+function getMaxAgeMs(): number {
+  const days = Number(process.env.UNBROWSE_BROWSE_JOURNAL_MAX_AGE_DAYS);
+  return (Number.isFinite(days) && days > 0 ? days : DEFAULT_MAX_AGE_DAYS) * 24 * 3600 * 1000;
+}
+
+function scrubHeaders(headers: Record<string, string> | undefined): Record<string, string> {
+  return Object.fromEntries(Object.entries(scrubAuthHeaders(headers ?? {}))
+    .filter(([name]) => !CREDENTIAL_HEADERS.has(name.toLowerCase()) && !isSensitiveHeader(name)));
+}
+
+function redactFields(value: unknown): unknown {
+  if (Array.isArray(value)) return value.map(redactFields);
+  if (!value || typeof value !== "object") return value;
+  return Object.fromEntries(Object.entries(value as Record<string, unknown>).map(([k, v]) =>
+    [k, SECRET_FIELD.test(k) && (typeof v === "string" || typeof v === "number") ? REDACTED : redactFields(v)]));
+}
+
+/** JSON and form bodies get secret fields redacted; anything else is kept only if it doesn't look like a secret. */
+function scrubBody(body: string | undefined): string | undefined {
+  if (!body) return body;
+  try {
+    return JSON.stringify(redactSecrets(redactFields(JSON.parse(body))));
+  } catch { /* not JSON */ }
+  if (/^[^=&\s]+=[^&]*(&[^=&\s]+=[^&]*)*$/.test(body)) {
+    const form = new URLSearchParams(body);
+    for (const [key, value] of [...form.entries()]) {
+      if (SECRET_FIELD.test(key) || redactSecrets(value, key) !== value) form.set(key, REDACTED);
+    }
+    return form.toString();
+  }
+  // Truncated JSON no longer parses — redact `"key": "value"` pairs textually.
+  const redacted = body.replace(/"([^"\\]+)"(\s*:\s*)"(?:[^"\\]|\\.)*"/g, (pair, key: string, sep: string) =>
+    SECRET_FIELD.test(key) ? `"${key}"${sep}"${REDACTED}"` : pair);
+  return redactSecrets(redacted) as string;
+}
+
+/** Copy of `request` safe to write to disk, bodies capped at PERSISTED_MAX_BODY_BYTES. */
+export function scrubJournalRequest(request: RawRequest): RawRequest {
+  const [capped] = truncateRequestBodies([request], PERSISTED_MAX_BODY_BYTES);
+  return {
+    ...capped,
+    request_headers: scrubHeaders(capped.request_headers),
+    response_headers: scrubHeaders(capped.response_headers),
+    request_body: scrubBody(capped.request_body),
+    response_body: scrubBody(capped.response_body),
+  };
+}
+
+/** Delete journals not written to within the retention window. */
+export function pruneBrowseJournals(maxAgeMs = getMaxAgeMs()): number {
+  const dir = getJournalRoot();
+  if (!existsSync(dir)) return 0;
+  let pruned = 0;
+  for (const name of readdirSync(dir)) {
+    if (!name.endsWith(".jsonl")) continue;
+    const file = join(dir, name);
+    try {
+      if (Date.now() - statSync(file).mtimeMs < maxAgeMs) continue;
+      unlinkSync(file);
+      pruned++;
+    } catch (err) {
+      logWarn("browse", `failed to prune journal ${name}: ${(err as Error).message}`);
+    }
+  }
+  if (pruned > 0) log("browse", `pruned ${pruned} browse journal(s) older than ${Math.round(maxAgeMs / 86_400_000)}d`);
+  return pruned;
+}
+
+/**
+ * Append one entry to a session journal. Writes are synchronous so the line
+ * is on disk before the route replies. Graceful degradation: fs failures are
+ * logged, never thrown.
+ */
+export function appendBrowseJournal(sessionId: string, entry: BrowseJournalEntry): void {
+  try {
+    const dir = getJournalRoot();
+    if (!existsSync(dir)) mkdirSync(dir, { recursive: true, mode: 0o700 });
+    if (entry.type === "start") pruneBrowseJournals();
+    const line = entry.type === "api_call" ? { ...entry, request: scrubJournalRequest(entry.request) } : entry;
+    appendFileSync(journalFilePath(sessionId), JSON.stringify(line) + "\n", { encoding: "utf-8", mode: 0o600 });
+  } catch (err) {
+    logError("browse", `failed to append to browse journal: ${(err as Error).message}`);
+  }
+}
+
+/** Rebuild a session from its journal. Malformed (e.g. half-written) lines are skipped. */
+export function loadBrowseJournal(sessionId: string): RecoveredBrowseSession | null {
+  const filePath = journalFilePath(sessionId);
+  if (!existsSync(filePath)) return null;
+  let content: string;
+  try {
+    content = readFileSync(filePath, "utf-8");
+  } catch {
+    return null;
+  }
+
+  const recovered: RecoveredBrowseSession = {
+    session_id: sessionId,
+    url: "about:blank",
+    domain: "",
+    closed: false,
+    navigations: [],
+    actions: [],
+    requests: [],
+  };
+  for (const line of content.split("\n")) {
+    if (!line.trim()) continue;
+    let entry: BrowseJournalEntry;
+    try {
+      entry = JSON.parse(line) as BrowseJournalEntry;
+    } catch {
+      continue;
+    }
+    switch (entry.type) {
+      case "start":
+        recovered.started_at = entry.at;
+        recovered.url = entry.url;
+        recovered.domain = entry.domain;
+        break;
+      case "navigation":
+        recovered.url = entry.url;
+        recovered.domain = entry.domain;
+        recovered.navigations.push(entry.url);
+        break;
+      case "action":
+        recovered.actions.push({ action: entry.action, at: entry.at, ...(entry.args ? { args: entry.args } : {}) });
+        break;
+      case "api_call":
+        recovered.requests.push(entry.request);
+        break;
+      case "close":
+        recovered.closed = true;
+        break;
+    }
+  }
+  return recovered;
+}
+
+/** Most recently written journal that never reached a close entry, if any. */
+export function findInterruptedBrowseJournal(): string | null {
+  const dir = getJournalRoot();
+  if (!existsSync(dir)) return null;
+  const candidates: Array<{ id: string; mtime: number }> = [];
+  for (const name of readdirSync(dir)) {
+    if (!name.endsWith(".jsonl")) continue;
+    try {
+      candidates.push({ id: name.slice(0, -".jsonl".length), mtime: statSync(join(dir, name)).mtimeMs });
+    } catch {
+      continue;
+    }
+  }
+  candidates.sort((a, b) => b.mtime - a.mtime);
+  for (const candidate of candidates) {
+    const recovered = loadBrowseJournal(candidate.id);
+    if (recovered && !recovered.closed) return candidate.id;
+  }
+  return null;
+}
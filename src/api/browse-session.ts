@@ -1,10 +1,23 @@
 import { getKuriErrorMessage } from "../kuri/client.js";
 
+/** Session used by callers that don't name one (single-recording CLI flows). */
+export const DEFAULT_BROWSE_SESSION = "default";
+
 export interface BrowseSession {
+  /** Registry key — DEFAULT_BROWSE_SESSION unless the caller opened a named session. */
+  id?: string;
   tabId: string;
   url: string;
   harActive: boolean;
   domain: string;
+  /** Append-only journal id (see browse-journal.ts); assigned on first write. */
+  journalId?: string;
+  /** API calls already journaled (method, url, timestamp), so the periodic drain and the final flush don't write one twice. */
+  journaledRequests?: Set<string>;
+  /** Interceptor buffer position the periodic drain has read up to. */
+  journalCursor?: number;
+  /** Periodic drain of intercepted calls into the journal; cleared on close. */
+  journalTimer?: ReturnType<typeof setInterval>;
 }
 
 export interface BrowseTabRef {
@@ -47,13 +60,14 @@ async function createBrowseSession(
   sessions: Map<string, BrowseSession>,
   client: BrowseSessionClient,
   injectInterceptor: (tabId: string) => Promise<unknown>,
+  sessionId: string,
 ): Promise<BrowseSession> {
   await client.start().catch(() => {});
   const tabId = await client.newTab();
   await client.harStart(tabId).catch(() => {});
   await injectInterceptor(tabId);
-  const session: BrowseSession = { tabId, url: "about:blank", harActive: true, domain: "" };
-  sessions.set("default", session);
+  const session: BrowseSession = { id: sessionId, tabId, url: "about:blank", harActive: true, domain: "" };
+  sessions.set(sessionId, session);
   return session;
 }
 
@@ -66,14 +80,22 @@ function extractDomain(url: string | undefined): string {
   }
 }
 
+/**
+ * Adopt an open tab for the default session (e.g. one the orchestrator left
+ * behind). Named sessions always get a fresh tab, and a tab another session
+ * already records is never taken.
+ */
 async function adoptExistingBrowseTab(
   sessions: Map<string, BrowseSession>,
   client: BrowseSessionClient,
   injectInterceptor: (tabId: string) => Promise<unknown>,
+  sessionId: string,
   preferredDomain?: string,
 ): Promise<BrowseSession | null> {
+  if (sessionId !== DEFAULT_BROWSE_SESSION) return null;
   try {
-    const tabs = await client.discoverTabs();
+    const owned = new Set([...sessions.values()].map((s) => s.tabId));
+    const tabs = (await client.discoverTabs()).filter((tab) => !owned.has(tab.id));
     const normalizedPreferred = preferredDomain?.replace(/^www\./, "") ?? "";
     const candidate =
       tabs.find((tab) => {
@@ -86,12 +108,13 @@ async function adoptExistingBrowseTab(
     await client.harStart(candidate.id).catch(() => {});
     await injectInterceptor(candidate.id);
     const session: BrowseSession = {
+      id: sessionId,
       tabId: candidate.id,
       url: candidate.url ?? "about:blank",
       harActive: true,
       domain: extractDomain(candidate.url),
     };
-    sessions.set("default", session);
+    sessions.set(sessionId, session);
     return session;
   } catch {
     return null;
@@ -105,8 +128,9 @@ async function dropBrowseSession(
 ): Promise<void> {
   if (!session) return;
   await client.closeTab(session.tabId).catch(() => {});
-  if (sessions.get("default")?.tabId === session.tabId) {
-    sessions.delete("default");
+  const sessionId = session.id ?? DEFAULT_BROWSE_SESSION;
+  if (sessions.get(sessionId)?.tabId === session.tabId) {
+    sessions.delete(sessionId);
   }
 }
 
@@ -130,27 +154,29 @@ export async function resetBrowseSession(
   sessions: Map<string, BrowseSession>,
   client: BrowseSessionClient,
   injectInterceptor: (tabId: string) => Promise<unknown>,
+  sessionId = DEFAULT_BROWSE_SESSION,
 ): Promise<BrowseSession> {
-  const existing = sessions.get("default");
+  const existing = sessions.get(sessionId);
   const preferredDomain = existing?.domain || extractDomain(existing?.url);
   await dropBrowseSession(sessions, client, existing);
-  const adopted = await adoptExistingBrowseTab(sessions, client, injectInterceptor, preferredDomain);
+  const adopted = await adoptExistingBrowseTab(sessions, client, injectInterceptor, sessionId, preferredDomain);
   if (adopted) return adopted;
-  return createBrowseSession(sessions, client, injectInterceptor);
+  return createBrowseSession(sessions, client, injectInterceptor, sessionId);
 }
 
 export async function getOrCreateBrowseSession(
   sessions: Map<string, BrowseSession>,
   client: BrowseSessionClient,
   injectInterceptor: (tabId: string) => Promise<unknown>,
+  sessionId = DEFAULT_BROWSE_SESSION,
 ): Promise<BrowseSession> {
-  const existing = sessions.get("default");
+  const existing = sessions.get(sessionId);
   if (existing && await isBrowseSessionLive(existing, client)) return existing;
   const preferredDomain = existing?.domain || extractDomain(existing?.url);
   if (existing) await dropBrowseSession(sessions, client, existing);
-  const adopted = await adoptExistingBrowseTab(sessions, client, injectInterceptor, preferredDomain);
+  const adopted = await adoptExistingBrowseTab(sessions, client, injectInterceptor, sessionId, preferredDomain);
   if (adopted) return adopted;
-  return createBrowseSession(sessions, client, injectInterceptor);
+  return createBrowseSession(sessions, client, injectInterceptor, sessionId);
 }
 
 export async function withRecoveredBrowseSession<T>(
@@ -159,8 +185,9 @@ export async function withRecoveredBrowseSession<T>(
   injectInterceptor: (tabId: string) => Promise<unknown>,
   run: (session: BrowseSession) => Promise<T>,
   shouldReset?: (result: T) => boolean,
+  sessionId = DEFAULT_BROWSE_SESSION,
 ): Promise<{ session: BrowseSession; result: T; recovered: boolean }> {
-  let session = await getOrCreateBrowseSession(sessions, client, injectInterceptor);
+  let session = await getOrCreateBrowseSession(sessions, client, injectInterceptor, sessionId);
 
   try {
     const result = await run(session);
@@ -171,7 +198,7 @@ export async function withRecoveredBrowseSession<T>(
     if (!isRecoverableBrowseFailure(error)) throw error;
   }
 
-  session = await resetBrowseSession(sessions, client, injectInterceptor);
+  session = await resetBrowseSession(sessions, client, injectInterceptor, sessionId);
   const result = await run(session);
   return { session, result, recovered: true };
 }
@@ -1,35 +1,53 @@
-import type { FastifyInstance } from "fastify";
+import type { FastifyInstance, FastifyRequest } from "fastify";
 import * as kuri from "../kuri/client.js";
 import type { KuriHarEntry } from "../kuri/client.js";
-import { extractEndpoints, extractAuthHeaders } from "../reverse-engineer/index.js";
-import { INTERCEPTOR_SCRIPT, collectInterceptedRequests, injectInterceptor, type RawRequest } from "../capture/index.js";
+import { extractEndpoints, extractAuthHeaders, type MethodsFilter, type StatusFilter } from "../reverse-engineer/index.js";
+import { dedupeAuthHeaders, encodeBasicAuth } from "../reverse-engineer/auth-detect.js";
+import { INTERCEPTOR_SCRIPT, collectInterceptedRequests, collectInterceptedRequestsSince, injectInterceptor, type CaptureFilter, type RawRequest } from "../capture/index.js";
 import { queueBackgroundIndex } from "../indexer/index.js";
 import { nanoid } from "nanoid";
-import type { ExecutionTrace, OrchestrationTiming, ProjectionOptions, SkillManifest } from "../types/index.js";
-import { extractBrowserCookies } from "../auth/browser-cookies.js";
-import { mergeEndpoints } from "../marketplace/index.js";
+import type { AuthRefreshConfig, EndpointDescriptor, ExecutionTrace, OrchestrationTiming, ProjectionOptions, SkillManifest } from "../types/index.js";
+import type { AssertionResult, RedirectInfo, ResponseAssertion } from "../verification/index.js";
+import { extractBrowserCookies, listChromiumProfiles } from "../auth/browser-cookies.js";
+import { parseNetscapeCookies } from "../auth/netscape-cookies.js";
+import type { MarketplaceFailure } from "../payments/x402.js";
+import { diffSkills, mergeEndpoints, normalizeTemplate } from "../marketplace/index.js";
+import { endpointsFromOpenApi } from "../reverse-engineer/openapi.js";
 import { buildSkillOperationGraph } from "../graph/index.js";
 import { augmentEndpointsWithAgent } from "../graph/agent-augment.js";
 import { findExistingSkillForDomain, cachePublishedSkill } from "../client/index.js";
-import { storeCredential } from "../vault/index.js";
+import { getCredential, storeCredential } from "../vault/index.js";
 import { generateLocalDescription, writeSkillSnapshot, buildResolveCacheKey, getDomainReuseKey, domainSkillCache, persistDomainCache, scopedCacheKey, snapshotPathForCacheKey, invalidateRouteCacheForDomain, summarizeSchema, extractSampleValues } from "../orchestrator/index.js";
 import { TRACE_VERSION, CODE_HASH, GIT_SHA } from "../version.js";
 import { promoteExplicitExecution, resolveAndExecute, type OrchestratorResult } from "../orchestrator/index.js";
 import { getSkill } from "../marketplace/index.js";
 import { executeSkill, rankEndpoints } from "../execution/index.js";
+import { rankEndpointsByEvidence } from "../execution/evidence-rank.js";
+import { hasLintErrors, lintSkill } from "../marketplace/lint.js";
+import { scoreSkill } from "../marketplace/score.js";
 import { interactiveLogin, extractBrowserAuth } from "../auth/index.js";
+import { detectLoginFields } from "../auth/login-fields.js";
 import { publishSkill } from "../marketplace/index.js";
-import { recordFeedback, recordDiagnostics, recordExecution, getApiKey, getRecentLocalSkill, recordAnalyticsSession, type AnalyticsSessionPayload } from "../client/index.js";
+import { recordFeedback, recordDiagnostics, recordExecution, getApiKey, getMarketplaceHome, getRecentLocalSkill, recordAnalyticsSession, type AnalyticsSessionPayload } from "../client/index.js";
 import { ROUTE_LIMITS } from "../ratelimit/index.js";
-import { getSkillChunk, toAgentSkillChunkView } from "../graph/index.js";
+import { getSkillChunk, groupEndpointsByCategory, toAgentSkillChunkView } from "../graph/index.js";
 import { listRecentSessionsForDomain } from "../session-logs.js";
 import { mergeAgentReview } from "../indexer/index.js";
 import { attachAgentOutcomeHints } from "../agent-outcome.js";
 import { writeFileSync, existsSync, mkdirSync } from "fs";
 import { join } from "path";
-import { type BrowseSession, getOrCreateBrowseSession, isRecoverableBrowseFailure, withRecoveredBrowseSession } from "./browse-session.js";
+import { type BrowseSession, DEFAULT_BROWSE_SESSION, getOrCreateBrowseSession, isRecoverableBrowseFailure, withRecoveredBrowseSession } from "./browse-session.js";
 import { cacheBrowseRequests, harEntriesToRawRequests, mergeBrowseRequests } from "./browse-index.js";
 import { submitBrowseForm } from "./browse-submit.js";
+import { errorPayload, getErrorCode, unbrowseError } from "../errors.js";
+import { executeWorkflow, type WorkflowDefinition } from "../workflow/index.js";
+import { buildLoginWorkflow, replayLoginHar } from "../workflow/login.js";
+import { captureAndMerge, captureUrlsAndMerge } from "../capture/merge.js";
+import { generatePostmanCollection } from "../export/postman.js";
+import { endpointToCurlRequest, requestToCurl } from "../export/curl.js";
+import { healthCheck } from "../runtime/health.js";
+import { getLogLevel, isLogLevel, setLogLevel } from "../logger.js";
+import { appendBrowseJournal, findInterruptedBrowseJournal, loadBrowseJournal, type BrowseJournalEntry } from "./browse-journal.js";
 
 const BETA_API_URL = process.env.UNBROWSE_BACKEND_URL || "https://beta-api.unbrowse.ai";
 
@@ -108,7 +126,7 @@ function passiveIndexFromRequests(requests: RawRequest[], pageUrl: string): void
       const capturedAuthHeaders = extractAuthHeaders(requests);
       if (Object.keys(capturedAuthHeaders).length > 0) {
         const authKey = `${domain}-session`;
-        await storeCredential(authKey, JSON.stringify({ headers: capturedAuthHeaders }));
+        await storeCredential(authKey, JSON.stringify(dedupeAuthHeaders(capturedAuthHeaders)), { source_url: pageUrl });
       }
 
       // 3. Merge with existing skill for this domain (never reduce endpoint count)
@@ -196,7 +214,92 @@ const browseSessions = new Map<string, BrowseSession>();
 
 /** Register a browse session from the orchestrator (Phase 4 handoff) */
 export function registerBrowseSession(tabId: string, url: string, domain: string): void {
-  browseSessions.set("default", { tabId, url, harActive: true, domain });
+  browseSessions.set(DEFAULT_BROWSE_SESSION, { id: DEFAULT_BROWSE_SESSION, tabId, url, harActive: true, domain });
+}
+
+/**
+ * Browse session a request targets: `X-Unbrowse-Session` header, then a
+ * `session` body/query field. Unnamed requests share the default session, so
+ * single-recording callers need no changes.
+ */
+function browseSessionId(req: FastifyRequest): string {
+  const header = req.headers["x-unbrowse-session"];
+  const fromHeader = Array.isArray(header) ? header[0] : header;
+  const fromBody = (req.body as { session?: unknown } | undefined)?.session;
+  const fromQuery = (req.query as { session?: unknown } | undefined)?.session;
+  const id = fromHeader ?? (typeof fromBody === "string" ? fromBody : undefined) ?? (typeof fromQuery === "string" ? fromQuery : undefined);
+  return id?.trim() || DEFAULT_BROWSE_SESSION;
+}
+
+function withBrowseSession<T>(
+  req: FastifyRequest,
+  run: (session: BrowseSession) => Promise<T>,
+  shouldReset?: (result: T) => boolean,
+): Promise<{ session: BrowseSession; result: T; recovered: boolean }> {
+  return withRecoveredBrowseSession(browseSessions, kuri, injectInterceptor, run, shouldReset, browseSessionId(req));
+}
+
+/** How often intercepted API calls are moved into the journal between flushes. */
+const BROWSE_JOURNAL_DRAIN_MS = 2_000;
+
+/** Append to the session's crash-safe journal, opening it on first write. */
+function journalBrowse(session: BrowseSession, entry: BrowseJournalEntry): void {
+  if (!session.journalId) {
+    session.journalId = `${new Date().toISOString().replace(/[:.]/g, "-")}-${nanoid(6)}`;
+    appendBrowseJournal(session.journalId, {
+      type: "start",
+      at: new Date().toISOString(),
+      session_id: session.journalId,
+      url: session.url,
+      domain: session.domain,
+    });
+    // Calls made while the agent is idle reach the journal within a few
+    // seconds instead of at the next snapshot/close flush.
+    session.journalTimer = setInterval(() => { void drainBrowseIntercepts(session); }, BROWSE_JOURNAL_DRAIN_MS);
+    session.journalTimer.unref?.();
+  }
+  appendBrowseJournal(session.journalId, entry);
+  if (entry.type === "close") clearInterval(session.journalTimer);
+}
+
+function journalBrowseRequests(session: BrowseSession, requests: RawRequest[]): void {
+  const at = new Date().toISOString();
+  const written = session.journaledRequests ??= new Set();
+  for (const request of requests) {
+    const key = `${request.method} ${request.url} ${request.timestamp}`;
+    if (written.has(key)) continue;
+    written.add(key);
+    journalBrowse(session, { type: "api_call", at, request });
+  }
+}
+
+function interceptedToRawRequest(request: Awaited<ReturnType<typeof collectInterceptedRequests>>[number]): RawRequest {
+  return {
+    url: request.url,
+    method: request.method,
+    request_headers: request.request_headers ?? {},
+    request_body: request.request_body,
+    response_status: request.response_status,
+    response_headers: request.response_headers ?? {},
+    response_body: request.response_body,
+    timestamp: request.timestamp,
+  };
+}
+
+/** Journal the API calls the page interceptor has seen since the last drain. */
+async function drainBrowseIntercepts(session: BrowseSession): Promise<void> {
+  if (browseSessions.get(session.id ?? DEFAULT_BROWSE_SESSION) !== session) {
+    clearInterval(session.journalTimer);
+    return;
+  }
+  const { next, requests } = await collectInterceptedRequestsSince(session.tabId, session.journalCursor ?? 0);
+  session.journalCursor = next;
+  const calls = requests.filter((request) => !request.is_js).map(interceptedToRawRequest);
+  if (calls.length > 0) journalBrowseRequests(session, mergeBrowseRequests(calls, [], session.url));
+}
+
+function journalBrowseAction(session: BrowseSession, action: string, args?: Record<string, unknown>): void {
+  journalBrowse(session, { type: "action", at: new Date().toISOString(), action, ...(args ? { args } : {}) });
 }
 
 // ── /v1/stats cache ──────────────────────────────────────────────────
@@ -341,7 +444,7 @@ export async function registerRoutes(app: FastifyInstance) {
   // POST /v1/intent/resolve
   app.post("/v1/intent/resolve", { config: { rateLimit: ROUTE_LIMITS["/v1/intent/resolve"] } }, async (req, reply) => {
     const clientScope = clientScopeFor(req);
-    const { intent, params, context, projection, confirm_unsafe, dry_run, force_capture } = req.body as {
+    const { intent, params, context, projection, confirm_unsafe, dry_run, force_capture, statuses } = req.body as {
       intent: string;
       params?: Record<string, unknown>;
       context?: { url?: string; domain?: string };
@@ -349,10 +452,12 @@ export async function registerRoutes(app: FastifyInstance) {
       confirm_unsafe?: boolean;
       dry_run?: boolean;
       force_capture?: boolean;
+      /** Live capture: keep only endpoints whose responses fall in these ranges */
+      statuses?: StatusFilter;
     };
     if (!intent) return reply.code(400).send({ error: "intent required" });
     try {
-      const result = await resolveAndExecute(intent, params ?? {}, context, projection, { confirm_unsafe, dry_run, force_capture, client_scope: clientScope });
+      const result = await resolveAndExecute(intent, params ?? {}, context, projection, { confirm_unsafe, dry_run, force_capture, statuses, client_scope: clientScope });
 
       // Surface timing breakdown
       const res = attachAgentOutcomeHints({ ...result } as Record<string, unknown>, {
@@ -377,7 +482,7 @@ export async function registerRoutes(app: FastifyInstance) {
 
       return reply.send(res);
     } catch (err) {
-      return reply.code(500).send({ error: (err as Error).message });
+      return reply.code(500).send(errorPayload(err));
     }
   });
 
@@ -395,11 +500,151 @@ export async function registerRoutes(app: FastifyInstance) {
         }
       }
     }
-    if (!skill) skill = await getSkill(skill_id, clientScope);
+    const failures: MarketplaceFailure[] = [];
+    if (!skill) skill = await getSkill(skill_id, clientScope, { on_error: (f) => failures.push(f) });
+    const failure = failures[0];
+    if (!skill && failure && failure.kind !== "error") {
+      const status = failure.kind === "already_owned" ? 409 : 402;
+      return reply.code(status).send({ error: failure.message, code: failure.kind === "already_owned" ? "already_owned" : "payment_required", marketplace_error: failure });
+    }
     if (!skill) return reply.code(404).send({ error: "Skill not found" });
     return reply.send(skill);
   });
 
+  // GET /v1/skills/:skill_id/postman — Postman v2.1 collection for the skill
+  app.get("/v1/skills/:skill_id/postman", async (req, reply) => {
+    const clientScope = clientScopeFor(req);
+    const { skill_id } = req.params as { skill_id: string };
+    const skill = getRecentLocalSkill(skill_id, clientScope) ?? await getSkill(skill_id, clientScope);
+    if (!skill) return reply.code(404).send({ error: "Skill not found" });
+    return reply.type("application/json").send(generatePostmanCollection(skill));
+  });
+
+  // GET /v1/skills/:skill_id/endpoints/:endpoint_id/curl — runnable curl; secrets as $ENV refs unless include_secrets
+  app.get("/v1/skills/:skill_id/endpoints/:endpoint_id/curl", async (req, reply) => {
+    const clientScope = clientScopeFor(req);
+    const { skill_id, endpoint_id } = req.params as { skill_id: string; endpoint_id: string };
+    const { include_secrets } = (req.query ?? {}) as { include_secrets?: string };
+    const skill = getRecentLocalSkill(skill_id, clientScope) ?? await getSkill(skill_id, clientScope);
+    if (!skill) return reply.code(404).send({ error: "Skill not found" });
+    const endpoint = skill.endpoints.find((e) => e.endpoint_id === endpoint_id);
+    if (!endpoint) return reply.code(404).send({ error: "Endpoint not found" });
+
+    let bundle: { headers?: Record<string, string>; cookies?: Array<{ name: string; value: string }> } | null = null;
+    if (skill.auth_profile_ref) {
+      try { bundle = JSON.parse((await getCredential(skill.auth_profile_ref)) ?? "null"); } catch { /* no usable bundle */ }
+    }
+    const authHeaders = bundle?.headers ?? {};
+    const cookies = Object.fromEntries((bundle?.cookies ?? []).map((c) => [c.name, c.value]));
+    const withSecrets = include_secrets === "true" || include_secrets === "1";
+    const curl = requestToCurl(endpointToCurlRequest(endpoint, skill), authHeaders, cookies, { include_secrets: withSecrets });
+    const env = withSecrets ? [] : [...curl.matchAll(/\$([A-Z0-9_]+)"/g)].map((m) => m[1]);
+    return reply.send({ skill_id, endpoint_id, curl, ...(env.length > 0 ? { env } : {}) });
+  });
+
+  // GET /v1/skills/:skill_id/endpoints/ranked — evidence-based ranking with score components
+  app.get("/v1/skills/:skill_id/endpoints/ranked", async (req, reply) => {
+    const clientScope = clientScopeFor(req);
+    const { skill_id } = req.params as { skill_id: string };
+    const skill = getRecentLocalSkill(skill_id, clientScope) ?? await getSkill(skill_id, clientScope);
+    if (!skill) return reply.code(404).send({ error: "Skill not found" });
+    const ranked = rankEndpointsByEvidence(skill.endpoints).map(({ endpoint, score, components }) => ({
+      endpoint_id: endpoint.endpoint_id,
+      method: endpoint.method,
+      url_template: endpoint.url_template,
+      score,
+      components,
+    }));
+    return reply.send({ skill_id, ranked });
+  });
+
+  // POST /v1/skills/diff — { old, new } as skill ids or full manifests
+  app.post("/v1/skills/diff", async (req, reply) => {
+    const clientScope = clientScopeFor(req);
+    const body = (req.body ?? {}) as { old?: string | SkillManifest; new?: string | SkillManifest };
+    const load = async (ref: string | SkillManifest | undefined) => typeof ref === "string"
+      ? getRecentLocalSkill(ref, clientScope) ?? await getSkill(ref, clientScope)
+      : ref?.endpoints ? ref : null;
+    const [oldSkill, newSkill] = await Promise.all([load(body.old), load(body.new)]);
+    if (!oldSkill || !newSkill) return reply.code(404).send({ error: "Skill not found" });
+    return reply.send(diffSkills(oldSkill, newSkill));
+  });
+
+  // POST /v1/skills/import/openapi — { spec, base_url? } seed a skill from an OpenAPI 3 document.
+  // Endpoints already captured for the domain are merged on top (they keep their ids).
+  app.post("/v1/skills/import/openapi", async (req, reply) => {
+    const clientScope = clientScopeFor(req);
+    const body = (req.body ?? {}) as { spec?: unknown; base_url?: string };
+    if (!body.spec) return reply.code(400).send(errorPayload(unbrowseError("invalid_input", "spec required")));
+    try {
+      const imported = endpointsFromOpenApi(body.spec, body.base_url);
+      const domain = new URL(imported.base_url).hostname;
+      const existing = findExistingSkillForDomain(domain);
+      const captured = existing?.endpoints ?? [];
+      const capturedIds = new Map(captured.map((ep) => [`${ep.method}:${normalizeTemplate(ep.url_template)}`, ep.endpoint_id]));
+      const endpoints = mergeEndpoints(imported.endpoints, captured).map((ep) => {
+        const id = capturedIds.get(`${ep.method}:${normalizeTemplate(ep.url_template)}`);
+        return id ? { ...ep, endpoint_id: id } : ep;
+      });
+      const now = new Date().toISOString();
+      const skill: SkillManifest = {
+        ...(existing ?? {}),
+        skill_id: existing?.skill_id ?? nanoid(),
+        version: existing?.version ?? "1.0.0",
+        schema_version: existing?.schema_version ?? "1",
+        lifecycle: existing?.lifecycle ?? "active",
+        execution_type: "http",
+        created_at: existing?.created_at ?? now,
+        updated_at: now,
+        name: existing?.name ?? domain,
+        intent_signature: existing?.intent_signature ?? `browse ${domain}`,
+        domain,
+        description: existing?.description ?? `API skill for ${domain}`,
+        owner_type: existing?.owner_type ?? "agent",
+        endpoints,
+        operation_graph: buildSkillOperationGraph(endpoints),
+        intents: existing?.intents ?? [`browse ${domain}`],
+        ...(existing?.auth_method ?? imported.auth_method ? { auth_method: existing?.auth_method ?? imported.auth_method } : {}),
+      };
+      cachePublishedSkill(skill, clientScope);
+      invalidateRouteCacheForDomain(domain);
+      return reply.send({
+        skill_id: skill.skill_id,
+        domain,
+        base_url: imported.base_url,
+        endpoints: endpoints.length,
+        from_spec: endpoints.filter((ep) => ep.from_spec).length,
+        ...(skill.auth_method ? { auth_method: skill.auth_method } : {}),
+      });
+    } catch (err) {
+      return reply.code(getErrorCode(err) === "invalid_input" ? 400 : 500).send(errorPayload(err));
+    }
+  });
+
+  // POST /v1/skills/:skill_id/capture-merge — capture one URL (or urls[]) and merge into the skill atomically
+  app.post("/v1/skills/:skill_id/capture-merge", async (req, reply) => {
+    const clientScope = clientScopeFor(req);
+    const { skill_id } = req.params as { skill_id: string };
+    const body = (req.body ?? {}) as { url?: string; urls?: string[]; concurrency?: number; intent?: string; ephemeral?: boolean; methods?: MethodsFilter; statuses?: StatusFilter; session_first_party?: boolean } & CaptureFilter;
+    if (!body.url && !body.urls?.length) return reply.code(400).send({ error: "url or urls[] required" });
+    if ((body.url !== undefined && typeof body.url !== "string") ||
+        (body.urls !== undefined && (!Array.isArray(body.urls) || body.urls.some((u) => typeof u !== "string")))) {
+      return reply.code(400).send({ error: "url must be a string and urls an array of strings" });
+    }
+    const existing = getRecentLocalSkill(skill_id, clientScope) ?? await getSkill(skill_id, clientScope);
+    if (!existing) return reply.code(404).send({ error: "Skill not found" });
+    try {
+      const { url, urls, ...options } = body;
+      if (!urls?.length) return reply.send(await captureAndMerge(existing, url!, { ...options, client_scope: clientScope }));
+      // Stop starting new pages when the caller disconnects.
+      const cancel = new AbortController();
+      reply.raw.on("close", () => { if (!reply.raw.writableFinished) cancel.abort(); });
+      return reply.send(await captureUrlsAndMerge(existing, Array.from(new Set(url ? [url, ...urls] : urls)), { ...options, client_scope: clientScope, signal: cancel.signal }));
+    } catch (err) {
+      return reply.code(getErrorCode(err) === "invalid_input" ? 400 : 500).send(errorPayload(err));
+    }
+  });
+
   // POST /v1/skills/:skill_id/review — agent submits reviewed descriptions + synthetic examples
   app.post("/v1/skills/:skill_id/review", async (req, reply) => {
     const clientScope = clientScopeFor(req);
@@ -463,13 +708,15 @@ export async function registerRoutes(app: FastifyInstance) {
   app.post("/v1/skills/:skill_id/publish", async (req, reply) => {
     const clientScope = clientScopeFor(req);
     const { skill_id } = req.params as { skill_id: string };
-    const { endpoints: reviews } = (req.body as {
+    const { endpoints: reviews, force } = (req.body as {
       endpoints?: Array<{
         endpoint_id: string;
         description?: string;
         action_kind?: string;
         resource_kind?: string;
       }>;
+      /** Publish despite lint errors */
+      force?: boolean;
     }) ?? {};
 
     // Load skill from local caches → marketplace
@@ -488,6 +735,15 @@ export async function registerRoutes(app: FastifyInstance) {
     // Phase 2: merge descriptions + publish
     if (reviews?.length) {
       const updated = mergeAgentReview(skill.endpoints, reviews);
+      // Lint before touching the (possibly cached) skill so a blocked publish changes nothing.
+      const lint = lintSkill({ ...skill, endpoints: updated });
+      if (hasLintErrors(lint) && !force) {
+        return reply.code(422).send({
+          ...errorPayload(unbrowseError("invalid_input", `Skill ${skill.skill_id} failed publish lint`)),
+          issues: lint,
+          _next_step: "Fix the errors, or pass force: true (--force) to publish anyway",
+        });
+      }
       skill.endpoints = updated;
       skill.updated_at = new Date().toISOString();
 
@@ -518,11 +774,14 @@ export async function registerRoutes(app: FastifyInstance) {
         skill_id: skill.skill_id,
         endpoints_updated: reviews.length,
         published: true,
+        ...(lint.length > 0 ? { lint } : {}),
       });
     }
 
     // Phase 1: return endpoints needing descriptions
-    const ranked = rankEndpoints(skill.endpoints, skill.intent_signature, skill.domain);
+    // Grouped by category (auth, search, list, ...) so related endpoints are described together.
+    const ranked = groupEndpointsByCategory(rankEndpoints(skill.endpoints, skill.intent_signature, skill.domain).map((r) => r.endpoint))
+      .flatMap(({ category, endpoints }) => endpoints.map((endpoint) => ({ endpoint, category })));
     const endpoints_to_describe = ranked.map((r) => ({
       endpoint_id: r.endpoint.endpoint_id,
       method: r.endpoint.method,
@@ -541,6 +800,7 @@ export async function registerRoutes(app: FastifyInstance) {
         example: b.example_value,
       })) ?? [],
       dom_extraction: !!r.endpoint.dom_extraction,
+      category: r.category,
       _fill_description: "DESCRIBE THIS ENDPOINT — what it returns, key params, action type",
     }));
 
@@ -549,6 +809,8 @@ export async function registerRoutes(app: FastifyInstance) {
       domain: skill.domain,
       endpoint_count: skill.endpoints.length,
       endpoints_to_describe,
+      lint: lintSkill(skill),
+      score: scoreSkill(skill),
       _next_step: `Fill each endpoint's description, then call: unbrowse publish --skill ${skill.skill_id} --endpoints '[{endpoint_id, description, action_kind, resource_kind}]'`,
     });
   });
@@ -678,7 +940,50 @@ export async function registerRoutes(app: FastifyInstance) {
       });
       return reply.send(response);
     } catch (err) {
-      return reply.code(500).send({ error: (err as Error).message });
+      return reply.code(500).send(errorPayload(err));
+    }
+  });
+
+  // POST /v1/workflows/execute -- run skill_call/http_request steps in order over a shared variable map
+  app.post("/v1/workflows/execute", { config: { rateLimit: ROUTE_LIMITS["/v1/skills/:skill_id/execute"] } }, async (req, reply) => {
+    const workflow = req.body as WorkflowDefinition;
+    if (!Array.isArray(workflow?.steps) || workflow.steps.length === 0) {
+      return reply.code(400).send({ error: "steps[] required" });
+    }
+    // Cancel the run when the caller disconnects (e.g. Ctrl-C in the CLI).
+    const cancel = new AbortController();
+    reply.raw.on("close", () => { if (!reply.raw.writableFinished) cancel.abort(); });
+    try {
+      const result = await executeWorkflow(workflow, { client_scope: clientScopeFor(req), signal: cancel.signal });
+      return reply.send(result);
+    } catch (err) {
+      return reply.code(500).send(errorPayload(err));
+    }
+  });
+
+  // POST /v1/workflows/login-from-har -- turn a recorded login into a replayable http_request workflow
+  app.post("/v1/workflows/login-from-har", async (req, reply) => {
+    const { har, seed_url } = (req.body ?? {}) as { har?: unknown; seed_url?: string };
+    if (!har) return reply.code(400).send({ error: "har required" });
+    try {
+      return reply.send({ workflow: buildLoginWorkflow(har, seed_url) });
+    } catch (err) {
+      const code = getErrorCode(err);
+      return reply.code(code === "har_parse" || code === "invalid_input" ? 400 : 500).send(errorPayload(err));
+    }
+  });
+
+  // POST /v1/workflows/replay-har -- learn the login workflow from a HAR and run it once to validate it
+  app.post("/v1/workflows/replay-har", { config: { rateLimit: ROUTE_LIMITS["/v1/skills/:skill_id/execute"] } }, async (req, reply) => {
+    const { har, seed_url, variables } = (req.body ?? {}) as { har?: unknown; seed_url?: string; variables?: Record<string, unknown> };
+    if (!har) return reply.code(400).send({ error: "har required" });
+    const cancel = new AbortController();
+    reply.raw.on("close", () => { if (!reply.raw.writableFinished) cancel.abort(); });
+    try {
+      return reply.send(await replayLoginHar(har, variables ?? {}, { seed_url, client_scope: clientScopeFor(req), signal: cancel.signal }));
+    } catch (err) {
+      const code = getErrorCode(err);
+      return reply.code(code === "har_parse" || code === "invalid_input" ? 400 : 500).send(errorPayload(err));
     }
   });
 
@@ -690,21 +995,70 @@ export async function registerRoutes(app: FastifyInstance) {
 
     const body = req.body as {
       cookies?: Array<{ name: string; value: string; domain: string; path?: string }>;
+      /** Netscape cookies.txt contents (curl/wget jar); appended to `cookies` */
+      cookie_file?: string;
       headers?: Record<string, string>;
+      /** Token refresh endpoint; refresh_token_ref defaults to `${domain}-refresh` */
+      refresh?: Omit<AuthRefreshConfig, "refresh_token_ref"> & { refresh_token_ref?: string };
+      refresh_token?: string;
+      /** Basic-auth pair; encoded into the authorization header on every replay */
+      basic_auth?: { username: string; password: string };
+      /** HMAC key for endpoints with request_signing; kept across later stores that omit it */
+      signing_secret?: string;
     };
-    if (!body.cookies && !body.headers) {
-      return reply.code(400).send({ error: "Provide cookies or headers" });
+    if (!body.cookies && !body.cookie_file && !body.headers && !body.basic_auth && !body.signing_secret) {
+      return reply.code(400).send({ error: "Provide cookies, cookie_file, headers, basic_auth, or signing_secret" });
+    }
+    if (body.signing_secret !== undefined && typeof body.signing_secret !== "string") {
+      return reply.code(400).send({ error: "signing_secret must be a string" });
+    }
+    const cookies = [...(body.cookies ?? []), ...(body.cookie_file ? parseNetscapeCookies(body.cookie_file) : [])];
+    if (body.cookie_file && cookies.length === (body.cookies?.length ?? 0)) {
+      return reply.code(400).send({ error: "cookie_file has no cookies in Netscape cookies.txt format" });
+    }
+    if (body.basic_auth && typeof body.basic_auth.username !== "string") {
+      return reply.code(400).send({ error: "basic_auth requires username and password" });
+    }
+    if (body.refresh && (!body.refresh.url || !body.refresh.token_path)) {
+      return reply.code(400).send({ error: "refresh requires url and token_path" });
     }
 
     const ref = `${skill.domain}-session`;
-    await storeCredential(ref, JSON.stringify({ cookies: body.cookies ?? [], headers: body.headers ?? {} }));
+    let existing: Record<string, unknown> | null = null;
+    try { existing = JSON.parse((await getCredential(ref)) ?? "null"); } catch { /* replace malformed bundle */ }
+    const signingSecret = body.signing_secret
+      || (typeof existing?.signing_secret === "string" ? existing.signing_secret : undefined);
+    const onlySecret = !body.cookies && !body.cookie_file && !body.headers && !body.basic_auth;
+    const headers = body.basic_auth
+      ? { ...(body.headers ?? {}), authorization: encodeBasicAuth(body.basic_auth.username, body.basic_auth.password ?? "") }
+      : body.headers ?? {};
+    // Storing new cookies/headers replaces the session, but not the signing key.
+    await storeCredential(ref, JSON.stringify(onlySecret && existing
+      ? { ...existing, signing_secret: signingSecret }
+      : {
+        cookies,
+        headers,
+        ...(body.basic_auth ? { basic_auth: { username: body.basic_auth.username, password: body.basic_auth.password ?? "" } } : {}),
+        ...(signingSecret ? { signing_secret: signingSecret } : {}),
+      }));
+
+    const authRefresh: AuthRefreshConfig | undefined = body.refresh
+      ? { ...body.refresh, refresh_token_ref: body.refresh.refresh_token_ref ?? `${skill.domain}-refresh` }
+      : undefined;
+    if (authRefresh && body.refresh_token) {
+      await storeCredential(authRefresh.refresh_token_ref, body.refresh_token);
+    }
 
     // Patch the skill manifest to reference the stored credentials
-    if (!skill.auth_profile_ref) {
-      await publishSkill({ ...skill, auth_profile_ref: ref });
+    if (!skill.auth_profile_ref || authRefresh) {
+      await publishSkill({
+        ...skill,
+        auth_profile_ref: skill.auth_profile_ref ?? ref,
+        ...(authRefresh ? { auth_refresh: authRefresh } : {}),
+      });
     }
 
-    return reply.send({ ok: true, auth_profile_ref: ref });
+    return reply.send({ ok: true, auth_profile_ref: ref, ...(authRefresh ? { auth_refresh: authRefresh } : {}) });
   });
 
   // POST /v1/auth/login — interactive OAuth flow or direct browser cookie extraction
@@ -715,10 +1069,16 @@ export async function registerRoutes(app: FastifyInstance) {
       const result = await interactiveLogin(url);
       return reply.send(result);
     } catch (err) {
-      return reply.code(500).send({ error: (err as Error).message });
+      return reply.code(500).send(errorPayload(err));
     }
   });
 
+  // GET /v1/auth/browser-profiles — Chrome (or a Chromium user-data dir's) profiles, for picking chrome_profile
+  app.get("/v1/auth/browser-profiles", async (req, reply) => {
+    const { user_data_dir } = req.query as { user_data_dir?: string };
+    return reply.send({ profiles: listChromiumProfiles(user_data_dir) });
+  });
+
   // POST /v1/auth/steal — extract cookies from Firefox/Chrome/custom Chromium-family SQLite DBs.
   // No browser launch, Chrome can stay open. Higher rate limit since it's instant.
   app.post("/v1/auth/steal", { config: { rateLimit: { max: 30, timeWindow: "1 minute" } } }, async (req, reply) => {
@@ -760,21 +1120,163 @@ export async function registerRoutes(app: FastifyInstance) {
       });
       return reply.send(result);
     } catch (err) {
-      return reply.code(500).send({ error: (err as Error).message });
+      return reply.code(500).send(errorPayload(err));
     }
   });
 
   // POST /v1/skills/:skill_id/verify — trigger verification
   app.post("/v1/skills/:skill_id/verify", async (req, reply) => {
     const { skill_id } = req.params as { skill_id: string };
-    const skill = await getSkill(skill_id);
+    const { min_interval_ms, respect_robots, follow_cookies, assertions } = (req.body as {
+      min_interval_ms?: number;
+      respect_robots?: boolean;
+      follow_cookies?: boolean;
+      assertions?: Record<string, ResponseAssertion[]>;
+    }) ?? {};
+    const clientScope = clientScopeFor(req);
+    const skill = getRecentLocalSkill(skill_id, clientScope) ?? await getSkill(skill_id, clientScope);
     if (!skill) return reply.code(404).send({ error: "Skill not found" });
     try {
       const { verifySkill } = await import("../verification/index.js");
-      const results = await verifySkill(skill);
-      return reply.send({ skill_id, verification: results });
+      const redirects: Record<string, RedirectInfo> = {};
+      const assertionResults: Record<string, AssertionResult[]> = {};
+      const results = await verifySkill(skill, {
+        min_interval_ms,
+        respect_robots,
+        follow_cookies,
+        assertions,
+        on_redirect: (endpointId, info) => { redirects[endpointId] = info; },
+        on_assertions: (endpointId, outcomes) => { assertionResults[endpointId] = outcomes; },
+      });
+      // verifySkill records what it learned on the endpoints — keep it.
+      cachePublishedSkill(skill, clientScope);
+      const endpoints = Object.fromEntries(skill.endpoints
+        .filter((ep) => ep.endpoint_id in results && (ep.last_verified_at || ep.rate_limit))
+        .map((ep) => [ep.endpoint_id, {
+          ...(ep.last_verified_at ? { last_verified_at: ep.last_verified_at } : {}),
+          ...(ep.supports_conditional !== undefined ? { supports_conditional: ep.supports_conditional } : {}),
+          ...(ep.rate_limit ? { rate_limit: ep.rate_limit } : {}),
+        }]));
+      return reply.send({
+        skill_id,
+        verification: results,
+        ...(Object.keys(endpoints).length > 0 ? { endpoints } : {}),
+        ...(Object.keys(redirects).length > 0 ? { redirects } : {}),
+        ...(Object.keys(assertionResults).length > 0 ? { assertions: assertionResults } : {}),
+      });
     } catch (err) {
-      return reply.code(500).send({ error: (err as Error).message });
+      return reply.code(500).send(errorPayload(err));
+    }
+  });
+
+  // POST /v1/skills/:skill_id/auth/validate — probe the stored auth bundle end to end (refreshing on 401/403)
+  app.post("/v1/skills/:skill_id/auth/validate", async (req, reply) => {
+    const clientScope = clientScopeFor(req);
+    const { skill_id } = req.params as { skill_id: string };
+    const { probe_path, endpoint_id, refresh } = (req.body ?? {}) as { probe_path?: string; endpoint_id?: string; refresh?: boolean };
+    const skill = getRecentLocalSkill(skill_id, clientScope) ?? await getSkill(skill_id, clientScope);
+    if (!skill) return reply.code(404).send({ error: "Skill not found" });
+    try {
+      const { validateSkillAuth } = await import("../auth/validate.js");
+      return reply.send({ skill_id, ...(await validateSkillAuth(skill, { probe_path, endpoint_id, refresh })) });
+    } catch (err) {
+      return reply.code(500).send(errorPayload(err));
+    }
+  });
+
+  // POST /v1/skills/:skill_id/auth/minimize — probe which stored auth headers/cookies endpoints check
+  app.post("/v1/skills/:skill_id/auth/minimize", async (req, reply) => {
+    const clientScope = clientScopeFor(req);
+    const { skill_id } = req.params as { skill_id: string };
+    const { endpoint_id, endpoint_ids, max_probes, min_interval_ms, apply } = (req.body ?? {}) as {
+      endpoint_id?: string;
+      /** Probe several endpoints; the result keeps what any of them needed */
+      endpoint_ids?: string[];
+      /** Per endpoint */
+      max_probes?: number;
+      min_interval_ms?: number;
+      /** Rewrite the stored bundle with only the headers/cookies the probed endpoints needed */
+      apply?: boolean;
+    };
+    const skill = getRecentLocalSkill(skill_id, clientScope) ?? await getSkill(skill_id, clientScope);
+    if (!skill) return reply.code(404).send({ error: "Skill not found" });
+    if (!skill.auth_profile_ref) return reply.code(400).send({ error: "Skill has no stored auth to minimize" });
+    const isSafe = (e: EndpointDescriptor) => e.method === "GET" || e.method === "HEAD";
+    // Probes replay requests many times, so only safe endpoints qualify — and
+    // a bundle rewritten from them could drop what a POST/DELETE needs.
+    if (apply && skill.endpoints.some((e) => !isSafe(e) && e.method !== "WS")) {
+      return reply.code(400).send({ error: "apply needs a skill with only GET/HEAD endpoints; its other endpoints can't be probed and may need credentials the probes drop" });
+    }
+    const ids = endpoint_ids?.length ? endpoint_ids : endpoint_id ? [endpoint_id] : null;
+    let endpoints: EndpointDescriptor[];
+    if (ids) {
+      endpoints = [];
+      for (const id of ids) {
+        const found = skill.endpoints.find((e) => e.endpoint_id === id);
+        if (!found) return reply.code(404).send({ error: `Endpoint not found: ${id}` });
+        endpoints.push(found);
+      }
+    } else if (apply) {
+      // Applying keeps the union over every endpoint the bundle serves.
+      endpoints = skill.endpoints.filter(isSafe);
+    } else {
+      const picked = skill.endpoints.find((e) => e.method === "GET" && e.verification_status === "verified")
+        ?? skill.endpoints.find((e) => e.method === "GET");
+      endpoints = picked ? [picked] : [];
+    }
+    if (endpoints.length === 0) return reply.code(404).send({ error: "Skill has no GET endpoint to probe" });
+    if (!endpoints.every(isSafe)) return reply.code(400).send({ error: "Only GET/HEAD endpoints can be probed" });
+
+    let bundle: { headers?: Record<string, string>; cookies?: Array<{ name: string; value: string }> } & Record<string, unknown> | null = null;
+    try { bundle = JSON.parse((await getCredential(skill.auth_profile_ref)) ?? "null"); } catch { /* no usable bundle */ }
+    if (!bundle) return reply.code(404).send({ error: `No credentials stored for ${skill.auth_profile_ref}` });
+    try {
+      const { minimizeAuth } = await import("../verification/minimize-auth.js");
+      const authNames = new Set(Object.keys(bundle.headers ?? {}).map((k) => k.toLowerCase()));
+      const perEndpoint = [];
+      for (const endpoint of endpoints) {
+        const request = endpointToCurlRequest(endpoint, skill);
+        const baseHeaders = Object.fromEntries(Object.entries(request.headers).filter(([k]) => !authNames.has(k.toLowerCase()) && k.toLowerCase() !== "cookie"));
+        const result = await minimizeAuth(request.url, bundle.headers ?? {}, bundle.cookies ?? [], {
+          method: request.method,
+          max_probes,
+          min_interval_ms,
+          base_headers: baseHeaders,
+        });
+        perEndpoint.push({ endpoint_id: endpoint.endpoint_id, ...result });
+      }
+      // Union: a header or cookie stays if any probed endpoint needed it.
+      const keptHeaderNames = new Set(perEndpoint.flatMap((r) => Object.keys(r.headers)));
+      const keptCookies = new Set(perEndpoint.flatMap((r) => r.cookies));
+      const headers = Object.fromEntries(Object.entries(bundle.headers ?? {}).filter(([k]) => keptHeaderNames.has(k)));
+      const dropped = [
+        ...Object.keys(bundle.headers ?? {}).filter((k) => !keptHeaderNames.has(k)).map((k) => `header:${k}`),
+        ...(bundle.cookies ?? []).filter((c) => !keptCookies.has(c.name)).map((c) => `cookie:${c.name}`),
+      ];
+      const failed = perEndpoint.some((r) => r.baseline_failed);
+      const applied = !!apply && !failed && dropped.length > 0;
+      if (applied) {
+        await storeCredential(skill.auth_profile_ref, JSON.stringify({
+          ...bundle,
+          headers,
+          cookies: (bundle.cookies ?? []).filter((c) => keptCookies.has(c.name)),
+        }));
+      }
+      const summary = perEndpoint.length === 1
+        ? perEndpoint[0]
+        : {
+          endpoint_ids: perEndpoint.map((r) => r.endpoint_id),
+          baseline_failed: failed,
+          headers,
+          cookies: [...keptCookies],
+          dropped,
+          probes: perEndpoint.reduce((n, r) => n + r.probes, 0),
+          truncated: perEndpoint.some((r) => r.truncated),
+          per_endpoint: perEndpoint,
+        };
+      return reply.send({ skill_id, ...summary, applied });
+    } catch (err) {
+      return reply.code(500).send(errorPayload(err));
     }
   });
 
@@ -807,7 +1309,7 @@ export async function registerRoutes(app: FastifyInstance) {
       }
       return reply.send({ ok: true, avg_rating });
     } catch (err) {
-      return reply.code(500).send({ error: (err as Error).message });
+      return reply.code(500).send(errorPayload(err));
     }
   });
 
@@ -817,13 +1319,42 @@ export async function registerRoutes(app: FastifyInstance) {
       const data = await fetchStats();
       return reply.send(data);
     } catch (err) {
-      return reply.code(500).send({ error: (err as Error).message });
+      return reply.code(500).send(errorPayload(err));
+    }
+  });
+
+  // GET /v1/marketplace/home — trending + featured; a failing section becomes a warning
+  app.get("/v1/marketplace/home", async (req, reply) => {
+    const { limit } = (req.query ?? {}) as { limit?: string };
+    try {
+      return reply.send(await getMarketplaceHome(limit ? Number(limit) || undefined : undefined));
+    } catch (err) {
+      return reply.code(500).send(errorPayload(err));
     }
   });
 
   // GET /health
   app.get("/health", async (_req, reply) => reply.send({ status: "ok", trace_version: TRACE_VERSION, code_hash: CODE_HASH, git_sha: GIT_SHA }));
 
+  // POST /v1/log-level — raise/lower server verbosity without a restart
+  app.post("/v1/log-level", async (req, reply) => {
+    const { level } = (req.body ?? {}) as { level?: unknown };
+    if (!isLogLevel(level)) {
+      return reply.code(400).send(errorPayload(unbrowseError("invalid_input", "level must be one of error, warn, info, debug, trace")));
+    }
+    setLogLevel(level);
+    return reply.send({ level: getLogLevel() });
+  });
+
+  // GET /v1/health/check — per-dependency probes; /health stays a cheap liveness check
+  app.get("/v1/health/check", async (_req, reply) => {
+    try {
+      return reply.send(await healthCheck());
+    } catch (err) {
+      return reply.code(500).send(errorPayload(err));
+    }
+  });
+
   // GET /v1/sessions/:domain — read local trace/debug files instead of proxying to backend
   app.get("/v1/sessions/:domain", async (req, reply) => {
     const { domain } = req.params as { domain: string };
@@ -877,6 +1408,8 @@ export async function registerRoutes(app: FastifyInstance) {
     await kuri.harStart(session.tabId).catch(() => {});
     await kuri.scriptInject(session.tabId, INTERCEPTOR_SCRIPT).catch(() => {});
     session.harActive = true;
+    // The next page starts a fresh interceptor buffer.
+    session.journalCursor = 0;
     await injectInterceptor(session.tabId).catch(() => {});
   }
 
@@ -904,16 +1437,7 @@ export async function registerRoutes(app: FastifyInstance) {
     let intercepted: RawRequest[] = [];
     try {
       const raw = await collectInterceptedRequests(session.tabId);
-      intercepted = raw.map((request) => ({
-        url: request.url,
-        method: request.method,
-        request_headers: request.request_headers ?? {},
-        request_body: request.request_body,
-        response_status: request.response_status,
-        response_headers: request.response_headers ?? {},
-        response_body: request.response_body,
-        timestamp: request.timestamp,
-      }));
+      intercepted = raw.map(interceptedToRawRequest);
     } catch { /* non-fatal */ }
 
     let harEntries: KuriHarEntry[] = [];
@@ -926,6 +1450,7 @@ export async function registerRoutes(app: FastifyInstance) {
     session.harActive = false;
 
     const allRequests = mergeBrowseRequests(intercepted, harEntries, session.url);
+    journalBrowseRequests(session, allRequests);
     const syncResult = await cacheBrowseRequests({
       sessionUrl: session.url,
       sessionDomain: session.domain,
@@ -970,14 +1495,36 @@ export async function registerRoutes(app: FastifyInstance) {
     };
   }
 
+  // POST /v1/browse/sessions — open a named browse session (own tab + HAR) and return its id.
+  // Pass it as X-Unbrowse-Session (or `session`) on later /v1/browse/* calls.
+  app.post("/v1/browse/sessions", async (req, reply) => {
+    const { session_id } = (req.body as { session_id?: string }) ?? {};
+    const id = session_id?.trim() || nanoid(10);
+    try {
+      const session = await getOrCreateBrowseSession(browseSessions, kuri, injectInterceptor, id);
+      return reply.send({ session_id: id, tab_id: session.tabId, url: session.url });
+    } catch (err) {
+      return reply.code(500).send(errorPayload(err));
+    }
+  });
+
+  // GET /v1/browse/sessions — sessions currently recording
+  app.get("/v1/browse/sessions", async (_req, reply) => reply.send({
+    sessions: [...browseSessions.entries()].map(([id, session]) => ({
+      session_id: id,
+      tab_id: session.tabId,
+      url: session.url,
+      domain: session.domain,
+      har_active: session.harActive,
+    })),
+  }));
+
   // POST /v1/browse/go — navigate to URL
   app.post("/v1/browse/go", async (req, reply) => {
     const { url } = req.body as { url: string };
     if (!url) return reply.code(400).send({ error: "url required" });
-    const { session, result } = await withRecoveredBrowseSession(
-      browseSessions,
-      kuri,
-      injectInterceptor,
+    const { session, result } = await withBrowseSession(
+      req,
       async (session) => {
         const newDomain = profileName(url);
 
@@ -985,6 +1532,7 @@ export async function registerRoutes(app: FastifyInstance) {
         if (session.harActive && session.url !== "about:blank") {
           try {
             const { entries } = await kuri.harStop(session.tabId);
+            journalBrowseRequests(session, harEntriesToRawRequests(entries, session.url));
             passiveIndexHar(entries, session.url);
           } catch { /* non-fatal */ }
           session.harActive = false;
@@ -1019,6 +1567,7 @@ export async function registerRoutes(app: FastifyInstance) {
         const finalUrl = await kuri.getCurrentUrl(session.tabId).catch(() => url);
         session.url = typeof finalUrl === "string" && finalUrl.startsWith("http") ? finalUrl : url;
         session.domain = profileName(session.url);
+        journalBrowse(session, { type: "navigation", at: new Date().toISOString(), url: session.url, domain: session.domain });
 
         await injectInterceptor(session.tabId);
 
@@ -1052,10 +1601,8 @@ export async function registerRoutes(app: FastifyInstance) {
       timeout_ms?: number;
     }) ?? {};
 
-    const { session, result, recovered } = await withRecoveredBrowseSession(
-      browseSessions,
-      kuri,
-      injectInterceptor,
+    const { session, result, recovered } = await withBrowseSession(
+      req,
       async (session) => submitBrowseForm(
         {
           client: kuri,
@@ -1096,22 +1643,30 @@ export async function registerRoutes(app: FastifyInstance) {
   // POST /v1/browse/snap — a11y snapshot
   app.post("/v1/browse/snap", async (req, reply) => {
     const { filter } = (req.body as { filter?: string }) ?? {};
-    const { session, result: snapshot } = await withRecoveredBrowseSession(
-      browseSessions,
-      kuri,
-      injectInterceptor,
+    const { session, result: snapshot } = await withBrowseSession(
+      req,
       async (session) => kuri.snapshot(session.tabId, filter),
       (snapshot) => typeof snapshot !== "string" || snapshot.trim().length === 0,
     );
     return reply.send({ snapshot, tab_id: session.tabId });
   });
 
+  // POST /v1/browse/login-fields — username/password/submit refs of the login form on the page
+  app.post("/v1/browse/login-fields", async (req, reply) => {
+    const { session, result: fields } = await withBrowseSession(
+      req,
+      async (session) => detectLoginFields(await kuri.snapshot(session.tabId, "interactive")),
+    );
+    return reply.send({ fields, tab_id: session.tabId });
+  });
+
   // POST /v1/browse/click — click by ref
   app.post("/v1/browse/click", async (req, reply) => {
     const { ref } = req.body as { ref: string };
     if (!ref) return reply.code(400).send({ error: "ref required" });
-    await withRecoveredBrowseSession(browseSessions, kuri, injectInterceptor, async (session) => {
+    await withBrowseSession(req, async (session) => {
       await kuri.click(session.tabId, ref);
+      journalBrowseAction(session, "click", { ref });
       return true;
     });
     return reply.send({ ok: true });
@@ -1121,8 +1676,9 @@ export async function registerRoutes(app: FastifyInstance) {
   app.post("/v1/browse/fill", async (req, reply) => {
     const { ref, value } = req.body as { ref: string; value: string };
     if (!ref || value === undefined) return reply.code(400).send({ error: "ref and value required" });
-    await withRecoveredBrowseSession(browseSessions, kuri, injectInterceptor, async (session) => {
+    await withBrowseSession(req, async (session) => {
       await kuri.fill(session.tabId, ref, value);
+      journalBrowseAction(session, "fill", { ref });
       return true;
     });
     return reply.send({ ok: true });
@@ -1132,8 +1688,9 @@ export async function registerRoutes(app: FastifyInstance) {
   app.post("/v1/browse/type", async (req, reply) => {
     const { text } = req.body as { text: string };
     if (!text) return reply.code(400).send({ error: "text required" });
-    await withRecoveredBrowseSession(browseSessions, kuri, injectInterceptor, async (session) => {
+    await withBrowseSession(req, async (session) => {
       await kuri.keyboardType(session.tabId, text);
+      journalBrowseAction(session, "type");
       return true;
     });
     return reply.send({ ok: true });
@@ -1143,8 +1700,9 @@ export async function registerRoutes(app: FastifyInstance) {
   app.post("/v1/browse/press", async (req, reply) => {
     const { key } = req.body as { key: string };
     if (!key) return reply.code(400).send({ error: "key required" });
-    await withRecoveredBrowseSession(browseSessions, kuri, injectInterceptor, async (session) => {
+    await withBrowseSession(req, async (session) => {
       await kuri.press(session.tabId, key);
+      journalBrowseAction(session, "press", { key });
       return true;
     });
     return reply.send({ ok: true });
@@ -1154,29 +1712,46 @@ export async function registerRoutes(app: FastifyInstance) {
   app.post("/v1/browse/select", async (req, reply) => {
     const { ref, value } = req.body as { ref: string; value: string };
     if (!ref || value === undefined) return reply.code(400).send({ error: "ref and value required" });
-    await withRecoveredBrowseSession(browseSessions, kuri, injectInterceptor, async (session) => {
+    await withBrowseSession(req, async (session) => {
       await kuri.select(session.tabId, ref, value);
+      journalBrowseAction(session, "select", { ref, value });
       return true;
     });
     return reply.send({ ok: true });
   });
 
+  // POST /v1/browse/upload — set a file input's files by ref
+  app.post("/v1/browse/upload", async (req, reply) => {
+    const { ref, file_path } = req.body as { ref: string; file_path: string };
+    if (!ref || !file_path) return reply.code(400).send({ error: "ref and file_path required" });
+    try {
+      await withBrowseSession(req, async (session) => {
+        await kuri.uploadFile(session.tabId, ref, file_path);
+        journalBrowseAction(session, "upload", { ref });
+        return true;
+      });
+    } catch (err) {
+      if (getErrorCode(err) === "invalid_input") return reply.code(400).send(errorPayload(err));
+      throw err;
+    }
+    return reply.send({ ok: true });
+  });
+
   // POST /v1/browse/scroll — scroll
   app.post("/v1/browse/scroll", async (req, reply) => {
     const { direction, amount } = (req.body as { direction?: string; amount?: number }) ?? {};
-    await withRecoveredBrowseSession(browseSessions, kuri, injectInterceptor, async (session) => {
+    await withBrowseSession(req, async (session) => {
       await kuri.scroll(session.tabId, (direction as any) ?? "down", amount);
+      journalBrowseAction(session, "scroll", { direction: direction ?? "down", amount });
       return true;
     });
     return reply.send({ ok: true });
   });
 
   // GET /v1/browse/screenshot — capture screenshot
-  app.get("/v1/browse/screenshot", async (_req, reply) => {
-    const { session, result: data } = await withRecoveredBrowseSession(
-      browseSessions,
-      kuri,
-      injectInterceptor,
+  app.get("/v1/browse/screenshot", async (req, reply) => {
+    const { session, result: data } = await withBrowseSession(
+      req,
       async (session) => kuri.screenshot(session.tabId),
       (data) => typeof data !== "string" || data.trim().length === 0,
     );
@@ -1184,11 +1759,9 @@ export async function registerRoutes(app: FastifyInstance) {
   });
 
   // GET /v1/browse/text — page text
-  app.get("/v1/browse/text", async (_req, reply) => {
-    const { result: text } = await withRecoveredBrowseSession(
-      browseSessions,
-      kuri,
-      injectInterceptor,
+  app.get("/v1/browse/text", async (req, reply) => {
+    const { result: text } = await withBrowseSession(
+      req,
       async (session) => kuri.getText(session.tabId),
       (text) => typeof text !== "string",
     );
@@ -1196,11 +1769,9 @@ export async function registerRoutes(app: FastifyInstance) {
   });
 
   // GET /v1/browse/markdown — page as markdown
-  app.get("/v1/browse/markdown", async (_req, reply) => {
-    const { result: markdown } = await withRecoveredBrowseSession(
-      browseSessions,
-      kuri,
-      injectInterceptor,
+  app.get("/v1/browse/markdown", async (req, reply) => {
+    const { result: markdown } = await withBrowseSession(
+      req,
       async (session) => kuri.getMarkdown(session.tabId),
       (markdown) => typeof markdown !== "string",
     );
@@ -1208,11 +1779,9 @@ export async function registerRoutes(app: FastifyInstance) {
   });
 
   // GET /v1/browse/cookies — page cookies
-  app.get("/v1/browse/cookies", async (_req, reply) => {
-    const { result: cookies } = await withRecoveredBrowseSession(
-      browseSessions,
-      kuri,
-      injectInterceptor,
+  app.get("/v1/browse/cookies", async (req, reply) => {
+    const { result: cookies } = await withBrowseSession(
+      req,
       async (session) => kuri.getCookies(session.tabId),
     );
     return reply.send({ cookies });
@@ -1222,10 +1791,8 @@ export async function registerRoutes(app: FastifyInstance) {
   app.post("/v1/browse/eval", async (req, reply) => {
     const { expression } = req.body as { expression: string };
     if (!expression) return reply.code(400).send({ error: "expression required" });
-    const { result } = await withRecoveredBrowseSession(
-      browseSessions,
-      kuri,
-      injectInterceptor,
+    const { result } = await withBrowseSession(
+      req,
       async (session) => kuri.evaluate(session.tabId, expression),
       (result) => isRecoverableBrowseFailure(result),
     );
@@ -1233,26 +1800,28 @@ export async function registerRoutes(app: FastifyInstance) {
   });
 
   // POST /v1/browse/back — navigate back
-  app.post("/v1/browse/back", async (_req, reply) => {
-    await withRecoveredBrowseSession(browseSessions, kuri, injectInterceptor, async (session) => {
+  app.post("/v1/browse/back", async (req, reply) => {
+    await withBrowseSession(req, async (session) => {
       await kuri.goBack(session.tabId);
+      journalBrowseAction(session, "back");
       return true;
     });
     return reply.send({ ok: true });
   });
 
   // POST /v1/browse/forward — navigate forward
-  app.post("/v1/browse/forward", async (_req, reply) => {
-    await withRecoveredBrowseSession(browseSessions, kuri, injectInterceptor, async (session) => {
+  app.post("/v1/browse/forward", async (req, reply) => {
+    await withBrowseSession(req, async (session) => {
       await kuri.goForward(session.tabId);
+      journalBrowseAction(session, "forward");
       return true;
     });
     return reply.send({ ok: true });
   });
 
   // POST /v1/browse/sync — flush captured traffic into local skill cache without closing tab
-  app.post("/v1/browse/sync", async (_req, reply) => {
-    const session = browseSessions.get("default");
+  app.post("/v1/browse/sync", async (req, reply) => {
+    const session = browseSessions.get(browseSessionId(req));
     if (!session) return reply.send({ ok: false, error: "no active session" });
     const syncResult = await flushBrowseCapture(session);
 
@@ -1272,8 +1841,9 @@ export async function registerRoutes(app: FastifyInstance) {
   });
 
   // POST /v1/browse/close — close session, flush HAR, index, save auth
-  app.post("/v1/browse/close", async (_req, reply) => {
-    const session = browseSessions.get("default");
+  app.post("/v1/browse/close", async (req, reply) => {
+    const sessionId = browseSessionId(req);
+    const session = browseSessions.get(sessionId);
     if (!session) return reply.send({ ok: true, message: "no active session" });
 
     // Save auth profile for the current domain before closing
@@ -1282,8 +1852,9 @@ export async function registerRoutes(app: FastifyInstance) {
     }
 
     const syncResult = await flushBrowseCapture(session, { queueBackgroundPublish: true });
+    journalBrowse(session, { type: "close", at: new Date().toISOString() });
     await kuri.closeTab(session.tabId).catch(() => {});
-    browseSessions.delete("default");
+    browseSessions.delete(sessionId);
     return reply.send({
       ok: true,
       indexed: syncResult.indexed,
@@ -1294,6 +1865,40 @@ export async function registerRoutes(app: FastifyInstance) {
       auth_saved: session.domain || null,
     });
   });
+
+  // POST /v1/browse/recover — rebuild an interrupted session from its journal and index its traffic
+  app.post("/v1/browse/recover", async (req, reply) => {
+    const { session_id } = (req.body as { session_id?: string }) ?? {};
+    const journalId = session_id ?? findInterruptedBrowseJournal();
+    if (!journalId) return reply.send({ ok: false, error: "no interrupted session" });
+    const recovered = loadBrowseJournal(journalId);
+    if (!recovered) return reply.code(404).send({ error: `journal not found: ${journalId}` });
+
+    const syncResult = recovered.requests.length > 0
+      ? await cacheBrowseRequests({
+          sessionUrl: recovered.url,
+          sessionDomain: recovered.domain,
+          requests: recovered.requests,
+        })
+      : null;
+    if (!recovered.closed) {
+      appendBrowseJournal(journalId, { type: "close", at: new Date().toISOString(), reason: "recovered" });
+    }
+
+    return reply.send({
+      ok: true,
+      session_id: journalId,
+      url: recovered.url,
+      domain: recovered.domain,
+      started_at: recovered.started_at,
+      navigations: recovered.navigations,
+      action_count: recovered.actions.length,
+      request_count: recovered.requests.length,
+      indexed: syncResult?.indexed ?? false,
+      skill_id: syncResult?.skill?.skill_id ?? null,
+      endpoint_count: syncResult?.skill?.endpoints.length ?? 0,
+    });
+  });
 }
 
 function saveTrace(trace: unknown) {
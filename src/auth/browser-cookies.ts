@@ -2,19 +2,25 @@
  * Extract cookies directly from Chrome/Firefox SQLite databases.
  * Adapted from github.com/jawond/bird — generalized for any domain.
  *
- * Chrome cookies are AES-128-CBC encrypted with a key from the macOS keychain.
- * Firefox cookies are stored unencrypted.
+ * Chrome cookies are AES-128-CBC encrypted. The key comes from the macOS
+ * keychain, or on Linux from libsecret (v11 values) or Chromium's fixed
+ * "peanuts" password (v10 values, keyring-less installs). Windows values are
+ * DPAPI/AES-GCM protected and not decrypted here. Firefox cookies are stored
+ * unencrypted.
  *
  * This avoids needing to launch a browser or close Chrome (reads a copy of the DB).
  */
 
 import { execSync, execFileSync } from "node:child_process";
 import { createDecipheriv, pbkdf2Sync } from "node:crypto";
-import { copyFileSync, existsSync, mkdtempSync, readdirSync, rmSync } from "node:fs";
+import { copyFileSync, existsSync, mkdtempSync, readdirSync, readFileSync, rmSync } from "node:fs";
 import { tmpdir, homedir, platform } from "node:os";
 import { join } from "node:path";
 import { log } from "../logger.js";
 import { getRegistrableDomain, isDomainMatch } from "../domain.js";
+import { chromeTimestampToEpochSeconds } from "../time.js";
+import { getErrorCode, type UnbrowseError } from "../errors.js";
+import { readKeychainPassword } from "./keychain.js";
 
 export interface BrowserCookie {
   name: string;
@@ -43,6 +49,18 @@ export interface ChromiumCookieSourceOptions {
   browserName?: string;
 }
 
+/** A profile directory under a Chromium user-data dir, as listed in `Local State`. */
+export interface ChromiumProfile {
+  /** Directory name to pass as `profile` — "Default", "Profile 1", ... */
+  dir: string;
+  /** Name shown in the profile picker */
+  name: string;
+  /** Signed-in account, when the profile has one */
+  user_name?: string;
+  /** Whether the profile has a cookies DB to read */
+  has_cookies: boolean;
+}
+
 export interface ExtractBrowserCookiesOptions {
   browser?: BrowserSource;
   chromeProfile?: string;
@@ -66,13 +84,27 @@ function getChromeUserDataDir(): string {
   return join(home, ".config", "google-chrome");
 }
 
+/** Default user-data dir for `browserName`: Chromium's own (incl. the Linux snap) or Chrome's. */
+function getDefaultUserDataDir(browserName?: string): string {
+  if (browserName?.toLowerCase() !== "chromium") return getChromeUserDataDir();
+  const home = homedir();
+  if (platform() === "darwin") return join(home, "Library", "Application Support", "Chromium");
+  if (platform() === "win32") {
+    const appData = process.env.LOCALAPPDATA ?? join(home, "AppData", "Local");
+    return join(appData, "Chromium", "User Data");
+  }
+  const snap = join(home, "snap", "chromium", "common", "chromium");
+  const native = join(home, ".config", "chromium");
+  return !existsSync(native) && existsSync(snap) ? snap : native;
+}
+
 export function resolveChromiumCookiesPath(opts?: ChromiumCookieSourceOptions): string | null {
   if (opts?.cookieDbPath) {
     return opts.cookieDbPath.replace(/^~\//, homedir() + "/");
   }
 
   const profileDir = opts?.profile || "Default";
-  const userDataDir = (opts?.userDataDir || getChromeUserDataDir()).replace(/^~\//, homedir() + "/");
+  const userDataDir = (opts?.userDataDir || getDefaultUserDataDir(opts?.browserName)).replace(/^~\//, homedir() + "/");
   const candidates = [
     join(userDataDir, profileDir, "Network", "Cookies"),
     join(userDataDir, profileDir, "Cookies"),
@@ -83,6 +115,37 @@ export function resolveChromiumCookiesPath(opts?: ChromiumCookieSourceOptions):
   return candidates.find((candidate) => existsSync(candidate)) ?? candidates[0] ?? null;
 }
 
+/**
+ * Profiles in a Chromium user-data dir (Chrome's by default), read from
+ * `Local State`. Falls back to scanning for "Default" / "Profile N"
+ * directories when Local State is missing or unreadable.
+ */
+export function listChromiumProfiles(userDataDir?: string): ChromiumProfile[] {
+  const root = (userDataDir || getChromeUserDataDir()).replace(/^~\//, homedir() + "/");
+  const withCookies = (dir: string) => existsSync(join(root, dir, "Network", "Cookies")) || existsSync(join(root, dir, "Cookies"));
+  let infoCache: Record<string, { name?: string; user_name?: string }> | undefined;
+  try {
+    const localState = JSON.parse(readFileSync(join(root, "Local State"), "utf8")) as {
+      profile?: { info_cache?: Record<string, { name?: string; user_name?: string }> };
+    };
+    infoCache = localState.profile?.info_cache;
+  } catch (err) {
+    if (existsSync(root)) log("auth", `could not read Chromium Local State in ${root}: ${(err as Error).message}`);
+  }
+  if (infoCache) {
+    return Object.entries(infoCache).map(([dir, info]) => ({
+      dir,
+      name: info.name || dir,
+      ...(info.user_name ? { user_name: info.user_name } : {}),
+      has_cookies: withCookies(dir),
+    }));
+  }
+  if (!existsSync(root)) return [];
+  return readdirSync(root, { withFileTypes: true })
+    .filter((e) => e.isDirectory() && /^(Default|Profile \d+)$/.test(e.name))
+    .map((e) => ({ dir: e.name, name: e.name, has_cookies: withCookies(e.name) }));
+}
+
 function getFirefoxProfilesRoot(): string | null {
   const home = homedir();
   if (platform() === "darwin") {
@@ -119,36 +182,76 @@ function getFirefoxCookiesPath(profile?: string): string | null {
 }
 
 // ---------------------------------------------------------------------------
-// Chrome decryption (macOS — uses keychain + PBKDF2 + AES-128-CBC)
+// Chrome decryption (PBKDF2 + AES-128-CBC; key from the macOS keychain or,
+// on Linux, libsecret / the "peanuts" fallback)
 // ---------------------------------------------------------------------------
 
 const _chromiumKeyCache = new Map<string, Buffer>();
+// A denied/timed-out prompt is remembered briefly so one extraction doesn't
+// re-prompt (and re-wait) for every encrypted cookie.
+const _chromiumKeyDenied = new Map<string, { error: UnbrowseError; at: number }>();
+const KEY_DENIAL_TTL_MS = 60_000;
 
 function getChromiumKeychainServiceName(opts?: ChromiumCookieSourceOptions): string {
   if (opts?.safeStorageService) return opts.safeStorageService;
   return `${opts?.browserName || "Chrome"} Safe Storage`;
 }
 
-function getChromiumDecryptionKey(opts?: ChromiumCookieSourceOptions): Buffer | null {
+/** Chromium's hard-coded password for v10 values on Linux (no keyring). */
+const LINUX_V10_PASSWORD = "peanuts";
+
+/** Chromium's libsecret password for `browserName` ("chrome", "chromium", "brave", ...). */
+function readLibsecretPassword(browserName: string): string | null {
+  try {
+    const out = execFileSync("secret-tool", ["lookup", "application", browserName.toLowerCase()], {
+      encoding: "utf8",
+      timeout: 5_000,
+      stdio: ["ignore", "pipe", "ignore"],
+    }).trim();
+    return out || null;
+  } catch {
+    return null; // secret-tool missing, or no entry (basic password store)
+  }
+}
+
+/** Linux keys use a single PBKDF2 iteration; v10 is the fixed password, v11 the keyring one. */
+function getLinuxChromiumKey(version: string, opts?: ChromiumCookieSourceOptions): Buffer | null {
+  const browserName = opts?.browserName || "chrome";
+  const cacheKey = `linux:${browserName}:${version}`;
+  const cached = _chromiumKeyCache.get(cacheKey);
+  if (cached) return cached;
+  const password = version === "v10" ? LINUX_V10_PASSWORD : readLibsecretPassword(browserName);
+  if (!password) return null;
+  const derived = pbkdf2Sync(password, "saltysalt", 1, 16, "sha1");
+  _chromiumKeyCache.set(cacheKey, derived);
+  return derived;
+}
+
+function getChromiumDecryptionKey(opts?: ChromiumCookieSourceOptions, version = "v10"): Buffer | null {
+  if (platform() === "linux") return getLinuxChromiumKey(version, opts);
+  // Windows wraps the key with DPAPI in Local State and uses AES-GCM — not supported.
+  if (platform() !== "darwin") return null;
   const service = getChromiumKeychainServiceName(opts);
   const cached = _chromiumKeyCache.get(service);
   if (cached) return cached;
-  if (platform() !== "darwin") return null; // TODO: Linux/Windows support
+  const denied = _chromiumKeyDenied.get(service);
+  if (denied && Date.now() - denied.at < KEY_DENIAL_TTL_MS) throw denied.error;
 
+  let keyOutput: string | null;
   try {
-    const keyOutput = execFileSync(
-      "security",
-      ["find-generic-password", "-s", service, "-w"],
-      { encoding: "utf8", stdio: ["pipe", "pipe", "pipe"] },
-    ).trim();
-    if (!keyOutput) return null;
-
-    const derived = pbkdf2Sync(keyOutput, "saltysalt", 1003, 16, "sha1");
-    _chromiumKeyCache.set(service, derived);
-    return derived;
-  } catch {
+    keyOutput = readKeychainPassword(service);
+  } catch (err) {
+    if (getErrorCode(err) === "keychain_access_denied") {
+      _chromiumKeyDenied.set(service, { error: err as UnbrowseError, at: Date.now() });
+      throw err;
+    }
     return null;
   }
+  if (!keyOutput) return null;
+
+  const derived = pbkdf2Sync(keyOutput, "saltysalt", 1003, 16, "sha1");
+  _chromiumKeyCache.set(service, derived);
+  return derived;
 }
 
 function decryptChromiumValue(encryptedHex: string, opts?: ChromiumCookieSourceOptions): string | null {
@@ -162,7 +265,7 @@ function decryptChromiumValue(encryptedHex: string, opts?: ChromiumCookieSourceO
       return buf.toString("utf8");
     }
 
-    const key = getChromiumDecryptionKey(opts);
+    const key = getChromiumDecryptionKey(opts, version);
     if (!key) return null;
 
     const payload = buf.subarray(3);
@@ -189,7 +292,9 @@ function decryptChromiumValue(encryptedHex: string, opts?: ChromiumCookieSourceO
     decipher.setAutoPadding(true);
     const decrypted = Buffer.concat([decipher.update(payload), decipher.final()]);
     return decrypted.toString("utf8").replace(/[^\x20-\x7E]/g, "");
-  } catch {
+  } catch (err) {
+    // Surface Keychain denial to the extraction warnings instead of "no cookies".
+    if (getErrorCode(err) === "keychain_access_denied") throw err;
     return null;
   }
 }
@@ -279,6 +384,7 @@ export function extractFromChromium(
     return { cookies: [], source: null, warnings };
   }
 
+  let undecryptable = 0;
   try {
     const cookies = withTempCopy(dbPath, (tempDb) => {
       const where = buildDomainWhereClause(domain, "host_key");
@@ -292,7 +398,10 @@ export function extractFromChromium(
         if (parts.length < 9) continue;
         const [name, rawValue, encHex, host, cookiePath, secure, httpOnly, sameSite, expiresUtc] = parts;
         const value = decodeChromiumCookieValue(rawValue, encHex, opts);
-        if (!value) continue;
+        if (!value) {
+          if (encHex) undecryptable++;
+          continue;
+        }
 
         results.push({
           name,
@@ -302,10 +411,7 @@ export function extractFromChromium(
           secure: secure === "1",
           httpOnly: httpOnly === "1",
           sameSite: sameSite === "0" ? "None" : sameSite === "1" ? "Lax" : "Strict",
-          // Chrome stores expiry as microseconds since 1601-01-01
-          expires: expiresUtc === "0" ? -1 : Math.floor(
-            (Number(expiresUtc) - 11644473600000000) / 1000000
-          ),
+          expires: chromeTimestampToEpochSeconds(expiresUtc),
         });
       }
       return results;
@@ -318,6 +424,11 @@ export function extractFromChromium(
         : opts?.profile
           ? `${sourceLabel} profile "${opts.profile}"`
           : `${sourceLabel} default profile`;
+    if (undecryptable > 0) {
+      warnings.push(platform() === "win32"
+        ? `${undecryptable} ${sourceLabel} cookie(s) are DPAPI-encrypted, which is not supported on Windows; use Firefox or /v1/auth/login`
+        : `${undecryptable} ${sourceLabel} cookie(s) could not be decrypted${platform() === "linux" ? " (is secret-tool installed and the keyring unlocked?)" : ""}`);
+    }
     if (cookies.length === 0) {
       warnings.push(`No cookies for ${domain} found in ${source}`);
     }
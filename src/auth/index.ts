@@ -1,12 +1,16 @@
 import * as kuri from "../kuri/client.js";
-import { storeCredential, getCredential, deleteCredential } from "../vault/index.js";
+import { storeCredential, getCredential, deleteCredential, listCredentialAccounts } from "../vault/index.js";
 import { nanoid } from "nanoid";
 import { isDomainMatch, getRegistrableDomain } from "../domain.js";
-import { log } from "../logger.js";
+import { log, logError } from "../logger.js";
 import path from "node:path";
 import os from "node:os";
 import fs from "node:fs";
 import { getDefaultLoginConfig } from "../runtime/supervisor.js";
+import { resolvePath } from "../transform/index.js";
+import type { AuthRefreshConfig } from "../types/index.js";
+import { extractStorageAuthTokens } from "../capture/index.js";
+import { decodeBasicAuth, getHeaderValue, storageBearerHeader } from "../reverse-engineer/auth-detect.js";
 
 const LOGIN_TIMEOUT_MS = 300_000;
 const POLL_INTERVAL_MS = 2_000;
@@ -206,9 +210,16 @@ export async function interactiveLogin(
       secure: c.secure, httpOnly: c.httpOnly, sameSite: c.sameSite, expires: c.expires,
     }));
 
+    // Token-in-storage SPAs authenticate with a header, not the cookies alone.
+    const storageTokens = await extractStorageAuthTokens(tabId);
+    const headers = storageBearerHeader(storageTokens);
+
     const vaultKey = `auth:${getRegistrableDomain(targetDomain)}`;
-    await storeCredential(vaultKey, JSON.stringify({ cookies: storableCookies }));
-    log("auth", `stored ${storableCookies.length} cookies under ${vaultKey}`);
+    await storeCredential(vaultKey, JSON.stringify({
+      cookies: storableCookies,
+      ...(Object.keys(headers).length > 0 ? { headers } : {}),
+    }), { source_url: url });
+    log("auth", `stored ${storableCookies.length} cookies${storageTokens.length > 0 ? ` + ${storageTokens[0].storage}Storage token "${storageTokens[0].key}"` : ""} under ${vaultKey}`);
 
     // Also save as Kuri auth profile so browse commands (go/snap/click) have auth
     try {
@@ -286,27 +297,166 @@ function filterExpired(cookies: AuthCookie[]): AuthCookie[] {
   });
 }
 
+function cookieKey(cookie: AuthCookie): string {
+  return `${cookie.name}\u0000${cookie.domain}\u0000${cookie.path ?? "/"}`;
+}
+
+/**
+ * Fold a fresh capture into a stored session bundle instead of replacing it.
+ * Cookies (by name+domain+path) and header values from the capture win;
+ * headers and cookies it did not see are kept unless expired, as are any
+ * other keys already in the bundle (header_aliases, source_meta, hand-added
+ * fields). Device identifiers in `context` merge per header name. The analog
+ * of mergeEndpoints for credentials.
+ *
+ * A Basic Authorization header is also stored decoded as `basic_auth`
+ * ({ username, password }, vault-only); replay re-encodes it, so editing the
+ * pair is enough to swap the credentials. `signing_secret` (the HMAC key for
+ * endpoints with request_signing) is never seen in traffic, so a capture
+ * keeps the stored one unless a new one is passed.
+ */
+export function mergeAuthBundle(
+  existing: Record<string, unknown> | null | undefined,
+  fresh: {
+    cookies: AuthCookie[];
+    headers?: Record<string, string>;
+    header_aliases?: Record<string, unknown>;
+    context?: { device_identifiers?: Record<string, string> };
+    signing_secret?: string;
+  },
+): Record<string, unknown> {
+  if (!existing) {
+    const basic = decodeBasicAuth(getHeaderValue(fresh.headers, "authorization"));
+    return { ...fresh, ...(basic ? { basic_auth: basic } : {}) };
+  }
+  const cookies = new Map<string, AuthCookie>();
+  for (const cookie of filterExpired(Array.isArray(existing.cookies) ? existing.cookies as AuthCookie[] : [])) {
+    cookies.set(cookieKey(cookie), cookie);
+  }
+  for (const cookie of fresh.cookies) cookies.set(cookieKey(cookie), cookie);
+
+  const freshHeaderNames = new Set(Object.keys(fresh.headers ?? {}).map((k) => k.toLowerCase()));
+  const existingHeaders = (existing.headers && typeof existing.headers === "object" ? existing.headers : {}) as Record<string, string>;
+  const headers = {
+    ...Object.fromEntries(Object.entries(existingHeaders).filter(([k]) => !freshHeaderNames.has(k.toLowerCase()))),
+    ...(fresh.headers ?? {}),
+  };
+  const aliases = {
+    ...((existing.header_aliases && typeof existing.header_aliases === "object" ? existing.header_aliases : {}) as Record<string, unknown>),
+    ...(fresh.header_aliases ?? {}),
+  };
+
+  const existingContext = (existing.context && typeof existing.context === "object" ? existing.context : {}) as Record<string, unknown>;
+  const deviceIds = {
+    ...((existingContext.device_identifiers && typeof existingContext.device_identifiers === "object" ? existingContext.device_identifiers : {}) as Record<string, string>),
+    ...(fresh.context?.device_identifiers ?? {}),
+  };
+
+  // A fresh Basic header replaces the structured credentials; otherwise keep
+  // the user's (possibly edited) basic_auth.
+  const freshBasic = decodeBasicAuth(getHeaderValue(fresh.headers, "authorization"));
+  return {
+    ...existing,
+    cookies: [...cookies.values()],
+    headers: Object.keys(headers).length > 0 ? headers : undefined,
+    ...(Object.keys(aliases).length > 0 ? { header_aliases: aliases } : {}),
+    ...(Object.keys(deviceIds).length > 0 ? { context: { ...existingContext, device_identifiers: deviceIds } } : {}),
+    ...(freshBasic ? { basic_auth: freshBasic } : {}),
+    ...(fresh.signing_secret ? { signing_secret: fresh.signing_secret } : {}),
+  };
+}
+
 /**
  * Retrieve stored auth cookies for a domain from the vault.
  * Filters out expired cookies automatically.
  */
 export async function getStoredAuth(
-  domain: string
+  domain: string,
+  opts: CredentialLookupOptions = {},
 ): Promise<AuthCookie[] | null> {
-  const bundle = await getStoredAuthBundle(domain);
+  const bundle = await getStoredAuthBundle(domain, opts);
   return bundle?.cookies?.length ? bundle.cookies : null;
 }
 
+export interface CredentialLookupOptions {
+  /** Only the exact host (and its www. twin) — no registrable-domain or sibling-subdomain fallback */
+  strict?: boolean;
+}
+
+/**
+ * Candidate vault accounts for a domain, most specific first: the exact host,
+ * its www. twin, then (unless strict) each parent host up to the registrable
+ * domain — so credentials saved for example.com are found when capturing
+ * app.example.com — and finally any of `storedAccounts` whose host shares
+ * the registrable domain, so a login on login.example.com serves
+ * app.example.com too. Shared hosts (github.io, herokuapp.com, ...) are
+ * public suffixes, so one tenant never picks up another's credentials.
+ */
+export function findDomainCredentialKeys(
+  domain: string,
+  keyFor: (host: string) => string,
+  opts: CredentialLookupOptions = {},
+  storedAccounts: string[] = [],
+): string[] {
+  const host = domain.toLowerCase().replace(/\.$/, "");
+  const bare = host.replace(/^www\./, "");
+  const keys = [keyFor(host), keyFor(bare === host ? `www.${host}` : bare)];
+  if (!opts.strict) {
+    const regDomain = getRegistrableDomain(bare);
+    const labels = bare.split(".");
+    for (let i = 1; i < labels.length; i++) {
+      const parent = labels.slice(i).join(".");
+      if (parent.length < regDomain.length) break;
+      keys.push(keyFor(parent));
+    }
+    const siblings = storedAccounts
+      .map((account) => ({ account, host: accountHost(account, keyFor) }))
+      .filter(({ host: h }) => h && getRegistrableDomain(h) === regDomain)
+      .map(({ account }) => account)
+      .sort();
+    keys.push(...siblings);
+  }
+  return [...new Set(keys)];
+}
+
+/** Host a vault account was stored under, when it has keyFor's shape. */
+function accountHost(account: string, keyFor: (host: string) => string): string | null {
+  const [prefix, suffix = ""] = keyFor("\u0000").split("\u0000");
+  if (account.length <= prefix.length + suffix.length || !account.startsWith(prefix) || !account.endsWith(suffix)) return null;
+  const host = account.slice(prefix.length, account.length - suffix.length).toLowerCase();
+  return /^[a-z0-9.-]+$/.test(host) ? host : null;
+}
+
+/** Stored account names for the sibling-subdomain pass; none when strict or unlistable. */
+async function siblingCandidates(opts: CredentialLookupOptions): Promise<string[]> {
+  if (opts.strict) return [];
+  try { return await listCredentialAccounts(); } catch { return []; }
+}
+
+/**
+ * Captured session bundle (`{host}-session`: cookies + auth headers) for a
+ * domain, using the same fallback order as findDomainCredentialKeys.
+ */
+export async function getSessionCredential(
+  domain: string,
+  opts: CredentialLookupOptions = {},
+): Promise<{ key: string; value: string } | null> {
+  for (const key of findDomainCredentialKeys(domain, (host) => `${host}-session`, opts, await siblingCandidates(opts))) {
+    const value = await getCredential(key);
+    if (value) return { key, value };
+  }
+  return null;
+}
+
 /**
  * Retrieve the stored auth bundle for a domain from the vault.
  * Preserves headers/source metadata while filtering expired cookies.
  */
 export async function getStoredAuthBundle(
-  domain: string
+  domain: string,
+  opts: CredentialLookupOptions = {},
 ): Promise<StoredAuthBundle | null> {
-  const regDomain = getRegistrableDomain(domain);
-  const keysToTry = [`auth:${regDomain}`];
-  if (domain !== regDomain) keysToTry.push(`auth:${domain}`);
+  const keysToTry = findDomainCredentialKeys(domain, (host) => `auth:${host}`, opts, await siblingCandidates(opts));
 
   for (const key of keysToTry) {
     const stored = await getCredential(key);
@@ -357,7 +507,7 @@ export async function getAuthCookies(
       return getStoredAuth(domain);
     }
   } catch (err) {
-    log("auth", `browser auto-extract failed for ${domain}: ${err instanceof Error ? err.message : err}`);
+    logError("auth", `browser auto-extract failed for ${domain}: ${err instanceof Error ? err.message : err}`);
   }
 
   return null;
@@ -376,7 +526,74 @@ export async function refreshAuthFromBrowser(domain: string): Promise<boolean> {
       return true;
     }
   } catch (err) {
-    log("auth", `browser refresh failed for ${domain}: ${err instanceof Error ? err.message : err}`);
+    logError("auth", `browser refresh failed for ${domain}: ${err instanceof Error ? err.message : err}`);
   }
   return false;
 }
+
+function substituteRefreshToken(value: unknown, refreshToken: string): unknown {
+  if (typeof value === "string") return value.replace(/\{\{\s*refresh_token\s*\}\}/g, refreshToken);
+  if (Array.isArray(value)) return value.map((v) => substituteRefreshToken(v, refreshToken));
+  if (value && typeof value === "object") {
+    return Object.fromEntries(Object.entries(value as Record<string, unknown>).map(([k, v]) => [k, substituteRefreshToken(v, refreshToken)]));
+  }
+  return value;
+}
+
+/**
+ * Mint a fresh access token via a skill's AuthRefreshConfig after a 401/403.
+ * The new token replaces the auth header in the stored session bundle at
+ * `authRef` (cookies are kept); a rotated refresh token is written back to
+ * the vault. Returns true if a new token was stored.
+ */
+export async function refreshAuthToken(config: AuthRefreshConfig, authRef: string): Promise<boolean> {
+  const refreshToken = await getCredential(config.refresh_token_ref);
+  if (!refreshToken) {
+    log("auth", `token refresh skipped: no refresh token at ${config.refresh_token_ref}`);
+    return false;
+  }
+  try {
+    const method = config.method ?? "POST";
+    const body = config.body ? substituteRefreshToken(config.body, refreshToken) as Record<string, unknown> : undefined;
+    const form = config.body_format === "form";
+    const res = await fetch(config.url, {
+      method,
+      headers: {
+        accept: "application/json",
+        ...(body && method !== "GET" ? { "content-type": form ? "application/x-www-form-urlencoded" : "application/json" } : {}),
+      },
+      body: body && method !== "GET"
+        ? (form ? new URLSearchParams(Object.entries(body).map(([k, v]) => [k, String(v)])).toString() : JSON.stringify(body))
+        : undefined,
+      signal: AbortSignal.timeout(15_000),
+    });
+    if (!res.ok) {
+      logError("auth", `token refresh failed: HTTP ${res.status} from ${config.url}`);
+      return false;
+    }
+    const data = await res.json() as unknown;
+    const token = resolvePath(data, config.token_path)[0];
+    if (typeof token !== "string" || !token) {
+      log("auth", `token refresh response has no string at ${config.token_path}`);
+      return false;
+    }
+
+    let bundle: { cookies?: unknown[]; headers?: Record<string, string>; header_aliases?: unknown } = {};
+    try { bundle = JSON.parse((await getCredential(authRef)) ?? "{}"); } catch { /* replace malformed bundle */ }
+    const header = (config.header ?? "authorization").toLowerCase();
+    const scheme = config.scheme ?? "Bearer";
+    const headers = Object.fromEntries(Object.entries(bundle.headers ?? {}).filter(([k]) => k.toLowerCase() !== header));
+    headers[header] = scheme ? `${scheme} ${token}` : token;
+    await storeCredential(authRef, JSON.stringify({ ...bundle, cookies: bundle.cookies ?? [], headers }));
+
+    if (config.refresh_token_path) {
+      const rotated = resolvePath(data, config.refresh_token_path)[0];
+      if (typeof rotated === "string" && rotated) await storeCredential(config.refresh_token_ref, rotated);
+    }
+    log("auth", `access token refreshed into ${authRef}`);
+    return true;
+  } catch (err) {
+    logError("auth", `token refresh failed: ${err instanceof Error ? err.message : err}`);
+    return false;
+  }
+}
@@ -0,0 +1,67 @@
+/**
+ * macOS Keychain prompts: denial and timeout handling.
+ *
+ * Reading a Keychain item from a fresh process can pop an "allow access"
+ * dialog. Clicking Deny, or leaving the prompt unanswered, used to surface as
+ * a generic failure (or a hang). Both now raise `keychain_access_denied` with
+ * a message telling the user what to do; a missing item is not an error.
+ */
+
+import { execFileSync } from "node:child_process";
+import { unbrowseError, type UnbrowseError } from "../errors.js";
+
+const DEFAULT_KEYCHAIN_TIMEOUT_MS = 30_000;
+
+/** `security` exits 44 (errSecItemNotFound) when the item does not exist. */
+const NOT_FOUND_EXIT = 44;
+/** errSecUserCanceled (-128), errSecInteractionNotAllowed (-25308 → 36), errSecAuthFailed (-25293 → 51) */
+const DENIED_EXITS = new Set([128, 36, 51]);
+const DENIED_TEXT = /user canceled|user interaction is not allowed|authorization was denied|passphrase you entered is not correct|not allowed to access/i;
+const NOT_FOUND_TEXT = /could not be found in the keychain/i;
+
+/** Prompt timeout; UNBROWSE_KEYCHAIN_TIMEOUT_MS overrides the 30s default. */
+export function getKeychainTimeoutMs(): number {
+  const configured = Number(process.env.UNBROWSE_KEYCHAIN_TIMEOUT_MS);
+  return Number.isFinite(configured) && configured > 0 ? configured : DEFAULT_KEYCHAIN_TIMEOUT_MS;
+}
+
+export function keychainDeniedError(service: string, reason: "denied" | "timeout", detail?: string): UnbrowseError {
+  const what = reason === "timeout"
+    ? `Keychain prompt for "${service}" was not answered within ${Math.round(getKeychainTimeoutMs() / 1000)}s`
+    : `Keychain access to "${service}" was denied`;
+  return unbrowseError(
+    "keychain_access_denied",
+    `${what}. Re-run and click "Always Allow" when macOS asks, or grant access in Keychain Access.app ` +
+      `(set UNBROWSE_KEYCHAIN_TIMEOUT_MS to wait longer).${detail ? ` (${detail})` : ""}`,
+    { service, reason },
+  );
+}
+
+/** Whether a keychain client error (keytar or `security`) means the user said no. */
+export function isKeychainDenial(error: unknown): boolean {
+  const message = error instanceof Error ? error.message : String(error);
+  return DENIED_TEXT.test(message);
+}
+
+/**
+ * `security find-generic-password -w` with a timeout. Returns null when the
+ * item does not exist; throws keychain_access_denied on Deny or timeout.
+ */
+export function readKeychainPassword(service: string, account?: string): string | null {
+  try {
+    return execFileSync(
+      "security",
+      ["find-generic-password", "-s", service, ...(account ? ["-a", account] : []), "-w"],
+      { encoding: "utf8", stdio: ["pipe", "pipe", "pipe"], timeout: getKeychainTimeoutMs(), killSignal: "SIGKILL" },
+    ).trim() || null;
+  } catch (error) {
+    const err = error as { status?: number | null; signal?: string | null; code?: string; stderr?: string | Buffer };
+    const stderr = String(err.stderr ?? "").trim();
+    if (err.code === "ETIMEDOUT" || (err.signal && err.status == null)) throw keychainDeniedError(service, "timeout");
+    if (err.status === NOT_FOUND_EXIT || NOT_FOUND_TEXT.test(stderr)) return null;
+    if ((err.status != null && DENIED_EXITS.has(err.status)) || DENIED_TEXT.test(stderr)) {
+      throw keychainDeniedError(service, "denied", stderr || undefined);
+    }
+    throw error;
+  }
+}
@@ -0,0 +1,50 @@
+/**
+ * Login form discovery from an accessibility snapshot.
+ *
+ * Filling every field name that might be a username (`email`, `user`,
+ * `login`, ...) over-fills forms and looks like a bot. Instead, pick the
+ * concrete elements from the page: the password box, the identifier box
+ * that precedes it, and the button that submits them.
+ */
+
+import * as kuri from "../kuri/client.js";
+import type { SnapshotElement } from "../kuri/client.js";
+
+export interface LoginFormFields {
+  username?: SnapshotElement;
+  password: SnapshotElement;
+  submit?: SnapshotElement;
+}
+
+const INPUT_ROLES = new Set(["textbox", "searchbox", "combobox"]);
+const BUTTON_ROLES = new Set(["button", "link"]);
+const PASSWORD_NAME = /pass(word|code|wort)?|mot de passe|contraseña|\bpin\b/i;
+const USERNAME_NAME = /e-?mail|user(name)?|login|sign.?in id|phone|mobile|account|identifier|handle/i;
+const SEARCH_NAME = /search|find|query/i;
+// "Sign in with Google" / "Continue via SSO" start a federated login, not this form's submit.
+const SUBMIT_NAME = /^(?!.*\b(with|via|using)\b)(?:(log ?in|sign ?in|continue|next|submit|enter)\b|.*\b(log ?in|sign ?in)$)/i;
+
+/**
+ * Username / password / submit elements of the login form in a snapshot
+ * (text or parsed), or null when no password field is present. The
+ * username is the identifier-looking input before the password, else the
+ * nearest input before it; the submit is the first login-ish button after it.
+ */
+export function detectLoginFields(snapshot: string | SnapshotElement[]): LoginFormFields | null {
+  const elements = typeof snapshot === "string" ? kuri.parseSnapshotElements(snapshot) : snapshot;
+  const passwordIndex = elements.findIndex((el) => INPUT_ROLES.has(el.role) && PASSWORD_NAME.test(el.name));
+  if (passwordIndex === -1) return null;
+
+  const before = elements.slice(0, passwordIndex).filter((el) => INPUT_ROLES.has(el.role) && !SEARCH_NAME.test(el.name));
+  const username = [...before].reverse().find((el) => USERNAME_NAME.test(el.name)) ?? before[before.length - 1];
+  const buttons = elements.filter((el) => BUTTON_ROLES.has(el.role) && SUBMIT_NAME.test(el.name.trim()));
+  const submit = buttons.find((el) => elements.indexOf(el) > passwordIndex && el.role === "button")
+    ?? buttons.find((el) => elements.indexOf(el) > passwordIndex)
+    ?? buttons[0];
+
+  return {
+    password: elements[passwordIndex],
+    ...(username ? { username } : {}),
+    ...(submit ? { submit } : {}),
+  };
+}
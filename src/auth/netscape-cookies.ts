@@ -0,0 +1,77 @@
+/**
+ * Netscape `cookies.txt` — the cookie jar format curl (`-b`/`-c`), wget and
+ * most "export cookies" browser extensions read and write.
+ *
+ * One cookie per line, seven tab-separated fields:
+ *
+ *   domain  include_subdomains  path  secure  expires  name  value
+ *
+ * `#` lines are comments, except the `#HttpOnly_` prefix curl puts in front
+ * of the domain of HttpOnly cookies. `expires` is a Unix epoch; 0 marks a
+ * session cookie, matching the `expires <= 0` convention of stored bundles.
+ */
+
+import type { BrowserCookie } from "./browser-cookies.js";
+
+const HTTP_ONLY_PREFIX = "#HttpOnly_";
+const HEADER = "# Netscape HTTP Cookie File";
+
+type ExportableCookie = Pick<BrowserCookie, "name" | "value" | "domain"> & Partial<Omit<BrowserCookie, "name" | "value" | "domain">>;
+
+/**
+ * Parse a cookies.txt jar. Malformed lines are skipped rather than failing
+ * the whole file — exports from extensions are often slightly off. A
+ * domain with include_subdomains TRUE keeps (or gains) its leading dot.
+ */
+export function parseNetscapeCookies(contents: string): BrowserCookie[] {
+  const cookies: BrowserCookie[] = [];
+  for (const rawLine of contents.split(/\r?\n/)) {
+    // Only leading space is trimmed: a trailing tab is an empty value, not padding.
+    let line = rawLine.trimStart();
+    let httpOnly = false;
+    if (line.startsWith(HTTP_ONLY_PREFIX)) {
+      httpOnly = true;
+      line = line.slice(HTTP_ONLY_PREFIX.length);
+    } else if (!line.trim() || line.startsWith("#")) {
+      continue;
+    }
+    // The value may itself contain tabs; everything after the sixth tab is value.
+    const fields = line.split("\t");
+    if (fields.length < 7) continue;
+    const [domainField, includeSubdomains, path, secure, expires, name] = fields;
+    const value = fields.slice(6).join("\t");
+    if (!domainField || !name) continue;
+    const domain = includeSubdomains.toUpperCase() === "TRUE" && !domainField.startsWith(".") ? `.${domainField}` : domainField;
+    const expiresAt = Number(expires);
+    cookies.push({
+      name,
+      value,
+      domain,
+      path: path || "/",
+      secure: secure.toUpperCase() === "TRUE",
+      httpOnly,
+      sameSite: "Lax",
+      expires: Number.isFinite(expiresAt) && expiresAt > 0 ? Math.floor(expiresAt) : -1,
+    });
+  }
+  return cookies;
+}
+
+/** Serialize cookies as a cookies.txt jar curl and wget can load. */
+export function exportNetscapeCookies(cookies: ExportableCookie[]): string {
+  const lines = [HEADER, "# Exported by unbrowse; contains session secrets.", ""];
+  for (const cookie of cookies) {
+    const domain = cookie.domain || "";
+    const fields = [
+      `${cookie.httpOnly ? HTTP_ONLY_PREFIX : ""}${domain}`,
+      domain.startsWith(".") ? "TRUE" : "FALSE",
+      cookie.path || "/",
+      cookie.secure ? "TRUE" : "FALSE",
+      String(cookie.expires && cookie.expires > 0 ? Math.floor(cookie.expires) : 0),
+      cookie.name,
+      cookie.value.replace(/[\r\n]/g, ""),
+    ];
+    lines.push(fields.join("\t"));
+  }
+  return lines.join("\n") + "\n";
+}
@@ -91,9 +91,11 @@ export async function resolveAuthPrerequisites(
  * Derive auth dependencies from a skill manifest endpoints.
  * Inspects semantic.auth_required and auth_profile_ref to surface
  * which domains need authentication and what strategy to use.
+ * A public skill (auth_scheme "none") keeps a bundle only for device ids,
+ * so that alone doesn't ask for a login.
  */
 export function deriveAuthDependencies(
-  skill: { domain: string; auth_profile_ref?: string; endpoints: Array<{ endpoint_id: string; semantic?: { auth_required?: boolean } }> },
+  skill: { domain: string; auth_profile_ref?: string; auth_scheme?: string; endpoints: Array<{ endpoint_id: string; semantic?: { auth_required?: boolean } }> },
   targetEndpointId?: string,
 ): AuthDependency[] {
   const endpoints = targetEndpointId
@@ -104,7 +106,7 @@ export function deriveAuthDependencies(
     (ep) => ep.semantic?.auth_required === true,
   );
 
-  if (!needsAuth && !skill.auth_profile_ref) return [];
+  if (!needsAuth && (!skill.auth_profile_ref || skill.auth_scheme === "none")) return [];
 
   return [
     {
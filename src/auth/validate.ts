@@ -0,0 +1,124 @@
+/**
+ * End-to-end check of a skill's stored auth bundle — "is this skill still
+ * usable?".
+ *
+ * The bundle at `auth_profile_ref` is turned back into a request exactly as
+ * replay would send it (headers, aliases, `context` device identifiers,
+ * Basic credentials, cookies), a safe endpoint is probed, and the outcome
+ * comes back with a structured reason. When the skill has `auth_refresh`
+ * and the probe is rejected, the token is refreshed and the probe repeated.
+ */
+
+import { log } from "../logger.js";
+import { getCredential } from "../vault/index.js";
+import { authBundleHeaders, loginRedirectTarget, type AuthBundleHeaderFields } from "../reverse-engineer/auth-detect.js";
+import { endpointToCurlRequest } from "../export/curl.js";
+import type { EndpointDescriptor, SkillManifest } from "../types/index.js";
+import { refreshAuthToken } from "./index.js";
+
+export type AuthValidationReason =
+  | "ok"
+  | "unauthorized"
+  | "forbidden"
+  | "login_redirect"
+  | "no_credentials"
+  | "no_probe_endpoint"
+  | "network_error"
+  | "unexpected_status";
+
+export interface AuthValidation {
+  valid: boolean;
+  reason: AuthValidationReason;
+  /** Status of the last probe */
+  status?: number;
+  probe_url?: string;
+  /** Login page a 3xx bounced the probe to */
+  login_url?: string;
+  /** Set when the probe was rejected and a token refresh was attempted; `succeeded` is the refresh itself */
+  refresh?: { attempted: true; succeeded: boolean };
+  detail?: string;
+}
+
+export interface ValidateAuthOptions {
+  /** Path (or absolute URL) to probe instead of picking a GET endpoint */
+  probe_path?: string;
+  endpoint_id?: string;
+  /** Refresh and re-probe on 401/403 when the skill has auth_refresh (default true) */
+  refresh?: boolean;
+}
+
+const PROBE_TIMEOUT_MS = 15_000;
+
+/** GET endpoint to probe: the requested one, else a verified one, else any without unfilled path slots. */
+function pickProbeEndpoint(skill: SkillManifest, endpointId?: string): EndpointDescriptor | undefined {
+  if (endpointId) return skill.endpoints.find((ep) => ep.endpoint_id === endpointId);
+  const gets = skill.endpoints.filter((ep) => ep.method === "GET" && ep.verification_status !== "disabled");
+  const concrete = (ep: EndpointDescriptor) => !/\{[^}]+\}/.test(endpointToCurlRequest(ep, skill).url);
+  return gets.find((ep) => ep.verification_status === "verified" && concrete(ep)) ?? gets.find(concrete);
+}
+
+async function loadBundle(ref: string): Promise<(AuthBundleHeaderFields & { cookies?: Array<{ name: string; value: string }> }) | null> {
+  try {
+    return JSON.parse((await getCredential(ref)) ?? "null");
+  } catch {
+    return null;
+  }
+}
+
+function classify(status: number, url: string, location: string | null): Pick<AuthValidation, "valid" | "reason" | "login_url"> {
+  if (status >= 200 && status < 300) return { valid: true, reason: "ok" };
+  if (status === 401) return { valid: false, reason: "unauthorized" };
+  if (status === 403) return { valid: false, reason: "forbidden" };
+  const loginUrl = loginRedirectTarget({ url, response_status: status, response_headers: location ? { location } : {} });
+  if (loginUrl) return { valid: false, reason: "login_redirect", login_url: loginUrl };
+  return { valid: false, reason: "unexpected_status" };
+}
+
+/**
+ * Probe `skill` with its stored credentials. Valid means a 2xx; 401, 403
+ * and a bounce to a login page are reported as such. A rejected probe is
+ * retried once after refreshAuthToken when the skill can refresh.
+ */
+export async function validateSkillAuth(skill: SkillManifest, options: ValidateAuthOptions = {}): Promise<AuthValidation> {
+  if (!skill.auth_profile_ref) return { valid: false, reason: "no_credentials", detail: "skill has no auth_profile_ref" };
+  const authRef = skill.auth_profile_ref;
+  if (!(await loadBundle(authRef))) return { valid: false, reason: "no_credentials", detail: `nothing stored at ${authRef}` };
+
+  let url: string;
+  let baseHeaders: Record<string, string> = {};
+  const endpoint = pickProbeEndpoint(skill, options.endpoint_id);
+  if (options.probe_path) {
+    const origin = (() => { try { return new URL(endpoint?.url_template ?? `https://${skill.domain}`).origin; } catch { return `https://${skill.domain}`; } })();
+    try { url = new URL(options.probe_path, origin).href; } catch { return { valid: false, reason: "no_probe_endpoint", detail: `bad probe_path ${options.probe_path}` }; }
+  } else if (endpoint && endpoint.method === "GET") {
+    const request = endpointToCurlRequest(endpoint, skill);
+    url = request.url;
+    baseHeaders = request.headers;
+  } else {
+    return { valid: false, reason: "no_probe_endpoint", detail: options.endpoint_id ? `${options.endpoint_id} is not a GET endpoint of this skill` : "skill has no GET endpoint to probe" };
+  }
+
+  const probe = async (): Promise<AuthValidation> => {
+    const bundle = (await loadBundle(authRef)) ?? {};
+    const headers: Record<string, string> = { ...baseHeaders, ...authBundleHeaders(bundle) };
+    const cookies = (bundle.cookies ?? []).map((c) => `${c.name}=${c.value}`).join("; ");
+    if (cookies) headers.cookie = cookies;
+    try {
+      const res = await fetch(url, { method: "GET", headers, redirect: "manual", signal: AbortSignal.timeout(PROBE_TIMEOUT_MS) });
+      await res.body?.cancel().catch(() => {});
+      return { ...classify(res.status, url, res.headers.get("location")), status: res.status, probe_url: url };
+    } catch (err) {
+      return { valid: false, reason: "network_error", probe_url: url, detail: err instanceof Error ? err.message : String(err) };
+    }
+  };
+
+  const first = await probe();
+  const rejected = first.reason === "unauthorized" || first.reason === "forbidden" || first.reason === "login_redirect";
+  if (!rejected || !skill.auth_refresh || options.refresh === false) return first;
+
+  const refreshed = await refreshAuthToken(skill.auth_refresh, authRef);
+  if (!refreshed) return { ...first, refresh: { attempted: true, succeeded: false } };
+  const second = await probe();
+  log("auth", `auth for ${skill.skill_id}: ${first.reason} → refreshed → ${second.reason}`);
+  return { ...second, refresh: { attempted: true, succeeded: true } };
+}
@@ -3,7 +3,9 @@ import type { KuriHarEntry } from "../kuri/client.js";
 import { resolveAndExecute } from "../orchestrator/index.js";
 import { generateLocalDescription } from "../orchestrator/index.js";
 import { extractEndpoints, extractAuthHeaders } from "../reverse-engineer/index.js";
-import type { RawRequest } from "../capture/index.js";
+import { dedupeAuthHeaders } from "../reverse-engineer/auth-detect.js";
+import { canonicalHeaders, type RawRequest } from "../capture/index.js";
+import { parseHarDateTime } from "../time.js";
 import { queueBackgroundIndex } from "../indexer/index.js";
 import { mergeEndpoints } from "../marketplace/index.js";
 import { buildSkillOperationGraph } from "../graph/index.js";
@@ -46,16 +48,12 @@ function harEntriesToRawRequests(entries: KuriHarEntry[]): RawRequest[] {
     .map(e => ({
       url: e.request.url,
       method: e.request.method,
-      request_headers: Object.fromEntries(
-        (e.request.headers ?? []).map(h => [h.name.toLowerCase(), h.value])
-      ),
-      request_body: e.request.postData?.text,
+      request_headers: canonicalHeaders(e.request.headers),
+      request_body: kuri.harPostDataText(e.request.postData),
       response_status: e.response.status,
-      response_headers: Object.fromEntries(
-        (e.response.headers ?? []).map(h => [h.name.toLowerCase(), h.value])
-      ),
-      response_body: e.response.content?.text,
-      timestamp: e.startedDateTime ?? new Date().toISOString(),
+      response_headers: canonicalHeaders(e.response.headers),
+      ...kuri.harResponseBody(e.response.content),
+      timestamp: parseHarDateTime(e.startedDateTime),
     }));
 }
 
@@ -78,7 +76,7 @@ function passiveIndexHar(entries: KuriHarEntry[], pageUrl: string): void {
       // Store auth credentials
       const capturedAuthHeaders = extractAuthHeaders(requests);
       if (Object.keys(capturedAuthHeaders).length > 0) {
-        await storeCredential(`${domain}-session`, JSON.stringify({ headers: capturedAuthHeaders }));
+        await storeCredential(`${domain}-session`, JSON.stringify(dedupeAuthHeaders(capturedAuthHeaders)), { source_url: pageUrl });
       }
 
       // Merge with existing skill (never reduce endpoint count)
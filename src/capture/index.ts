@@ -1,9 +1,12 @@
 import * as kuri from "../kuri/client.js";
 import { nanoid } from "nanoid";
-import { getRegistrableDomain } from "../domain.js";
-import { log } from "../logger.js";
+import { getRegistrableDomain, isDomainMatch } from "../domain.js";
+import { epochMsToIso, parseHarDateTime } from "../time.js";
+import { unbrowseError } from "../errors.js";
+import { log, logWarn } from "../logger.js";
 import type { BrowserAccessConfig } from "../runtime/browser-access.js";
 import { DEFAULT_BROWSER_ACCESS } from "../runtime/browser-access.js";
+import { findStorageAuthTokens, type StorageAuthToken } from "../reverse-engineer/auth-detect.js";
 
 /**
  * Check whether the current BrowserAccessConfig allows browser-based capture.
@@ -34,6 +37,35 @@ const interceptorInjectedTabs = new Set<string>();
 const CAPTURE_TIMEOUT_MS = 90_000;
 const CAPTURE_NAV_TIMEOUT_MS = 20_000;
 
+/** Default polls when a capture has seen no API traffic yet (UNBROWSE_CAPTURE_EMPTY_RETRIES). */
+const DEFAULT_EMPTY_RETRIES = 0;
+const EMPTY_RETRY_INTERVAL_MS = 1500;
+
+function envEmptyRetries(): number {
+  const n = Number(process.env.UNBROWSE_CAPTURE_EMPTY_RETRIES);
+  return Number.isInteger(n) && n >= 0 ? n : DEFAULT_EMPTY_RETRIES;
+}
+
+const API_RESPONSE_CONTENT_TYPE = /json|grpc|protobuf|msgpack/i;
+
+function looksLikeApiResponse(req: RawRequest): boolean {
+  if (API_RESPONSE_CONTENT_TYPE.test(req.response_headers["content-type"] ?? "")) return true;
+  const body = req.response_body?.trimStart() ?? "";
+  return body.startsWith("{") || body.startsWith("[");
+}
+
+// Captured bodies beyond this are truncated so pathological multi-MB feeds
+// don't exhaust memory. Set high on purpose: extraction needs the complete
+// response to infer response_schema and samples, so ordinary large JSON must
+// survive. Override via UNBROWSE_MAX_BODY_BYTES.
+export const DEFAULT_MAX_BODY_BYTES = (() => {
+  const raw = Number(process.env.UNBROWSE_MAX_BODY_BYTES);
+  return Number.isFinite(raw) && raw > 0 ? Math.floor(raw) : 8 * 1024 * 1024;
+})();
+
+/** Cap for bodies written to disk (browse journals) — only persisted copies are cut this small. */
+export const PERSISTED_MAX_BODY_BYTES = 64 * 1024;
+
 /**
  * Races `fn()` against an AbortSignal so that each CDP phase exits immediately
  * when the overall capture timeout fires — instead of waiting for kuri's own
@@ -105,6 +137,8 @@ export interface CapturedWsMessage {
   direction: "sent" | "received";
   data: string;
   timestamp: string;
+  /** Sec-WebSocket-Protocol negotiated on the handshake, when known */
+  protocol?: string;
 }
 
 export interface CaptureResult {
@@ -112,6 +146,8 @@ export interface CaptureResult {
   har_lineage_id: string;
   domain: string;
   cookies?: Array<{ name: string; value: string; domain: string; path?: string; httpOnly?: boolean; secure?: boolean }>;
+  /** Auth tokens found in localStorage/sessionStorage, best first */
+  storage_tokens?: StorageAuthToken[];
   final_url: string;
   ws_messages?: CapturedWsMessage[];
   html?: string;
@@ -121,6 +157,17 @@ export interface CaptureResult {
     known_bindings: Record<string, unknown>;
     suggested_next: string[];
   };
+  /** What the page sent, to tell an idle page from API traffic lost to filters */
+  traffic?: CaptureTraffic;
+}
+
+export interface CaptureTraffic {
+  /** Merged requests before the capture filter */
+  total_requests: number;
+  /** Of those, responses that look like API data (JSON, gRPC/protobuf, msgpack) */
+  api_requests: number;
+  /** Empty-capture polls performed before giving up or finding traffic */
+  empty_retries: number;
 }
 
 export interface RawRequest {
@@ -131,7 +178,13 @@ export interface RawRequest {
   response_status: number;
   response_headers: Record<string, string>;
   response_body?: string;
+  /** Set when request_body was cut at max_body_bytes — not a complete payload */
+  request_body_truncated?: boolean;
+  /** Set when response_body was cut at max_body_bytes — do not parse as complete JSON */
+  response_body_truncated?: boolean;
   timestamp: string;
+  /** Total request duration in ms (HAR `time`), when known */
+  duration_ms?: number;
   /** Query hook bridge: which action step triggered this request (#114) */
   triggered_by_step?: number;
   triggered_by_action?: string;
@@ -475,6 +528,25 @@ export async function collectInterceptedRequests(tabId: string): Promise<Array<{
   return [];
 }
 
+/**
+ * Interceptor entries from index `cursor` on, plus the cursor for the next
+ * call — lets a caller stream the buffer without re-reading whole bodies. A
+ * buffer shorter than the cursor belongs to a new page and is read from 0.
+ */
+export async function collectInterceptedRequestsSince(tabId: string, cursor: number): Promise<{
+  next: number;
+  requests: Awaited<ReturnType<typeof collectInterceptedRequests>>;
+}> {
+  try {
+    const result = await kuri.evaluate(tabId, `(function(){var b=window.__unbrowse_intercepted||[],c=${Math.max(0, Math.floor(cursor))};if(b.length<c)c=0;return JSON.stringify({next:b.length,requests:b.slice(c)})})()`);
+    if (typeof result === "string" && result.startsWith("{")) {
+      const parsed = JSON.parse(result) as { next: number; requests: Awaited<ReturnType<typeof collectInterceptedRequests>> };
+      return { next: parsed.next, requests: parsed.requests ?? [] };
+    }
+  } catch { /* non-fatal */ }
+  return { next: cursor, requests: [] };
+}
+
 /**
  * Inject the interceptor script in chunks to work around kuri's ~1KB evaluate limit.
  * Falls back to scriptInject for persistent injection on new navigations.
@@ -494,12 +566,137 @@ export async function injectInterceptor(tabId: string): Promise<void> {
 }
 
 
+export interface CaptureFilter {
+  /** Keep only hosts under this host's registrable domain (public-suffix aware) */
+  same_registrable_domain_as?: string;
+  /** Extra hosts (and their subdomains) kept even when outside the registrable domain */
+  extra_allow?: string[];
+  /** Hosts (and their subdomains) always dropped — wins over everything else */
+  extra_deny?: string[];
+}
+
+/**
+ * Apply a CaptureFilter to merged requests, before endpoint extraction.
+ * With no options set every request is kept. Exported for testability.
+ */
+export function applyCaptureFilter<T extends { url: string }>(requests: T[], filter: CaptureFilter): T[] {
+  const deny = filter.extra_deny ?? [];
+  const allow = filter.extra_allow ?? [];
+  let root: string | undefined;
+  if (filter.same_registrable_domain_as) {
+    let host = filter.same_registrable_domain_as;
+    try { host = new URL(host).hostname; } catch { /* already a hostname */ }
+    root = getRegistrableDomain(host);
+  }
+  if (!root && deny.length === 0 && allow.length === 0) return requests;
+
+  return requests.filter((request) => {
+    let host: string;
+    try { host = new URL(request.url).hostname; } catch { return false; }
+    if (deny.some((d) => isDomainMatch(d, host))) return false;
+    if (allow.some((a) => isDomainMatch(a, host))) return true;
+    if (root) return getRegistrableDomain(host) === root;
+    return allow.length === 0;
+  });
+}
+
+/**
+ * Order requests by start time so two captures of the same flow yield the
+ * same sequence. Stable: entries with equal or unparseable timestamps keep
+ * their relative order.
+ */
+export function sortRequestsByTime<T extends { timestamp: string }>(requests: T[]): T[] {
+  return requests
+    .map((request, index) => ({ request, index, ts: Date.parse(request.timestamp) }))
+    .sort((a, b) => {
+      if (Number.isNaN(a.ts) || Number.isNaN(b.ts) || a.ts === b.ts) return a.index - b.index;
+      return a.ts - b.ts;
+    })
+    .map(({ request }) => request);
+}
+
+function truncateBody(body: string, maxBytes: number): string | null {
+  if (body.length <= maxBytes / 4 || Buffer.byteLength(body, "utf-8") <= maxBytes) return null;
+  // Cutting mid-codepoint leaves a replacement char at the end — drop it.
+  return Buffer.from(body, "utf-8").subarray(0, maxBytes).toString("utf-8").replace(/\uFFFD+$/, "");
+}
+
+/**
+ * Cap stored request/response bodies at `maxBytes`, flagging cut bodies with
+ * `*_body_truncated`. Headers are untouched, so content-type detection still works.
+ * A non-positive `maxBytes` disables truncation.
+ */
+export function truncateRequestBodies<T extends RawRequest>(requests: T[], maxBytes = DEFAULT_MAX_BODY_BYTES): T[] {
+  if (!(maxBytes > 0)) return requests;
+  return requests.map((request) => {
+    const requestBody = request.request_body ? truncateBody(request.request_body, maxBytes) : null;
+    const responseBody = request.response_body ? truncateBody(request.response_body, maxBytes) : null;
+    if (requestBody == null && responseBody == null) return request;
+    return {
+      ...request,
+      ...(requestBody != null ? { request_body: requestBody, request_body_truncated: true } : {}),
+      ...(responseBody != null ? { response_body: responseBody, response_body_truncated: true } : {}),
+    };
+  });
+}
+
+/**
+ * Extract WebSocket frames from HAR entries carrying Chrome's `_webSocketMessages`.
+ * Binary frames (opcode 2) are skipped — only text payloads are useful as samples.
+ */
+export function harWebSocketMessages(harEntries: kuri.KuriHarEntry[]): CapturedWsMessage[] {
+  const messages: CapturedWsMessage[] = [];
+  for (const entry of harEntries) {
+    const frames = entry._webSocketMessages;
+    const url = entry.request?.url;
+    if (!url || !frames || frames.length === 0) continue;
+    const protocol = (entry.response?.headers ?? [])
+      .find((h) => h.name.toLowerCase() === "sec-websocket-protocol")?.value;
+    for (const frame of frames) {
+      if (frame.opcode === 2 || typeof frame.data !== "string") continue;
+      messages.push({
+        url,
+        direction: frame.type === "send" ? "sent" : "received",
+        data: frame.data,
+        timestamp: new Date(frame.time * 1000).toISOString(),
+        ...(protocol ? { protocol } : {}),
+      });
+    }
+  }
+  return messages;
+}
+
+/**
+ * Lowercase header names and merge case-insensitive duplicates. Repeats are
+ * joined with ", " (RFC 7230 §3.2.2) — except set-cookie, whose values contain
+ * commas and are kept one per line, and cookie, which joins with "; ".
+ * Without this `Authorization` and `authorization` from different capture
+ * sources were counted as two auth headers.
+ */
+export function canonicalHeaders(
+  headers: Array<{ name: string; value: string }> | Record<string, string> | undefined,
+): Record<string, string> {
+  const pairs = Array.isArray(headers)
+    ? headers.map((h) => [h.name, h.value] as const)
+    : Object.entries(headers ?? {});
+  const out: Record<string, string> = {};
+  for (const [rawName, value] of pairs) {
+    const name = rawName.toLowerCase();
+    if (value === undefined || value === null) continue;
+    if (!(name in out)) { out[name] = value; continue; }
+    if (out[name] === value) continue;
+    const sep = name === "set-cookie" ? "\n" : name === "cookie" ? "; " : ", ";
+    out[name] = `${out[name]}${sep}${value}`;
+  }
+  return out;
+}
+
 /**
  * Merge three passive capture data sources into a unified RawRequest list.
  * Priority: JS interceptor (has bodies) > HAR entries > extension observer > responseBodies-only.
  * Deduplicates by URL, keeps the highest-priority version.
  */
-function mergePassiveCaptureData(
+export function mergePassiveCaptureData(
   intercepted: Array<{ url: string; method: string; response_body?: string; response_status: number; request_headers: Record<string, string>; response_headers: Record<string, string>; request_body?: string; content_type?: string; is_js?: boolean; timestamp: string }>,
   harEntries: kuri.KuriHarEntry[],
   extensionEntries: ExtensionEntry[],
@@ -513,44 +710,57 @@ function mergePassiveCaptureData(
     seen.set(entry.url, {
       url: entry.url,
       method: entry.method,
-      request_headers: entry.request_headers,
+      request_headers: canonicalHeaders(entry.request_headers),
       request_body: entry.request_body,
       response_status: entry.response_status,
-      response_headers: entry.response_headers,
+      response_headers: canonicalHeaders(entry.response_headers),
       response_body: entry.response_body,
       timestamp: entry.timestamp,
     });
   }
 
   // Priority 2: HAR entries (supplement with responseBodies map)
-  // Skip OPTIONS (CORS preflight) — they pollute method attribution
+  // OPTIONS (CORS preflight) would pollute method attribution, so they are
+  // held aside and only kept when their real request never showed up. They
+  // are collected before the URL dedupe: a URL that answered a GET can still
+  // have had its POST blocked.
+  const preflights: RawRequest[] = [];
   for (const entry of harEntries) {
     const url = entry.request?.url;
-    if (!url || seen.has(url)) continue;
-    if (entry.request.method === "OPTIONS") continue;
-    const reqHeaders: Record<string, string> = {};
-    for (const h of entry.request.headers ?? []) reqHeaders[h.name] = h.value;
-    const respHeaders: Record<string, string> = {};
-    for (const h of entry.response.headers ?? []) respHeaders[h.name] = h.value;
+    if (!url) continue;
+    if (entry.request.method === "OPTIONS") {
+      preflights.push({
+        url,
+        method: "OPTIONS",
+        request_headers: canonicalHeaders(entry.request.headers),
+        response_status: entry.response?.status ?? 0,
+        response_headers: canonicalHeaders(entry.response?.headers),
+        timestamp: parseHarDateTime(entry.startedDateTime),
+      });
+      continue;
+    }
+    if (seen.has(url)) continue;
+    if (entry._webSocketMessages || entry.response?.status === 101) continue; // WS handshake — handled by harWebSocketMessages
+    const reqHeaders = canonicalHeaders(entry.request.headers);
+    const respHeaders = canonicalHeaders(entry.response.headers);
     seen.set(url, {
       url,
       method: entry.request.method,
       request_headers: reqHeaders,
-      request_body: entry.request.postData?.text,
+      request_body: kuri.harPostDataText(entry.request.postData),
       response_status: entry.response.status,
       response_headers: respHeaders,
-      response_body: responseBodies.get(url) ?? entry.response.content?.text,
-      timestamp: entry.startedDateTime,
+      ...(responseBodies.has(url) ? { response_body: responseBodies.get(url) } : kuri.harResponseBody(entry.response.content)),
+      timestamp: parseHarDateTime(entry.startedDateTime),
+      ...(typeof entry.time === "number" && entry.time >= 0 ? { duration_ms: entry.time } : {}),
     });
   }
 
   // Priority 3: Extension entries (URL+headers supplement, no bodies)
   for (const entry of extensionEntries) {
     if (seen.has(entry.url)) continue;
-    const reqHeaders: Record<string, string> = {};
-    for (const h of entry.requestHeaders ?? []) reqHeaders[h.name] = h.value;
-    const respHeaders: Record<string, string> = {};
-    for (const h of entry.responseHeaders ?? []) respHeaders[h.name] = h.value;
+    const reqHeaders = canonicalHeaders(entry.requestHeaders);
+    const respHeaders = canonicalHeaders(entry.responseHeaders);
     seen.set(entry.url, {
       url: entry.url,
       method: entry.method,
@@ -558,7 +768,7 @@ function mergePassiveCaptureData(
       response_status: entry.statusCode ?? 0,
       response_headers: respHeaders,
       response_body: responseBodies.get(entry.url),
-      timestamp: new Date(entry.timestamp).toISOString(),
+      timestamp: epochMsToIso(entry.timestamp),
     });
   }
 
@@ -577,7 +787,17 @@ function mergePassiveCaptureData(
     });
   }
 
-  return Array.from(seen.values());
+  // Keyed by the method the preflight asked for, so findCorsBlockedRequests
+  // can pair each one with (the absence of) its real request.
+  const orphanedPreflights = new Map<string, RawRequest>();
+  for (const preflight of preflights) {
+    const method = Object.entries(preflight.request_headers)
+      .find(([k]) => k.toLowerCase() === "access-control-request-method")?.[1]?.trim().toUpperCase() ?? "";
+    if (seen.get(preflight.url)?.method.toUpperCase() === method) continue;
+    const key = `${method}:${preflight.url}`;
+    if (!orphanedPreflights.has(key)) orphanedPreflights.set(key, preflight);
+  }
+  return sortRequestsByTime([...seen.values(), ...orphanedPreflights.values()]);
 }
 /**
  * Collect network requests observed by kuri's builtin extension (chrome.webRequest).
@@ -766,20 +986,30 @@ async function injectCookies(
   try {
     await kuri.setCookies(tabId, sanitized);
   } catch (batchErr) {
-    log("capture", `batch cookie injection failed: ${batchErr instanceof Error ? batchErr.message : batchErr} — falling back to per-cookie`);
+    logWarn("capture", `batch cookie injection failed: ${batchErr instanceof Error ? batchErr.message : batchErr} — falling back to per-cookie`);
     let injected = 0;
     for (const cookie of sanitized) {
       try {
         await kuri.setCookie(tabId, cookie);
         injected++;
       } catch (err) {
-        log("capture", `failed to inject cookie "${cookie.name}" for ${cookie.domain}: ${err instanceof Error ? err.message : err}`);
+        logWarn("capture", `failed to inject cookie "${cookie.name}" for ${cookie.domain}: ${err instanceof Error ? err.message : err}`);
       }
     }
     log("capture", `per-cookie fallback: ${injected}/${sanitized.length} injected`);
   }
 }
 
+/** Auth tokens the page keeps in web storage rather than sending as a static header. */
+export async function extractStorageAuthTokens(tabId: string): Promise<StorageAuthToken[]> {
+  try {
+    const { local, session } = await kuri.getWebStorage(tabId);
+    return [...findStorageAuthTokens("local", local), ...findStorageAuthTokens("session", session)];
+  } catch {
+    return [];
+  }
+}
+
 /**
  * Extract cookies from page via document.cookie (CDP getCookies crashes Kuri).
  * Parses simple name=value pairs — httpOnly cookies are NOT visible via JS.
@@ -810,7 +1040,15 @@ export async function captureSession(
   authHeaders?: Record<string, string>,
   cookies?: Array<{ name: string; value: string; domain: string; path?: string; secure?: boolean; httpOnly?: boolean; sameSite?: string; expires?: number }>,
   intent?: string,
-  options?: { forceEphemeral?: boolean },
+  options?: {
+    forceEphemeral?: boolean;
+    max_body_bytes?: number;
+    /** Re-check this many times when no API traffic has been seen yet (default UNBROWSE_CAPTURE_EMPTY_RETRIES, else 0) */
+    empty_retries?: number;
+    empty_retry_interval_ms?: number;
+    /** Scroll between empty-capture polls to provoke lazy-loaded requests (default true) */
+    scroll_on_retry?: boolean;
+  } & CaptureFilter,
 ): Promise<CaptureResult> {
   await acquireTabSlot();
 
@@ -942,6 +1180,20 @@ export async function captureSession(
     // Incremental collection: drain intercepted data after content ready
     await drainIntercepted();
 
+    // The page may not have fired its API calls yet (lazy loading, an auth
+    // redirect still settling) — poll a few times before capturing nothing.
+    const emptyRetries = options?.empty_retries ?? envEmptyRetries();
+    let emptyRetriesUsed = 0;
+    while (emptyRetriesUsed < emptyRetries && responseBodies.size === 0 && !signal.aborted) {
+      emptyRetriesUsed++;
+      log("capture", `no API traffic yet for ${url}; poll ${emptyRetriesUsed}/${emptyRetries}`);
+      if (options?.scroll_on_retry !== false) {
+        await phase("scroll:empty-retry", () => kuri.scroll(tabId).catch(() => {}));
+      }
+      await new Promise((r) => setTimeout(r, options?.empty_retry_interval_ms ?? EMPTY_RETRY_INTERVAL_MS));
+      await drainIntercepted();
+    }
+
     // Collect all intercepted requests (final sweep)
     const intercepted = await phase("collectIntercepted", () => collectInterceptedRequests(tabId));
     const extensionEntries = await phase("collectExtension", () => collectExtensionRequests(tabId));
@@ -1056,12 +1308,23 @@ export async function captureSession(
     } catch {}
 
     // Merge all passive capture sources into unified request list
-    const requests: RawRequest[] = mergePassiveCaptureData(intercepted, harEntries, extensionEntries, responseBodies);
+    const merged = mergePassiveCaptureData(intercepted, harEntries, extensionEntries, responseBodies);
+    const traffic: CaptureTraffic = {
+      total_requests: merged.length,
+      api_requests: merged.filter(looksLikeApiResponse).length,
+      empty_retries: emptyRetriesUsed,
+    };
+    const requests: RawRequest[] = truncateRequestBodies(
+      applyCaptureFilter(merged, options ?? {}),
+      options?.max_body_bytes,
+    );
+    const wsMessages = harWebSocketMessages(harEntries);
     log("capture", `tracked ${harEntries.length} HAR, ${intercepted.length} intercepted, ${extensionEntries.length} extension, ${responseBodies.size} bodies → ${requests.length} merged`);
 
     // Extract session cookies via document.cookie
     const rawCookies = await phase("extractCookies", () => extractCookiesFromPage(tabId, url));
     const sessionCookies = filterFirstPartySessionCookies(rawCookies, url, final_url);
+    const storageTokens = await phase("extractStorageTokens", () => extractStorageAuthTokens(tabId));
 
     if (captureTimedOut) throw new Error(`captureSession timed out after ${CAPTURE_TIMEOUT_MS}ms for ${url}`);
     log("capture", `captured ${jsBundleBodies.size} JS bundles for route scanning`);
@@ -1075,10 +1338,7 @@ export async function captureSession(
       // On ephemeral retry, if still blocked by Cloudflare WAF, throw auth_required
       // so the caller can surface a login prompt instead of retrying forever
       if (options?.forceEphemeral && html && /Cloudflare|cf\.errors\.css|cf-error-details/i.test(html)) {
-        throw Object.assign(new Error("cloudflare_waf_block"), {
-          code: "auth_required",
-          login_url: url,
-        });
+        throw unbrowseError("auth_required", "cloudflare_waf_block", { login_url: url });
       }
       retryFreshTab = true;
       log("capture", `rendered blocked app shell for ${url}; retrying with fresh tab`);
@@ -1088,18 +1348,20 @@ export async function captureSession(
         har_lineage_id,
         domain,
         cookies: sessionCookies.length > 0 ? sessionCookies : undefined,
+        storage_tokens: storageTokens.length > 0 ? storageTokens : undefined,
         final_url,
-        // WebSocket capture skipped (Kuri limitation)
-        ws_messages: undefined,
+        // WebSocket frames only arrive when the HAR carries _webSocketMessages
+        ws_messages: wsMessages.length > 0 ? wsMessages : undefined,
         html,
         js_bundles: jsBundleBodies.size > 0 ? jsBundleBodies : undefined,
+        traffic,
       };
     }
   } catch (error) {
     captureError = error;
     if (shouldRetryEphemeralProfileError(error)) {
       retryFreshTab = true;
-      log("capture", `tab failed for ${url}; retrying with fresh tab (${error instanceof Error ? error.message : String(error)})`);
+      logWarn("capture", `tab failed for ${url}; retrying with fresh tab (${error instanceof Error ? error.message : String(error)})`);
     } else {
       throw error;
     }
@@ -1110,7 +1372,7 @@ export async function captureSession(
     releaseTabSlot(tabId);
   }
   if (retryFreshTab && !options?.forceEphemeral) {
-    return captureSession(url, authHeaders, cookies, intent, { forceEphemeral: true });
+    return captureSession(url, authHeaders, cookies, intent, { ...options, forceEphemeral: true });
   }
   if (captureError) throw captureError;
   throw new Error(`captureSession failed without returning a result for ${url}`);
@@ -1122,8 +1384,10 @@ export async function executeInBrowser(
   requestHeaders: Record<string, string>,
   body?: unknown,
   authHeaders?: Record<string, string>,
-  cookies?: Array<{ name: string; value: string; domain: string; path?: string; secure?: boolean; httpOnly?: boolean; sameSite?: string; expires?: number }>
-): Promise<{ status: number; data: unknown; trace_id: string }> {
+  cookies?: Array<{ name: string; value: string; domain: string; path?: string; secure?: boolean; httpOnly?: boolean; sameSite?: string; expires?: number }>,
+  /** Also return the tab's cookies after the fetch (including any it set). */
+  collectCookies = false,
+): Promise<kuri.InPageFetchResult & { trace_id: string; cookies?: kuri.KuriCookie[] }> {
   if (!isBrowserAccessAvailable()) {
     throw new Error("Browser access is not available (proxy-only config)");
   }
@@ -1153,7 +1417,8 @@ export async function executeInBrowser(
     await waitForReadyState(tabId, 5000);
 
     const result = await kuri.executeInPageFetch(tabId, url, method, requestHeaders, body);
-    return { ...result, trace_id: nanoid() };
+    const tabCookies = collectCookies ? await kuri.getCookies(tabId).catch(() => undefined) : undefined;
+    return { ...result, trace_id: nanoid(), ...(tabCookies ? { cookies: tabCookies } : {}) };
   } finally {
     await resetTab(tabId);
     releaseTabSlot(tabId);
@@ -1496,19 +1761,17 @@ export async function executeActionSequence(
         }
 
         const requests: RawRequest[] = harResult.entries.map((entry) => {
-          const reqHeaders: Record<string, string> = {};
-          for (const h of entry.request.headers ?? []) reqHeaders[h.name] = h.value;
-          const respHeaders: Record<string, string> = {};
-          for (const h of entry.response.headers ?? []) respHeaders[h.name] = h.value;
+          const reqHeaders = canonicalHeaders(entry.request.headers);
+          const respHeaders = canonicalHeaders(entry.response.headers);
           return {
             url: entry.request.url,
             method: entry.request.method,
             request_headers: reqHeaders,
-            request_body: entry.request.postData?.text,
+            request_body: kuri.harPostDataText(entry.request.postData),
             response_status: entry.response.status,
             response_headers: respHeaders,
-            response_body: responseBodies.get(entry.request.url) ?? entry.response.content?.text,
-            timestamp: entry.startedDateTime,
+            ...(responseBodies.has(entry.request.url) ? { response_body: responseBodies.get(entry.request.url) } : kuri.harResponseBody(entry.response.content)),
+            timestamp: parseHarDateTime(entry.startedDateTime),
           };
         });
 
@@ -1547,7 +1810,7 @@ export async function executeActionSequence(
           html,
         };
       } catch (err) {
-        log("capture", `background capture collection failed: ${err instanceof Error ? err.message : String(err)}`);
+        logWarn("capture", `background capture collection failed: ${err instanceof Error ? err.message : String(err)}`);
       }
     }
 
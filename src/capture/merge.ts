@@ -0,0 +1,233 @@
+/**
+ * Incremental capture: capture fresh traffic and fold it into an existing
+ * skill in one call.
+ *
+ * The regular capture path builds a new draft and merges it with the cached
+ * skill later (background index / publish); if that step fails the local
+ * cache keeps the pre-capture endpoints. Here the merge, the auth-bundle
+ * refresh, and the local cache write all happen before returning, and old
+ * endpoints are never dropped.
+ */
+
+import { captureSession, type CaptureFilter, type CaptureResult } from "./index.js";
+import { getAuthCookies, mergeAuthBundle } from "../auth/index.js";
+import { cachePublishedSkill } from "../client/index.js";
+import { isSameRegistrableDomain } from "../domain.js";
+import { buildSkillOperationGraph } from "../graph/index.js";
+import { unbrowseError } from "../errors.js";
+import { log, logError } from "../logger.js";
+import { diffSkills, mergeEndpoints, type SkillDiff } from "../marketplace/index.js";
+import { detectAuthHeaderRotations, extractAuthHeaders, extractDeviceIdentifiers, extractEndpoints, extractVersionHeaders, type MethodsFilter, type StatusFilter } from "../reverse-engineer/index.js";
+import { apiResponseStatuses, classifyAuth, dedupeAuthHeaders, findAuthChallenges } from "../reverse-engineer/auth-detect.js";
+import { getCredential, storeCredential } from "../vault/index.js";
+import type { EndpointDescriptor, SkillManifest } from "../types/index.js";
+
+export interface CaptureMergeOptions extends CaptureFilter {
+  intent?: string;
+  /** Capture in a fresh ephemeral tab instead of the shared default tab */
+  ephemeral?: boolean;
+  max_body_bytes?: number;
+  client_scope?: string;
+  /** Method allow/deny list for the captured endpoints, e.g. `{ only: ["GET"] }` */
+  methods?: MethodsFilter;
+  /** Response-status ranges to keep, e.g. `{ include_errors: false }` for 2xx–3xx only */
+  statuses?: StatusFilter;
+  /** Keep cross-domain calls that carry the target's session cookie (ExtractionContext.sessionFirstParty) */
+  session_first_party?: boolean;
+}
+
+export interface CaptureMergeResult {
+  skill: SkillManifest;
+  /** What the capture changed relative to `existing` */
+  diff: SkillDiff;
+  /** Endpoints extracted from this capture alone, before merging */
+  captured_endpoints: number;
+}
+
+export interface CaptureUrlsOptions extends CaptureMergeOptions {
+  /** Pages captured at once (default 1, at most MAX_CAPTURE_CONCURRENCY). Above 1 every page gets its own ephemeral tab. */
+  concurrency?: number;
+  /** Stops starting new pages; pages already captured are still merged */
+  signal?: AbortSignal;
+}
+
+export interface CapturedUrl {
+  url: string;
+  /** `METHOD url_template` of each endpoint extracted from this page alone; empty for dead pages */
+  endpoints: string[];
+  /** Capture failure for this page; the other pages are still merged */
+  error?: string;
+}
+
+export interface CaptureUrlsResult extends CaptureMergeResult {
+  /** Per-page breakdown, in input order */
+  per_url: CapturedUrl[];
+  /** Pages never started because the capture was cancelled */
+  cancelled?: string[];
+}
+
+/**
+ * Parse a page URL to capture into `existing`. Anything that isn't an
+ * http(s) URL on the skill's registrable domain is rejected up front, so
+ * another site's endpoints and session never get merged into this skill.
+ */
+function parseSkillPageUrl(existing: SkillManifest, url: string): URL {
+  let parsed: URL;
+  try {
+    parsed = new URL(url);
+  } catch {
+    throw unbrowseError("invalid_input", `invalid url: ${url}`);
+  }
+  if (parsed.protocol !== "http:" && parsed.protocol !== "https:") {
+    throw unbrowseError("invalid_input", `url must be http(s): ${url}`);
+  }
+  if (!isSameRegistrableDomain(parsed.hostname, existing.domain)) {
+    throw unbrowseError("invalid_input", `${parsed.hostname} is not on ${existing.domain}, the domain of skill ${existing.skill_id}`);
+  }
+  return parsed;
+}
+
+/**
+ * Capture `seedUrl` and union the result with `existing`: endpoints merge on
+ * method + templated URL (mergeEndpoints), captured auth is merged into the
+ * skill's session bundle (mergeAuthBundle), and the merged skill is written
+ * to the local cache before it is returned together with its diff. A seed
+ * URL that doesn't parse or is off the skill's domain throws `invalid_input`.
+ */
+export async function captureAndMerge(
+  existing: SkillManifest,
+  seedUrl: string,
+  options: CaptureMergeOptions = {},
+): Promise<CaptureMergeResult> {
+  const { per_url: _perUrl, cancelled: _cancelled, ...result } = await captureUrlsAndMerge(existing, [seedUrl], options);
+  return result;
+}
+
+/** Pages one capture-merge call may visit, and how many of them at once. */
+export const MAX_CAPTURE_URLS = 20;
+export const MAX_CAPTURE_CONCURRENCY = 4;
+
+/**
+ * captureAndMerge over several pages. Each page is captured in its own
+ * session, so its traffic is exactly what navigating to it produced; the
+ * endpoints extracted per page are reported in `per_url`, which makes pages
+ * that yielded nothing easy to prune from a crawl. Failed pages are recorded
+ * and skipped; the call only throws when no page was captured at all, or up
+ * front when `urls` is too long or any of them is off the skill's domain.
+ */
+export async function captureUrlsAndMerge(
+  existing: SkillManifest,
+  urls: string[],
+  options: CaptureUrlsOptions = {},
+): Promise<CaptureUrlsResult> {
+  if (!Array.isArray(urls) || urls.some((url) => typeof url !== "string")) {
+    throw unbrowseError("invalid_input", "urls must be an array of strings");
+  }
+  if (urls.length === 0) throw unbrowseError("invalid_input", "at least one url is required");
+  if (urls.length > MAX_CAPTURE_URLS) {
+    throw unbrowseError("invalid_input", `at most ${MAX_CAPTURE_URLS} urls per capture, got ${urls.length}`);
+  }
+  const domain = parseSkillPageUrl(existing, urls[0]).hostname;
+  for (const url of urls.slice(1)) parseSkillPageUrl(existing, url);
+  const intent = options.intent ?? existing.intent_signature ?? `browse ${domain}`;
+  const { intent: _intent, ephemeral, client_scope, methods, statuses, session_first_party, concurrency: rawConcurrency, signal, ...captureOptions } = options;
+  const concurrency = Math.max(1, Math.min(Math.floor(Number(rawConcurrency) || 1), urls.length, MAX_CAPTURE_CONCURRENCY));
+  // Parallel pages can't share the default tab: one navigation would clobber the other's traffic.
+  const forceEphemeral = ephemeral || concurrency > 1;
+
+  const pages: Array<{ captured?: CaptureResult; error?: Error; cancelled?: boolean }> = urls.map(() => ({}));
+  let next = 0;
+  const worker = async () => {
+    while (next < urls.length) {
+      const i = next++;
+      if (signal?.aborted) { pages[i].cancelled = true; continue; }
+      const url = urls[i];
+      try {
+        const cookies = await getAuthCookies(new URL(url).hostname) ?? undefined;
+        pages[i].captured = await captureSession(url, undefined, cookies, intent, { ...captureOptions, forceEphemeral });
+      } catch (err) {
+        pages[i].error = err as Error;
+        logError("capture", `capture ${url} failed: ${(err as Error).message}`);
+      }
+    }
+  };
+  await Promise.all(Array.from({ length: concurrency }, worker));
+
+  const captures = pages.flatMap((page, i) => page.captured ? [{ url: urls[i], captured: page.captured }] : []);
+  if (captures.length === 0) {
+    const failed = pages.find((page) => page.error);
+    if (failed) throw failed.error;
+    throw unbrowseError("cancelled", "Capture cancelled before any page was visited");
+  }
+
+  const per_url: CapturedUrl[] = [];
+  let fresh: EndpointDescriptor[] = [];
+  pages.forEach((page, i) => {
+    if (page.cancelled) return;
+    const extracted = page.captured
+      ? extractEndpoints(page.captured.requests, page.captured.ws_messages, { pageUrl: urls[i], finalUrl: page.captured.final_url, methods, statuses, sessionFirstParty: session_first_party })
+      : [];
+    per_url.push({
+      url: urls[i],
+      endpoints: Array.from(new Set(extracted.map((ep) => `${ep.method} ${ep.url_template}`))),
+      ...(page.error ? { error: page.error.message } : {}),
+    });
+    fresh = mergeEndpoints(fresh, extracted);
+  });
+  const cancelled = urls.filter((_, i) => pages[i].cancelled);
+  const endpoints = mergeEndpoints(existing.endpoints, fresh);
+
+  // Auth and version headers are read from every captured page at once.
+  const requests = captures.flatMap(({ captured }) => captured.requests);
+  const lastCapture = captures[captures.length - 1];
+  const captured = {
+    requests,
+    cookies: captures.flatMap(({ captured }) => captured.cookies ?? []),
+    final_url: lastCapture.captured.final_url || lastCapture.url,
+  };
+
+  const { headers: authHeaders, header_aliases } = dedupeAuthHeaders(extractAuthHeaders(captured.requests));
+  const deviceIds = extractDeviceIdentifiers(captured.requests);
+  const hasAuth = Object.keys(authHeaders).length > 0 || (captured.cookies?.length ?? 0) > 0;
+  const needsBundle = hasAuth || Object.keys(deviceIds).length > 0;
+  const authRef = existing.auth_profile_ref ?? (needsBundle ? `${domain}-session` : undefined);
+  if (needsBundle && authRef) {
+    let bundle: Record<string, unknown> | null = null;
+    try { bundle = JSON.parse((await getCredential(authRef)) ?? "null"); } catch { /* replace malformed bundle */ }
+    await storeCredential(authRef, JSON.stringify(mergeAuthBundle(bundle, {
+      cookies: captured.cookies ?? [],
+      headers: Object.keys(authHeaders).length > 0 ? authHeaders : undefined,
+      ...(header_aliases ? { header_aliases } : {}),
+      ...(Object.keys(deviceIds).length > 0 ? { context: { device_identifiers: deviceIds } } : {}),
+    })), { source_url: captured.final_url });
+  }
+
+  const challenges = findAuthChallenges(captured.requests);
+  const rotated = Object.keys(detectAuthHeaderRotations(captured.requests));
+  const versionHeaders = { ...(existing.version_headers ?? {}), ...extractVersionHeaders(captured.requests) };
+  const classified = classifyAuth(authHeaders, captured.cookies ?? [], challenges, apiResponseStatuses(captured.requests));
+  // An anonymous re-capture doesn't make an authenticated skill public.
+  const classification = classified.scheme === "unknown" || (classified.scheme === "none" && existing.auth_method && existing.auth_scheme !== "none")
+    ? null
+    : classified;
+  const skill: SkillManifest = {
+    ...existing,
+    updated_at: new Date().toISOString(),
+    endpoints,
+    operation_graph: buildSkillOperationGraph(endpoints),
+    intents: Array.from(new Set([...(existing.intents ?? []), intent])),
+    ...(authRef ? { auth_profile_ref: authRef } : {}),
+    ...(classification ? { auth_method: classification.label, auth_scheme: classification.scheme } : {}),
+    ...(classification?.signed_requests ? { signed_requests: true } : {}),
+    ...(challenges.length > 0 ? { auth_challenge: challenges[0] } : {}),
+    ...(rotated.length > 0 ? { rotated_auth_headers: rotated } : {}),
+    ...(authRef && Object.keys(deviceIds).length > 0 ? { device_identifier_headers: Object.keys(deviceIds) } : {}),
+    ...(Object.keys(versionHeaders).length > 0 ? { version_headers: versionHeaders } : {}),
+  };
+  cachePublishedSkill(skill, client_scope);
+
+  const diff = diffSkills(existing, skill);
+  const pageNote = urls.length > 1 ? ` from ${captures.length}/${urls.length} page(s)` : "";
+  log("capture", `capture-merge ${existing.skill_id}: ${fresh.length} captured${pageNote}, +${diff.added.length} added, ${diff.changed.length} changed (${endpoints.length} total)`);
+  return { skill, diff, captured_endpoints: fresh.length, per_url, ...(cancelled.length > 0 ? { cancelled } : {}) };
+}
@@ -9,6 +9,8 @@
 
 import { config as loadEnv } from "dotenv";
 import { spawn } from "node:child_process";
+import { readFileSync, writeFileSync } from "node:fs";
+import { resolve } from "node:path";
 import {
   detectTelemetryHostType,
   ensureCliInstallTracked,
@@ -23,12 +25,44 @@ import { isMainModule, resolveSiblingEntrypoint, runtimeArgsForEntrypoint } from
 import { drainPendingIndexJobs } from "./indexer/index.js";
 import { drainPendingPassivePublishes } from "./orchestrator/passive-publish.js";
 import { runSetup, type SetupReport, type SetupScope } from "./runtime/setup.js";
+import type { HealthReport } from "./runtime/health.js";
+import {
+  getActiveWalletProfile,
+  listWalletProfiles,
+  removeWalletProfile,
+  requestAirdrop,
+  resolveSolanaNetwork,
+  saveWalletProfile,
+  setActiveWalletProfile,
+  setSolanaNetwork,
+  type SolanaNetwork,
+} from "./payments/wallet.js";
+import { buildUsdcTransfer } from "./payments/transfer.js";
+import { installProxyFetch } from "./runtime/proxy.js";
+import {
+  clearVault,
+  countCredentials,
+  credentialsUpdatedSince,
+  deleteCredentialsMatching,
+  exportVaultEncrypted,
+  getCredential,
+  getCredentialInfo,
+  importVaultEncrypted,
+  repairVault,
+  storeCredential,
+  VAULT_CLEAR_CONFIRMATION,
+  verifyVault,
+} from "./vault/index.js";
+import { exportNetscapeCookies, parseNetscapeCookies } from "./auth/netscape-cookies.js";
 
 loadEnv({ quiet: true });
 loadEnv({ path: ".env.runtime", quiet: true });
+installProxyFetch();
 
 const BASE_URL = process.env.UNBROWSE_URL || "http://localhost:6969";
 const CLI_CLIENT_ID = process.env.UNBROWSE_CLIENT_ID || `cli-${process.ppid || process.pid}`;
+/** Named browse session (see `POST /v1/browse/sessions`); unset uses the default one. */
+const CLI_BROWSE_SESSION = process.env.UNBROWSE_SESSION;
 
 // ---------------------------------------------------------------------------
 // Arg parser
@@ -68,6 +102,7 @@ async function api(method: string, path: string, body?: unknown): Promise<unknow
     headers: {
       ...(body ? { "Content-Type": "application/json" } : {}),
       "x-unbrowse-client-id": CLI_CLIENT_ID,
+      ...(CLI_BROWSE_SESSION ? { "x-unbrowse-session": CLI_BROWSE_SESSION } : {}),
     },
     body: body ? JSON.stringify(body) : undefined,
   });
@@ -205,7 +240,12 @@ function emitNextActionSummary(result: Record<string, unknown>): void {
 
 
 async function cmdHealth(flags: Record<string, string | boolean>): Promise<void> {
-  output(await api("GET", "/health"), !!flags.pretty);
+  const server = await api("GET", "/health") as Record<string, unknown>;
+  const report = await api("GET", "/v1/health/check") as HealthReport;
+  for (const [name, component] of Object.entries(report.components ?? {})) {
+    info(`  ${component.status.toUpperCase().padEnd(5)} ${name.padEnd(14)} ${component.message}`);
+  }
+  output({ ...server, ok: report.ok, components: report.components }, !!flags.pretty);
 }
 
 function telemetryDomainFromInput(domain?: string, url?: string): string | null {
@@ -218,6 +258,12 @@ function telemetryDomainFromInput(domain?: string, url?: string): string | null
   }
 }
 
+async function cmdLogLevel(args: string[]): Promise<void> {
+  const level = args[0];
+  if (!level) die("Usage: unbrowse log-level <error|warn|info|debug|trace>");
+  output(await api("POST", "/v1/log-level", { level }), false);
+}
+
 async function cmdResolve(flags: Record<string, string | boolean>): Promise<void> {
   const intent = flags.intent as string;
   if (!intent) die("--intent is required");
@@ -706,7 +752,7 @@ async function cmdPublish(flags: Record<string, string | boolean>): Promise<void
     // Phase 2: merge descriptions + publish
     const endpoints = JSON.parse(endpointsJson) as Array<Record<string, unknown>>;
     if (!Array.isArray(endpoints) || endpoints.length === 0) die("--endpoints must be a non-empty JSON array");
-    output(await api("POST", `/v1/skills/${skillId}/publish`, { endpoints }), !!flags.pretty);
+    output(await api("POST", `/v1/skills/${skillId}/publish`, { endpoints, ...(flags.force ? { force: true } : {}) }), !!flags.pretty);
   } else {
     // Phase 1: return endpoints needing descriptions
     output(await api("POST", `/v1/skills/${skillId}/publish`, {}), !!flags.pretty);
@@ -723,12 +769,51 @@ async function cmdSkills(flags: Record<string, string | boolean>): Promise<void>
   output(await api("GET", "/v1/skills"), !!flags.pretty);
 }
 
+async function cmdHome(flags: Record<string, string | boolean>): Promise<void> {
+  const qs = flags.limit ? `?limit=${encodeURIComponent(String(flags.limit))}` : "";
+  const feed = await api("GET", `/v1/marketplace/home${qs}`) as { warnings?: Array<{ section: string; error: string }> };
+  for (const w of feed.warnings ?? []) info(`${w.section}: ${w.error}`);
+  output(feed, !!flags.pretty);
+}
+
 async function cmdSkill(args: string[], flags: Record<string, string | boolean>): Promise<void> {
   const id = args[0] ?? flags.id as string;
   if (!id) die("skill <id> or --id required");
+  if (flags.postman) {
+    output(await api("GET", `/v1/skills/${id}/postman`), true);
+    return;
+  }
+  if (flags.curl) {
+    // skill <id> --curl <endpoint_id> [--include-secrets]
+    if (typeof flags.curl !== "string") die("Usage: unbrowse skill <id> --curl <endpoint_id> [--include-secrets]");
+    const qs = flags["include-secrets"] ? "?include_secrets=true" : "";
+    const res = await api("GET", `/v1/skills/${id}/endpoints/${encodeURIComponent(flags.curl)}/curl${qs}`) as { curl?: string };
+    if (res.curl) process.stdout.write(res.curl + "\n");
+    else output(res, !!flags.pretty);
+    return;
+  }
+  if (flags.rank) {
+    output(await api("GET", `/v1/skills/${id}/endpoints/ranked`), !!flags.pretty);
+    return;
+  }
   output(await api("GET", `/v1/skills/${id}`), !!flags.pretty);
 }
 
+async function cmdImportOpenApi(args: string[], flags: Record<string, string | boolean>): Promise<void> {
+  const source = args[0];
+  if (!source) die("Usage: unbrowse import-openapi <file|url> [--base-url <url>]");
+  let spec: string;
+  if (/^https?:\/\//i.test(source)) {
+    const res = await fetch(source);
+    if (!res.ok) die(`Failed to fetch ${source}: HTTP ${res.status}`);
+    spec = await res.text();
+  } else {
+    spec = readFileSync(source, "utf-8");
+  }
+  const base_url = typeof flags["base-url"] === "string" ? flags["base-url"] : undefined;
+  output(await api("POST", "/v1/skills/import/openapi", { spec, ...(base_url ? { base_url } : {}) }), !!flags.pretty);
+}
+
 async function cmdSearch(flags: Record<string, string | boolean>): Promise<void> {
   const intent = flags.intent as string;
   if (!intent) die("--intent is required");
@@ -792,6 +877,184 @@ async function cmdSessions(flags: Record<string, string | boolean>): Promise<voi
   output(await api("GET", `/v1/sessions/${domain}?limit=${limit}`), !!flags.pretty);
 }
 
+function walletNetworkFlag(flags: Record<string, string | boolean>): SolanaNetwork | undefined {
+  const network = flags.network;
+  if (network === undefined) return undefined;
+  if (network !== "mainnet" && network !== "devnet" && network !== "testnet") die("--network must be mainnet, devnet, or testnet");
+  return network;
+}
+
+async function cmdWallet(args: string[], flags: Record<string, string | boolean>): Promise<void> {
+  const [sub = "list", name, address, provider] = args;
+  switch (sub) {
+    case "list":
+      output({ active: getActiveWalletProfile() ?? null, profiles: listWalletProfiles() }, !!flags.pretty);
+      return;
+    case "use":
+      if (!name) die("Usage: unbrowse wallet use <name>");
+      try { setActiveWalletProfile(name); } catch (err) { die((err as Error).message); }
+      output({ ok: true, active: name }, !!flags.pretty);
+      return;
+    case "add":
+      if (!name || !address) die("Usage: unbrowse wallet add <name> <address> [provider]");
+      saveWalletProfile(name, { wallet_address: address, ...(provider ? { wallet_provider: provider } : {}) });
+      output({ ok: true, profile: name, active: getActiveWalletProfile() ?? null }, !!flags.pretty);
+      return;
+    case "remove":
+      if (!name) die("Usage: unbrowse wallet remove <name>");
+      removeWalletProfile(name);
+      output({ ok: true, removed: name }, !!flags.pretty);
+      return;
+    case "transfer": {
+      // wallet transfer <recipient> <amount_usdc> --blockhash <hash>
+      const blockhash = flags.blockhash as string | undefined;
+      if (!name || !address || !blockhash) die("Usage: unbrowse wallet transfer <recipient> <amount_usdc> --blockhash <recent_blockhash>");
+      try {
+        output(await buildUsdcTransfer(name, Number(address), blockhash, { network: walletNetworkFlag(flags) }), !!flags.pretty);
+      } catch (err) {
+        die((err as Error).message);
+      }
+      return;
+    }
+    case "network": {
+      // wallet network [mainnet|devnet|testnet] [rpc_url|--default-rpc]
+      if (name) {
+        if (name !== "mainnet" && name !== "devnet" && name !== "testnet") die("Usage: unbrowse wallet network [mainnet|devnet|testnet] [rpc_url | --default-rpc]");
+        setSolanaNetwork(name, flags["default-rpc"] ? null : address);
+      }
+      output(resolveSolanaNetwork(), !!flags.pretty);
+      return;
+    }
+    case "airdrop": {
+      if (!name) die("Usage: unbrowse wallet airdrop <sol> [--network devnet|testnet]");
+      try {
+        output(await requestAirdrop(Number(name), { network: walletNetworkFlag(flags) }), !!flags.pretty);
+      } catch (err) {
+        die((err as Error).message);
+      }
+      return;
+    }
+    default:
+      die(`Unknown wallet subcommand: ${sub}`);
+  }
+}
+
+/** "captured from app.example.com 3 days ago" */
+function describeProvenance(sourceUrl: string, at: string | undefined): string {
+  let host = sourceUrl;
+  try { host = new URL(sourceUrl).hostname; } catch { /* keep as given */ }
+  const ms = at ? Date.now() - new Date(at).getTime() : NaN;
+  if (!Number.isFinite(ms)) return `captured from ${host}`;
+  const minutes = Math.floor(ms / 60_000);
+  const ago = minutes < 1 ? "just now"
+    : minutes < 60 ? `${minutes} minute${minutes === 1 ? "" : "s"} ago`
+    : minutes < 1440 ? `${Math.floor(minutes / 60)} hour${minutes < 120 ? "" : "s"} ago`
+    : `${Math.floor(minutes / 1440)} day${minutes < 2880 ? "" : "s"} ago`;
+  return `captured from ${host} ${ago}`;
+}
+
+async function cmdVault(args: string[], flags: Record<string, string | boolean>): Promise<void> {
+  const [sub, file] = args;
+  if (sub === "verify") {
+    const report = await verifyVault();
+    const bad = report.entries.filter((e) => e.status === "corrupt").length;
+    output({ ok: report.file !== "corrupt" && bad === 0, ...report }, !!flags.pretty);
+    return;
+  }
+  if (sub === "repair") {
+    output({ ok: true, ...(await repairVault({ backup: !flags["no-backup"] })) }, !!flags.pretty);
+    return;
+  }
+  if (sub === "count") {
+    output({ count: await countCredentials() }, !!flags.pretty);
+    return;
+  }
+  if (sub === "info") {
+    if (!file) die("Usage: unbrowse vault info <account>");
+    const meta = await getCredentialInfo(file);
+    if (!meta) die(`No credential stored for ${file}`);
+    output({ ...meta, ...(meta.source_url ? { summary: describeProvenance(meta.source_url, meta.captured_at ?? meta.stored_at) } : {}) }, !!flags.pretty);
+    return;
+  }
+  if (sub === "cookies") {
+    // vault cookies <account> [file] — the bundle's cookies as a curl/wget cookies.txt
+    if (!file) die("Usage: unbrowse vault cookies <account> [file]");
+    let bundle: { cookies?: Parameters<typeof exportNetscapeCookies>[0] } | null = null;
+    try { bundle = JSON.parse((await getCredential(file)) ?? "null"); } catch { die(`${file} is not a cookie bundle`); }
+    if (!bundle?.cookies?.length) die(`No cookies stored for ${file}`);
+    const jar = exportNetscapeCookies(bundle.cookies);
+    if (args[2]) {
+      writeFileSync(args[2], jar, { mode: 0o600 });
+      output({ ok: true, file: args[2], cookies: bundle.cookies.length }, !!flags.pretty);
+    } else {
+      process.stdout.write(jar);
+    }
+    return;
+  }
+  if (sub === "import-cookies") {
+    // vault import-cookies <file> <account> — merge a cookies.txt jar into the account's bundle
+    const account = args[2];
+    if (!file || !account) die("Usage: unbrowse vault import-cookies <cookies.txt> <account>");
+    const cookies = parseNetscapeCookies(readFileSync(file, "utf-8"));
+    if (cookies.length === 0) die(`${file} has no cookies in Netscape cookies.txt format`);
+    const { mergeAuthBundle } = await import("./auth/index.js");
+    let bundle: Record<string, unknown> | null = null;
+    try { bundle = JSON.parse((await getCredential(account)) ?? "null"); } catch { /* replace malformed bundle */ }
+    await storeCredential(account, JSON.stringify(mergeAuthBundle(bundle, { cookies })));
+    output({ ok: true, account, imported: cookies.length }, !!flags.pretty);
+    return;
+  }
+  if (sub === "signing-secret") {
+    // vault signing-secret <account> --secret ... — HMAC key for endpoints with request_signing
+    const account = file;
+    const secret = (flags.secret as string | undefined) ?? process.env.UNBROWSE_SIGNING_SECRET;
+    if (!account || !secret) die("Usage: unbrowse vault signing-secret <account> --secret ... (or UNBROWSE_SIGNING_SECRET)");
+    const { mergeAuthBundle } = await import("./auth/index.js");
+    let bundle: Record<string, unknown> | null = null;
+    try { bundle = JSON.parse((await getCredential(account)) ?? "null"); } catch { /* replace malformed bundle */ }
+    await storeCredential(account, JSON.stringify(mergeAuthBundle(bundle, { cookies: [], signing_secret: secret })));
+    output({ ok: true, account }, !!flags.pretty);
+    return;
+  }
+  if (sub === "updated-since") {
+    if (!file) die("Usage: unbrowse vault updated-since <iso-timestamp>");
+    output({ accounts: await credentialsUpdatedSince(file) }, !!flags.pretty);
+    return;
+  }
+  if (sub === "delete") {
+    if (!file) die("Usage: unbrowse vault delete <prefix|glob>");
+    output({ ok: true, deleted: await deleteCredentialsMatching(file) }, !!flags.pretty);
+    return;
+  }
+  if (sub === "clear") {
+    if (flags.confirm !== VAULT_CLEAR_CONFIRMATION) die(`Refusing to clear the vault; pass --confirm ${VAULT_CLEAR_CONFIRMATION}`);
+    output({ ok: true, deleted: await clearVault(flags.confirm as string) }, !!flags.pretty);
+    return;
+  }
+  const passphrase = (flags.passphrase as string | undefined) ?? process.env.UNBROWSE_VAULT_PASSPHRASE;
+  if (!passphrase) die("--passphrase (or UNBROWSE_VAULT_PASSPHRASE) is required");
+  switch (sub) {
+    case "export": {
+      const blob = await exportVaultEncrypted(passphrase);
+      if (file) {
+        writeFileSync(file, blob + "\n", { mode: 0o600 });
+        output({ ok: true, file }, !!flags.pretty);
+      } else {
+        process.stdout.write(blob + "\n");
+      }
+      return;
+    }
+    case "import": {
+      if (!file) die("Usage: unbrowse vault import <file> --passphrase ...");
+      const imported = await importVaultEncrypted(readFileSync(file, "utf-8"), passphrase);
+      output({ ok: true, imported }, !!flags.pretty);
+      return;
+    }
+    default:
+      die("Usage: unbrowse vault export [file] | import <file> --passphrase ... | verify | repair [--no-backup] | count | info <account> | cookies <account> [file] | import-cookies <cookies.txt> <account> | signing-secret <account> --secret ... | updated-since <iso> | delete <prefix|glob> | clear --confirm ...");
+  }
+}
+
 async function cmdSetup(flags: Record<string, string | boolean>): Promise<void> {
   const hostType = detectTelemetryHostType();
   await ensureCliInstallTracked(hostType);
@@ -890,19 +1153,24 @@ async function cmdSetup(flags: Record<string, string | boolean>): Promise<void>
 
 export const CLI_REFERENCE = {
   commands: [
-    { name: "health", usage: "", desc: "Server health check" },
+    { name: "health", usage: "", desc: "Server health plus browser, vault, cookies, marketplace, and wallet checks" },
     { name: "mcp", usage: "[--no-auto-start]", desc: "Run the stdio MCP server" },
+    { name: "log-level", usage: "<error|warn|info|debug|trace>", desc: "Set server log verbosity (debug: filter decisions, trace: outbound calls)" },
     { name: "setup", usage: "[--opencode auto|global|project|off] [--no-start]", desc: "Bootstrap browser deps + Open Code command" },
     { name: "resolve", usage: '--intent "..." --url "..." [opts]', desc: "Resolve intent → search/capture/execute" },
     { name: "execute", usage: "--skill ID --endpoint ID [opts]", desc: "Execute a specific endpoint" },
     { name: "feedback", usage: "--skill ID --endpoint ID --rating N", desc: "Submit feedback (mandatory after resolve)" },
     { name: "review", usage: "--skill ID --endpoints '[...]'", desc: "Push reviewed descriptions/metadata back to skill" },
-    { name: "publish", usage: "--skill ID [--endpoints '[...]']", desc: "Describe + publish skill to marketplace (two-phase)" },
+    { name: "publish", usage: "--skill ID [--endpoints '[...]'] [--force]", desc: "Describe + publish skill to marketplace (two-phase; lint errors block unless --force)" },
     { name: "login", usage: '--url "..."', desc: "Interactive browser login" },
     { name: "skills", usage: "", desc: "List all skills" },
-    { name: "skill", usage: "<id>", desc: "Get skill details" },
+    { name: "home", usage: "[--limit N]", desc: "Marketplace trending and featured skills (sections that fail are reported as warnings)" },
+    { name: "skill", usage: "<id> [--postman | --rank | --curl <endpoint_id> [--include-secrets]]", desc: "Get skill details (or a Postman collection, endpoints ranked by observed evidence, or one endpoint as curl)" },
     { name: "search", usage: '--intent "..." [--domain "..."]', desc: "Search marketplace" },
+    { name: "import-openapi", usage: "<file|url> [--base-url <url>]", desc: "Seed a skill from an OpenAPI 3 JSON spec; captured endpoints for the domain are merged on top" },
     { name: "sessions", usage: '--domain "..." [--limit N]', desc: "Debug session logs" },
+    { name: "vault", usage: "export [file] | import <file> --passphrase ... | verify | repair [--no-backup] | count | info <account> | cookies <account> [file] | import-cookies <cookies.txt> <account> | signing-secret <account> --secret ... | updated-since <iso> | delete <prefix|glob> | clear --confirm delete-all-credentials", desc: "Passphrase-encrypted credential backup; integrity check, corruption recovery, provenance, cookies.txt import/export and bulk housekeeping" },
+    { name: "wallet", usage: "[list|use <name>|add <name> <address> [provider]|remove <name>|network [mainnet|devnet|testnet] [rpc_url]|airdrop <sol>|transfer <recipient> <usdc> --blockhash <hash>] [--network ...]", desc: "Manage named wallet profiles and Solana cluster; devnet airdrops; build a signed USDC transfer (UNBROWSE_WALLET_SECRET_KEY)" },
     { name: "go", usage: '<url>', desc: "Open a live Kuri browser tab for capture-first workflows" },
     { name: "submit", usage: "[--form-selector sel] [--submit-selector sel] [--wait-for hint]", desc: "Submit current form, auto-flush current capture, and fall back to same-origin rehydrate for JS-heavy flows" },
     { name: "snap", usage: "[--filter interactive]", desc: "A11y snapshot with @eN refs" },
@@ -911,6 +1179,7 @@ export const CLI_REFERENCE = {
     { name: "type", usage: "<text>", desc: "Type text with key events" },
     { name: "press", usage: "<key>", desc: "Press key (Enter, Tab, Escape)" },
     { name: "select", usage: "<ref> <value>", desc: "Select option by ref" },
+    { name: "upload", usage: "<ref> <file>", desc: "Set a file input's file by ref" },
     { name: "scroll", usage: "[up|down|left|right]", desc: "Scroll the page" },
     { name: "screenshot", usage: "", desc: "Capture screenshot (base64 PNG)" },
     { name: "text", usage: "", desc: "Get page text content" },
@@ -921,6 +1190,7 @@ export const CLI_REFERENCE = {
     { name: "forward", usage: "", desc: "Navigate forward" },
     { name: "sync", usage: "", desc: "Flush the current step's captured traffic into route cache without closing tab" },
     { name: "close", usage: "", desc: "Close browse session, flush + index traffic" },
+    { name: "recover", usage: "[session-id]", desc: "Rebuild an interrupted browse session from its journal and index its traffic" },
   ],
   globalFlags: [
     { flag: "--pretty", desc: "Indented JSON output" },
@@ -1294,6 +1564,12 @@ async function cmdSelect(args: string[]): Promise<void> {
   output(await api("POST", "/v1/browse/select", { ref, value }), false);
 }
 
+async function cmdUpload(args: string[]): Promise<void> {
+  const [ref, filePath] = args;
+  if (!ref || !filePath) die("Usage: unbrowse upload <ref> <file>");
+  output(await api("POST", "/v1/browse/upload", { ref, file_path: resolve(filePath) }), false);
+}
+
 async function cmdScroll(args: string[]): Promise<void> {
   const direction = args[0] ?? "down";
   output(await api("POST", "/v1/browse/scroll", { direction }), false);
@@ -1347,6 +1623,10 @@ async function cmdClose(): Promise<void> {
   output(await api("POST", "/v1/browse/close"), false);
 }
 
+async function cmdRecover(args: string[], flags: Record<string, string | boolean>): Promise<void> {
+  output(await api("POST", "/v1/browse/recover", args[0] ? { session_id: args[0] } : {}), !!flags.pretty);
+}
+
 async function cmdConnectChrome(): Promise<void> {
   const { execSync, spawn: spawnProc } = require("child_process");
   
@@ -1421,14 +1701,16 @@ async function main(): Promise<void> {
   if (command === "restart") return cmdRestart(flags);
   if (command === "upgrade" || command === "update") return cmdUpgrade(flags);
   if (command === "connect-chrome") return cmdConnectChrome();
+  if (command === "wallet") return cmdWallet(args, flags);
+  if (command === "vault") return cmdVault(args, flags);
 
   // --- Shortcut resolution: unbrowse <site> [task] [flags] ---
   const KNOWN_COMMANDS = new Set([
-    "health", "mcp", "setup", "resolve", "execute", "exec",
-    "feedback", "fb", "review", "publish", "login", "skills", "skill", "search", "sessions",
+    "health", "mcp", "log-level", "setup", "resolve", "execute", "exec",
+    "feedback", "fb", "review", "publish", "login", "skills", "skill", "search", "sessions", "wallet", "vault",
     "status", "stop", "restart", "upgrade", "update",
-    "go", "submit", "snap", "click", "fill", "type", "press", "select", "scroll",
-    "screenshot", "text", "markdown", "cookies", "eval", "back", "forward", "sync", "close",
+    "go", "submit", "snap", "click", "fill", "type", "press", "select", "upload", "scroll",
+    "screenshot", "text", "markdown", "cookies", "eval", "back", "forward", "sync", "close", "recover",
     "connect-chrome",
   ]);
 
@@ -1456,6 +1738,7 @@ async function main(): Promise<void> {
   switch (command) {
     case "health": return cmdHealth(flags);
     case "mcp": return cmdMcp(flags);
+    case "log-level": return cmdLogLevel(args);
     case "setup": return cmdSetup(flags);
     case "resolve": return cmdResolve(flags);
     case "execute": case "exec": return cmdExecute(flags);
@@ -1464,8 +1747,10 @@ async function main(): Promise<void> {
     case "publish": return cmdPublish(flags);
     case "login": return cmdLogin(flags);
     case "skills": return cmdSkills(flags);
+    case "home": return cmdHome(flags);
     case "skill": return cmdSkill(args, flags);
     case "search": return cmdSearch(flags);
+    case "import-openapi": return cmdImportOpenApi(args, flags);
     case "sessions": return cmdSessions(flags);
     // Browse commands — Kuri browser actions with passive indexing
     case "go": return cmdGo(args, flags);
@@ -1476,6 +1761,7 @@ async function main(): Promise<void> {
     case "type": return cmdType(args);
     case "press": return cmdPress(args);
     case "select": return cmdSelect(args);
+    case "upload": return cmdUpload(args);
     case "scroll": return cmdScroll(args);
     case "screenshot": return cmdScreenshot(flags);
     case "text": return cmdText(flags);
@@ -1486,6 +1772,7 @@ async function main(): Promise<void> {
     case "forward": return cmdForward();
     case "sync": return cmdSync(flags);
     case "close": return cmdClose();
+    case "recover": return cmdRecover(args, flags);
     case "connect-chrome": return cmdConnectChrome();
     default: info(`Unknown command: ${command}`); printHelp(); process.exit(1);
   }
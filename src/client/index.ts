@@ -1,13 +1,18 @@
-import { readFileSync, writeFileSync, existsSync, mkdirSync, readdirSync } from "fs";
+import { readFileSync, writeFileSync, existsSync, mkdirSync, readdirSync, statSync } from "fs";
 import { join } from "path";
 import { homedir, hostname } from "os";
 import { randomBytes, createHash } from "crypto";
 import { createInterface } from "readline";
 import type { AgentSkillChunkView, EndpointStats, ExecutionTrace, OrchestrationTiming, SkillManifest, ValidationResult } from "../types/index.js";
 import { ensureCascadeSplitForSkill } from "../payments/cascade.js";
+import { resolveWalletProfile } from "../payments/wallet.js";
+import { classifyMarketplaceError, parseX402Challenge, type MarketplaceFailure } from "../payments/x402.js";
+import { errorPayload, getErrorCode, unbrowseError, type UnbrowseErrorCode } from "../errors.js";
+import { redactEndpointExamples } from "../transform/redact.js";
 import { attributeLifecycle } from "../runtime/lifecycle.js";
 import type { LifecycleEvent } from "../runtime/lifecycle.js";
 import { detectHostEnvironment } from "../runtime/browser-host.js";
+import { getUnbrowseUserAgent } from "../runtime/user-agent.js";
 
 const API_URL = process.env.UNBROWSE_BACKEND_URL || "https://beta-api.unbrowse.ai";
 const PROFILE_NAME = sanitizeProfileName(process.env.UNBROWSE_PROFILE ?? "");
@@ -65,6 +70,11 @@ export function getActiveProfile(): string {
   return PROFILE_NAME || "default";
 }
 
+/** Marketplace backend base URL (UNBROWSE_BACKEND_URL override). */
+export function getBackendUrl(): string {
+  return API_URL;
+}
+
 export function isLocalOnlyMode(): boolean {
   return LOCAL_ONLY;
 }
@@ -177,6 +187,7 @@ async function postTelemetry(path: string, body: Record<string, unknown>): Promi
       headers: {
         "Content-Type": "application/json",
         "Accept-Encoding": "gzip, deflate",
+        "User-Agent": getUnbrowseUserAgent(),
         ...(key ? { Authorization: `Bearer ${key}` } : {}),
       },
       body: JSON.stringify(body),
@@ -275,21 +286,10 @@ export function resolveAgentName(preferredEmail: string | undefined, fallbackNam
   return isValidAgentEmail(normalized) ? normalized : fallbackName;
 }
 
-export function getLocalWalletContext(): { wallet_address?: string; wallet_provider?: string } {
-  const lobsterWallet = process.env.LOBSTER_WALLET_ADDRESS?.trim();
-  if (lobsterWallet) {
-    return { wallet_address: lobsterWallet, wallet_provider: "lobster.cash" };
-  }
-
-  const genericWallet = process.env.AGENT_WALLET_ADDRESS?.trim();
-  if (genericWallet) {
-    return {
-      wallet_address: genericWallet,
-      wallet_provider: process.env.AGENT_WALLET_PROVIDER?.trim() || undefined,
-    };
-  }
-
-  return {};
+export function getLocalWalletContext(profile?: string): { wallet_address?: string; wallet_provider?: string } {
+  const wallet = resolveWalletProfile(profile);
+  if (!wallet) return {};
+  return { wallet_address: wallet.wallet_address, wallet_provider: wallet.wallet_provider };
 }
 
 export function getApiKey(): string {
@@ -334,6 +334,7 @@ async function validateApiKey(key: string): Promise<ApiKeyValidationResult> {
       method: "GET",
       headers: {
         "Accept-Encoding": "gzip, deflate",
+        "User-Agent": getUnbrowseUserAgent(),
         Authorization: `Bearer ${key}`,
       },
       signal: controller.signal,
@@ -405,11 +406,17 @@ async function apiRequest<T = unknown>(
         // Bun + Cloudflare Brotli bug: chunked br responses hang for ~40s.
         // Force identity encoding to avoid the issue.
         "Accept-Encoding": "gzip, deflate",
+        "User-Agent": getUnbrowseUserAgent(),
         ...(key ? { Authorization: `Bearer ${key}` } : {}),
       },
       body: body ? JSON.stringify(body) : undefined,
       signal: controller.signal,
     });
+  } catch (err) {
+    const aborted = (err as Error).name === "AbortError";
+    throw unbrowseError(aborted ? "timeout" : "network", aborted
+      ? `API request to ${path} timed out`
+      : `API request to ${path} failed: ${(err as Error).message}`);
   } finally {
     clearTimeout(timer);
   }
@@ -418,14 +425,14 @@ async function apiRequest<T = unknown>(
     data = await res.json() as T & { error?: string };
   } catch {
     // Backend returned a non-JSON response (e.g. CF Worker error page)
-    throw new Error(`API error ${res.status} from ${path}`);
+    throw unbrowseError("api_error", `API error ${res.status} from ${path}`, { status: res.status });
   }
 
   // Handle ToS update required — tell user to restart
   if (res.status === 403 && (data as Record<string, unknown>).error === "tos_update_required") {
     console.warn("\n[unbrowse] The Terms of Service have been updated.");
     console.warn("[unbrowse] Please restart the unbrowse service to accept the new terms.");
-    throw new Error("ToS update required. Restart unbrowse to accept new terms.");
+    throw unbrowseError("tos_update_required", "ToS update required. Restart unbrowse to accept new terms.");
   }
 
   // Handle x402 payment required — surface payment terms to the caller
@@ -437,17 +444,24 @@ async function apiRequest<T = unknown>(
       : legacyPaymentTerms
         ? JSON.parse(legacyPaymentTerms)
         : (data as Record<string, unknown>).terms;
-    const err = new Error(`Payment required: ${(data as Record<string, unknown>).error ?? "This skill requires payment"}`);
-    (err as Error & { x402: boolean; terms: unknown; status: number }).x402 = true;
-    (err as Error & { terms: unknown }).terms = terms;
-    (err as Error & { status: number }).status = 402;
-    throw err;
+    const serverError = (data as Record<string, unknown>).error;
+    throw unbrowseError(
+      "payment_required",
+      `Payment required: ${serverError ?? "This skill requires payment"}`,
+      {
+        x402: true,
+        terms,
+        status: 402,
+        payment: parseX402Challenge(terms, res.headers.get("WWW-Authenticate")),
+        ...(typeof serverError === "string" ? { server_error: serverError } : {}),
+      },
+    );
   }
 
   if (!res.ok) {
     const errData = data as { error?: string; details?: string[] };
     const msg = errData.details?.length ? `${errData.error}: ${errData.details.join("; ")}` : errData.error ?? `API HTTP ${res.status}`;
-    throw new Error(msg);
+    throw unbrowseError("api_error", msg, { status: res.status, ...(errData.error ? { server_error: errData.error } : {}) });
   }
   return { data: data as T, headers: res.headers };
 }
@@ -733,18 +747,61 @@ export function findExistingSkillForDomain(domain: string, intent?: string): Ski
   return null;
 }
 
-export async function getSkill(skillId: string, scopeId?: string): Promise<SkillManifest | null> {
-  const recent = getRecentLocalSkill(skillId, scopeId ?? process.env.UNBROWSE_CLIENT_ID);
-  if (recent) return recent;
+const DEFAULT_SKILL_CACHE_TTL_SECS = 300;
+
+function getSkillCacheTtlMs(): number {
+  const raw = process.env.UNBROWSE_SKILL_CACHE_TTL_SECS?.trim();
+  const configured = raw ? Number(raw) : NaN;
+  return (Number.isFinite(configured) && configured >= 0 ? configured : DEFAULT_SKILL_CACHE_TTL_SECS) * 1000;
+}
+
+const SKILL_CACHE_TTL_MS = getSkillCacheTtlMs();
+
+export interface GetSkillOptions {
+  /** How long an on-disk cached skill counts as fresh. Defaults to UNBROWSE_SKILL_CACHE_TTL_SECS (300s). */
+  cache_ttl_ms?: number;
+  /** Skip the in-memory and on-disk caches and always hit the marketplace. */
+  force_refresh?: boolean;
+  /** Called with the typed reason when the marketplace fetch fails (payment required, already owned, ...). */
+  on_error?: (failure: MarketplaceFailure) => void;
+}
+
+function skillCacheAgeMs(skillId: string): number | null {
+  try {
+    return Date.now() - statSync(skillCachePath(skillId)).mtimeMs;
+  } catch { return null; }
+}
+
+/**
+ * Fetch a skill, preferring a fresh local copy. A failed network fetch falls
+ * back to the cached copy (however stale) instead of returning null, and never
+ * touches local state.
+ */
+export async function getSkill(skillId: string, scopeId?: string, opts: GetSkillOptions = {}): Promise<SkillManifest | null> {
+  if (!opts.force_refresh) {
+    const recent = getRecentLocalSkill(skillId, scopeId ?? process.env.UNBROWSE_CLIENT_ID);
+    if (recent) return recent;
+  }
   if (LOCAL_ONLY) {
     return readSkillCache(skillId);
   }
+  const ttlMs = opts.cache_ttl_ms ?? SKILL_CACHE_TTL_MS;
+  if (!opts.force_refresh) {
+    const age = skillCacheAgeMs(skillId);
+    if (age != null && age < ttlMs) {
+      const cached = readSkillCache(skillId);
+      if (cached) return cached;
+    }
+  }
   try {
     const skill = await api<SkillManifest>("GET", `/v1/skills/${skillId}`, undefined, { noAuth: true });
     writeSkillCache(skill, scopeId);
     return skill;
-  } catch {
-    return null;
+  } catch (err) {
+    opts.on_error?.(classifyMarketplaceError(err));
+    const cached = readSkillCache(skillId);
+    if (cached) console.warn(`[cache] marketplace fetch failed for ${skillId}; using cached copy`);
+    return cached;
   }
 }
 
@@ -757,7 +814,7 @@ export async function getSkillChunk(
     max_operations?: number;
   }
 ): Promise<AgentSkillChunkView> {
-  if (LOCAL_ONLY) throw new Error("local-only mode does not support remote chunk fetch");
+  if (LOCAL_ONLY) throw unbrowseError("local_only", "local-only mode does not support remote chunk fetch");
   return api("POST", `/v1/skills/${skillId}/chunk`, opts ?? {});
 }
 
@@ -780,6 +837,59 @@ export async function listSkills(): Promise<SkillManifest[]> {
   return data.skills;
 }
 
+export type MarketplaceSection = "trending" | "featured";
+
+/** Marketplace home: each section is whatever its endpoint returned; failures land in `warnings`. */
+export interface HomeFeed {
+  trending: SkillManifest[];
+  featured: SkillManifest[];
+  warnings: Array<{ section: MarketplaceSection; error: string; code: UnbrowseErrorCode; status?: number }>;
+}
+
+/**
+ * One marketplace listing section. A 404 means the index doesn't offer it and
+ * yields an empty list; other failures throw a coded error carrying
+ * `section` and `status` so callers can downgrade them to a warning.
+ */
+async function getMarketplaceSection(section: MarketplaceSection, limit?: number): Promise<SkillManifest[]> {
+  if (LOCAL_ONLY) return [];
+  const qs = limit ? `?limit=${limit}` : "";
+  try {
+    const data = await api<{ skills?: SkillManifest[] }>("GET", `/v1/skills/${section}${qs}`);
+    return data.skills ?? [];
+  } catch (err) {
+    const status = (err as { status?: number }).status;
+    if (status === 404) return [];
+    throw unbrowseError(getErrorCode(err), `Marketplace ${section} unavailable: ${(err as Error).message}`, { section, status });
+  }
+}
+
+export async function getTrendingSkills(limit?: number): Promise<SkillManifest[]> {
+  return getMarketplaceSection("trending", limit);
+}
+
+export async function getFeaturedSkills(limit?: number): Promise<SkillManifest[]> {
+  return getMarketplaceSection("featured", limit);
+}
+
+/** Trending and featured fetched concurrently; one failing section doesn't blank the other. */
+export async function getMarketplaceHome(limit?: number): Promise<HomeFeed> {
+  const sections: MarketplaceSection[] = ["trending", "featured"];
+  const results = await Promise.allSettled(sections.map((section) => getMarketplaceSection(section, limit)));
+  const feed: HomeFeed = { trending: [], featured: [], warnings: [] };
+  results.forEach((result, i) => {
+    const section = sections[i];
+    if (result.status === "fulfilled") {
+      feed[section] = result.value;
+      return;
+    }
+    const { error, code } = errorPayload(result.reason);
+    const status = (result.reason as { status?: number }).status;
+    feed.warnings.push({ section, error, code, ...(status ? { status } : {}) });
+  });
+  return feed;
+}
+
 export async function publishSkill(
   draft: Omit<SkillManifest, "skill_id" | "created_at" | "updated_at" | "version"> & {
     skill_id?: string;
@@ -797,10 +907,12 @@ export async function publishSkill(
       warnings: ["skipped_publish_empty_endpoints"],
     } as SkillManifest & { warnings: string[] };
   }
-  if (LOCAL_ONLY) throw new Error("local-only mode");
+  if (LOCAL_ONLY) throw unbrowseError("local_only", "local-only mode");
   const wallet = getLocalWalletContext();
   const published = await api<SkillManifest & { warnings: string[] }>("POST", "/v1/skills", {
     ...draft,
+    // Captured examples are the user's own data — never publish them unmasked.
+    endpoints: draft.endpoints.map((ep) => redactEndpointExamples(ep)),
     ...(wallet.wallet_address ? wallet : {}),
   }, { timeoutMs: PUBLISH_TIMEOUT_MS });
 
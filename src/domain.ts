@@ -1,28 +1,35 @@
 /**
- * Shared domain utilities for ccTLD-aware domain parsing and suffix matching.
+ * Shared domain utilities for Public Suffix List–aware domain parsing and suffix matching.
  */
 
-/** Common ccTLD patterns (country-code second-level domains). */
-const CC_TLDS = new Set([
-  "co.uk", "co.nz", "co.jp", "co.kr", "co.in",
-  "com.br", "com.au", "com.cn", "com.mx", "com.ar", "com.tw",
-  "org.uk", "gov.uk", "ac.uk", "net.au", "org.au",
-]);
+import { getDomain, getPublicSuffix } from "tldts";
+
+// The private section matters: alice.github.io and bob.github.io are
+// different tenants, not one "github.io" site.
+const PSL_OPTIONS = { allowPrivateDomains: true };
+
+/** True when `suffix` (lowercase, no leading dot) is a public or shared-hosting suffix. */
+export function isPublicSuffix(suffix: string): boolean {
+  return suffix.length > 0 && getPublicSuffix(suffix, PSL_OPTIONS) === suffix;
+}
 
 /**
- * Get the registrable domain, handling ccTLDs like .co.uk, .com.br, etc.
+ * Get the registrable domain (eTLD+1) per the Public Suffix List, including
+ * shared hosts like github.io, where each tenant is its own site.
  * e.g., "api.example.co.uk" → "example.co.uk"
+ *        "www.example.com.sg" → "example.com.sg"
  *        "sub.example.com"   → "example.com"
+ *        "docs.alice.github.io" → "alice.github.io"
+ * Hosts with no registrable part (IPs, localhost, bare suffixes) come back as-is.
  */
 export function getRegistrableDomain(hostname: string): string {
-  const parts = hostname.split(".");
-  if (parts.length >= 3) {
-    const lastTwo = parts.slice(-2).join(".");
-    if (CC_TLDS.has(lastTwo)) {
-      return parts.slice(-3).join(".");
-    }
-  }
-  return parts.slice(-2).join(".");
+  const host = hostname.toLowerCase().replace(/\.$/, "");
+  return getDomain(host, PSL_OPTIONS) ?? host;
+}
+
+/** True when both hostnames share the same registrable domain (api.x.co.uk ≡ www.x.co.uk). */
+export function isSameRegistrableDomain(a: string, b: string): boolean {
+  return getRegistrableDomain(a) === getRegistrableDomain(b);
 }
 
 /**
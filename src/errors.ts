@@ -0,0 +1,59 @@
+/**
+ * Stable error codes for API/CLI callers.
+ *
+ * Errors stay plain `Error`s (same shape as the existing `auth_required`
+ * captures) with a `code` attached, so catch sites can branch on
+ * `getErrorCode(err)` instead of matching message strings.
+ */
+
+const ERROR_CODES = [
+  "keychain_unavailable",
+  "keychain_access_denied",
+  "vault_not_found",
+  "vault_locked",
+  "decryption_failed",
+  "har_parse",
+  "network",
+  "timeout",
+  "browser_timeout",
+  "cancelled",
+  "api_error",
+  "tos_update_required",
+  "payment_required",
+  "already_owned",
+  "auth_required",
+  "local_only",
+  "invalid_input",
+  "internal",
+] as const;
+
+export type UnbrowseErrorCode = typeof ERROR_CODES[number];
+
+export type UnbrowseError = Error & { code: UnbrowseErrorCode };
+
+const KNOWN_CODES: ReadonlySet<string> = new Set(ERROR_CODES);
+
+/** Build an Error carrying a stable code plus any extra context fields. */
+export function unbrowseError(
+  code: UnbrowseErrorCode,
+  message: string,
+  extra?: Record<string, unknown>,
+): UnbrowseError {
+  return Object.assign(new Error(message), { ...extra, code });
+}
+
+/** Stable code for any thrown value; fetch aborts map to "timeout", unknown errors to "internal". */
+export function getErrorCode(error: unknown): UnbrowseErrorCode {
+  const code = (error as { code?: unknown } | null)?.code;
+  if (typeof code === "string" && KNOWN_CODES.has(code as UnbrowseErrorCode)) return code as UnbrowseErrorCode;
+  if (error instanceof Error && (error.name === "AbortError" || error.name === "TimeoutError")) return "timeout";
+  return "internal";
+}
+
+/** JSON body for route error replies: `{ error, code }`. */
+export function errorPayload(error: unknown): { error: string; code: UnbrowseErrorCode } {
+  return {
+    error: error instanceof Error ? error.message : String(error),
+    code: getErrorCode(error),
+  };
+}
@@ -0,0 +1,81 @@
+/**
+ * Evidence-based endpoint ranking.
+ *
+ * rankEndpoints scores relevance to an intent; this ranks on what was actually
+ * observed: did the endpoint ever answer 2xx, is it a safe GET, does it return
+ * JSON, how many path params must be filled, and how often the page called it
+ * during capture. Deterministic (ties break on endpoint_id) and exposes every
+ * score component, so the executor and `unbrowse skill <id> --rank` agree.
+ */
+
+import type { EndpointDescriptor } from "../types/index.js";
+
+export interface EndpointRankComponents {
+  /** Returned 2xx during capture or was verified since */
+  ever_succeeded: boolean;
+  /** GET with safe idempotency */
+  safe_get: boolean;
+  /** Response was JSON (schema inferred or JSON content-type at capture) */
+  json: boolean;
+  path_param_count: number;
+  /** Times the page called the endpoint during capture (0 when unknown) */
+  capture_calls: number;
+}
+
+export interface EvidenceRankedEndpoint {
+  endpoint: EndpointDescriptor;
+  score: number;
+  components: EndpointRankComponents;
+}
+
+const PATH_PARAM = /\{[^}]+\}/g;
+
+function pathParamCount(urlTemplate: string): number {
+  const path = urlTemplate.split("?")[0];
+  return path.match(PATH_PARAM)?.length ?? 0;
+}
+
+/** True when there is positive evidence the endpoint has answered 2xx. */
+export function everSucceeded(ep: EndpointDescriptor): boolean {
+  return (ep.capture_stats?.ok_calls ?? 0) > 0 || ep.verification_status === "verified";
+}
+
+/**
+ * True when the endpoint was seen and never succeeded — captured calls all
+ * failed and nothing has verified it since. Unknown history is not a failure.
+ */
+export function neverSucceeded(ep: EndpointDescriptor): boolean {
+  return ep.capture_stats !== undefined && ep.capture_stats.ok_calls === 0 && ep.verification_status !== "verified";
+}
+
+export function endpointRankComponents(ep: EndpointDescriptor): EndpointRankComponents {
+  return {
+    ever_succeeded: everSucceeded(ep),
+    safe_get: ep.method === "GET" && ep.idempotency === "safe",
+    json: ep.capture_stats?.json ?? ep.response_schema !== undefined,
+    path_param_count: pathParamCount(ep.url_template),
+    capture_calls: ep.capture_stats?.calls ?? 0,
+  };
+}
+
+function scoreComponents(c: EndpointRankComponents): number {
+  let score = 0;
+  if (c.ever_succeeded) score += 10;
+  if (c.safe_get) score += 4;
+  if (c.json) score += 3;
+  score -= Math.min(c.path_param_count, 5);
+  // Diminishing returns: polling endpoints shouldn't swamp everything else.
+  score += Math.min(Math.log2(c.capture_calls + 1), 4);
+  return Math.round(score * 100) / 100;
+}
+
+/** Rank endpoints best-first by observed evidence. Disabled endpoints are excluded. */
+export function rankEndpointsByEvidence(endpoints: EndpointDescriptor[]): EvidenceRankedEndpoint[] {
+  return endpoints
+    .filter((ep) => ep.verification_status !== "disabled")
+    .map((endpoint) => {
+      const components = endpointRankComponents(endpoint);
+      return { endpoint, score: scoreComponents(components), components };
+    })
+    .sort((a, b) => b.score - a.score || a.endpoint.endpoint_id.localeCompare(b.endpoint.endpoint_id));
+}
@@ -1,11 +1,12 @@
 import { executeInBrowser, triggerAndIntercept } from "../capture/index.js";
 import { captureSession } from "../capture/index.js";
-import { extractEndpoints, extractAuthHeaders, type ExtractionContext } from "../reverse-engineer/index.js";
+import { deterministicEndpointId, detectAuthHeaderRotations, extractDeviceIdentifiers, extractEndpoints, extractAuthHeaders, extractVersionHeaders, type ExtractionContext } from "../reverse-engineer/index.js";
+import { apiResponseStatuses, authBundleHeaders, dedupeAuthHeaders, expandAuthHeaderAliases, findAuthChallenges, getHeaderValue, classifyAuth, summarizeLoginRedirects, storageBearerHeader, type AuthBundleHeaderFields, type AuthHeaderAlias } from "../reverse-engineer/auth-detect.js";
 import { scanBundlesForRoutes } from "../reverse-engineer/bundle-scanner.js";
 import { publishSkill, mergeEndpoints } from "../marketplace/index.js";
 import { updateEndpointScore } from "../marketplace/index.js";
 import { getCredential, storeCredential, deleteCredential } from "../vault/index.js";
-import { getStoredAuth, getAuthCookies, refreshAuthFromBrowser } from "../auth/index.js";
+import { getStoredAuth, getAuthCookies, getSessionCredential, mergeAuthBundle, refreshAuthFromBrowser, refreshAuthToken } from "../auth/index.js";
 import { resolvePreExecutionAuth } from "../auth/dependency-runtime.js";
 import { authRuntime } from "../auth/runtime.js";
 import { applyProjection, inferSchema } from "../transform/index.js";
@@ -13,13 +14,16 @@ import { detectSchemaDrift } from "../transform/drift.js";
 import { recordExecution, recordTransaction, cachePublishedSkill, findExistingSkillForDomain, getLocalWalletContext, updateEndpointSchema } from "../client/index.js";
 import { validateManifest } from "../client/index.js";
 import { withRetry, isRetryableStatus } from "./retry.js";
+import { everSucceeded, neverSucceeded } from "./evidence-rank.js";
+import { parseStreamBody, readResponseText } from "../transform/stream.js";
 import type { EndpointDescriptor, ExecutionOptions, ExecutionTrace, ProjectionOptions, SkillManifest } from "../types/index.js";
 import { nanoid } from "nanoid";
 import { getRegistrableDomain } from "../domain.js";
 import { extractFromDOM, extractFromDOMWithHint } from "../extraction/index.js";
 import { buildSkillOperationGraph, inferEndpointSemantic, resolveEndpointSemantic } from "../graph/index.js";
 import { augmentEndpointsWithAgent } from "../graph/agent-augment.js";
-import { log } from "../logger.js";
+import { log, logTrace, logWarn } from "../logger.js";
+import { applyUserAgentOverride, getDefaultRequestHeaders, getUserAgentOverride } from "../runtime/user-agent.js";
 import { TRACE_VERSION } from "../version.js";
 import { buildQueryBindingMap, extractTemplateQueryBindings, mergeContextTemplateParams } from "../template-params.js";
 import { assessIntentResult, projectIntentData } from "../intent-match.js";
@@ -38,6 +42,7 @@ import {
 } from "../orchestrator/index.js";
 import { checkPaymentRequirement } from "../payments/index.js";
 import { isAllowedByRobots } from "./robots.js";
+import { signRequest } from "./signing.js";
 /** Stamp every trace with the code version hash for telemetry tracking */
 function stampTrace(trace: ExecutionTrace): ExecutionTrace {
   trace.trace_version = TRACE_VERSION;
@@ -47,6 +52,11 @@ function stampTrace(trace: ExecutionTrace): ExecutionTrace {
 const DEFAULT_BROWSER_UA =
   "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/133.0.0.0 Safari/537.36";
 
+/** Replays look like the capturing browser unless UNBROWSE_USER_AGENT says otherwise. */
+function replayUserAgent(): string {
+  return getUserAgentOverride() ?? DEFAULT_BROWSER_UA;
+}
+
 // ---------------------------------------------------------------------------
 // Quality gate — validate extracted data before marketplace publishing
 // ---------------------------------------------------------------------------
@@ -249,7 +259,7 @@ export function resolveExecutionUrlTemplate(
   contextUrl?: string,
 ): string {
   if (!contextUrl) return endpoint.url_template;
-  if (endpoint.method !== "GET") return endpoint.url_template;
+  if ((endpoint.wire_method ?? endpoint.method) !== "GET") return endpoint.url_template;
   if (!isDocumentLikeUrl(endpoint.url_template)) return endpoint.url_template;
   if (endpoint.trigger_url && !isDocumentLikeUrl(endpoint.trigger_url)) return endpoint.url_template;
   return contextUrl;
@@ -259,7 +269,7 @@ export function shouldIgnoreLearnedBrowserStrategy(
   endpoint: EndpointDescriptor,
   resolvedUrl: string,
 ): boolean {
-  return endpoint.method === "GET" && !endpoint.dom_extraction && !isDocumentLikeUrl(resolvedUrl);
+  return (endpoint.wire_method ?? endpoint.method) === "GET" && !endpoint.dom_extraction && !isDocumentLikeUrl(resolvedUrl);
 }
 
 function deriveStructuredDataReplay(url: string, mode: "concrete" | "template"): string {
@@ -558,13 +568,13 @@ export function buildStructuredReplayHeaders(
       replayTarget.hostname !== originalTarget.hostname ||
       /\/api\/|graphql|\/rest\/|\/rpc\/|\/v\d+\//i.test(replayTarget.pathname);
     if (needsApiReplayHeaders) {
-      headers["user-agent"] ??= DEFAULT_BROWSER_UA;
+      headers["user-agent"] ??= replayUserAgent();
       headers["accept-language"] ??= "en-US,en;q=0.9";
       headers["referer"] ??= originalTarget.toString();
       headers["accept"] ??= "application/json,text/plain,*/*";
     }
     if (host === "reddit.com" || host === "old.reddit.com" || host === "np.reddit.com") {
-      headers["user-agent"] ??= DEFAULT_BROWSER_UA;
+      headers["user-agent"] ??= replayUserAgent();
       headers["accept-language"] ??= "en-US,en;q=0.9";
       headers["referer"] ??= originalTarget.toString();
       headers["accept"] = "application/json,text/plain,*/*";
@@ -575,6 +585,24 @@ export function buildStructuredReplayHeaders(
   return headers;
 }
 
+/**
+ * Apply per-call header_overrides and remove_headers (ExecutionOptions)
+ * in place, after auth has been layered in.
+ */
+function applyHeaderOverrides(headers: Record<string, string>, options?: ExecutionOptions): Record<string, string> {
+  for (const [key, value] of Object.entries(options?.header_overrides ?? {})) {
+    for (const existing of Object.keys(headers)) {
+      if (existing.toLowerCase() === key.toLowerCase()) delete headers[existing];
+    }
+    headers[key.toLowerCase()] = value;
+  }
+  const removed = new Set((options?.remove_headers ?? []).map((h) => h.toLowerCase()));
+  for (const key of Object.keys(headers)) {
+    if (removed.has(key.toLowerCase())) delete headers[key];
+  }
+  return headers;
+}
+
 function normalizeReplayHeaders(
   ...bags: Array<Record<string, string> | undefined>
 ): Record<string, string> {
@@ -590,6 +618,35 @@ function normalizeReplayHeaders(
   return normalized;
 }
 
+/**
+ * Encode a templated request body the way it was captured: urlencoded and
+ * multipart bodies are re-serialized as forms, everything else as JSON.
+ * For multipart the stale boundary header is dropped so fetch sets a fresh one.
+ */
+function encodeRequestBody(
+  body: Record<string, unknown> | undefined,
+  headers: Record<string, string>,
+): string | FormData | undefined {
+  if (!body) return undefined;
+  const ct = (headers["content-type"] ?? "").toLowerCase();
+  if (ct.includes("application/x-www-form-urlencoded")) {
+    const form = new URLSearchParams();
+    for (const [k, v] of Object.entries(body)) {
+      if (v != null) form.append(k, typeof v === "string" ? v : JSON.stringify(v));
+    }
+    return form.toString();
+  }
+  if (ct.includes("multipart/form-data")) {
+    const form = new FormData();
+    for (const [k, v] of Object.entries(body)) {
+      if (v != null) form.append(k, typeof v === "string" ? v : JSON.stringify(v));
+    }
+    delete headers["content-type"];
+    return form;
+  }
+  return JSON.stringify(body);
+}
+
 function shouldFallbackToBrowserReplay(
   data: unknown,
   endpoint: EndpointDescriptor,
@@ -865,8 +922,9 @@ async function trySeedPublicDocumentFetchSkill(
 ): Promise<ExecutionResult | undefined> {
   const headers: Record<string, string> = {
     accept: "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8",
-    "user-agent": DEFAULT_BROWSER_UA,
+    "user-agent": replayUserAgent(),
     "accept-language": "en-US,en;q=0.9",
+    ...getDefaultRequestHeaders(),
     ...(authHeaders ?? {}),
   };
   if (cookies && cookies.length > 0) {
@@ -1106,7 +1164,7 @@ async function executeBrowserCapture(
     };
   }
 
-  const endpoints = extractEndpoints(captured.requests, captured.ws_messages, { pageUrl: url, finalUrl: captured.final_url, intent });
+  const endpoints = extractEndpoints(captured.requests, captured.ws_messages, { pageUrl: url, finalUrl: captured.final_url, intent, statuses: options?.statuses });
 
   // Detect structured search forms from captured HTML and attach to search-like endpoints
   if (captured.html) {
@@ -1178,7 +1236,7 @@ async function executeBrowserCapture(
       }
 
       endpoints.push({
-        endpoint_id: nanoid(),
+        endpoint_id: deterministicEndpointId("GET", epUrl),
         method: "GET",
         url_template: epUrl,
         query: epQuery,
@@ -1210,14 +1268,41 @@ async function executeBrowserCapture(
   // extractAuthHeaders collects everything sanitizeHeaders strips from skill manifests
   // (authorization, x-csrf-token, api keys, etc.) — stored encrypted in vault.
   let auth_profile_ref: string | undefined;
-  const capturedAuthHeaders = extractAuthHeaders(captured.requests);
-
-  if ((captured.cookies && captured.cookies.length > 0) || Object.keys(capturedAuthHeaders).length > 0) {
+  const observedAuthHeaders = extractAuthHeaders(captured.requests);
+  const rotated_auth_headers = Object.keys(detectAuthHeaderRotations(captured.requests));
+  if (rotated_auth_headers.length > 0) {
+    log("execution", `auth rotated mid-capture on ${captured.domain} (${rotated_auth_headers.join(", ")}) — keeping latest value`);
+  }
+  // SPAs that keep their token in web storage may not send it during an idle
+  // capture — synthesize the bearer header they would send.
+  if (!getHeaderValue(observedAuthHeaders, "authorization") && captured.storage_tokens?.length) {
+    Object.assign(observedAuthHeaders, storageBearerHeader(captured.storage_tokens));
+    log("execution", `using ${captured.storage_tokens[0].storage}Storage token "${captured.storage_tokens[0].key}" as bearer auth for ${captured.domain}`);
+  }
+  const { headers: capturedAuthHeaders, header_aliases } = dedupeAuthHeaders(observedAuthHeaders);
+  // Stable per-install ids sent next to the token — context, not credentials.
+  const device_identifiers = extractDeviceIdentifiers(captured.requests);
+  const version_headers = extractVersionHeaders(captured.requests);
+  // 401 challenges name the expected scheme even when no token was ever sent.
+  const authChallenges = findAuthChallenges(captured.requests);
+  // With no credentials at all, response statuses tell a public API ("none")
+  // from one that refused every call — the latter is left unlabelled as before.
+  const classified = classifyAuth(capturedAuthHeaders, captured.cookies ?? [], authChallenges, apiResponseStatuses(captured.requests));
+  const capturedClassification = classified.scheme === "unknown" ? undefined : classified;
+
+  // Device ids are stripped from manifest headers, so they need a bundle even without auth.
+  if ((captured.cookies && captured.cookies.length > 0) || Object.keys(capturedAuthHeaders).length > 0 || Object.keys(device_identifiers).length > 0) {
     auth_profile_ref = `${domain}-session`;
-    await storeCredential(auth_profile_ref, JSON.stringify({
+    // Merge rather than overwrite — a re-capture must not drop headers the
+    // previous session had (e.g. one minted via auth_refresh) or hand-added fields.
+    let existingBundle: Record<string, unknown> | null = null;
+    try { existingBundle = JSON.parse((await getCredential(auth_profile_ref)) ?? "null"); } catch { /* replace malformed bundle */ }
+    await storeCredential(auth_profile_ref, JSON.stringify(mergeAuthBundle(existingBundle, {
       cookies: captured.cookies ?? [],
       headers: Object.keys(capturedAuthHeaders).length > 0 ? capturedAuthHeaders : undefined,
-    }));
+      ...(header_aliases ? { header_aliases } : {}),
+      ...(Object.keys(device_identifiers).length > 0 ? { context: { device_identifiers } } : {}),
+    })), { source_url: url, captured_at: startedAt });
   }
 
   // BUG-004 fix: set auth_profile_ref when vault has stored auth for this domain
@@ -1226,7 +1311,8 @@ async function executeBrowserCapture(
     const hasStoredAuth = (await getCredential(vaultKey)) != null;
     if (hasStoredAuth) auth_profile_ref = vaultKey;
   }
-  const authBackedCapture = usedStoredAuth || !!auth_profile_ref;
+  const deviceIdOnlyBundle = auth_profile_ref === `${domain}-session` && !(captured.cookies?.length) && Object.keys(capturedAuthHeaders).length === 0;
+  const authBackedCapture = usedStoredAuth || (!!auth_profile_ref && !deviceIdOnlyBundle);
   if (authBackedCapture) {
     for (const endpoint of cleanEndpoints) {
       endpoint.semantic = {
@@ -1351,6 +1437,32 @@ async function executeBrowserCapture(
       };
     }
 
+    // No API-looking response at all: say whether the page was idle or the
+    // traffic it did make was all non-API (filters / path rules to check).
+    const traffic = captured.traffic;
+    if (traffic && traffic.api_requests === 0) {
+      const hint = traffic.total_requests > 0
+        ? `${traffic.total_requests} non-API request(s) were seen, so the API may be filtered out (check UNBROWSE_API_PATH_PATTERNS / capture filters).`
+        : "The page made no network requests; it may be idle, lazy-loading, or still redirecting.";
+      const trace: ExecutionTrace = stampTrace({
+        trace_id: traceId,
+        skill_id: skill.skill_id,
+        endpoint_id: "browser-capture",
+        started_at: startedAt,
+        completed_at: new Date().toISOString(),
+        success: false,
+        error: "no_traffic_captured",
+      });
+      return {
+        trace,
+        result: {
+          error: "no_traffic_captured",
+          message: `No API traffic captured at ${url} after ${traffic.empty_retries} retr${traffic.empty_retries === 1 ? "y" : "ies"}. ${hint}`,
+          traffic,
+        },
+      };
+    }
+
     const trace: ExecutionTrace = stampTrace({
       trace_id: traceId,
       skill_id: skill.skill_id,
@@ -1372,6 +1484,13 @@ async function executeBrowserCapture(
   // Reuse existing skill for this domain to preserve skill_id and learned exec_strategy.
   // This prevents duplicate skills accumulating in the marketplace on re-captures.
   const existingSkill = findExistingSkillForDomain(domain, intent);
+  // "none" only when the calls really went out anonymously: not on stored
+  // credentials, and an anonymous re-capture doesn't make an authenticated
+  // skill public (same guard as captureUrlsAndMerge).
+  const keepExistingAuth = capturedClassification?.scheme === "none"
+    && (authBackedCapture || (!!existingSkill?.auth_method && existingSkill.auth_scheme !== "none"));
+  const authClassification = keepExistingAuth ? undefined : capturedClassification;
+  const auth_method = authClassification?.label;
   if (existingSkill) {
     // Carry forward learned exec_strategy from old endpoints to matching new ones
     for (const ep of cleanEndpoints) {
@@ -1416,6 +1535,15 @@ async function executeBrowserCapture(
     endpoints: localEndpoints,
     intents: Array.from(new Set([...(existingSkill?.intents ?? []), intent])),
     ...(auth_profile_ref ? { auth_profile_ref } : {}),
+    ...(authClassification ? { auth_method, auth_scheme: authClassification.scheme } : {}),
+    ...(keepExistingAuth && existingSkill?.auth_method ? { auth_method: existingSkill.auth_method, auth_scheme: existingSkill.auth_scheme } : {}),
+    ...(authClassification?.signed_requests ? { signed_requests: true } : {}),
+    ...(authChallenges.length > 0 ? { auth_challenge: authChallenges[0] } : {}),
+    ...(rotated_auth_headers.length > 0 ? { rotated_auth_headers } : {}),
+    ...(auth_profile_ref && Object.keys(device_identifiers).length > 0 ? { device_identifier_headers: Object.keys(device_identifiers) } : {}),
+    ...(Object.keys(version_headers).length > 0 ? { version_headers } : {}),
+    // Refresh config is hand-tuned or detected once; a re-capture keeps it.
+    ...(existingSkill?.auth_refresh ? { auth_refresh: existingSkill.auth_refresh } : {}),
   };
   // Generate local descriptions immediately so BM25 ranking works on first cache hit
   for (const ep of localDraft.endpoints) {
@@ -1477,12 +1605,19 @@ async function executeBrowserCapture(
   // Only flag this when no auth was used (so a retry with auth has a chance of succeeding).
   const hasMeaningfulEndpoint = cleanEndpoints.some((ep) => isSupportEvidenceEndpoint(ep));
   const authRecommended = !usedStoredAuth && !hasMeaningfulEndpoint && !inferredOnlyCapture;
+  // Requests bounced to a login page mean the session was logged out, whatever else was captured.
+  const loginRedirects = summarizeLoginRedirects(captured.requests);
 
   return {
     trace,
     result: {
       ...(trace.result as Record<string, unknown>),
-      ...(authRecommended ? {
+      ...(loginRedirects.auth_required ? {
+        login_redirects: loginRedirects,
+        auth_recommended: true,
+        auth_hint: `${loginRedirects.login_redirect_count} request(s) redirected to ${loginRedirects.login_url} — this capture ran logged out. ` +
+          `Log in (unbrowse login) or store browser cookies for ${domain}, then retry this capture.`,
+      } : authRecommended ? {
         auth_recommended: true,
         auth_hint: `No data endpoints found — ${domain} likely requires authentication. ` +
           `Store browser cookies for this domain via the auth endpoints, then retry this capture.`,
@@ -1768,17 +1903,17 @@ export async function executeEndpoint(
   const startedAt = new Date().toISOString();
   const authHeaders: Record<string, string> = {};
   const cookies: Array<{ name: string; value: string; domain: string }> = [];
+  // HMAC key for endpoints with request_signing; stored alongside the bundle's headers
+  let signingSecret: string | undefined;
 
   if (skill.auth_profile_ref) {
     const stored = await getCredential(skill.auth_profile_ref);
     if (stored) {
       try {
-        const parsed = JSON.parse(stored) as {
-          headers?: Record<string, string>;
-          cookies?: typeof cookies;
-        };
-        Object.assign(authHeaders, parsed.headers ?? {});
+        const parsed = JSON.parse(stored) as AuthBundleHeaderFields & { cookies?: typeof cookies; signing_secret?: string };
+        Object.assign(authHeaders, authBundleHeaders(parsed));
         cookies.push(...(parsed.cookies ?? []));
+        if (typeof parsed.signing_secret === "string" && parsed.signing_secret) signingSecret = parsed.signing_secret;
       } catch {
         // malformed stored cred — skip
       }
@@ -1806,11 +1941,17 @@ export async function executeEndpoint(
   // These are captured during browser-capture and stored alongside cookies.
   if (Object.keys(authHeaders).length === 0) {
     try {
-      const sessionKey = `${getRegistrableDomain(epDomain)}-session`;
-      const sessionData = await getCredential(sessionKey);
-      if (sessionData) {
-        const parsed = JSON.parse(sessionData) as { headers?: Record<string, string>; cookies?: typeof cookies };
-        if (parsed.headers) Object.assign(authHeaders, parsed.headers);
+      const session = await getSessionCredential(epDomain);
+      if (session) {
+        const parsed = JSON.parse(session.value) as {
+          headers?: Record<string, string>;
+          header_aliases?: Record<string, AuthHeaderAlias>;
+          cookies?: typeof cookies;
+          context?: { device_identifiers?: Record<string, string> };
+        };
+        // Device identifiers ride along even for cookie-only bundles.
+        Object.assign(authHeaders, parsed.context?.device_identifiers ?? {});
+        if (parsed.headers) Object.assign(authHeaders, expandAuthHeaderAliases(parsed.headers, parsed.header_aliases));
         if (parsed.cookies && cookies.length === 0) cookies.push(...parsed.cookies);
       }
     } catch { /* skip */ }
@@ -1857,10 +1998,53 @@ export async function executeEndpoint(
       // URL parse failure — skip query merge
     }
   }
+  // Per-request signatures (AWS SigV4) can't be replayed from the captured
+  // Authorization value and unbrowse has no key to re-sign with; fail here
+  // rather than send a stale signature the API will reject.
+  if (skill.signed_requests && !endpoint.request_signing) {
+    log("exec", `endpoint ${endpoint.endpoint_id}: skill ${skill.skill_id} uses per-request signatures (${skill.auth_method ?? "signed"}); not replaying`);
+    return {
+      trace: stampTrace({
+        trace_id: nanoid(),
+        skill_id: skill.skill_id,
+        endpoint_id: endpoint.endpoint_id,
+        started_at: new Date().toISOString(),
+        completed_at: new Date().toISOString(),
+        success: false,
+        error: "signed_requests_unsupported",
+      }),
+      result: {
+        error: "signed_requests_unsupported",
+        message: `${skill.domain} signs every request (${skill.auth_method ?? "per-request signature"}); a captured signature can't be replayed. Use force_capture to run it in the browser.`,
+      },
+    };
+  }
+  // Credential fields were never stored — without them the literal "{password}" would be sent.
+  const missingInputs = (endpoint.required_body_params ?? []).filter((k) => mergedParams[k] == null || mergedParams[k] === "");
+  if (endpoint.body && missingInputs.length > 0) {
+    return {
+      trace: stampTrace({
+        trace_id: nanoid(),
+        skill_id: skill.skill_id,
+        endpoint_id: endpoint.endpoint_id,
+        started_at: new Date().toISOString(),
+        completed_at: new Date().toISOString(),
+        success: false,
+        error: "missing_input",
+      }),
+      result: {
+        error: "missing_input",
+        message: `Missing input for ${endpoint.method} ${endpoint.url_template}: ${missingInputs.join(", ")}. These fields are never stored; pass them as params.`,
+        missing: missingInputs,
+      },
+    };
+  }
   let url = interpolate(urlTemplate, mergedParams);
   const body = endpoint.body ? interpolateObj(endpoint.body, mergedParams) : undefined;
 
-  const isSafe = endpoint.method === "GET";
+  // GET-only paths (query appending, document replay, trigger-intercept) follow
+  // the wire method, so a tunnelled GET is replayed as the POST it was.
+  const isSafe = (endpoint.wire_method ?? endpoint.method) === "GET";
 
   // Append leftover params as query string on GET requests.
   // Params already consumed by path_params, endpoint.query, or {template} vars are skipped.
@@ -1932,18 +2116,26 @@ export async function executeEndpoint(
   const hasStructuredReplay = structuredReplayUrl !== url;
 
   const serverFetch = async (): Promise<{ data: unknown; status: number; trace_id: string }> => {
+    const versionHeaders = normalizeReplayHeaders(skill.version_headers);
     const endpointHeaders = normalizeReplayHeaders(endpoint.headers_template);
     const sessionHeaders = normalizeReplayHeaders(authHeaders);
+    const requestHeaders = normalizeReplayHeaders(options?.extra_headers);
+    const configuredHeaders = normalizeReplayHeaders(getDefaultRequestHeaders());
 
     // Default accept to JSON, but never overwrite the endpoint's own accept header
     // (e.g. LinkedIn uses "application/vnd.linkedin.normalized+json+2.1")
-    const defaultAccept: Record<string, string> = (!endpoint.dom_extraction && !endpointHeaders["accept"] && !sessionHeaders["accept"])
+    const defaultAccept: Record<string, string> = (!endpoint.dom_extraction && !endpointHeaders["accept"] && !sessionHeaders["accept"] && !versionHeaders["accept"])
       ? { "accept": "application/json" } : {};
-    const headers: Record<string, string> = {
+    // UNBROWSE_DEFAULT_HEADERS sit under everything captured; the UA override
+    // sits over it, since templates keep the capturing browser's user-agent.
+    const headers: Record<string, string> = applyUserAgentOverride({
       ...defaultAccept,
+      ...configuredHeaders,
+      ...versionHeaders,
       ...endpointHeaders,
+      ...requestHeaders,
       ...sessionHeaders,
-    };
+    });
     // Strip browser-only headers that cause issues server-side
     delete headers["sec-ch-ua"];
     delete headers["sec-ch-ua-mobile"];
@@ -1987,21 +2179,41 @@ export async function executeEndpoint(
       }
     }
 
+    applyHeaderOverrides(headers, options);
+
     const replayUrls = hasStructuredReplay ? deriveStructuredDataReplayCandidates(structuredReplayUrl) : [structuredReplayUrl];
     let last: { data: unknown; status: number } = { data: null, status: 0 };
 
-    for (const replayUrl of replayUrls) {
+    for (let replayUrl of replayUrls) {
       const replayHeaders = buildStructuredReplayHeaders(url, replayUrl, headers);
+      const requestBody = encodeRequestBody(body, replayHeaders);
+      // A captured signature only matches its own timestamp — mint fresh ones per attempt.
+      if (endpoint.request_signing && signingSecret) {
+        const signed = signRequest(endpoint.request_signing, signingSecret, endpoint.wire_method ?? endpoint.method, replayUrl, typeof requestBody === "string" ? requestBody : undefined);
+        // Replace the captured signature/timestamp whatever their case, so the stale one isn't sent too.
+        for (const [name, value] of Object.entries(signed.headers)) {
+          for (const key of Object.keys(replayHeaders)) if (key.toLowerCase() === name.toLowerCase()) delete replayHeaders[key];
+          replayHeaders[name.toLowerCase()] = value;
+        }
+        if (Object.keys(signed.query).length > 0) {
+          const u = new URL(replayUrl);
+          for (const [k, v] of Object.entries(signed.query)) u.searchParams.set(k, v);
+          replayUrl = u.href;
+        }
+      }
       log("exec", `server-fetch: ${endpoint.method} ${replayUrl.substring(0, 80)} csrf=${replayHeaders["x-csrf-token"]?.substring(0, 10)}... cookies=${(replayHeaders["cookie"]?.length ?? 0)}chars`);
       const res = await fetch(replayUrl, {
-        method: endpoint.method,
+        // Tunnelled endpoints go out as their wire method (the override header/field is in the template)
+        method: endpoint.wire_method ?? endpoint.method,
         headers: replayHeaders,
-        body: body ? JSON.stringify(body) : undefined,
+        body: requestBody,
         redirect: "follow",
+        signal: options?.signal,
       });
       let data: unknown;
-      const text = await res.text();
-      try { data = JSON.parse(text); } catch { data = text; }
+      const text = await readResponseText(res);
+      logTrace("exec", `server-fetch ← ${res.status} ${replayUrl.substring(0, 80)} (${text.length}B)`);
+      try { data = JSON.parse(text); } catch { data = parseStreamBody(text, res.headers.get("content-type") ?? undefined)?.items ?? text; }
       last = { data, status: res.status };
       if (res.ok && !(typeof data === "string" && isHtml(data))) {
         return { data, status: res.status, trace_id: nanoid() };
@@ -2013,10 +2225,10 @@ export async function executeEndpoint(
 
   const browserCall = () => executeInBrowser(
     url,
-    endpoint.method,
-    endpoint.headers_template ?? {},
+    endpoint.wire_method ?? endpoint.method,
+    applyHeaderOverrides({ ...(endpoint.headers_template ?? {}), ...(options?.extra_headers ?? {}) }, options),
     body,
-    authHeaders,
+    applyHeaderOverrides({ ...authHeaders }, { remove_headers: options?.remove_headers }),
     cookies
   );
 
@@ -2165,7 +2377,7 @@ export async function executeEndpoint(
     if (strategy && result.status >= 200 && result.status < 400 && strategy !== endpoint.exec_strategy) {
       log("exec", `learned exec_strategy=${strategy} for endpoint ${endpoint.endpoint_id}`);
       endpoint.exec_strategy = strategy;
-      try { cachePublishedSkill(skill, options?.client_scope); } catch (e) { log("exec", `failed to cache strategy: ${e}`); }
+      try { cachePublishedSkill(skill, options?.client_scope); } catch (e) { logWarn("exec", `failed to cache strategy: ${e}`); }
     }
   } else if (isSafe) {
     // No auth: fetch-first for safe GETs — fall back to browser if SPA shell or error
@@ -2205,16 +2417,27 @@ export async function executeEndpoint(
   }
 
   // Stale credential detection: on 401/403, attempt auth recovery before giving up.
-  // Chain: authRuntime.refreshSession (lightweight) → refreshAuthFromBrowser (re-extract)
-  //        → authRuntime.loginIfNeeded (full interactive login)
+  // Chain: skill.auth_refresh (token endpoint) → authRuntime.refreshSession (lightweight)
+  //        → refreshAuthFromBrowser (re-extract) → authRuntime.loginIfNeeded (full interactive login)
   if (status === 401 || status === 403) {
     let authRecovered = false;
     try {
+      // 0. Refresh-token grant, when the skill declares one
+      if (skill.auth_refresh) {
+        const authRef = skill.auth_profile_ref ?? `${getRegistrableDomain(epDomain)}-session`;
+        if (await refreshAuthToken(skill.auth_refresh, authRef)) {
+          log("auth", `access token refreshed for ${epDomain}`);
+          authRecovered = true;
+        }
+      }
+
       // 1. Lightweight session refresh via authRuntime
-      const sessionRefreshed = await authRuntime.refreshSession(epDomain);
-      if (sessionRefreshed) {
-        log("auth", `session refreshed via authRuntime for ${epDomain} — retry should succeed`);
-        authRecovered = true;
+      if (!authRecovered) {
+        const sessionRefreshed = await authRuntime.refreshSession(epDomain);
+        if (sessionRefreshed) {
+          log("auth", `session refreshed via authRuntime for ${epDomain} — retry should succeed`);
+          authRecovered = true;
+        }
       }
 
       // 2. Re-extract cookies from browser SQLite (bird pattern)
@@ -2236,6 +2459,7 @@ export async function executeEndpoint(
       }
 
       if (authRecovered) {
+        trace.auth_refreshed = true;
         trace.error = `${trace.error} (credentials refreshed — retry should succeed)`;
       } else {
         // No recovery path worked — delete stale credentials
@@ -3055,6 +3279,12 @@ function selectBestEndpoint(endpoints: EndpointDescriptor[], intent?: string, sk
 
   const ranked = rankEndpoints(endpoints, intent, skillDomain, contextUrl);
   if (ranked.length === 0) throw new Error("All endpoints are disabled");
+  // Don't keep picking an endpoint whose captured calls all failed when a
+  // relevant (positively scored) one is known to have answered 2xx.
+  if (neverSucceeded(ranked[0].endpoint)) {
+    const proven = ranked.find((r) => r.score > 0 && everSucceeded(r.endpoint));
+    if (proven) return proven.endpoint;
+  }
   return ranked[0].endpoint;
 }
 
@@ -1,3 +1,5 @@
+import type { RateLimitInfo } from "../types/index.js";
+
 const RETRYABLE_STATUSES = new Set([500, 502, 503, 504, 429]);
 const MAX_RETRIES = 2;
 const BASE_DELAY_MS = 1000;
@@ -43,4 +45,113 @@ export async function withRetry<T>(
 
 export function isRetryableStatus(status: number): boolean {
   return RETRYABLE_STATUSES.has(status);
-}
\ No newline at end of file
+}
+
+/**
+ * Parse a Retry-After header (delta-seconds or HTTP-date) into a delay in ms.
+ * Returns undefined when absent or unparseable.
+ */
+export function parseRetryAfter(value: string | null | undefined, now = Date.now()): number | undefined {
+  if (!value) return undefined;
+  const trimmed = value.trim();
+  if (/^\d+(\.\d+)?$/.test(trimmed)) return Math.round(parseFloat(trimmed) * 1000);
+  const date = Date.parse(trimmed);
+  if (Number.isNaN(date)) return undefined;
+  return Math.max(0, date - now);
+}
+
+function headerLookup(headers: Record<string, string>): (name: string) => string | undefined {
+  const lower = new Map(Object.entries(headers).map(([k, v]) => [k.toLowerCase(), v]));
+  return (name) => lower.get(name);
+}
+
+function parseCount(value: string | undefined): number | undefined {
+  if (value == null) return undefined;
+  // Multi-policy values ("100, 100;w=60") — the first number is the active one.
+  const n = Number.parseInt(value.trim(), 10);
+  return Number.isFinite(n) && n >= 0 ? n : undefined;
+}
+
+/**
+ * Reset values are epoch seconds in the GitHub family and delta seconds in
+ * the IETF draft, but both appear in the wild under either name — anything
+ * past 2001-09-09 (1e9) is treated as an epoch.
+ */
+function parseReset(value: string | undefined, now: number): string | undefined {
+  const n = value != null ? Number(value.trim()) : NaN;
+  if (!Number.isFinite(n) || n < 0) return undefined;
+  return new Date(n > 1e9 ? n * 1000 : now + n * 1000).toISOString();
+}
+
+/**
+ * Rate-limit metadata from response headers: `X-RateLimit-*`, the IETF draft
+ * `RateLimit-*` (or the combined `RateLimit: limit=…, remaining=…, reset=…`
+ * field plus `RateLimit-Policy`), and `Retry-After`. Returns undefined when
+ * none are present.
+ */
+export function parseRateLimitHeaders(
+  headers: Record<string, string> | undefined,
+  now = Date.now(),
+): RateLimitInfo | undefined {
+  if (!headers) return undefined;
+  const get = headerLookup(headers);
+  const retryAfterMs = parseRetryAfter(get("retry-after"), now);
+  const retryAfter = retryAfterMs != null ? { retry_after_ms: retryAfterMs } : {};
+
+  const xLimit = get("x-ratelimit-limit") ?? get("x-rate-limit-limit");
+  const xRemaining = get("x-ratelimit-remaining") ?? get("x-rate-limit-remaining");
+  const xReset = get("x-ratelimit-reset") ?? get("x-rate-limit-reset");
+  if (xLimit != null || xRemaining != null || xReset != null) {
+    return compactRateLimit({
+      scheme: "x-ratelimit",
+      limit: parseCount(xLimit),
+      remaining: parseCount(xRemaining),
+      reset_at: parseReset(xReset, now),
+      ...retryAfter,
+    });
+  }
+
+  const combined = get("ratelimit");
+  const fields = new Map<string, string>();
+  for (const part of combined?.split(/[,;]/) ?? []) {
+    const [k, v] = part.split("=").map((x) => x.trim());
+    if (k && v != null) fields.set(k.toLowerCase(), v);
+  }
+  const limit = get("ratelimit-limit") ?? fields.get("limit");
+  const remaining = get("ratelimit-remaining") ?? fields.get("remaining");
+  const reset = get("ratelimit-reset") ?? fields.get("reset");
+  const window = get("ratelimit-policy")?.match(/;\s*w=(\d+)/)?.[1];
+  if (limit != null || remaining != null || reset != null) {
+    return compactRateLimit({
+      scheme: "ratelimit",
+      limit: parseCount(limit),
+      remaining: parseCount(remaining),
+      reset_at: parseReset(reset, now),
+      window_seconds: parseCount(window),
+      ...retryAfter,
+    });
+  }
+
+  return retryAfterMs != null ? { scheme: "retry-after", ...retryAfter } : undefined;
+}
+
+function compactRateLimit(info: RateLimitInfo): RateLimitInfo {
+  return Object.fromEntries(Object.entries(info).filter(([, v]) => v !== undefined)) as RateLimitInfo;
+}
+
+// Last request time per host, for min-interval throttling.
+const lastRequestAt = new Map<string, number>();
+
+/**
+ * Wait until at least `minIntervalMs` has passed since the previous request
+ * to the same host, then claim the slot. No-op when minIntervalMs <= 0.
+ */
+export async function waitForHostSlot(url: string, minIntervalMs: number): Promise<void> {
+  if (!(minIntervalMs > 0)) return;
+  let host: string;
+  try { host = new URL(url).host; } catch { return; }
+  const last = lastRequestAt.get(host);
+  const wait = last != null ? last + minIntervalMs - Date.now() : 0;
+  lastRequestAt.set(host, Date.now() + Math.max(0, wait));
+  if (wait > 0) await new Promise((r) => setTimeout(r, wait));
+}
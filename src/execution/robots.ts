@@ -10,6 +10,9 @@
  * treated as fully permissive.
  */
 
+import { getUnbrowseUserAgent } from "../runtime/user-agent.js";
+
+/** Product token matched against robots.txt User-agent lines */
 const USER_AGENT = "unbrowse";
 const TTL_MS = 24 * 60 * 60 * 1000; // 24 h
 
@@ -111,7 +114,7 @@ async function fetchRules(origin: string): Promise<AgentRules[]> {
 
   try {
     const res = await fetch(`${origin}/robots.txt`, {
-      headers: { "user-agent": USER_AGENT },
+      headers: { "user-agent": getUnbrowseUserAgent() },
       redirect: "follow",
       signal: AbortSignal.timeout(5000),
     });
@@ -132,7 +135,7 @@ async function fetchRules(origin: string): Promise<AgentRules[]> {
 }
 
 /**
- * Check whether the Unbrowse user-agent is allowed to access `url`.
+ * Check whether `userAgent` may fetch `path` on `baseUrl` per its robots.txt.
  *
  * Returns `true` (allowed) when:
  * - robots.txt cannot be fetched or parsed
@@ -143,19 +146,17 @@ async function fetchRules(origin: string): Promise<AgentRules[]> {
  * is a Disallow directive with a longer (or equal) prefix than any
  * matching Allow directive.
  */
-export async function isAllowedByRobots(url: string): Promise<boolean> {
+export async function checkRobots(baseUrl: string, path: string, userAgent: string = USER_AGENT): Promise<boolean> {
   let origin: string;
-  let pathname: string;
   try {
-    const parsed = new URL(url);
-    origin = parsed.origin;
-    pathname = parsed.pathname || "/";
+    origin = new URL(baseUrl).origin;
   } catch {
-    return true; // unparseable URL — let execution proceed
+    return true; // unparseable URL — let the caller proceed
   }
+  const pathname = path.split(/[?#]/)[0] || "/";
 
   const groups = await fetchRules(origin);
-  const rules = selectRules(groups, USER_AGENT);
+  const rules = selectRules(groups, userAgent);
   if (!rules) return true; // no matching rules — fully allowed
 
   const allowLen = longestMatch(pathname, rules.allow);
@@ -165,3 +166,14 @@ export async function isAllowedByRobots(url: string): Promise<boolean> {
   if (allowLen >= disallowLen) return true; // allow wins on tie or longer match
   return false;
 }
+
+/** Check whether the Unbrowse user-agent is allowed to access `url`. */
+export async function isAllowedByRobots(url: string): Promise<boolean> {
+  let parsed: URL;
+  try {
+    parsed = new URL(url);
+  } catch {
+    return true; // unparseable URL — let execution proceed
+  }
+  return checkRobots(parsed.origin, parsed.pathname || "/");
+}
@@ -0,0 +1,95 @@
+/**
+ * Re-signing for replay-protected APIs.
+ *
+ * detectRequestSigning records where a request's HMAC, timestamp and nonce
+ * travel (RequestSigningInfo). A captured signature only ever matches its
+ * own timestamp, so replay has to mint a fresh timestamp and nonce and sign
+ * again with the skill's secret. The string-to-sign is the spec's
+ * `canonical_fields` joined by its separator — a best-effort guess at
+ * capture that the user can correct on the endpoint.
+ */
+
+import { createHmac, randomBytes } from "crypto";
+import type { RequestSigningInfo } from "../types/index.js";
+
+type CanonicalField = NonNullable<RequestSigningInfo["canonical_fields"]>[number];
+
+export const DEFAULT_CANONICAL_FIELDS: CanonicalField[] = ["method", "path", "timestamp", "body"];
+
+export interface SignedRequestFields {
+  /** Signature, timestamp and nonce headers to set */
+  headers: Record<string, string>;
+  /** Same, for fields the spec puts in the query string */
+  query: Record<string, string>;
+}
+
+function formatTimestamp(format: RequestSigningInfo["timestamp_format"], now: number): string {
+  if (format === "epoch_ms") return String(now);
+  if (format === "iso") return new Date(now).toISOString();
+  return String(Math.floor(now / 1000));
+}
+
+/** The string-to-sign for one request, per the spec's canonical layout. */
+export function canonicalSigningString(
+  spec: RequestSigningInfo,
+  parts: { method: string; url: string; body?: string; timestamp?: string; nonce?: string },
+): string {
+  let path = parts.url;
+  let query = "";
+  try {
+    const u = new URL(parts.url);
+    // The signature param can't sign itself.
+    if (spec.location === "query") u.searchParams.delete(spec.signature_name);
+    path = u.pathname;
+    query = u.search.replace(/^\?/, "");
+  } catch { /* relative URL — sign it as given */ }
+  const values: Record<CanonicalField, string> = {
+    method: parts.method.toUpperCase(),
+    path,
+    query,
+    timestamp: parts.timestamp ?? "",
+    nonce: parts.nonce ?? "",
+    body: parts.body ?? "",
+  };
+  return (spec.canonical_fields ?? DEFAULT_CANONICAL_FIELDS).map((field) => values[field]).join(spec.canonical_separator ?? "\n");
+}
+
+/**
+ * Fresh timestamp, nonce and signature for a request. The HMAC uses the
+ * detected digest (sha256 when unknown) and encoding; each field lands in
+ * `headers` or `query` as the spec says.
+ */
+export function signRequest(
+  spec: RequestSigningInfo,
+  secret: string,
+  method: string,
+  url: string,
+  body?: string,
+  now = Date.now(),
+): SignedRequestFields {
+  const out: SignedRequestFields = { headers: {}, query: {} };
+  const place = (location: "header" | "query" | undefined, name: string, value: string) => {
+    if (location === "query") out.query[name] = value;
+    else out.headers[name] = value;
+  };
+  const timestamp = spec.timestamp_name ? formatTimestamp(spec.timestamp_format, now) : undefined;
+  const nonce = spec.nonce_name ? randomBytes(16).toString("hex") : undefined;
+  if (spec.timestamp_name && timestamp) place(spec.timestamp_location, spec.timestamp_name, timestamp);
+  if (spec.nonce_name && nonce) place(spec.nonce_location, spec.nonce_name, nonce);
+
+  // Query-located timestamp/nonce are part of the URL being signed.
+  let signedUrl = url;
+  if (Object.keys(out.query).length > 0) {
+    try {
+      const u = new URL(url);
+      for (const [k, v] of Object.entries(out.query)) u.searchParams.set(k, v);
+      signedUrl = u.href;
+    } catch { /* relative URL — sign it as given */ }
+  }
+  const digest = spec.digest_guess === "unknown" ? "sha256" : spec.digest_guess;
+  const signature = createHmac(digest, secret)
+    .update(canonicalSigningString(spec, { method, url: signedUrl, body, timestamp, nonce }))
+    .digest(spec.encoding);
+  place(spec.location, spec.signature_name, signature);
+  return out;
+}
@@ -0,0 +1,118 @@
+/**
+ * cURL export for a single request — the quickest way to hand a failing
+ * endpoint to someone else.
+ *
+ * Auth headers and cookies are emitted as `$ENV_VAR` references by default
+ * (`-H "Authorization: $AUTHORIZATION"`, `-b "$UNBROWSE_COOKIES"`) so the
+ * command can be pasted into a ticket; `include_secrets` inlines the values.
+ */
+
+import type { EndpointDescriptor, SkillManifest } from "../types/index.js";
+
+export interface CurlRequest {
+  method: string;
+  url: string;
+  headers?: Record<string, string>;
+  body?: string;
+}
+
+export interface CurlOptions {
+  /** Emit auth header and cookie values literally instead of env references */
+  include_secrets?: boolean;
+}
+
+const PLACEHOLDER = /(?<!\{)\{([A-Za-z_][\w.\-\[\]]*)\}(?!\})/g;
+/** Headers curl computes itself. */
+const SKIP_HEADERS = new Set(["content-length", "host", "cookie", "connection", "accept-encoding"]);
+
+/** Single-quote for POSIX shells; embedded quotes become '\''. */
+export function shellQuote(value: string): string {
+  return `'${value.replace(/'/g, `'\\''`)}'`;
+}
+
+/** Double-quoted word with `$VAR` left to expand; the literal part is escaped. */
+function withEnvRef(literal: string, envVar: string): string {
+  return `"${literal.replace(/(["\\$`])/g, "\\$1")}$${envVar}"`;
+}
+
+/** `x-api-key` → `X_API_KEY` */
+function envNameFor(header: string): string {
+  return header.toUpperCase().replace(/[^A-Z0-9]+/g, "_").replace(/^_+|_+$/g, "") || "TOKEN";
+}
+
+/**
+ * A runnable curl command for `req`. `authHeaders` and `cookies` are the
+ * secret parts (redacted unless include_secrets); the request's own
+ * headers are emitted as-is, minus ones curl sets itself.
+ */
+export function requestToCurl(
+  req: CurlRequest,
+  authHeaders: Record<string, string> = {},
+  cookies: Record<string, string> = {},
+  options: CurlOptions = {},
+): string {
+  const parts = ["curl", "-X", req.method.toUpperCase(), shellQuote(req.url)];
+  const authNames = new Set(Object.keys(authHeaders).map((k) => k.toLowerCase()));
+
+  for (const [name, value] of Object.entries(req.headers ?? {})) {
+    const lower = name.toLowerCase();
+    if (SKIP_HEADERS.has(lower) || authNames.has(lower)) continue;
+    parts.push("-H", shellQuote(`${name}: ${value}`));
+  }
+  for (const [name, value] of Object.entries(authHeaders)) {
+    if (SKIP_HEADERS.has(name.toLowerCase())) continue;
+    parts.push("-H", options.include_secrets ? shellQuote(`${name}: ${value}`) : withEnvRef(`${name}: `, envNameFor(name)));
+  }
+
+  const cookieHeader = Object.entries(cookies).map(([name, value]) => `${name}=${value}`).join("; ");
+  if (cookieHeader) parts.push("-b", options.include_secrets ? shellQuote(cookieHeader) : withEnvRef("", "UNBROWSE_COOKIES"));
+
+  if (req.body !== undefined && req.body !== "") parts.push("--data-raw", shellQuote(req.body));
+  return parts.join(" \\\n  ");
+}
+
+function fillDefaults(template: string, defaults: Record<string, unknown>): string {
+  return template.replace(PLACEHOLDER, (match, name: string) => {
+    const value = defaults[name];
+    if (value === undefined || value === null) return match;
+    return encodeURIComponent(typeof value === "string" ? value : JSON.stringify(value));
+  });
+}
+
+function fillBody(value: unknown, defaults: Record<string, unknown>): unknown {
+  if (typeof value === "string") {
+    const whole = value.match(/^\{([A-Za-z_][\w.\-\[\]]*)\}$/);
+    if (whole && defaults[whole[1]] !== undefined) return defaults[whole[1]];
+    return value.replace(PLACEHOLDER, (match, name: string) => defaults[name] === undefined ? match : String(defaults[name]));
+  }
+  if (Array.isArray(value)) return value.map((item) => fillBody(item, defaults));
+  if (value && typeof value === "object") {
+    return Object.fromEntries(Object.entries(value as Record<string, unknown>).map(([k, v]) => [k, fillBody(v, defaults)]));
+  }
+  return value;
+}
+
+/**
+ * The request an endpoint replays, with `{param}` placeholders filled from
+ * captured defaults (unknown ones stay as `{param}`). Tunnelled endpoints
+ * use their wire method.
+ */
+export function endpointToCurlRequest(endpoint: EndpointDescriptor, skill?: Pick<SkillManifest, "version_headers">): CurlRequest {
+  const defaults: Record<string, unknown> = { ...(endpoint.query ?? {}), ...(endpoint.path_params ?? {}), ...(endpoint.body_params ?? {}) };
+  const headers = { ...(skill?.version_headers ?? {}), ...(endpoint.headers_template ?? {}) };
+  const contentType = Object.entries(headers).find(([k]) => k.toLowerCase() === "content-type")?.[1] ?? "";
+  let body: string | undefined;
+  if (endpoint.body && endpoint.method !== "GET") {
+    const filled = fillBody(endpoint.body, defaults) as Record<string, unknown>;
+    body = /x-www-form-urlencoded/i.test(contentType)
+      ? new URLSearchParams(Object.entries(filled).map(([k, v]) => [k, typeof v === "string" ? v : JSON.stringify(v)])).toString()
+      : JSON.stringify(filled);
+    if (!contentType) headers["content-type"] = "application/json";
+  }
+  return {
+    method: endpoint.wire_method ?? endpoint.method,
+    url: fillDefaults(endpoint.url_template, defaults),
+    headers,
+    ...(body !== undefined ? { body } : {}),
+  };
+}
@@ -0,0 +1,168 @@
+/**
+ * Postman v2.1 collection export for skills.
+ *
+ * One folder per API host, split into category subfolders (auth, search,
+ * list, … — see classifyEndpoint), one request per endpoint. `{param}` placeholders
+ * from url/body templates become Postman `{{param}}` variables with the
+ * captured defaults as collection variables; credentials are never exported —
+ * the collection auth block references `{{token}}`-style variables instead.
+ */
+
+import { groupEndpointsByCategory } from "../graph/index.js";
+import type { EndpointDescriptor, SkillManifest } from "../types/index.js";
+
+const POSTMAN_SCHEMA = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+const PLACEHOLDER = /(?<!\{)\{([A-Za-z_][\w.\-\[\]]*)\}(?!\})/g;
+
+type PostmanVariable = { key: string; value: string };
+type PostmanAuth =
+  | { type: "noauth" }
+  | { type: "bearer"; bearer: Array<{ key: string; value: string; type: "string" }> }
+  | { type: "basic"; basic: Array<{ key: string; value: string; type: "string" }> }
+  | { type: "apikey"; apikey: Array<{ key: string; value: string; type: "string" }> };
+
+function toPostmanVars(value: string, variables: Map<string, string>): string {
+  return value.replace(PLACEHOLDER, (_match, name: string) => {
+    if (!variables.has(name)) variables.set(name, "");
+    return `{{${name}}}`;
+  });
+}
+
+function toPostmanBody(value: unknown, variables: Map<string, string>): unknown {
+  if (typeof value === "string") return toPostmanVars(value, variables);
+  if (Array.isArray(value)) return value.map((item) => toPostmanBody(item, variables));
+  if (value && typeof value === "object") {
+    return Object.fromEntries(
+      Object.entries(value as Record<string, unknown>).map(([k, v]) => [k, toPostmanBody(v, variables)]),
+    );
+  }
+  return value;
+}
+
+function stringifyDefault(value: unknown): string {
+  return typeof value === "string" ? value : JSON.stringify(value);
+}
+
+/** Collection-level auth matching SkillManifest.auth_method. */
+export function postmanAuthFor(authMethod: string | undefined): PostmanAuth {
+  const method = authMethod ?? "";
+  if (/^Bearer/i.test(method)) {
+    return { type: "bearer", bearer: [{ key: "token", value: "{{token}}", type: "string" }] };
+  }
+  if (/^Basic/i.test(method)) {
+    return {
+      type: "basic",
+      basic: [
+        { key: "username", value: "{{username}}", type: "string" },
+        { key: "password", value: "{{password}}", type: "string" },
+      ],
+    };
+  }
+  const apiKeyHeader = method.match(/^API Key \(([^)]+)\)/i)?.[1];
+  if (apiKeyHeader) {
+    return {
+      type: "apikey",
+      apikey: [
+        { key: "key", value: apiKeyHeader, type: "string" },
+        { key: "value", value: "{{api_key}}", type: "string" },
+        { key: "in", value: "header", type: "string" },
+      ],
+    };
+  }
+  return { type: "noauth" };
+}
+
+function endpointHost(endpoint: EndpointDescriptor, fallback: string): string {
+  try {
+    return new URL(endpoint.url_template.replace(PLACEHOLDER, "x")).host;
+  } catch {
+    return fallback;
+  }
+}
+
+function endpointName(endpoint: EndpointDescriptor): string {
+  const path = endpoint.url_template.replace(/^https?:\/\/[^/]+/, "").split("?")[0] || "/";
+  return `${endpoint.method} ${path}`;
+}
+
+function buildRequestItem(endpoint: EndpointDescriptor, skill: SkillManifest, variables: Map<string, string>) {
+  for (const [k, v] of Object.entries(endpoint.path_params ?? {})) if (!variables.get(k)) variables.set(k, stringifyDefault(v));
+  for (const [k, v] of Object.entries(endpoint.query ?? {})) if (!variables.get(k)) variables.set(k, stringifyDefault(v));
+  for (const [k, v] of Object.entries(endpoint.body_params ?? {})) if (!variables.get(k)) variables.set(k, stringifyDefault(v));
+
+  const headers = { ...(skill.version_headers ?? {}), ...(endpoint.headers_template ?? {}) };
+  const header = Object.entries(headers).map(([key, value]) => ({ key, value: toPostmanVars(value, variables) }));
+  const contentType = Object.entries(headers).find(([k]) => k.toLowerCase() === "content-type")?.[1] ?? "";
+
+  // Tunnelled endpoints are sent as their wire method, with the override header in headers_template.
+  const method = endpoint.wire_method ?? endpoint.method;
+  let body: Record<string, unknown> | undefined;
+  if (endpoint.body && method !== "GET") {
+    const templated = toPostmanBody(endpoint.body, variables) as Record<string, unknown>;
+    body = /x-www-form-urlencoded/i.test(contentType)
+      ? { mode: "urlencoded", urlencoded: Object.entries(templated).map(([key, value]) => ({ key, value: stringifyDefault(value) })) }
+      : { mode: "raw", raw: JSON.stringify(templated, null, 2), options: { raw: { language: "json" } } };
+  }
+
+  const request = {
+    method,
+    header,
+    url: { raw: toPostmanVars(endpoint.url_template, variables) },
+    ...(body ? { body } : {}),
+    ...(endpoint.description ? { description: endpoint.description } : {}),
+  };
+
+  const example = endpoint.semantic?.example_response_compact;
+  const response = example !== undefined
+    ? [{
+        name: "Captured response",
+        originalRequest: request,
+        status: "OK",
+        code: 200,
+        _postman_previewlanguage: "json",
+        header: [{ key: "Content-Type", value: "application/json" }],
+        body: JSON.stringify(example, null, 2),
+      }]
+    : [];
+
+  return { name: endpointName(endpoint), request, response };
+}
+
+/** Postman v2.1 collection JSON for one or more skills. WebSocket endpoints are skipped. */
+export function generatePostmanCollection(skills: SkillManifest | SkillManifest[]): string {
+  const list = Array.isArray(skills) ? skills : [skills];
+  const variables = new Map<string, string>();
+  const folders = new Map<string, Array<{ endpoint: EndpointDescriptor; skill: SkillManifest }>>();
+
+  for (const skill of list) {
+    for (const endpoint of skill.endpoints) {
+      if (endpoint.method === "WS") continue;
+      const host = endpointHost(endpoint, skill.domain);
+      folders.set(host, [...(folders.get(host) ?? []), { endpoint, skill }]);
+    }
+  }
+
+  const primary = list[0];
+  const authMethods = new Set(list.map((skill) => skill.auth_method).filter(Boolean));
+  const collection = {
+    info: {
+      name: list.length === 1 ? primary.name || primary.domain : "Unbrowse skills",
+      ...(list.length === 1 && primary.description ? { description: primary.description } : {}),
+      schema: POSTMAN_SCHEMA,
+    },
+    // A single auth block only makes sense when every skill agrees on it.
+    auth: postmanAuthFor(authMethods.size === 1 ? [...authMethods][0] : undefined),
+    item: [...folders.entries()].map(([name, entries]) => {
+      const skillOf = new Map(entries.map(({ endpoint, skill }) => [endpoint, skill]));
+      return {
+        name,
+        item: groupEndpointsByCategory(entries.map(({ endpoint }) => endpoint)).map(({ category, endpoints }) => ({
+          name: category,
+          item: endpoints.map((endpoint) => buildRequestItem(endpoint, skillOf.get(endpoint)!, variables)),
+        })),
+      };
+    }),
+    variable: [...variables.entries()].map(([key, value]): PostmanVariable => ({ key, value })),
+  };
+  return JSON.stringify(collection, null, 2);
+}
@@ -1,6 +1,7 @@
 import type {
   AgentAvailableOperation,
   AgentSkillChunkView,
+  EndpointCategory,
   EndpointDescriptor,
   OperationBinding,
   ResponseSchema,
@@ -281,7 +282,7 @@ function buildSemanticDescription(
 function inferRequires(endpoint: EndpointDescriptor): OperationBinding[] {
   const requires: OperationBinding[] = [];
   const seen = new Set<string>();
-  const add = (key: string, source: string, required = true) => {
+  const add = (key: string, source: string, required = true, example?: string) => {
     if (!key || seen.has(key) || key === "endpoint_id") return;
     seen.add(key);
     requires.push({
@@ -289,9 +290,22 @@ function inferRequires(endpoint: EndpointDescriptor): OperationBinding[] {
       required,
       source,
       semantic_type: key.endsWith("_id") || key === "id" ? "identifier" : "input",
+      ...(example !== undefined ? { example_value: example } : {}),
     });
   };
   for (const key of Object.keys(endpoint.path_params ?? {})) add(key, "path_params", false);
+  if (endpoint.query_params?.some((param) => param.role)) {
+    // Diffed across calls: constants and cache-busters replay from the captured
+    // defaults, so they're marked seen without becoming inputs.
+    for (const param of endpoint.query_params) {
+      const key = normalizeQueryBindingKey(param.name);
+      if (param.role === "constant" || param.role === "volatile") {
+        seen.add(key);
+        continue;
+      }
+      add(key, "query", param.role === "variable", param.examples[0]);
+    }
+  }
   for (const key of Object.keys(endpoint.query ?? {})) add(normalizeQueryBindingKey(key), "query", false);
   for (const match of endpoint.url_template.matchAll(/\{([^}]+)\}/g)) add(match[1], "url_template");
   return requires;
@@ -480,6 +494,56 @@ function chooseSemanticKind(
   return inferred;
 }
 
+const AUTH_PATH = /\/(login|logout|signin|sign-in|signup|sign-up|oauth2?|token|refresh|session|sessions|auth|authenticate|sso)(\/|$|\?|\.)/i;
+const SEARCH_PATH = /(^|[/?&_.-])(search|query|lookup|find|autocomplete|typeahead|suggest)(?=$|[/?&=_.-])|[?&](q|query|search|keyword)=/i;
+/** Wrapper keys whose array value makes an object response a list (`{ data: [...] }`). */
+const COLLECTION_KEYS = new Set(["data", "items", "results", "entries", "records", "list", "edges", "nodes", "hits", "rows"]);
+
+function isListShape(schema: ResponseSchema | undefined): boolean | undefined {
+  if (!schema) return undefined;
+  if (schema.type === "array" && !schema.stream) return true;
+  if (schema.type !== "object") return undefined;
+  return Object.entries(schema.properties ?? {}).some(([key, value]) => COLLECTION_KEYS.has(key.toLowerCase()) && value.type === "array");
+}
+
+/**
+ * Category of an endpoint from its method, URL and, when captured, response
+ * shape: auth paths first, then search, then create/update/delete by method.
+ * A GET is a list when it returns an array (or wraps one under data/items/…)
+ * and a detail when it returns an object; without a schema, a trailing
+ * `{param}` segment means detail.
+ */
+export function classifyEndpoint(method: string, urlTemplate: string, responseSchema?: ResponseSchema): EndpointCategory {
+  const upper = method.toUpperCase();
+  const [base, query = ""] = urlTemplate.split("?");
+  let path = base;
+  try { path = new URL(base.replace(/\{[^}]+\}/g, "x")).pathname; } catch { /* relative template */ }
+  const target = query ? `${path}?${query}` : path;
+  if (AUTH_PATH.test(target)) return "auth";
+  if (SEARCH_PATH.test(target)) return "search";
+  if (upper === "DELETE") return "delete";
+  if (upper === "PUT" || upper === "PATCH") return "update";
+  if (upper === "POST") return "create";
+  if (upper !== "GET" && upper !== "HEAD") return "other";
+  const list = isListShape(responseSchema);
+  if (list !== undefined) return list ? "list" : "detail";
+  if (responseSchema?.type === "object") return "detail";
+  return /\/\{[^}/]+\}\/?$/.test(base) ? "detail" : "list";
+}
+
+/** Display order for category groups. */
+export const ENDPOINT_CATEGORY_ORDER: EndpointCategory[] = ["auth", "search", "list", "detail", "create", "update", "delete", "other"];
+
+/** Endpoints grouped by classifyEndpoint, in ENDPOINT_CATEGORY_ORDER; empty groups are omitted. */
+export function groupEndpointsByCategory(endpoints: EndpointDescriptor[]): Array<{ category: EndpointCategory; endpoints: EndpointDescriptor[] }> {
+  const groups = new Map<EndpointCategory, EndpointDescriptor[]>();
+  for (const endpoint of endpoints) {
+    const category = classifyEndpoint(endpoint.method, endpoint.url_template, endpoint.response_schema);
+    groups.set(category, [...(groups.get(category) ?? []), endpoint]);
+  }
+  return ENDPOINT_CATEGORY_ORDER.filter((c) => groups.has(c)).map((category) => ({ category, endpoints: groups.get(category)! }));
+}
+
 export function inferEndpointSemantic(
   endpoint: EndpointDescriptor,
   opts?: {
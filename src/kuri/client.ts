@@ -9,15 +9,17 @@
  */
 
 import { execFileSync, spawn, type ChildProcess } from "node:child_process";
-import { existsSync } from "node:fs";
+import { existsSync, statSync } from "node:fs";
 import net from "node:net";
 import path from "node:path";
-import { log } from "../logger.js";
+import { unbrowseError } from "../errors.js";
+import { log, logError, logTrace, logWarn } from "../logger.js";
 import { getPackageRoot } from "../runtime/paths.js";
+import { MAX_STREAM_BYTES, MAX_STREAM_MS } from "../transform/stream.js";
 
 const KURI_DEFAULT_PORT = 7700;
 const KURI_STARTUP_TIMEOUT_MS = 10_000;
-const KURI_REQUEST_TIMEOUT_MS = 30_000;
+const DEFAULT_KURI_REQUEST_TIMEOUT_MS = 60_000;
 const KURI_SPAWN_RETRIES = 3;
 const KURI_SPAWN_RETRY_DELAY_MS = 1_000;
 const KURI_PORT_SEARCH_LIMIT = 10;
@@ -44,7 +46,7 @@ export interface KuriCookie {
 export type KuriActionType =
   | "click" | "dblclick" | "fill" | "type" | "select"
   | "check" | "uncheck" | "hover" | "focus" | "blur"
-  | "scroll" | "press";
+  | "scroll" | "press" | "upload";
 
 export interface KuriWaitResult {
   status: "found" | "ready" | "timeout";
@@ -58,19 +60,80 @@ export interface KuriDomQueryResult {
   nodeId?: number;
   nodeIds?: number[];
 }
+export interface KuriHarParam {
+  name: string;
+  value?: string;
+  fileName?: string;
+  contentType?: string;
+}
+
+/** HAR 1.2 postData: DevTools/ZAP often fill `params` for forms and leave `text` empty. */
+export interface KuriHarPostData {
+  mimeType?: string;
+  text?: string;
+  params?: KuriHarParam[];
+}
+
+/**
+ * Request body for a HAR entry. Falls back to re-encoding `params` as an
+ * urlencoded form when `text` is missing, so auth-field and refresh-token
+ * scans see form submissions too. File parts keep only their file name.
+ */
+export function harPostDataText(postData: KuriHarPostData | undefined): string | undefined {
+  if (!postData) return undefined;
+  if (postData.text) return postData.text;
+  if (!postData.params?.length) return undefined;
+  const form = new URLSearchParams();
+  for (const param of postData.params) form.append(param.name, param.value ?? param.fileName ?? "");
+  return form.toString();
+}
+
+/** HAR `response.content`, including the reassembly/truncation extensions some exporters add. */
+export interface KuriHarContent {
+  text?: string;
+  mimeType?: string;
+  /** Uncompressed body size in bytes, per the HAR spec */
+  size?: number;
+  encoding?: string;
+  /** Body split across pieces; appended to `text` in order */
+  _chunks?: Array<string | { text?: string }>;
+  /** Exporter-set flag: `text` was cut off */
+  _truncated?: boolean;
+}
+
+/**
+ * Response body of a HAR entry as RawRequest fields. `_chunks` are
+ * reassembled after `text`; the body is flagged truncated when the exporter
+ * says so or when it is shorter than the declared `content.size`, so shape
+ * inference doesn't trust a cut-off body.
+ */
+export function harResponseBody(content: KuriHarContent | undefined): { response_body?: string; response_body_truncated?: true } {
+  if (!content) return {};
+  const chunks = (content._chunks ?? []).map((chunk) => typeof chunk === "string" ? chunk : chunk?.text ?? "");
+  const text = chunks.length > 0 ? (content.text ?? "") + chunks.join("") : content.text;
+  if (text === undefined) return {};
+  const bytes = content.encoding === "base64" ? Buffer.from(text, "base64").length : Buffer.byteLength(text, "utf8");
+  const truncated = content._truncated === true || (typeof content.size === "number" && content.size > bytes);
+  return { response_body: text, ...(truncated ? { response_body_truncated: true as const } : {}) };
+}
+
 export interface KuriHarEntry {
   request: {
     method: string;
     url: string;
     headers: Array<{ name: string; value: string }>;
-    postData?: { text: string };
+    postData?: KuriHarPostData;
   };
   response: {
     status: number;
     headers: Array<{ name: string; value: string }>;
-    content?: { text?: string; mimeType?: string };
+    content?: KuriHarContent;
   };
   startedDateTime: string;
+  /** Total elapsed time of the request in ms */
+  time?: number;
+  /** Chrome's de-facto HAR extension: frames exchanged over a WebSocket entry. */
+  _webSocketMessages?: Array<{ type: "send" | "receive"; time: number; opcode: number; data: string }>;
 }
 
 export interface KuriPluginRehydrateResult {
@@ -274,7 +337,7 @@ async function ensureUserChromeRunning(): Promise<void> {
     }
     log("kuri", "user Chrome launched but CDP not responding — Kuri will launch managed Chrome");
   } catch (err) {
-    log("kuri", `failed to launch user Chrome: ${err instanceof Error ? err.message : err}`);
+    logWarn("kuri", `failed to launch user Chrome: ${err instanceof Error ? err.message : err}`);
   }
 }
 
@@ -288,35 +351,58 @@ function kuriUrl(path: string, params?: Record<string, string>): string {
   return `${base}?${parts.join("&")}`;
 }
 
-async function kuriGet(path: string, params?: Record<string, string>): Promise<unknown> {
-  const url = kuriUrl(path, params);
+let requestTimeoutOverride: number | null = null;
+
+/** Per-call timeout for browser control requests: setKuriRequestTimeout(), UNBROWSE_BROWSER_TIMEOUT_MS, or 60s. */
+export function getKuriRequestTimeoutMs(): number {
+  if (requestTimeoutOverride) return requestTimeoutOverride;
+  const configured = Number(process.env.UNBROWSE_BROWSER_TIMEOUT_MS);
+  return Number.isFinite(configured) && configured > 0 ? configured : DEFAULT_KURI_REQUEST_TIMEOUT_MS;
+}
+
+/** Override the browser control timeout for this process; null restores the default. */
+export function setKuriRequestTimeout(ms: number | null): void {
+  requestTimeoutOverride = ms && ms > 0 ? ms : null;
+}
+
+/**
+ * fetch() against Kuri with a hard deadline covering the body read too, so a
+ * hung browser server fails with `browser_timeout` instead of blocking the
+ * CLI forever. Connections are pooled and kept alive by the runtime's fetch
+ * (undici / Bun) — no per-call socket setup on these localhost round trips.
+ */
+async function kuriFetch(path: string, url: string, init: RequestInit = {}, timeoutMs = getKuriRequestTimeoutMs()): Promise<string> {
   const controller = new AbortController();
-  const timeout = setTimeout(() => controller.abort(), KURI_REQUEST_TIMEOUT_MS);
+  const timer = setTimeout(() => controller.abort(), timeoutMs);
   try {
-    const res = await fetch(url, { signal: controller.signal });
-    const text = await res.text();
-    try { return JSON.parse(text); } catch { return text; }
+    const res = await fetch(url, { ...init, signal: controller.signal });
+    return await res.text();
+  } catch (err) {
+    if (controller.signal.aborted) {
+      throw unbrowseError("browser_timeout", `Browser server did not answer ${path} within ${Math.round(timeoutMs / 1000)}s — Kuri may be hung; restart unbrowse or raise UNBROWSE_BROWSER_TIMEOUT_MS`, { path, timeout_ms: timeoutMs });
+    }
+    throw err;
   } finally {
-    clearTimeout(timeout);
+    clearTimeout(timer);
   }
 }
 
+async function kuriGet(path: string, params?: Record<string, string>, timeoutMs?: number): Promise<unknown> {
+  const url = kuriUrl(path, params);
+  logTrace("kuri", `GET ${path}${params?.tab_id ? ` tab=${params.tab_id}` : ""}`);
+  const text = await kuriFetch(path, url, {}, timeoutMs);
+  try { return JSON.parse(text); } catch { return text; }
+}
+
 async function kuriPost(path: string, params: Record<string, string>, body: unknown): Promise<unknown> {
   const url = kuriUrl(path, params);
-  const controller = new AbortController();
-  const timeout = setTimeout(() => controller.abort(), KURI_REQUEST_TIMEOUT_MS);
-  try {
-    const res = await fetch(url, {
-      method: "POST",
-      headers: { "Content-Type": "application/json" },
-      body: JSON.stringify(body),
-      signal: controller.signal,
-    });
-    const text = await res.text();
-    try { return JSON.parse(text); } catch { return text; }
-  } finally {
-    clearTimeout(timeout);
-  }
+  logTrace("kuri", `POST ${path}${params.tab_id ? ` tab=${params.tab_id}` : ""}`);
+  const text = await kuriFetch(path, url, {
+    method: "POST",
+    headers: { "Content-Type": "application/json" },
+    body: JSON.stringify(body),
+  });
+  try { return JSON.parse(text); } catch { return text; }
 }
 
 /** Find the kuri binary — check env, then common build locations. */
@@ -515,7 +601,7 @@ export async function getDefaultTab(): Promise<string> {
         return target.id;
       }
     } catch (err) {
-      log("kuri", `Chrome tab creation failed: ${err instanceof Error ? err.message : err}`);
+      logError("kuri", `Chrome tab creation failed: ${err instanceof Error ? err.message : err}`);
     }
   }
 
@@ -566,20 +652,12 @@ export async function evaluate(tabId: string, expression: string): Promise<unkno
   if (expression.length > 2000) {
     // Use POST with raw text body for large expressions to avoid URL length limits
     const url = kuriUrl("/evaluate", { tab_id: tabId });
-    const controller = new AbortController();
-    const timeout = setTimeout(() => controller.abort(), KURI_REQUEST_TIMEOUT_MS);
-    try {
-      const res = await fetch(url, {
-        method: "POST",
-        headers: { "Content-Type": "text/plain" },
-        body: expression,
-        signal: controller.signal,
-      });
-      const text = await res.text();
-      try { raw = JSON.parse(text); } catch { raw = text as never; }
-    } finally {
-      clearTimeout(timeout);
-    }
+    const text = await kuriFetch("/evaluate", url, {
+      method: "POST",
+      headers: { "Content-Type": "text/plain" },
+      body: expression,
+    });
+    try { raw = JSON.parse(text); } catch { raw = text as never; }
   } else {
     raw = (await kuriGet("/evaluate", { tab_id: tabId, expression })) as typeof raw;
   }
@@ -615,71 +693,123 @@ export async function getCookies(tabId: string): Promise<KuriCookie[]> {
   return raw?.result?.cookies ?? [];
 }
 
-/** Set a cookie via raw CDP WebSocket — supports all cookie attributes (secure, httpOnly, sameSite, expires). */
-async function setCookieViaCDP(wsUrl: string, cookie: {
+type CdpCookieParams = {
   name: string; value: string; domain: string; path: string;
   secure: boolean; httpOnly: boolean; sameSite: string; expires?: number;
-}): Promise<boolean> {
-  return new Promise((resolve) => {
-    const timer = setTimeout(() => { resolve(false); }, 3000);
+};
+
+/**
+ * Raw CDP WebSocket to one page, reused for every command in a batch —
+ * setCookies used to open a fresh socket (and re-list /json) per cookie.
+ */
+interface CdpSession {
+  send(method: string, params: Record<string, unknown>): Promise<Record<string, unknown> | null>;
+  close(): void;
+}
+
+const CDP_COMMAND_TIMEOUT_MS = 3000;
+
+async function resolveDebuggerUrl(tabId: string): Promise<string | null> {
+  const res = await fetch("http://127.0.0.1:9222/json", { signal: AbortSignal.timeout(1000) }).catch(() => null);
+  if (!res?.ok) return null;
+  const pages = await res.json() as Array<{ id: string; webSocketDebuggerUrl?: string }>;
+  return pages.find(p => p.id === tabId)?.webSocketDebuggerUrl ?? null;
+}
+
+async function openCdpSession(wsUrl: string): Promise<CdpSession | null> {
+  const WebSocket = require("ws") as typeof import("ws");
+  const ws = new WebSocket(wsUrl);
+  const opened = await new Promise<boolean>((resolve) => {
+    const timer = setTimeout(() => resolve(false), CDP_COMMAND_TIMEOUT_MS);
+    ws.once("open", () => { clearTimeout(timer); resolve(true); });
+    ws.once("error", () => { clearTimeout(timer); resolve(false); });
+  });
+  if (!opened) {
+    ws.terminate();
+    return null;
+  }
+
+  let nextId = 1;
+  const pending = new Map<number, (result: Record<string, unknown> | null) => void>();
+  ws.on("message", (data: Buffer) => {
     try {
-      const ws = new (require("ws") as typeof import("ws"))(wsUrl);
-      ws.on("open", () => {
-        ws.send(JSON.stringify({
-          id: 1,
-          method: "Network.setCookie",
-          params: {
-            ...cookie,
-            url: `https://${cookie.domain.replace(/^\./, "")}/`,
-          },
-        }));
-      });
-      ws.on("message", (data: Buffer) => {
-        clearTimeout(timer);
-        try {
-          const msg = JSON.parse(data.toString());
-          if (msg.id === 1) {
-            ws.close();
-            resolve(msg.result?.success ?? false);
-          }
-        } catch { ws.close(); resolve(false); }
+      const msg = JSON.parse(data.toString()) as { id?: number; result?: Record<string, unknown> };
+      if (msg.id == null) return;
+      pending.get(msg.id)?.(msg.result ?? null);
+      pending.delete(msg.id);
+    } catch { /* ignore non-JSON frames */ }
+  });
+  const failAll = () => {
+    for (const resolve of pending.values()) resolve(null);
+    pending.clear();
+  };
+  ws.on("error", failAll);
+  ws.on("close", failAll);
+
+  return {
+    send(method, params) {
+      return new Promise((resolve) => {
+        if (ws.readyState !== WebSocket.OPEN) return resolve(null);
+        const id = nextId++;
+        const timer = setTimeout(() => { pending.delete(id); resolve(null); }, CDP_COMMAND_TIMEOUT_MS);
+        pending.set(id, (result) => { clearTimeout(timer); resolve(result); });
+        ws.send(JSON.stringify({ id, method, params }));
       });
-      ws.on("error", () => { clearTimeout(timer); resolve(false); });
-    } catch { clearTimeout(timer); resolve(false); }
+    },
+    close() {
+      ws.close();
+    },
+  };
+}
+
+/** Set a cookie via raw CDP — supports all cookie attributes (secure, httpOnly, sameSite, expires). */
+async function setCookieViaCDP(session: CdpSession, cookie: CdpCookieParams): Promise<boolean> {
+  const result = await session.send("Network.setCookie", {
+    ...cookie,
+    url: `https://${cookie.domain.replace(/^\./, "")}/`,
   });
+  return result?.success === true;
+}
+
+function needsCdpCookie(cookie: KuriCookie): boolean {
+  return !!(cookie.secure || cookie.httpOnly);
 }
 
 /** Set a single cookie via raw CDP (Chrome debug port) for full attribute support.
- *  Falls back to Kuri's /cookies endpoint if CDP is unavailable. */
-/** Set a single cookie via raw CDP (Chrome debug port) for full attribute support.
- *  Falls back to Kuri's /cookies endpoint if CDP is unavailable. */
-export async function setCookie(tabId: string, cookie: KuriCookie): Promise<void> {
+ *  Falls back to Kuri's /cookies endpoint if CDP is unavailable. Pass an open
+ *  session to reuse one socket across a batch (see setCookies). */
+export async function setCookie(tabId: string, cookie: KuriCookie, session?: CdpSession | null): Promise<void> {
   // Strip wrapping quotes from cookie values (Chrome stores some values like JSESSIONID with literal quotes)
   const value = cookie.value.replace(/^"|"$/g, "");
 
   // Try raw CDP first — Kuri's /cookies endpoint doesn't pass secure/httpOnly/sameSite/expires
   // which causes auth failures on sites like LinkedIn that require secure cookies.
-  if (cookie.secure || cookie.httpOnly) {
+  if (needsCdpCookie(cookie)) {
+    let cdp = session;
+    let ownsSession = false;
     try {
-      const res = await fetch("http://127.0.0.1:9222/json", { signal: AbortSignal.timeout(1000) }).catch(() => null);
-      if (res?.ok) {
-        const pages = await res.json() as Array<{ id: string; webSocketDebuggerUrl?: string }>;
-        const page = pages.find(p => p.id === tabId);
-        if (page?.webSocketDebuggerUrl) {
-          const success = await setCookieViaCDP(page.webSocketDebuggerUrl, {
-            name: cookie.name,
-            value,
-            domain: cookie.domain,
-            path: cookie.path || "/",
-            secure: cookie.secure ?? false,
-            httpOnly: cookie.httpOnly ?? false,
-            sameSite: cookie.sameSite || "Lax",
-            ...(cookie.expires && cookie.expires > 0 ? { expires: cookie.expires } : {}),
-          });
-          if (success) return;
-        }
+      if (cdp === undefined) {
+        const wsUrl = await resolveDebuggerUrl(tabId);
+        cdp = wsUrl ? await openCdpSession(wsUrl) : null;
+        ownsSession = true;
+      }
+      if (cdp) {
+        const success = await setCookieViaCDP(cdp, {
+          name: cookie.name,
+          value,
+          domain: cookie.domain,
+          path: cookie.path || "/",
+          secure: cookie.secure ?? false,
+          httpOnly: cookie.httpOnly ?? false,
+          sameSite: cookie.sameSite || "Lax",
+          ...(cookie.expires && cookie.expires > 0 ? { expires: cookie.expires } : {}),
+        });
+        if (success) return;
       }
     } catch { /* CDP unavailable, fall through to Kuri */ }
+    finally {
+      if (ownsSession) cdp?.close();
+    }
   }
 
   // Fallback: Kuri's /cookies endpoint (no secure/httpOnly support)
@@ -692,10 +822,21 @@ export async function setCookie(tabId: string, cookie: KuriCookie): Promise<void
   });
 }
 
-/** Set multiple cookies. */
+/** Set multiple cookies over one shared CDP session. */
 export async function setCookies(tabId: string, cookies: KuriCookie[]): Promise<void> {
-  for (const cookie of cookies) {
-    await setCookie(tabId, cookie);
+  let session: CdpSession | null = null;
+  if (cookies.some(needsCdpCookie)) {
+    try {
+      const wsUrl = await resolveDebuggerUrl(tabId);
+      session = wsUrl ? await openCdpSession(wsUrl) : null;
+    } catch { /* CDP unavailable — every cookie falls back to Kuri */ }
+  }
+  try {
+    for (const cookie of cookies) {
+      await setCookie(tabId, cookie, session);
+    }
+  } finally {
+    session?.close();
   }
 }
 
@@ -762,6 +903,23 @@ export async function snapshot(tabId: string, filter?: string): Promise<string>
   return "";
 }
 
+/** One interactive element from a text snapshot line: `[e12] textbox "Email"`. */
+export interface SnapshotElement {
+  ref: string;
+  role: string;
+  name: string;
+}
+
+/** Parse a text snapshot into its ref'd elements, in page order. */
+export function parseSnapshotElements(snapshot: string): SnapshotElement[] {
+  const elements: SnapshotElement[] = [];
+  for (const line of snapshot.split("\n")) {
+    const match = line.trim().match(/^\[(\w+)\]\s+(\w+)\s+"?(.+?)"?\s*$/);
+    if (match) elements.push({ ref: match[1], role: match[2], name: match[3].replace(/"$/, "") });
+  }
+  return elements;
+}
+
 /** Close a tab. */
 export async function closeTab(tabId: string): Promise<void> {
   await kuriGet("/close", { tab_id: tabId });
@@ -785,6 +943,25 @@ export async function getCurrentUrl(tabId: string): Promise<string> {
   return typeof result === "string" ? result : "";
 }
 
+/**
+ * Read localStorage and sessionStorage for the tab's current origin.
+ * Storage access can throw (sandboxed iframes, opaque origins) — those
+ * come back as empty maps.
+ */
+export async function getWebStorage(tabId: string): Promise<{ local: Record<string, string>; session: Record<string, string> }> {
+  const expression = `JSON.stringify((() => {
+    const dump = (s) => { try { const o = {}; for (let i = 0; i < s.length; i++) { const k = s.key(i); o[k] = s.getItem(k); } return o; } catch { return {}; } };
+    return { local: dump(window.localStorage), session: dump(window.sessionStorage) };
+  })())`;
+  const result = await evaluate(tabId, expression);
+  try {
+    const parsed = JSON.parse(typeof result === "string" ? result : "{}") as { local?: Record<string, string>; session?: Record<string, string> };
+    return { local: parsed.local ?? {}, session: parsed.session ?? {} };
+  } catch {
+    return { local: {}, session: {} };
+  }
+}
+
 /** Get page HTML content via evaluate. */
 export async function getPageHtml(tabId: string): Promise<string> {
   const result = await evaluate(tabId, "document.documentElement.outerHTML");
@@ -920,6 +1097,20 @@ export async function waitForCloudflare(tabId: string, maxWaitMs = 15000): Promi
   return false;
 }
 
+export interface InPageFetchResult {
+  status: number;
+  data: unknown;
+  retry_after?: string;
+  etag?: string;
+  last_modified?: string;
+  /** Raw X-RateLimit-* / RateLimit-* / Retry-After headers, lowercased */
+  rate_limit_headers?: Record<string, string>;
+  /** URL of the response after any redirects */
+  final_url?: string;
+  /** The fetch followed at least one redirect (page fetch can't see the hop count) */
+  redirected?: boolean;
+}
+
 /**
  * Execute fetch() inside the browser page context.
  * Runs from the page's origin, inheriting cookies/CSRF.
@@ -930,7 +1121,7 @@ export async function executeInPageFetch(
   method: string,
   headers: Record<string, string>,
   body?: unknown,
-): Promise<{ status: number; data: unknown }> {
+): Promise<InPageFetchResult> {
   const fetchScript = `(async function() {
     try {
       var res = await fetch(${JSON.stringify(url)}, {
@@ -938,10 +1129,33 @@ export async function executeInPageFetch(
         headers: ${JSON.stringify(headers)},
         ${body ? `body: ${JSON.stringify(JSON.stringify(body))},` : ""}
       });
-      var text = await res.text();
+      var ct = res.headers.get("content-type") || "";
+      var text = "";
+      if (/text\/event-stream|ndjson|jsonl|json-seq|x-json-stream/i.test(ct) && res.body) {
+        // Streaming feed: read at most ${MAX_STREAM_BYTES} bytes / ${MAX_STREAM_MS}ms, then hang up.
+        var reader = res.body.getReader(), decoder = new TextDecoder(), bytes = 0, deadline = Date.now() + ${MAX_STREAM_MS};
+        while (bytes < ${MAX_STREAM_BYTES} && Date.now() < deadline) {
+          var chunk = await Promise.race([reader.read(), new Promise(function(r) { setTimeout(function() { r({ done: true }); }, Math.max(0, deadline - Date.now())); })]);
+          if (chunk.done || !chunk.value) break;
+          bytes += chunk.value.byteLength;
+          text += decoder.decode(chunk.value, { stream: true });
+        }
+        reader.cancel().catch(function() {});
+      } else {
+        text = await res.text();
+      }
       var data;
       try { data = JSON.parse(text); } catch(e) { data = text; }
-      return JSON.stringify({ status: res.status, data: data });
+      return JSON.stringify({
+        status: res.status,
+        data: data,
+        retry_after: res.headers.get("retry-after") || undefined,
+        etag: res.headers.get("etag") || undefined,
+        last_modified: res.headers.get("last-modified") || undefined,
+        rate_limit_headers: Object.fromEntries(Array.from(res.headers.entries()).filter(function(h) { return /ratelimit|rate-limit|retry-after/i.test(h[0]); })),
+        final_url: res.url || undefined,
+        redirected: res.redirected,
+      });
     } catch(e) {
       return JSON.stringify({ status: 0, data: { error: e.message } });
     }
@@ -949,7 +1163,7 @@ export async function executeInPageFetch(
 
   const result = await evaluate(tabId, fetchScript);
   try {
-    return JSON.parse(String(result)) as { status: number; data: unknown };
+    return JSON.parse(String(result)) as InPageFetchResult;
   } catch {
     return { status: 0, data: result };
   }
@@ -1042,6 +1256,19 @@ export async function select(tabId: string, ref: string, value: string): Promise
   return result;
 }
 
+/**
+ * Set a file input's files by ref (Kuri applies DOM.setFileInputFiles).
+ * The path is resolved and checked locally first — the browser runs on this
+ * machine, so a missing file would otherwise fail silently inside Chrome.
+ */
+export async function uploadFile(tabId: string, ref: string, filePath: string): Promise<unknown> {
+  const absolute = path.resolve(filePath);
+  if (!existsSync(absolute) || !statSync(absolute).isFile()) {
+    throw unbrowseError("invalid_input", `upload file not found: ${absolute}`, { file_path: absolute });
+  }
+  return action(tabId, "upload", ref, absolute);
+}
+
 /** Scroll the page (no ref needed, pass any ref value). */
 export async function scroll(tabId: string): Promise<unknown> {
   return kuriGet("/action", { tab_id: tabId, action: "scroll", ref: "_" });
@@ -1068,7 +1295,9 @@ export async function waitForSelector(
   const params: Record<string, string> = { tab_id: tabId };
   if (selector) params.selector = selector;
   if (timeoutMs !== undefined) params.timeout = String(timeoutMs);
-  return (await kuriGet("/wait", params)) as KuriWaitResult;
+  // Let Kuri's own wait deadline fire first; the client deadline only catches a hung server.
+  const clientTimeout = Math.max(getKuriRequestTimeoutMs(), (timeoutMs ?? 0) + 5_000);
+  return (await kuriGet("/wait", params, clientTimeout)) as KuriWaitResult;
 }
 
 /** Wait for page load (document.readyState === "complete"). */
@@ -4,6 +4,31 @@ import os from "node:os";
 
 const LOG_DIR = path.join(os.homedir(), ".unbrowse", "logs");
 
+export type LogLevel = "error" | "warn" | "info" | "debug" | "trace";
+const LOG_LEVELS: LogLevel[] = ["error", "warn", "info", "debug", "trace"];
+
+export function isLogLevel(value: unknown): value is LogLevel {
+  return typeof value === "string" && (LOG_LEVELS as string[]).includes(value);
+}
+
+// debug/trace are off unless UNBROWSE_LOG_LEVEL (or setLogLevel) turns them on;
+// warn/error silence log() (info) and, at error, logWarn.
+let currentLevel: LogLevel = isLogLevel(process.env.UNBROWSE_LOG_LEVEL) ? process.env.UNBROWSE_LOG_LEVEL : "info";
+
+/** Change verbosity at runtime (CLI `log-level`, POST /v1/log-level). */
+export function setLogLevel(level: LogLevel): void {
+  currentLevel = level;
+}
+
+export function getLogLevel(): LogLevel {
+  return currentLevel;
+}
+
+/** Check before building expensive debug/trace messages. */
+export function isLogLevelEnabled(level: LogLevel): boolean {
+  return LOG_LEVELS.indexOf(level) <= LOG_LEVELS.indexOf(currentLevel);
+}
+
 function getLogFile(): string {
   const date = new Date().toISOString().slice(0, 10); // YYYY-MM-DD
   return path.join(LOG_DIR, `unbrowse-${date}.log`);
@@ -13,11 +38,7 @@ function ensureLogDir(): void {
   fs.mkdirSync(LOG_DIR, { recursive: true });
 }
 
-/**
- * Log a message to both stdout and ~/.unbrowse/logs/unbrowse-YYYY-MM-DD.log.
- * Format: [HH:MM:SS] [module] message
- */
-export function log(module: string, message: string): void {
+function write(module: string, message: string): void {
   const ts = new Date().toTimeString().slice(0, 8); // HH:MM:SS
   const line = `[${ts}] [${module}] ${message}`;
   console.log(line);
@@ -28,3 +49,31 @@ export function log(module: string, message: string): void {
     // Never crash the main process if logging fails
   }
 }
+
+/**
+ * Log a message to both stdout and ~/.unbrowse/logs/unbrowse-YYYY-MM-DD.log.
+ * Format: [HH:MM:SS] [module] message. Info level — silenced at warn/error.
+ */
+export function log(module: string, message: string): void {
+  if (isLogLevelEnabled("info")) write(module, message);
+}
+
+/** Degraded but recovered (fallbacks, skipped input) — silenced only at error level. */
+export function logWarn(module: string, message: string): void {
+  if (isLogLevelEnabled("warn")) write(module, message);
+}
+
+/** Failures — emitted at every level. */
+export function logError(module: string, message: string): void {
+  write(module, message);
+}
+
+/** Filter decisions, per-request detail — emitted only at debug level or above. */
+export function logDebug(module: string, message: string): void {
+  if (isLogLevelEnabled("debug")) write(module, message);
+}
+
+/** Outbound HTTP calls and other high-volume detail — emitted only at trace level. */
+export function logTrace(module: string, message: string): void {
+  if (isLogLevelEnabled("trace")) write(module, message);
+}
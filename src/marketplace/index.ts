@@ -1,13 +1,14 @@
 import { nanoid } from "nanoid";
 import * as client from "../client/index.js";
-import type { EndpointDescriptor, SkillManifest, VerificationStatus } from "../types/index.js";
+import { diffSchemas } from "../transform/drift.js";
+import type { DriftResult, EndpointDescriptor, SkillManifest, VerificationStatus } from "../types/index.js";
 
 export async function listSkills(): Promise<SkillManifest[]> {
   return client.listSkills();
 }
 
-export async function getSkill(skillId: string, scopeId?: string): Promise<SkillManifest | null> {
-  return client.getSkill(skillId, scopeId);
+export async function getSkill(skillId: string, scopeId?: string, opts?: client.GetSkillOptions): Promise<SkillManifest | null> {
+  return client.getSkill(skillId, scopeId, opts);
 }
 
 export async function publishSkill(
@@ -87,14 +88,92 @@ export function mergeEndpoints(
       path_params: ep.path_params ?? dupe.path_params,
       body: ep.body ?? dupe.body,
       trigger_url: ep.trigger_url ?? dupe.trigger_url,
+      // A spec's documented description beats a heuristic one; the capture confirms the endpoint.
+      ...(dupe.from_spec && !ep.from_spec ? { description: dupe.description ?? ep.description, from_spec: false } : {}),
     };
   }
   return merged;
 }
 
+export interface EndpointChange {
+  /** Method + normalized template of the new endpoint */
+  key: string;
+  old_endpoint_id: string;
+  new_endpoint_id: string;
+  /** Same path captured under a different method */
+  method?: { was: EndpointDescriptor["method"]; now: EndpointDescriptor["method"] };
+  /** Response shape changed (both captures had a schema) */
+  schema?: DriftResult;
+}
+
+export interface SkillDiff {
+  added: EndpointDescriptor[];
+  removed: EndpointDescriptor[];
+  changed: EndpointChange[];
+  auth_method?: { was?: string; now?: string };
+}
+
+function endpointKey(ep: EndpointDescriptor): string {
+  return `${ep.method}:${normalizeTemplate(ep.url_template)}`;
+}
+
+function pathKey(ep: EndpointDescriptor): string {
+  return normalizeTemplate(ep.url_template);
+}
+
+/**
+ * What a re-capture changed relative to an earlier version of a skill.
+ * Endpoints are matched on method + templated URL (the mergeEndpoints key),
+ * so fresh endpoint_ids don't show up as spurious add/remove pairs. A lone
+ * removed/added pair on the same URL is reported as a method change.
+ */
+export function diffSkills(oldSkill: SkillManifest, newSkill: SkillManifest): SkillDiff {
+  const oldByKey = new Map(oldSkill.endpoints.map((ep) => [endpointKey(ep), ep]));
+  const newByKey = new Map(newSkill.endpoints.map((ep) => [endpointKey(ep), ep]));
+
+  const changed: EndpointChange[] = [];
+  for (const [key, now] of newByKey) {
+    const was = oldByKey.get(key);
+    if (!was || !was.response_schema || !now.response_schema) continue;
+    const drift = diffSchemas(was.response_schema, now.response_schema);
+    if (drift.drifted) changed.push({ key, old_endpoint_id: was.endpoint_id, new_endpoint_id: now.endpoint_id, schema: drift });
+  }
+
+  let added = newSkill.endpoints.filter((ep) => !oldByKey.has(endpointKey(ep)));
+  let removed = oldSkill.endpoints.filter((ep) => !newByKey.has(endpointKey(ep)));
+  const countByPath = (eps: EndpointDescriptor[]) => eps.reduce((m, ep) => m.set(pathKey(ep), (m.get(pathKey(ep)) ?? 0) + 1), new Map<string, number>());
+  const addedPaths = countByPath(added);
+  const removedPaths = countByPath(removed);
+  const methodChanged = new Set<string>();
+  for (const [path, count] of addedPaths) {
+    if (count !== 1 || removedPaths.get(path) !== 1) continue;
+    const now = added.find((ep) => pathKey(ep) === path)!;
+    const was = removed.find((ep) => pathKey(ep) === path)!;
+    changed.push({
+      key: endpointKey(now),
+      old_endpoint_id: was.endpoint_id,
+      new_endpoint_id: now.endpoint_id,
+      method: { was: was.method, now: now.method },
+      ...(was.response_schema && now.response_schema ? { schema: diffSchemas(was.response_schema, now.response_schema) } : {}),
+    });
+    methodChanged.add(path);
+  }
+  added = added.filter((ep) => !methodChanged.has(pathKey(ep)));
+  removed = removed.filter((ep) => !methodChanged.has(pathKey(ep)));
+
+  return {
+    added,
+    removed,
+    changed,
+    ...(oldSkill.auth_method !== newSkill.auth_method ? { auth_method: { was: oldSkill.auth_method, now: newSkill.auth_method } } : {}),
+  };
+}
+
 export function normalizeTemplate(t: string): string {
   return t
     .replace(/\{[^}]+\}/g, "{}")
+    // `/users/` and `/users` are one endpoint
+    .replace(/^([^?]*[^/?])\/+(?=\?|$)/, "$1")
     .replace(/([?&]queryid=)([^?&]+)/gi, (_match, prefix: string, value: string) => {
       if (value === "{}") return `${prefix}${value}`;
       return `${prefix}${value.replace(/\.[a-f0-9]{8,}$/i, "")}`;
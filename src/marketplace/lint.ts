@@ -0,0 +1,75 @@
+/**
+ * Pre-publish lint for skill manifests.
+ *
+ * Capture fills gaps with defaults ("API skill for x", "Unknown (may need
+ * login)", empty descriptions) and a broken capture can leave placeholder
+ * hosts in URL templates. Publishing those produces skills that look valid
+ * but can't be found or replayed. `error` issues block publish; `warning`
+ * issues are surfaced for the agent to fix.
+ */
+
+import type { SkillManifest } from "../types/index.js";
+
+export interface SkillLintIssue {
+  severity: "error" | "warning";
+  code: string;
+  message: string;
+  endpoint_id?: string;
+}
+
+const PLACEHOLDER_HOST = /(^|\.)(example\.(com|org|net)|localhost|invalid|test)$/i;
+const PLACEHOLDER_VALUE = /^(unknown|n\/a|none|todo|tbd|placeholder|undefined|null)$/i;
+const UNKNOWN_AUTH = /^unknown\b/i;
+
+function isPlaceholder(value: string | undefined): boolean {
+  return !value?.trim() || PLACEHOLDER_VALUE.test(value.trim());
+}
+
+/** Lint `skill` for publish. Issues are ordered errors first. */
+export function lintSkill(skill: SkillManifest): SkillLintIssue[] {
+  const issues: SkillLintIssue[] = [];
+  const error = (code: string, message: string, endpoint_id?: string) =>
+    issues.push({ severity: "error", code, message, ...(endpoint_id ? { endpoint_id } : {}) });
+  const warn = (code: string, message: string, endpoint_id?: string) =>
+    issues.push({ severity: "warning", code, message, ...(endpoint_id ? { endpoint_id } : {}) });
+
+  // Service
+  if (isPlaceholder(skill.domain)) error("missing_domain", "Skill has no domain");
+  else if (PLACEHOLDER_HOST.test(skill.domain)) error("placeholder_domain", `Domain ${skill.domain} is a placeholder`);
+  if (isPlaceholder(skill.name)) warn("missing_name", "Skill has no name");
+  if (!skill.description?.trim() || skill.description === `API skill for ${skill.domain}`) {
+    warn("default_description", "Skill description is missing or the capture default");
+  }
+
+  // Auth
+  // A warning, not an error: the vault fallback attaches auth_profile_ref to
+  // skills whose capture carried no auth headers to classify.
+  if (skill.auth_profile_ref && isPlaceholder(skill.auth_method)) {
+    warn("missing_auth_method", "Skill stores auth but has no auth_method");
+  } else if (skill.auth_method && UNKNOWN_AUTH.test(skill.auth_method)) {
+    warn("unknown_auth_method", `Auth method is "${skill.auth_method}"`);
+  }
+
+  // Endpoints
+  if (skill.endpoints.length === 0) {
+    error("no_endpoints", "Skill has no endpoints");
+    return issues;
+  }
+  for (const ep of skill.endpoints) {
+    let host: string | undefined;
+    try {
+      host = new URL(ep.url_template.replace(/\{[^}]+\}/g, "x")).hostname;
+    } catch {
+      error("invalid_url", `Endpoint URL is not absolute: ${ep.url_template}`, ep.endpoint_id);
+      continue;
+    }
+    if (PLACEHOLDER_HOST.test(host)) error("placeholder_url", `Endpoint points at placeholder host ${host}`, ep.endpoint_id);
+    if (isPlaceholder(ep.description)) warn("missing_description", `${ep.method} ${ep.url_template} has no description`, ep.endpoint_id);
+  }
+
+  return issues.sort((a, b) => (a.severity === b.severity ? 0 : a.severity === "error" ? -1 : 1));
+}
+
+export function hasLintErrors(issues: SkillLintIssue[]): boolean {
+  return issues.some((i) => i.severity === "error");
+}
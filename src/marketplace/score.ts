@@ -0,0 +1,105 @@
+/**
+ * Pre-publish quality score for skill manifests.
+ *
+ * Lint answers "can this be published"; the score answers "is it worth
+ * listing". Four components, each 0..1, are weighted into a 0–100 total
+ * and returned alongside it so a low score says what to improve. The
+ * marketplace badge is derived from the total.
+ */
+
+import type { EndpointDescriptor, SkillManifest, VerificationStatus } from "../types/index.js";
+
+/**
+ * Component weights (sum to 100). Working endpoints and a passing test run
+ * dominate: a skill that replays is worth more than a well-documented one
+ * that doesn't.
+ */
+export const SCORE_WEIGHTS = {
+  /** Distinct working endpoints, saturating at SATURATING_ENDPOINT_COUNT */
+  endpoints: 35,
+  /** How sure we are of the auth method (public skills score full) */
+  auth: 15,
+  /** Fraction of endpoints with an example request or response */
+  examples: 20,
+  /** Fraction of tested endpoints that verified (2xx) */
+  tests: 30,
+} as const;
+
+/** Working endpoints at which the endpoints component is maxed out. */
+export const SATURATING_ENDPOINT_COUNT = 5;
+
+/** Minimum totals for each badge; below `bronze` there is none. */
+export const BADGE_THRESHOLDS = { gold: 80, silver: 60, bronze: 40 } as const;
+
+export type SkillBadge = keyof typeof BADGE_THRESHOLDS;
+
+export interface SkillScoreComponent {
+  /** 0..1 before weighting */
+  value: number;
+  weight: number;
+  /** value × weight, rounded */
+  points: number;
+  detail: string;
+}
+
+export interface SkillScore {
+  /** 0–100 */
+  total: number;
+  badge?: SkillBadge;
+  breakdown: Record<keyof typeof SCORE_WEIGHTS, SkillScoreComponent>;
+}
+
+const UNKNOWN_AUTH = /^unknown\b/i;
+
+function component(key: keyof typeof SCORE_WEIGHTS, value: number, detail: string): SkillScoreComponent {
+  const weight = SCORE_WEIGHTS[key];
+  const clamped = Math.max(0, Math.min(1, value));
+  return { value: Number(clamped.toFixed(2)), weight, points: Math.round(clamped * weight), detail };
+}
+
+function hasExample(ep: EndpointDescriptor): boolean {
+  return ep.semantic?.example_response_compact !== undefined
+    || ep.semantic?.example_request !== undefined
+    || !!ep.body;
+}
+
+/** Auth confidence: public or a classified scheme is certain; a label without auth_scheme is partial; "Unknown" is low. */
+function authConfidence(skill: SkillManifest): { value: number; detail: string } {
+  if ((!skill.auth_method && !skill.auth_profile_ref) || skill.auth_scheme === "none") return { value: 1, detail: "no auth required" };
+  if (!skill.auth_method || UNKNOWN_AUTH.test(skill.auth_method) || skill.auth_scheme === "unknown") {
+    return { value: 0.2, detail: `auth method unknown${skill.auth_method ? ` ("${skill.auth_method}")` : ""}` };
+  }
+  if (!skill.auth_scheme) return { value: 0.6, detail: `${skill.auth_method} (unclassified)` };
+  return { value: 1, detail: skill.auth_method };
+}
+
+/**
+ * Score `skill` for marketplace listing. `testResults` (verifySkill's
+ * endpoint_id → status map) takes precedence over the statuses stored on
+ * the endpoints; endpoints that were never tested don't count either way.
+ */
+export function scoreSkill(skill: SkillManifest, testResults?: Record<string, VerificationStatus>): SkillScore {
+  const statusOf = (ep: EndpointDescriptor) => testResults?.[ep.endpoint_id] ?? ep.verification_status;
+  const endpoints = skill.endpoints.filter((ep) => statusOf(ep) !== "disabled");
+
+  const working = new Set(endpoints
+    .filter((ep) => statusOf(ep) === "verified")
+    .map((ep) => `${ep.method} ${ep.url_template}`));
+  const tested = endpoints.filter((ep) => statusOf(ep) === "verified" || statusOf(ep) === "failed");
+  const passed = tested.filter((ep) => statusOf(ep) === "verified").length;
+  const withExamples = endpoints.filter(hasExample).length;
+  const auth = authConfidence(skill);
+
+  const breakdown: SkillScore["breakdown"] = {
+    endpoints: component("endpoints", working.size / SATURATING_ENDPOINT_COUNT,
+      `${working.size} working of ${endpoints.length} endpoint(s)`),
+    auth: component("auth", auth.value, auth.detail),
+    examples: component("examples", endpoints.length ? withExamples / endpoints.length : 0,
+      `${withExamples} of ${endpoints.length} endpoint(s) have example bodies`),
+    tests: component("tests", tested.length ? passed / tested.length : 0,
+      tested.length ? `${passed} of ${tested.length} tested endpoint(s) returned 2xx` : "no endpoints tested"),
+  };
+  const total = Object.values(breakdown).reduce((sum, c) => sum + c.points, 0);
+  const badge = (Object.keys(BADGE_THRESHOLDS) as SkillBadge[]).find((b) => total >= BADGE_THRESHOLDS[b]);
+  return { total, ...(badge ? { badge } : {}), breakdown };
+}
@@ -451,12 +451,14 @@ async function executeResolvedEndpoint(result: Record<string, unknown>, args: Re
 const tools: ToolDefinition[] = [
   {
     name: "unbrowse_health",
-    description: "Check the local Unbrowse runtime health and version trace.",
+    description: "Check the local Unbrowse runtime health, version trace, and external dependencies (browser, vault, Chrome cookies, marketplace, wallet).",
     inputSchema: { type: "object", properties: {}, additionalProperties: false },
     annotations: { readOnlyHint: true },
     handler: async () => {
       await ensureServerReady();
-      return successResult(await api("GET", "/health"), "Unbrowse local runtime health.");
+      const server = await api("GET", "/health") as Record<string, unknown>;
+      const report = await api("GET", "/v1/health/check") as Record<string, unknown>;
+      return successResult({ ...server, ...report }, "Unbrowse local runtime health.");
     },
   },
   {
@@ -801,6 +803,24 @@ const tools: ToolDefinition[] = [
       return successResult(await api("POST", "/v1/browse/select", { ref: args.ref, value: args.value }), "Option selected.");
     },
   },
+  {
+    name: "unbrowse_upload",
+    description: "Set the file of a file input in the active browse session by ref.",
+    inputSchema: {
+      type: "object",
+      properties: {
+        ref: { type: "string", description: "File input ref from unbrowse_snap." },
+        file_path: { type: "string", description: "Absolute path of a local file to upload." },
+      },
+      required: ["ref", "file_path"],
+      additionalProperties: false,
+    },
+    annotations: { destructiveHint: true },
+    handler: async (args) => {
+      await ensureServerReady();
+      return successResult(await api("POST", "/v1/browse/upload", { ref: args.ref, file_path: args.file_path }), "File set.");
+    },
+  },
   {
     name: "unbrowse_scroll",
     description: "Scroll the current page in the active browse session.",
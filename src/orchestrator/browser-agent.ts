@@ -9,8 +9,10 @@
 
 import * as kuri from "../kuri/client.js";
 import type { KuriHarEntry } from "../kuri/client.js";
-import { INTERCEPTOR_SCRIPT, collectInterceptedRequests, type RawRequest } from "../capture/index.js";
+import { INTERCEPTOR_SCRIPT, canonicalHeaders, collectInterceptedRequests, type RawRequest } from "../capture/index.js";
+import { parseHarDateTime } from "../time.js";
 import { extractEndpoints, extractAuthHeaders } from "../reverse-engineer/index.js";
+import { dedupeAuthHeaders } from "../reverse-engineer/auth-detect.js";
 import { extractBrowserCookies } from "../auth/browser-cookies.js";
 import { queueBackgroundIndex } from "../indexer/index.js";
 import { mergeEndpoints } from "../marketplace/index.js";
@@ -86,14 +88,9 @@ function heuristicPlan(
   _currentUrl: string,
 ): PlannedAction {
   const intentLower = intent.toLowerCase();
-  const lines = snapshot.split("\n").filter(l => l.trim());
 
   // Parse refs from snapshot
-  const elements = lines.map(l => {
-    const match = l.match(/^\[(\w+)\]\s+(\w+)\s+"?(.+?)"?\s*$/);
-    if (!match) return null;
-    return { ref: match[1], role: match[2], name: match[3].replace(/"$/, "") };
-  }).filter(Boolean) as Array<{ ref: string; role: string; name: string }>;
+  const elements = kuri.parseSnapshotElements(snapshot);
 
   // Search intent: find a textbox and fill it
   const isSearch = /search|find|look\s*up|query/i.test(intentLower);
@@ -283,12 +280,12 @@ export async function agenticBrowserResolve(
       .map((e: KuriHarEntry) => ({
         url: e.request.url,
         method: e.request.method,
-        request_headers: Object.fromEntries((e.request.headers ?? []).map(h => [h.name.toLowerCase(), h.value])),
-        request_body: e.request.postData?.text,
+        request_headers: canonicalHeaders(e.request.headers),
+        request_body: kuri.harPostDataText(e.request.postData),
         response_status: e.response.status,
-        response_headers: Object.fromEntries((e.response.headers ?? []).map(h => [h.name.toLowerCase(), h.value])),
-        response_body: e.response.content?.text,
-        timestamp: e.startedDateTime ?? new Date().toISOString(),
+        response_headers: canonicalHeaders(e.response.headers),
+        ...kuri.harResponseBody(e.response.content),
+        timestamp: parseHarDateTime(e.startedDateTime),
       }));
     // Merge HAR requests that interceptor missed
     for (const r of harRequests) {
@@ -311,7 +308,7 @@ export async function agenticBrowserResolve(
     // Auth extraction + vault storage
     const capturedAuthHeaders = extractAuthHeaders(allRequests);
     if (Object.keys(capturedAuthHeaders).length > 0) {
-      await storeCredential(`${domain}-session`, JSON.stringify({ headers: capturedAuthHeaders })).catch(() => {});
+      await storeCredential(`${domain}-session`, JSON.stringify(dedupeAuthHeaders(capturedAuthHeaders)), { source_url: url }).catch(() => {});
     }
 
     // Merge with existing skill
@@ -17,7 +17,8 @@ import { getPrefetchTargets, executePrefetch } from "../capture/prefetch.js";
 import { tryFirstPassBrowserAction } from "./first-pass-action.js";
 import { DEFAULT_CAPTURE_TOKENS, computeTimingEconomics } from "./timing-economics.js";
 import { checkPaymentRequirement } from "../payments/index.js";
-import { checkWalletConfigured } from "../payments/wallet.js";
+import { classifyMarketplaceError } from "../payments/x402.js";
+import { checkWalletConfigured, getWalletBalance } from "../payments/wallet.js";
 import type {
   ExecutionOptions,
   ExecutionTrace,
@@ -2520,12 +2521,16 @@ export async function resolveAndExecute(
             try {
               const walletCheck = checkWalletConfigured();
               const wallet = getLocalWalletContext();
+              const usdcBalance = walletCheck.configured
+                ? await getWalletBalance().then((b) => b.usdc).catch(() => undefined)
+                : undefined;
               const paymentResult = await checkPaymentRequirement(
                 skill.skill_id,
                 candidate.endpoint.endpoint_id,
                 {
                   price_usd: String(skill.base_price_usd),
                   wallet_configured: walletCheck.configured,
+                  usdc_balance: usdcBalance,
                 },
               );
               if (paymentResult.status !== "free" && paymentResult.status !== "paid") {
@@ -3012,6 +3017,7 @@ export async function resolveAndExecute(
             indexing_fallback_available: true,
             tier: "tier3",
             terms: err.terms,
+            payment: classifyMarketplaceError(err),
           },
           trace,
           source: "marketplace",
@@ -1,6 +1,6 @@
 import { createHash } from "crypto";
-import bs58 from "bs58";
 import type { SkillManifest } from "../types/index.js";
+import { decodeSecretKey, toKeypairBytes } from "./keypair.js";
 
 type Contributor = NonNullable<SkillManifest["contributors"]>[number];
 
@@ -33,6 +33,12 @@ type CascadeDeps = {
   loadSdk?: () => Promise<CascadeSdk>;
   loadKit?: () => Promise<SolanaKit>;
   env?: Record<string, string | undefined>;
+  /**
+   * Platform address the signer key must belong to; defaults to
+   * UNBROWSE_CASCADE_SIGNER_ADDRESS. Unchecked when neither is set — the
+   * signer is the platform's key, not the user's wallet.
+   */
+  expectedSigner?: string;
 };
 
 export type CascadeProvisionResult = {
@@ -50,21 +56,6 @@ function cascadeLabel(skillId: string): string {
   return `ubr-${digest.slice(0, 23)}`;
 }
 
-function decodeSecretKey(raw: string): Uint8Array {
-  const trimmed = raw.trim();
-  if (!trimmed) throw new Error("empty signer secret");
-
-  if (trimmed.startsWith("[")) {
-    return Uint8Array.from(JSON.parse(trimmed) as number[]);
-  }
-
-  if (/^[1-9A-HJ-NP-Za-km-z]+$/.test(trimmed)) {
-    return Uint8Array.from(bs58.decode(trimmed));
-  }
-
-  return Uint8Array.from(Buffer.from(trimmed, "base64"));
-}
-
 function recipientsForSkill(
   skill: Pick<SkillManifest, "contributors">,
   platformWallet: string,
@@ -112,7 +103,8 @@ export async function ensureCascadeSplitForSkill(
   const loadKit = deps.loadKit ?? (async () => await import("@solana/kit") as unknown as SolanaKit);
   const [sdk, kit] = await Promise.all([loadSdk(), loadKit()]);
 
-  const signer = await kit.createKeyPairSignerFromBytes(decodeSecretKey(secretKey));
+  const expectedSigner = deps.expectedSigner ?? (env.UNBROWSE_CASCADE_SIGNER_ADDRESS?.trim() || undefined);
+  const signer = await kit.createKeyPairSignerFromBytes(toKeypairBytes(decodeSecretKey(secretKey), expectedSigner));
   const splits = sdk.createSplitsClient({
     rpc: kit.createSolanaRpc(rpcUrl),
     rpcSubscriptions: kit.createSolanaRpcSubscriptions(rpcWsUrl),
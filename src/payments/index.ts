@@ -12,6 +12,8 @@
  * @see https://lobster.cash/docs/skill-compatibility-guide
  */
 
+import { resolveSolanaNetwork } from "./wallet.js";
+
 // ---------------------------------------------------------------------------
 // Payment requirement — what unbrowse tells the agent/wallet
 // ---------------------------------------------------------------------------
@@ -23,6 +25,8 @@ export interface PaymentRequirement {
   reason: string;
   recipient?: string;
   memo?: string;
+  /** x402 network the payment must settle on, e.g. "solana-devnet"; servers reject other networks */
+  network?: string;
 }
 
 export type PaymentStatus =
@@ -51,7 +55,8 @@ export const X402_CONFIG = {
     solana: {
       network: "solana",
       currency: "USDC",
-      testnet: "solana-devnet",
+      devnet: "solana-devnet",
+      testnet: "solana-testnet",
       mainnet: "solana-mainnet",
     },
     base: {
@@ -135,6 +140,8 @@ export async function checkPaymentRequirement(
     price_usd?: string;
     skip_payment?: boolean;
     wallet_configured?: boolean;
+    /** Known USDC balance (see getWalletBalance); checked before the wallet is asked to sign. */
+    usdc_balance?: number;
   },
 ): Promise<PaymentGateResult> {
   if (options?.skip_payment || process.env.UNBROWSE_SKIP_PAYMENT === "1") {
@@ -163,6 +170,7 @@ export async function checkPaymentRequirement(
     reason: `Per-query fee for ${skillId}/${endpointId}`,
     recipient: X402_CONFIG.facilitator,
     memo: `unbrowse:${skillId}:${endpointId}`,
+    network: X402_CONFIG.chains.solana[resolveSolanaNetwork().network],
   };
 
   if (options?.wallet_configured === false) {
@@ -174,6 +182,15 @@ export async function checkPaymentRequirement(
     };
   }
 
+  if (options?.usdc_balance != null && options.usdc_balance < parseFloat(amount)) {
+    return {
+      status: "insufficient_balance",
+      requirement,
+      message: `Insufficient USDC: wallet holds ${options.usdc_balance}, this execution requires ${amount}.`,
+      next_step: "Fund your agent wallet with USDC and retry, or continue in indexing mode.",
+    };
+  }
+
   return {
     status: "payment_required",
     requirement,
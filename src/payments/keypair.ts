@@ -0,0 +1,54 @@
+/**
+ * Ed25519 signer secrets for Solana payments.
+ *
+ * @solana/kit wants the 64-byte Solana keypair (seed + public key), but
+ * keys exported by other wallets are often the 32-byte seed alone. Both are
+ * accepted; the public half is derived from the seed and, for 64-byte keys,
+ * checked against the stored one so a corrupted or spliced keypair fails
+ * here instead of signing from an unexpected address.
+ */
+
+import { createPrivateKey, createPublicKey } from "crypto";
+import bs58 from "bs58";
+
+/** PKCS#8 DER prefix for a raw Ed25519 seed (RFC 8410). */
+const ED25519_PKCS8_PREFIX = Buffer.from("302e020100300506032b657004220420", "hex");
+
+/** Decode a signer secret given as a JSON byte array, base58, or base64. */
+export function decodeSecretKey(raw: string): Uint8Array {
+  const trimmed = raw.trim();
+  if (!trimmed) throw new Error("empty signer secret");
+  if (trimmed.startsWith("[")) return Uint8Array.from(JSON.parse(trimmed) as number[]);
+  if (/^[1-9A-HJ-NP-Za-km-z]+$/.test(trimmed)) return Uint8Array.from(bs58.decode(trimmed));
+  return Uint8Array.from(Buffer.from(trimmed, "base64"));
+}
+
+function publicKeyFromSeed(seed: Uint8Array): Uint8Array {
+  const privateKey = createPrivateKey({ key: Buffer.concat([ED25519_PKCS8_PREFIX, seed]), format: "der", type: "pkcs8" });
+  const spki = createPublicKey(privateKey).export({ format: "der", type: "spki" });
+  return Uint8Array.from(spki.subarray(spki.length - 32));
+}
+
+/**
+ * The 64-byte keypair for `secret`: a 32-byte seed gets its public key
+ * appended; a 64-byte keypair must carry the public key its seed derives.
+ * With `expectedAddress` (base58), the keypair's address must match it.
+ */
+export function toKeypairBytes(secret: Uint8Array, expectedAddress?: string): Uint8Array {
+  if (secret.length !== 32 && secret.length !== 64) {
+    throw new Error(`signer secret must be a 32-byte seed or a 64-byte keypair, got ${secret.length} bytes`);
+  }
+  const seed = secret.subarray(0, 32);
+  const publicKey = publicKeyFromSeed(seed);
+  if (secret.length === 64 && !Buffer.from(secret.subarray(32)).equals(Buffer.from(publicKey))) {
+    throw new Error("signer keypair is inconsistent: its public key does not match its secret seed");
+  }
+  const address = bs58.encode(publicKey);
+  if (expectedAddress && address !== expectedAddress.trim()) {
+    throw new Error(`signer key is for ${address}, but the configured wallet is ${expectedAddress.trim()}`);
+  }
+  const keypair = new Uint8Array(64);
+  keypair.set(seed, 0);
+  keypair.set(publicKey, 32);
+  return keypair;
+}
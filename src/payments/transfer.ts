@@ -0,0 +1,166 @@
+/**
+ * Submittable USDC transfers for direct-settlement marketplaces.
+ *
+ * x402 payments only carry a signed intent and leave settlement to the
+ * server. This builds the actual SPL token transaction instead: an
+ * idempotent create of the recipient's associated token account followed by
+ * a TransferChecked from the sender's, signed with UNBROWSE_WALLET_SECRET_KEY
+ * and returned as a base64 wire transaction. Nothing is submitted here.
+ */
+
+import { decodeSecretKey, toKeypairBytes } from "./keypair.js";
+import { resolveSolanaNetwork, resolveWalletProfile, type SolanaNetwork } from "./wallet.js";
+
+const TOKEN_PROGRAM = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const ASSOCIATED_TOKEN_PROGRAM = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+const SYSTEM_PROGRAM = "11111111111111111111111111111111";
+const USDC_DECIMALS = 6;
+/** SPL Token instruction index for TransferChecked */
+const TRANSFER_CHECKED = 12;
+/** Associated Token Account instruction index for CreateIdempotent */
+const CREATE_ATA_IDEMPOTENT = 1;
+
+// AccountRole values from @solana/kit (bit 1 = signer, bit 0 = writable).
+const READONLY = 0;
+const WRITABLE = 1;
+const WRITABLE_SIGNER = 3;
+
+type KitSigner = { address: string };
+type AccountMeta = { address: string; role: number; signer?: KitSigner };
+type Instruction = { programAddress: string; accounts: AccountMeta[]; data: Uint8Array };
+
+type SolanaKit = {
+  address(value: string): string;
+  getAddressEncoder(): { encode(address: string): Uint8Array };
+  getProgramDerivedAddress(args: { programAddress: string; seeds: Uint8Array[] }): Promise<[string, number]>;
+  createKeyPairSignerFromBytes(secretKey: Uint8Array): Promise<KitSigner>;
+  createTransactionMessage(args: { version: 0 }): unknown;
+  setTransactionMessageFeePayerSigner(signer: KitSigner, message: unknown): unknown;
+  setTransactionMessageLifetimeUsingBlockhash(lifetime: { blockhash: string; lastValidBlockHeight: bigint }, message: unknown): unknown;
+  appendTransactionMessageInstructions(instructions: Instruction[], message: unknown): unknown;
+  signTransactionMessageWithSigners(message: unknown): Promise<unknown>;
+  getBase64EncodedWireTransaction(transaction: unknown): string;
+};
+
+type TransferDeps = {
+  loadKit?: () => Promise<SolanaKit>;
+  env?: Record<string, string | undefined>;
+  /** Cluster whose USDC mint to use; defaults to resolveSolanaNetwork() */
+  network?: SolanaNetwork;
+  /** Address the signer key must belong to; defaults to the configured wallet (resolveWalletProfile) */
+  expectedSender?: string;
+};
+
+export type UsdcTransfer = {
+  /** Base64 wire transaction, ready for sendTransaction */
+  transaction: string;
+  /** Cluster the transaction targets (the blockhash must come from it) */
+  network: SolanaNetwork;
+  sender: string;
+  recipient: string;
+  sender_token_account: string;
+  recipient_token_account: string;
+  /** Amount in base units (6 decimals) */
+  amount: string;
+};
+
+/** USDC amount → base units. Rejects non-positive and sub-micro-USDC amounts. */
+function toBaseUnits(amountUsdc: number): bigint {
+  if (!Number.isFinite(amountUsdc) || amountUsdc <= 0) throw new Error(`Invalid USDC amount: ${amountUsdc}`);
+  const units = Math.round(amountUsdc * 10 ** USDC_DECIMALS);
+  if (units <= 0) throw new Error(`USDC amount below the smallest unit: ${amountUsdc}`);
+  return BigInt(units);
+}
+
+async function associatedTokenAccount(kit: SolanaKit, owner: string, mint: string): Promise<string> {
+  const encoder = kit.getAddressEncoder();
+  const [ata] = await kit.getProgramDerivedAddress({
+    programAddress: kit.address(ASSOCIATED_TOKEN_PROGRAM),
+    seeds: [encoder.encode(owner), encoder.encode(kit.address(TOKEN_PROGRAM)), encoder.encode(mint)],
+  });
+  return ata;
+}
+
+function transferCheckedData(amount: bigint): Uint8Array {
+  const data = new Uint8Array(10);
+  const view = new DataView(data.buffer);
+  data[0] = TRANSFER_CHECKED;
+  view.setBigUint64(1, amount, true);
+  data[9] = USDC_DECIMALS;
+  return data;
+}
+
+/**
+ * Build and sign a USDC transfer of `amountUsdc` to `recipient` against
+ * `recentBlockhash`. The signer (UNBROWSE_WALLET_SECRET_KEY: JSON byte array,
+ * base58, or base64; a 32-byte seed or 64-byte keypair) is the sender and
+ * fee payer, and must belong to the configured wallet when one is set. The mint is the USDC mint
+ * of the resolved cluster (see resolveSolanaNetwork).
+ */
+export async function buildUsdcTransfer(
+  recipient: string,
+  amountUsdc: number,
+  recentBlockhash: string,
+  deps: TransferDeps = {},
+): Promise<UsdcTransfer> {
+  const env = deps.env ?? process.env;
+  const secretKey = env.UNBROWSE_WALLET_SECRET_KEY?.trim();
+  if (!secretKey) throw new Error("UNBROWSE_WALLET_SECRET_KEY is not set");
+  const amount = toBaseUnits(amountUsdc);
+
+  const kit = await (deps.loadKit ?? (async () => await import("@solana/kit") as unknown as SolanaKit))();
+  const expectedSender = deps.expectedSender ?? resolveWalletProfile()?.wallet_address;
+  const signer = await kit.createKeyPairSignerFromBytes(toKeypairBytes(decodeSecretKey(secretKey), expectedSender));
+  const cluster = resolveSolanaNetwork({ network: deps.network });
+  if (!cluster.usdcMint) throw new Error(`No USDC mint known for ${cluster.network}; set UNBROWSE_USDC_MINT_${cluster.network.toUpperCase()}`);
+  const mint = kit.address(cluster.usdcMint);
+  const to = kit.address(recipient);
+  const [source, destination] = await Promise.all([
+    associatedTokenAccount(kit, signer.address, mint),
+    associatedTokenAccount(kit, to, mint),
+  ]);
+
+  const instructions: Instruction[] = [
+    {
+      // No-op when the recipient already holds a USDC account.
+      programAddress: kit.address(ASSOCIATED_TOKEN_PROGRAM),
+      accounts: [
+        { address: signer.address, role: WRITABLE_SIGNER, signer },
+        { address: destination, role: WRITABLE },
+        { address: to, role: READONLY },
+        { address: mint, role: READONLY },
+        { address: kit.address(SYSTEM_PROGRAM), role: READONLY },
+        { address: kit.address(TOKEN_PROGRAM), role: READONLY },
+      ],
+      data: Uint8Array.of(CREATE_ATA_IDEMPOTENT),
+    },
+    {
+      programAddress: kit.address(TOKEN_PROGRAM),
+      accounts: [
+        { address: source, role: WRITABLE },
+        { address: mint, role: READONLY },
+        { address: destination, role: WRITABLE },
+        { address: signer.address, role: WRITABLE_SIGNER, signer },
+      ],
+      data: transferCheckedData(amount),
+    },
+  ];
+
+  // lastValidBlockHeight only drives client-side expiry checks; it is not
+  // part of the wire transaction, so an unbounded value is fine here.
+  let message = kit.createTransactionMessage({ version: 0 });
+  message = kit.setTransactionMessageFeePayerSigner(signer, message);
+  message = kit.setTransactionMessageLifetimeUsingBlockhash({ blockhash: recentBlockhash, lastValidBlockHeight: 2n ** 64n - 1n }, message);
+  message = kit.appendTransactionMessageInstructions(instructions, message);
+  const signed = await kit.signTransactionMessageWithSigners(message);
+
+  return {
+    transaction: kit.getBase64EncodedWireTransaction(signed),
+    network: cluster.network,
+    sender: signer.address,
+    recipient: to,
+    sender_token_account: source,
+    recipient_token_account: destination,
+    amount: amount.toString(),
+  };
+}
@@ -1,33 +1,268 @@
 /**
  * Wallet precheck — lobster.cash compatible.
  *
- * This module only checks whether the agent has a wallet configured.
+ * This module only checks whether the agent has a wallet configured,
+ * remembers named wallet addresses (profiles), reads public balances
+ * from a Solana RPC on the configured cluster, and requests test airdrops
+ * on devnet/testnet.
  * It does NOT generate wallets, manage keys, or call wallet APIs.
  * Wallet provisioning and transaction execution are owned by
  * the agent's wallet plugin (e.g. lobster.cash).
  */
 
+import { existsSync, mkdirSync, readFileSync, writeFileSync } from "fs";
+import { join } from "path";
+import { homedir } from "os";
+
 export type WalletCheckResult = {
   configured: boolean;
   provider?: string;
+  profile?: string;
+};
+
+export type WalletProfile = {
+  wallet_address: string;
+  wallet_provider?: string;
+};
+
+export type SolanaNetwork = "mainnet" | "devnet" | "testnet";
+
+type WalletProfileStore = {
+  active?: string;
+  wallets: Record<string, WalletProfile>;
+  /** Default Solana cluster for balance/transfer/airdrop calls */
+  network?: SolanaNetwork;
+  /** Custom RPC endpoint for `network` only; other clusters keep their public RPC */
+  rpc_url?: string;
 };
 
+function getWalletProfilesPath(): string {
+  const dir = process.env.UNBROWSE_CONFIG_DIR || join(homedir(), ".unbrowse");
+  return join(dir, "wallets.json");
+}
+
+function loadWalletProfiles(): WalletProfileStore {
+  try {
+    const path = getWalletProfilesPath();
+    if (existsSync(path)) {
+      const parsed = JSON.parse(readFileSync(path, "utf-8")) as Partial<WalletProfileStore>;
+      return { ...parsed, wallets: parsed.wallets ?? {} };
+    }
+  } catch { /* corrupt file — treat as empty */ }
+  return { wallets: {} };
+}
+
+function saveWalletProfiles(store: WalletProfileStore): void {
+  const path = getWalletProfilesPath();
+  const dir = join(path, "..");
+  if (!existsSync(dir)) mkdirSync(dir, { recursive: true });
+  writeFileSync(path, JSON.stringify(store, null, 2), { mode: 0o600 });
+}
+
+/** Names of all saved wallet profiles. */
+export function listWalletProfiles(): string[] {
+  return Object.keys(loadWalletProfiles().wallets).sort();
+}
+
+/** Active profile: UNBROWSE_WALLET_PROFILE, then the persisted default. */
+export function getActiveWalletProfile(): string | undefined {
+  return process.env.UNBROWSE_WALLET_PROFILE?.trim() || loadWalletProfiles().active;
+}
+
+/** Persist `name` as the default profile. Throws if it was never saved. */
+export function setActiveWalletProfile(name: string): void {
+  const store = loadWalletProfiles();
+  if (!store.wallets[name]) throw new Error(`Unknown wallet profile: ${name}`);
+  saveWalletProfiles({ ...store, active: name });
+}
+
+/** Save (or replace) a named wallet address. The first saved profile becomes active. */
+export function saveWalletProfile(name: string, wallet: WalletProfile): void {
+  const store = loadWalletProfiles();
+  store.wallets[name] = wallet;
+  saveWalletProfiles({ ...store, active: store.active ?? name });
+}
+
+export function removeWalletProfile(name: string): void {
+  const store = loadWalletProfiles();
+  delete store.wallets[name];
+  saveWalletProfiles({ ...store, active: store.active === name ? undefined : store.active });
+}
+
+/**
+ * Resolve the wallet to use. Env signals set by a wallet plugin always win
+ * (the unnamed profile, for backward compatibility); otherwise the named
+ * profile, defaulting to the active one.
+ */
+export function resolveWalletProfile(profile?: string): (WalletProfile & { profile?: string }) | undefined {
+  if (!profile) {
+    // lobster.cash plugin sets these when wallet is paired
+    const lobster = process.env.LOBSTER_WALLET_ADDRESS?.trim();
+    if (lobster) return { wallet_address: lobster, wallet_provider: "lobster.cash" };
+    // Generic wallet context (other providers)
+    const generic = process.env.AGENT_WALLET_ADDRESS?.trim();
+    if (generic) return { wallet_address: generic, wallet_provider: process.env.AGENT_WALLET_PROVIDER?.trim() || undefined };
+  }
+  const name = profile ?? getActiveWalletProfile();
+  if (!name) return undefined;
+  const wallet = loadWalletProfiles().wallets[name];
+  return wallet ? { ...wallet, profile: name } : undefined;
+}
+
 /**
  * Check if the agent has a wallet configured.
  *
- * Looks for wallet context signals that a wallet plugin would set.
+ * Looks for wallet context signals that a wallet plugin would set,
+ * then for a saved wallet profile.
  * Does NOT create or modify wallet state.
  */
-export function checkWalletConfigured(): WalletCheckResult {
-  // lobster.cash plugin sets these when wallet is paired
-  if (process.env.LOBSTER_WALLET_ADDRESS) {
-    return { configured: true, provider: "lobster.cash" };
-  }
+export function checkWalletConfigured(profile?: string): WalletCheckResult {
+  const wallet = resolveWalletProfile(profile);
+  if (!wallet) return { configured: false };
+  return {
+    configured: true,
+    provider: wallet.wallet_provider ?? "unknown",
+    ...(wallet.profile ? { profile: wallet.profile } : {}),
+  };
+}
 
-  // Generic wallet context (other providers)
-  if (process.env.AGENT_WALLET_ADDRESS) {
-    return { configured: true, provider: process.env.AGENT_WALLET_PROVIDER ?? "unknown" };
+/** Canonical mainnet USDC mint. */
+export const USDC_MINT = "EPjFWdd5AufqSSqeM2qtKxGFCYLxxUGd9tJ3VqK5zt1v";
+/** Circle's devnet USDC mint (faucet.circle.com). */
+export const DEVNET_USDC_MINT = "4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU";
+const PUBLIC_RPC_URLS: Record<SolanaNetwork, string> = {
+  mainnet: "https://api.mainnet-beta.solana.com",
+  devnet: "https://api.devnet.solana.com",
+  testnet: "https://api.testnet.solana.com",
+};
+const BALANCE_TIMEOUT_MS = 5_000;
+const LAMPORTS_PER_SOL = 1_000_000_000;
+
+export type SolanaNetworkConfig = {
+  network: SolanaNetwork;
+  rpcUrl: string;
+  /** USDC mint on this cluster; undefined on testnet (no canonical USDC) */
+  usdcMint?: string;
+};
+
+function isSolanaNetwork(value: string | undefined): value is SolanaNetwork {
+  return value === "mainnet" || value === "devnet" || value === "testnet";
+}
+
+/**
+ * Cluster and RPC to use. Each setting resolves per call argument, then
+ * env (UNBROWSE_SOLANA_NETWORK, UNBROWSE_SOLANA_RPC_URL, UNBROWSE_USDC_MINT),
+ * then wallets.json (`unbrowse wallet network`), then mainnet defaults.
+ *
+ * A configured RPC URL or mint belongs to the configured cluster: asking for
+ * a different network falls back to that network's public RPC and known
+ * mint rather than talking to the wrong cluster. Per-cluster mints can be
+ * set with UNBROWSE_USDC_MINT_MAINNET / _DEVNET / _TESTNET.
+ */
+export function resolveSolanaNetwork(opts: { network?: SolanaNetwork; rpcUrl?: string } = {}): SolanaNetworkConfig {
+  const store = loadWalletProfiles();
+  const envNetwork = process.env.UNBROWSE_SOLANA_NETWORK?.trim();
+  const configured = (isSolanaNetwork(envNetwork) ? envNetwork : undefined) ?? store.network ?? "mainnet";
+  const network = opts.network ?? configured;
+  const envRpcUrl = network === configured ? process.env.UNBROWSE_SOLANA_RPC_URL?.trim() || undefined : undefined;
+  const storedRpcUrl = network === (store.network ?? "mainnet") ? store.rpc_url : undefined;
+  const rpcUrl = opts.rpcUrl ?? envRpcUrl ?? storedRpcUrl ?? PUBLIC_RPC_URLS[network];
+  const usdcMint = process.env[`UNBROWSE_USDC_MINT_${network.toUpperCase()}`]?.trim()
+    || (network === configured ? process.env.UNBROWSE_USDC_MINT?.trim() : undefined)
+    || (network === "mainnet" ? USDC_MINT : network === "devnet" ? DEVNET_USDC_MINT : undefined);
+  return { network, rpcUrl, ...(usdcMint ? { usdcMint } : {}) };
+}
+
+/**
+ * Persist the default cluster and (optionally) a custom RPC URL; pass rpcUrl
+ * null to clear it. Switching cluster without a new URL drops the old one.
+ */
+export function setSolanaNetwork(network: SolanaNetwork, rpcUrl?: string | null): void {
+  const store = loadWalletProfiles();
+  const next: WalletProfileStore = { ...store, network };
+  if (rpcUrl === null || (rpcUrl === undefined && (store.network ?? "mainnet") !== network)) delete next.rpc_url;
+  else if (rpcUrl) next.rpc_url = rpcUrl;
+  saveWalletProfiles(next);
+}
+
+export type WalletBalance = {
+  address: string;
+  sol: number;
+  usdc: number;
+};
+
+/** Resolve the configured wallet address, if any (same signals as checkWalletConfigured). */
+export function getConfiguredWalletAddress(profile?: string): string | undefined {
+  return resolveWalletProfile(profile)?.wallet_address;
+}
+
+async function solanaRpc<T>(rpcUrl: string, method: string, params: unknown[]): Promise<T> {
+  const res = await fetch(rpcUrl, {
+    method: "POST",
+    headers: { "content-type": "application/json" },
+    body: JSON.stringify({ jsonrpc: "2.0", id: 1, method, params }),
+    signal: AbortSignal.timeout(BALANCE_TIMEOUT_MS),
+  });
+  if (!res.ok) throw new Error(`Solana RPC ${method} failed: HTTP ${res.status}`);
+  const body = await res.json() as { result?: T; error?: { message?: string } };
+  if (body.error) throw new Error(`Solana RPC ${method} failed: ${body.error.message ?? "unknown error"}`);
+  return body.result as T;
+}
+
+/**
+ * Query the wallet's SOL and USDC balances (read-only — never signs).
+ *
+ * Cluster, RPC, and mint come from resolveSolanaNetwork unless given. A
+ * wallet with no USDC token account yet (or a cluster without a USDC mint)
+ * reports 0 rather than an error.
+ */
+export async function getWalletBalance(opts: {
+  address?: string;
+  profile?: string;
+  network?: SolanaNetwork;
+  rpcUrl?: string;
+  usdcMint?: string;
+} = {}): Promise<WalletBalance> {
+  const address = opts.address ?? getConfiguredWalletAddress(opts.profile);
+  if (!address) throw new Error("No wallet address configured");
+  const cluster = resolveSolanaNetwork({ network: opts.network, rpcUrl: opts.rpcUrl });
+  const rpcUrl = cluster.rpcUrl;
+  const mint = opts.usdcMint ?? cluster.usdcMint;
+
+  const [lamports, tokenAccounts] = await Promise.all([
+    solanaRpc<{ value: number }>(rpcUrl, "getBalance", [address]),
+    mint
+      ? solanaRpc<{ value: Array<{ account: { data: { parsed?: { info?: { tokenAmount?: { uiAmount?: number | null } } } } } }> }>(
+        rpcUrl,
+        "getTokenAccountsByOwner",
+        [address, { mint }, { encoding: "jsonParsed" }],
+      )
+      : Promise.resolve({ value: [] }),
+  ]);
+
+  let usdc = 0;
+  for (const account of tokenAccounts?.value ?? []) {
+    usdc += account.account.data.parsed?.info?.tokenAmount?.uiAmount ?? 0;
   }
 
-  return { configured: false };
+  return { address, sol: (lamports?.value ?? 0) / LAMPORTS_PER_SOL, usdc };
+}
+
+/**
+ * Request a SOL airdrop to the wallet for testing. Refused on mainnet;
+ * returns the airdrop transaction signature.
+ */
+export async function requestAirdrop(sol: number, opts: {
+  address?: string;
+  profile?: string;
+  network?: SolanaNetwork;
+  rpcUrl?: string;
+} = {}): Promise<{ address: string; network: SolanaNetwork; signature: string }> {
+  if (!Number.isFinite(sol) || sol <= 0) throw new Error(`Invalid SOL amount: ${sol}`);
+  const address = opts.address ?? getConfiguredWalletAddress(opts.profile);
+  if (!address) throw new Error("No wallet address configured");
+  const cluster = resolveSolanaNetwork({ network: opts.network, rpcUrl: opts.rpcUrl });
+  if (cluster.network === "mainnet") throw new Error("Airdrops are only available on devnet and testnet");
+  const signature = await solanaRpc<string>(cluster.rpcUrl, "requestAirdrop", [address, Math.round(sol * LAMPORTS_PER_SOL)]);
+  return { address, network: cluster.network, signature };
 }
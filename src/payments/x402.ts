@@ -0,0 +1,127 @@
+/**
+ * Marketplace failure states, parsed from x402 challenges.
+ *
+ * A 402 from the marketplace carries its payment terms in the
+ * PAYMENT-REQUIRED header (base64 JSON), the legacy X-Payment-Required
+ * header, the body's `terms`, or a `WWW-Authenticate: x402 ...` challenge.
+ * Parsing them into the exact amount and recipient lets the wallet sign
+ * what the server asked for instead of the locally guessed price.
+ */
+
+import { getErrorCode, type UnbrowseErrorCode } from "../errors.js";
+
+/** USDC has 6 decimals; x402 amounts are in atomic units. */
+const USDC_DECIMALS = 6;
+
+/** One payment option from an x402 `accepts` list. */
+export interface X402Accept {
+  scheme?: string;
+  network?: string;
+  /** Atomic units, as sent by the server */
+  max_amount_required?: string;
+  pay_to?: string;
+  asset?: string;
+  resource?: string;
+  description?: string;
+}
+
+export interface X402Challenge {
+  /** Price of the first accepted option, in USDC */
+  price_usdc?: number;
+  recipient?: string;
+  network?: string;
+  accepts: X402Accept[];
+}
+
+export type MarketplaceFailure =
+  | ({ kind: "payment_required"; message: string } & X402Challenge)
+  | ({ kind: "insufficient_funds"; message: string } & X402Challenge)
+  | { kind: "already_owned"; message: string }
+  | { kind: "error"; message: string; code: UnbrowseErrorCode; status?: number };
+
+function str(value: unknown): string | undefined {
+  return typeof value === "string" && value ? value : typeof value === "number" ? String(value) : undefined;
+}
+
+function toUsdc(atomic: string | undefined): number | undefined {
+  if (!atomic || !/^\d+$/.test(atomic)) return undefined;
+  return Number(atomic) / 10 ** USDC_DECIMALS;
+}
+
+/** `x402 amount="1000", recipient="..."` → its auth-params. */
+function parseChallengeParams(header: string): Record<string, string> | null {
+  const match = header.match(/(?:^|,\s*)x402\s+(.*)$/i);
+  if (!match) return null;
+  const params: Record<string, string> = {};
+  for (const [, key, quoted, bare] of match[1].matchAll(/([\w-]+)\s*=\s*(?:"([^"]*)"|([^\s,]+))/g)) {
+    params[key.toLowerCase()] = quoted ?? bare;
+  }
+  return params;
+}
+
+/**
+ * Payment terms from a 402: the terms object (`{ accepts: [...] }`, or a
+ * single accept), with a `WWW-Authenticate` x402 challenge as the fallback.
+ * Amounts in the challenge header are taken as atomic units like `accepts`.
+ */
+export function parseX402Challenge(terms: unknown, wwwAuthenticate?: string | null): X402Challenge {
+  const record = terms && typeof terms === "object" ? terms as Record<string, unknown> : {};
+  const rawAccepts = Array.isArray(record.accepts) ? record.accepts : record.payTo || record.pay_to ? [record] : [];
+  const accepts: X402Accept[] = rawAccepts
+    .filter((a): a is Record<string, unknown> => !!a && typeof a === "object")
+    .map((a) => {
+      const accept: X402Accept = {
+        scheme: str(a.scheme),
+        network: str(a.network),
+        max_amount_required: str(a.maxAmountRequired ?? a.max_amount_required ?? a.amount),
+        pay_to: str(a.payTo ?? a.pay_to ?? a.recipient),
+        asset: str(a.asset),
+        resource: str(a.resource),
+        description: str(a.description),
+      };
+      return Object.fromEntries(Object.entries(accept).filter(([, v]) => v !== undefined)) as X402Accept;
+    });
+
+  if (accepts.length === 0 && wwwAuthenticate) {
+    const params = parseChallengeParams(wwwAuthenticate);
+    if (params && (params.amount || params.recipient || params.pay_to)) {
+      accepts.push(Object.fromEntries(Object.entries({
+        scheme: params.scheme,
+        network: params.network,
+        max_amount_required: params.amount,
+        pay_to: params.recipient ?? params.pay_to,
+        asset: params.asset,
+      }).filter(([, v]) => v !== undefined)) as X402Accept);
+    }
+  }
+
+  const first = accepts[0];
+  const price = toUsdc(first?.max_amount_required);
+  return {
+    ...(price !== undefined ? { price_usdc: price } : {}),
+    ...(first?.pay_to ? { recipient: first.pay_to } : {}),
+    ...(first?.network ? { network: first.network } : {}),
+    accepts,
+  };
+}
+
+const INSUFFICIENT_FUNDS = /insufficient[_ ](funds|balance)/i;
+const ALREADY_OWNED = /already[_ ](owned|purchased|paid)/i;
+
+/**
+ * Typed form of an error thrown by the marketplace client, for driving the
+ * payment UX: a 402 becomes payment_required (or insufficient_funds when the
+ * server says the payment bounced), a purchase the caller already holds
+ * becomes already_owned, and anything else is a plain error.
+ */
+export function classifyMarketplaceError(err: unknown): MarketplaceFailure {
+  const e = (err ?? {}) as { message?: string; status?: number; x402?: boolean; terms?: unknown; payment?: X402Challenge; server_error?: string };
+  const message = e.message ?? String(err);
+  const reason = `${e.server_error ?? ""} ${message}`;
+  if (e.x402 || e.status === 402) {
+    const challenge = e.payment ?? parseX402Challenge(e.terms);
+    return { kind: INSUFFICIENT_FUNDS.test(reason) ? "insufficient_funds" : "payment_required", message, ...challenge };
+  }
+  if (ALREADY_OWNED.test(reason)) return { kind: "already_owned", message };
+  return { kind: "error", message, code: getErrorCode(err), ...(typeof e.status === "number" ? { status: e.status } : {}) };
+}
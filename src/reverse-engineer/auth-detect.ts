@@ -0,0 +1,559 @@
+/**
+ * Auth method detection from captured request headers and cookies.
+ *
+ * Header names are compared lowercased only — captures arrive with
+ * `authorization`, `Authorization`, and `AUTHORIZATION` depending on the
+ * source (interceptor vs HAR vs extension), so every lookup folds case.
+ */
+
+import type { AuthChallenge, AuthScheme } from "../types/index.js";
+
+export interface ParsedAuthorization {
+  /** Lowercased scheme token, e.g. "bearer", "basic" */
+  scheme: string;
+  /** Everything after the scheme, whitespace-trimmed */
+  credentials: string;
+}
+
+type CookieLike = { name: string };
+
+/** Case-insensitive header lookup. */
+export function getHeaderValue(headers: Record<string, string> | undefined, name: string): string | undefined {
+  if (!headers) return undefined;
+  const target = name.toLowerCase();
+  for (const [key, value] of Object.entries(headers)) {
+    if (key.toLowerCase() === target) return value;
+  }
+  return undefined;
+}
+
+/**
+ * Split an Authorization value into scheme + credentials. Tolerates tabs,
+ * repeated spaces, and line breaks between the two (`Bearer\t<token>`,
+ * `bearer  <token>`), and a bare scheme with no credentials.
+ */
+export function parseAuthorizationHeader(value: string | undefined): ParsedAuthorization | null {
+  if (!value) return null;
+  const match = value.trim().match(/^([A-Za-z][A-Za-z0-9._~+\/-]*)(?:\s+([\s\S]*))?$/);
+  if (!match) return null;
+  return { scheme: match[1].toLowerCase(), credentials: (match[2] ?? "").trim() };
+}
+
+/** Bearer token from an Authorization header, whatever its casing/whitespace. */
+export function extractBearerToken(headers: Record<string, string> | undefined): string | null {
+  const parsed = parseAuthorizationHeader(getHeaderValue(headers, "authorization"));
+  return parsed?.scheme === "bearer" && parsed.credentials ? parsed.credentials : null;
+}
+
+export interface BasicAuthCredentials {
+  username: string;
+  password: string;
+}
+
+/** `Basic <base64(user:pass)>` — the Authorization value for a username/password pair. */
+export function encodeBasicAuth(username: string, password: string): string {
+  return `Basic ${Buffer.from(`${username}:${password}`, "utf8").toString("base64")}`;
+}
+
+/**
+ * Username/password from a `Basic ...` Authorization value. Null when the
+ * scheme isn't Basic or the credentials don't decode to `user:pass`.
+ */
+export function decodeBasicAuth(value: string | undefined): BasicAuthCredentials | null {
+  if (!value) return null;
+  const parsed = parseAuthorizationHeader(value);
+  // Only an explicit Basic scheme: bare tokens that happen to decode to `a:b` are custom credentials.
+  const encoded = parsed?.scheme === "basic" ? parsed.credentials : null;
+  if (!encoded || !/^[A-Za-z0-9+/]+=*$/.test(encoded)) return null;
+  const decoded = Buffer.from(encoded, "base64").toString("utf8");
+  const colon = decoded.indexOf(":");
+  if (colon === -1) return null;
+  return { username: decoded.slice(0, colon), password: decoded.slice(colon + 1) };
+}
+
+const API_KEY_HEADER = /^(x-api-key|api-key|apikey|x-app-key|x-goog-api-key|x-auth-token|x-access-token)$/;
+const SESSION_COOKIE = /sess|sid|auth|token|jwt|login/i;
+const CHALLENGE_TOKEN = /^[A-Za-z0-9!#$%&'*+.^_`|~\/-]+/;
+
+/** Whether a cookie name looks like it holds a login session. */
+export function isSessionCookieName(name: string): boolean {
+  return SESSION_COOKIE.test(name);
+}
+
+/**
+ * Parse a `WWW-Authenticate` value into its challenges. One header may carry
+ * several (`Bearer realm="api", Basic realm="api"`); auth-params may be
+ * quoted or bare, and a scheme may be followed by a bare token68 instead.
+ */
+export function parseWwwAuthenticate(value: string | undefined): AuthChallenge[] {
+  if (!value) return [];
+  const challenges: AuthChallenge[] = [];
+  let current: AuthChallenge | null = null;
+  let rest = value;
+  while (rest.length > 0) {
+    const separator = rest.match(/^[\s,]*/)?.[0] ?? "";
+    rest = rest.slice(separator.length);
+    const token = rest.match(CHALLENGE_TOKEN)?.[0];
+    if (!token) break;
+    rest = rest.slice(token.length);
+    const afterSpace = rest.replace(/^\s+/, "");
+    // `name=value` auth-param — but `==` / `=,` / trailing `=` is token68 padding.
+    if (afterSpace.startsWith("=") && !/^=\s*(=|,|$)/.test(afterSpace)) {
+      rest = afterSpace.slice(1).replace(/^\s+/, "");
+      let paramValue: string;
+      const quoted = rest.match(/^"((?:[^"\\]|\\.)*)"/);
+      if (quoted) {
+        paramValue = quoted[1].replace(/\\(.)/g, "$1");
+        rest = rest.slice(quoted[0].length);
+      } else {
+        paramValue = rest.match(/^[^,\s]*/)?.[0] ?? "";
+        rest = rest.slice(paramValue.length);
+      }
+      if (current) current.params[token.toLowerCase()] = paramValue;
+      continue;
+    }
+    if (current && !separator.includes(",") && Object.keys(current.params).length === 0) {
+      // token68 directly after the scheme (`Negotiate YII...==`)
+      const padding = afterSpace.match(/^=+/)?.[0] ?? "";
+      current.params.token68 = token + padding;
+      rest = afterSpace.slice(padding.length);
+      continue;
+    }
+    current = { scheme: token.toLowerCase(), params: {} };
+    challenges.push(current);
+  }
+  for (const challenge of challenges) {
+    const { realm, scope, error } = challenge.params;
+    if (realm !== undefined) challenge.realm = realm;
+    if (scope !== undefined) challenge.scope = scope;
+    if (error !== undefined) challenge.error = error;
+  }
+  return challenges;
+}
+
+/**
+ * Challenges from 401 responses, first-seen order, deduped on scheme + realm.
+ * An unauthenticated capture still says which scheme the API expects.
+ */
+export function findAuthChallenges(
+  responses: Array<{ response_status: number; response_headers: Record<string, string> }>,
+): AuthChallenge[] {
+  const seen = new Set<string>();
+  const found: AuthChallenge[] = [];
+  for (const res of responses) {
+    if (res.response_status !== 401) continue;
+    for (const challenge of parseWwwAuthenticate(getHeaderValue(res.response_headers, "www-authenticate"))) {
+      const key = `${challenge.scheme}\u0000${challenge.realm ?? ""}`;
+      if (seen.has(key)) continue;
+      seen.add(key);
+      found.push(challenge);
+    }
+  }
+  return found;
+}
+
+/** Login/auth page paths a redirect can land on. */
+const LOGIN_REDIRECT_PATH = /\/(login|log-in|signin|sign-in|sso|auth|authorize|oauth2?|uas\/login|checkpoint|accounts\/login|session\/new)(\/|$|\?|\.)/i;
+
+type RedirectLike = { url: string; response_status: number; response_headers: Record<string, string> };
+
+/** Login page a 3xx sends the request to, or null when it isn't a login bounce. */
+export function loginRedirectTarget(res: RedirectLike): string | null {
+  if (res.response_status < 300 || res.response_status >= 400) return null;
+  const location = getHeaderValue(res.response_headers, "location");
+  if (!location) return null;
+  try {
+    const target = new URL(location, res.url);
+    // A login endpoint redirecting onward (e.g. after a POST) isn't a bounce.
+    if (LOGIN_REDIRECT_PATH.test(new URL(res.url).pathname)) return null;
+    return LOGIN_REDIRECT_PATH.test(target.pathname + target.search) ? target.href : null;
+  } catch {
+    return null;
+  }
+}
+
+export interface LoginRedirectSummary {
+  /** At least one request was bounced to a login page — the capture ran logged out */
+  auth_required: boolean;
+  login_redirect_count: number;
+  /** First login page redirected to */
+  login_url?: string;
+}
+
+/** Count 3xx responses whose Location is a login page across a capture. */
+export function summarizeLoginRedirects(responses: RedirectLike[]): LoginRedirectSummary {
+  let count = 0;
+  let loginUrl: string | undefined;
+  for (const res of responses) {
+    const target = loginRedirectTarget(res);
+    if (!target) continue;
+    count++;
+    loginUrl ??= target;
+  }
+  return { auth_required: count > 0, login_redirect_count: count, ...(loginUrl ? { login_url: loginUrl } : {}) };
+}
+
+export interface AuthClassification {
+  scheme: AuthScheme;
+  /** What triggered it: a sent header, a sent cookie, or a 401 challenge */
+  source?: { kind: "header" | "cookie" | "challenge"; name: string };
+  /** 0..1 — a sent credential beats a challenge, which beats a name-based guess */
+  confidence: number;
+  /** Human-readable form, e.g. "Bearer Token" or "API Key (x-api-key)" */
+  label: string;
+  /** Each request carries its own signature (AWS SigV4), so a captured Authorization value won't replay */
+  signed_requests?: boolean;
+}
+
+/** auth_method of public APIs (`auth_scheme: "none"`) */
+export const NO_AUTH_LABEL = "No Auth Required";
+
+const JWT_SHAPE = /^eyJ[\w-]+\.eyJ[\w-]+\.[\w-]*$/;
+
+/** Classify an Authorization scheme token (lowercased first word of the value). */
+function classifyScheme(scheme: string, credentials = ""): Pick<AuthClassification, "scheme" | "label" | "signed_requests"> {
+  switch (scheme) {
+    case "bearer":
+      return JWT_SHAPE.test(credentials) ? { scheme: "jwt", label: "Bearer Token" } : { scheme: "bearer_token", label: "Bearer Token" };
+    case "basic": return { scheme: "basic_auth", label: "Basic Auth" };
+    case "digest": return { scheme: "digest_auth", label: "Digest Auth" };
+    case "aws4-hmac-sha256": return { scheme: "aws_sigv4", label: "AWS SigV4", signed_requests: true };
+    case "oauth": return { scheme: "oauth", label: "OAuth" };
+    case "token": return { scheme: "token_auth", label: "Token Auth" };
+    case "apikey":
+    case "api-key": return { scheme: "api_key", label: "API Key (Authorization)" };
+    case "hoba": return { scheme: "hoba", label: "HOBA" };
+    default: return { scheme: "custom_header", label: "Authorization Header" };
+  }
+}
+
+const API_RESPONSE_TYPE = /json|xml|graphql|protobuf|grpc|event-stream/i;
+
+/**
+ * Statuses of a capture's API-looking responses (JSON, XML, gRPC, ...), for
+ * classifyAuth. Page loads and static assets answer 200 whether or not the
+ * API behind them wants a token, so they are left out.
+ */
+export function apiResponseStatuses(
+  requests: Array<{ response_status: number; response_headers?: Record<string, string> }>,
+): number[] {
+  return requests
+    .filter((r) => r.response_status > 0 && API_RESPONSE_TYPE.test(getHeaderValue(r.response_headers, "content-type") ?? ""))
+    .map((r) => r.response_status);
+}
+
+/**
+ * Classify a capture's auth. A sent Authorization header wins; otherwise a
+ * 401 challenge (see findAuthChallenges) is taken over weaker header and
+ * cookie hints. With nothing auth-like, `statuses` (apiResponseStatuses)
+ * tell a public API — 2xx responses and no 401/403 — from one that refused
+ * every call; the first is `scheme: "none"`, the second `"unknown"`.
+ */
+export function classifyAuth(
+  headers: Record<string, string>,
+  cookies: CookieLike[] = [],
+  challenges: AuthChallenge[] = [],
+  statuses: number[] = [],
+): AuthClassification {
+  const authorization = parseAuthorizationHeader(getHeaderValue(headers, "authorization"));
+  if (authorization) {
+    return { ...classifyScheme(authorization.scheme, authorization.credentials), source: { kind: "header", name: "authorization" }, confidence: 0.95 };
+  }
+  if (challenges.length > 0) {
+    return { ...classifyScheme(challenges[0].scheme), source: { kind: "challenge", name: challenges[0].scheme }, confidence: 0.6 };
+  }
+  const names = Object.keys(headers).map((k) => k.toLowerCase());
+  const apiKeyHeader = names.find((k) => API_KEY_HEADER.test(k));
+  if (apiKeyHeader) {
+    return { scheme: "api_key", source: { kind: "header", name: apiKeyHeader }, confidence: 0.8, label: `API Key (${apiKeyHeader})` };
+  }
+  const jwtHeader = Object.entries(headers).find(([, v]) => JWT_SHAPE.test(v?.trim() ?? ""))?.[0]?.toLowerCase();
+  if (jwtHeader) {
+    return { scheme: "jwt", source: { kind: "header", name: jwtHeader }, confidence: 0.7, label: `JWT (${jwtHeader})` };
+  }
+  const sessionCookie = cookies.find((cookie) => SESSION_COOKIE.test(cookie.name));
+  if (sessionCookie) {
+    return { scheme: "session_cookie", source: { kind: "cookie", name: sessionCookie.name }, confidence: 0.7, label: "Session Cookie" };
+  }
+  if (cookies.length > 0) {
+    return { scheme: "session_cookie", source: { kind: "cookie", name: cookies[0].name }, confidence: 0.3, label: "Cookie" };
+  }
+  const succeeded = statuses.some((status) => status >= 200 && status < 300);
+  const refused = statuses.some((status) => status === 401 || status === 403);
+  if (succeeded && !refused) {
+    return { scheme: "none", confidence: 0.8, label: NO_AUTH_LABEL };
+  }
+  return { scheme: "unknown", confidence: 0, label: "Unknown (may need login)" };
+}
+
+/**
+ * Human-readable auth method for a capture, e.g. "Bearer Token" or
+ * "API Key (x-api-key)" — classifyAuth's label.
+ */
+export function guessAuthMethod(
+  headers: Record<string, string>,
+  cookies: CookieLike[] = [],
+  challenges: AuthChallenge[] = [],
+  statuses: number[] = [],
+): string {
+  return classifyAuth(headers, cookies, challenges, statuses).label;
+}
+
+/** Tracing/correlation headers that are never auth, whatever their value. */
+const TRACKING_HEADER_DENYLIST = new Set([
+  "x-request-id",
+  "x-correlation-id",
+  "x-amzn-trace-id",
+  "x-amz-cf-id",
+  "x-cloud-trace-context",
+  "x-client-trace-id",
+  "x-datadog-trace-id",
+  "x-datadog-parent-id",
+  "x-b3-traceid",
+  "x-b3-spanid",
+  "traceparent",
+  "tracestate",
+  "sentry-trace",
+  "baggage",
+]);
+const TRACKING_HEADER_NAME = /(^|[-_])(request|trace|span|correlation|transaction|tracking|session-replay)([-_]?id)?$/i;
+const UUID_VALUE = /^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$/i;
+
+export interface AuthHeaderScrubOptions {
+  /** Additional header names (any case) to drop */
+  denylist?: string[];
+}
+
+/** Extra denylisted names from UNBROWSE_AUTH_HEADER_DENYLIST (comma-separated). */
+function envHeaderDenylist(): string[] {
+  return (process.env.UNBROWSE_AUTH_HEADER_DENYLIST ?? "").split(",").map((h) => h.trim()).filter(Boolean);
+}
+
+/**
+ * Drop captured "auth" headers that aren't auth: denylisted tracing headers,
+ * UUID values under tracking-style names (`x-session-replay-id`), and empty
+ * values. Tenant/account context headers like `x-tenant-id` are kept.
+ */
+export function scrubAuthHeaders(headers: Record<string, string>, options: AuthHeaderScrubOptions = {}): Record<string, string> {
+  const denylist = new Set([...TRACKING_HEADER_DENYLIST, ...envHeaderDenylist(), ...(options.denylist ?? [])].map((h) => h.toLowerCase()));
+  return Object.fromEntries(Object.entries(headers).filter(([name, value]) => {
+    const lower = name.toLowerCase();
+    if (!value?.trim()) return false;
+    if (denylist.has(lower)) return false;
+    if (TRACKING_HEADER_NAME.test(lower) && UUID_VALUE.test(value.trim())) return false;
+    return true;
+  }));
+}
+
+/**
+ * Stable device / installation identifiers some mobile-web apps send next to
+ * a short-lived token. They must be replayed but are not bearer credentials:
+ * they are stored as bundle `context.device_identifiers`, not auth headers.
+ */
+const DEVICE_ID_HEADERS = new Set([
+  "x-device-id",
+  "x-device-uuid",
+  "device-id",
+  "x-install-id",
+  "x-installation-id",
+  "x-client-instance",
+  "x-client-instance-id",
+  "x-app-instance-id",
+  "x-ig-device-id",
+  "x-ig-android-id",
+  "x-fb-device-id",
+  "x-udid",
+]);
+
+export interface DeviceIdentifierOptions {
+  /** Additional header names (any case) to treat as device identifiers */
+  names?: string[];
+}
+
+/** Extra device-id header names from UNBROWSE_DEVICE_ID_HEADERS (comma-separated). */
+function envDeviceIdHeaders(): string[] {
+  return (process.env.UNBROWSE_DEVICE_ID_HEADERS ?? "").split(",").map((h) => h.trim()).filter(Boolean);
+}
+
+export function isDeviceIdentifierHeader(name: string, options: DeviceIdentifierOptions = {}): boolean {
+  const lower = name.toLowerCase();
+  if (DEVICE_ID_HEADERS.has(lower)) return true;
+  return [...envDeviceIdHeaders(), ...(options.names ?? [])].some((h) => h.toLowerCase() === lower);
+}
+
+/** Canonical names win when one token is sent under several headers. */
+const CANONICAL_AUTH_HEADERS = [
+  "authorization",
+  "x-api-key",
+  "api-key",
+  "x-auth-token",
+  "x-access-token",
+  "x-id-token",
+];
+
+const MIN_ALIAS_TOKEN_LENGTH = 16;
+
+export interface AuthHeaderAlias {
+  /** Canonical header carrying the same token */
+  of: string;
+  /** Alias carried the bare token while the canonical header has a scheme prefix */
+  token_only?: boolean;
+}
+
+function canonicalRank(name: string): number {
+  const index = CANONICAL_AUTH_HEADERS.indexOf(name);
+  return index === -1 ? CANONICAL_AUTH_HEADERS.length : index;
+}
+
+/** The token itself: Authorization credentials without the scheme, other headers as-is. */
+function authTokenValue(name: string, value: string): { token: string; schemed: boolean } {
+  if (name === "authorization") {
+    const parsed = parseAuthorizationHeader(value);
+    if (parsed?.credentials) return { token: parsed.credentials, schemed: true };
+  }
+  return { token: value.trim(), schemed: false };
+}
+
+/**
+ * Collapse auth headers that carry the same token under several names
+ * (`authorization`, `x-access-token`, `x-id-token`, ...). The most canonical
+ * name is kept; the rest are recorded in `header_aliases` so replay can send
+ * them again via expandAuthHeaderAliases.
+ */
+export function dedupeAuthHeaders(headers: Record<string, string>): {
+  headers: Record<string, string>;
+  header_aliases?: Record<string, AuthHeaderAlias>;
+} {
+  const groups = new Map<string, Array<{ name: string; value: string; schemed: boolean }>>();
+  for (const [rawName, value] of Object.entries(headers)) {
+    const name = rawName.toLowerCase();
+    const { token, schemed } = authTokenValue(name, value);
+    // Only token-like values are grouped — short flags ("1", "XMLHttpRequest") can coincide.
+    const groupKey = token.length >= MIN_ALIAS_TOKEN_LENGTH ? token : `${name}\u0000${value}`;
+    const group = groups.get(groupKey) ?? [];
+    group.push({ name, value, schemed });
+    groups.set(groupKey, group);
+  }
+
+  const kept: Record<string, string> = {};
+  const aliases: Record<string, AuthHeaderAlias> = {};
+  for (const group of groups.values()) {
+    group.sort((a, b) => canonicalRank(a.name) - canonicalRank(b.name) || a.name.localeCompare(b.name));
+    const [canonical, ...rest] = group;
+    kept[canonical.name] = canonical.value;
+    for (const alias of rest) {
+      aliases[alias.name] = { of: canonical.name, ...(canonical.schemed && !alias.schemed ? { token_only: true } : {}) };
+    }
+  }
+  return Object.keys(aliases).length > 0 ? { headers: kept, header_aliases: aliases } : { headers: kept };
+}
+
+/** Re-add alias headers recorded by dedupeAuthHeaders. */
+export function expandAuthHeaderAliases(
+  headers: Record<string, string>,
+  aliases: Record<string, AuthHeaderAlias> | undefined,
+): Record<string, string> {
+  if (!aliases) return headers;
+  const expanded = { ...headers };
+  for (const [name, alias] of Object.entries(aliases)) {
+    const value = getHeaderValue(headers, alias.of);
+    if (value == null || getHeaderValue(headers, name) != null) continue;
+    expanded[name] = alias.token_only ? authTokenValue(alias.of.toLowerCase(), value).token : value;
+  }
+  return expanded;
+}
+
+/** The header-bearing parts of a stored auth bundle. */
+export interface AuthBundleHeaderFields {
+  headers?: Record<string, string>;
+  header_aliases?: Record<string, AuthHeaderAlias>;
+  basic_auth?: BasicAuthCredentials;
+  context?: { device_identifiers?: Record<string, string> };
+}
+
+/**
+ * Every header a stored bundle sends on replay: device identifiers from
+ * `context`, the stored headers with their aliases re-added, and a Basic
+ * Authorization re-encoded from `basic_auth` (which wins over a stored one
+ * so edits to the pair take effect).
+ */
+export function authBundleHeaders(bundle: AuthBundleHeaderFields): Record<string, string> {
+  const stored = { ...(bundle.headers ?? {}) };
+  if (typeof bundle.basic_auth?.username === "string") {
+    for (const key of Object.keys(stored)) if (key.toLowerCase() === "authorization") delete stored[key];
+    stored.authorization = encodeBasicAuth(bundle.basic_auth.username, bundle.basic_auth.password ?? "");
+  }
+  return { ...(bundle.context?.device_identifiers ?? {}), ...expandAuthHeaderAliases(stored, bundle.header_aliases) };
+}
+
+export interface StorageAuthToken {
+  storage: "local" | "session";
+  /** Storage key, plus the JSON path when the token sat inside a JSON value (`auth0.spa.body.access_token`) */
+  key: string;
+  token: string;
+  jwt: boolean;
+}
+
+const STORAGE_AUTH_KEY = /token|auth|jwt|bearer|access|credential/i;
+const STORAGE_TOKEN_FIELD = /^(access_?token|id_?token|auth_?token|token|jwt)$/i;
+const OPAQUE_TOKEN = /^[\w\-.~+\/]+=*$/;
+const MAX_JSON_DEPTH = 4;
+
+function scanStorageJson(
+  value: unknown,
+  path: string,
+  depth: number,
+  out: Array<{ key: string; token: string }>,
+): void {
+  if (depth > MAX_JSON_DEPTH || !value || typeof value !== "object") return;
+  for (const [field, child] of Object.entries(value as Record<string, unknown>)) {
+    const childPath = `${path}.${field}`;
+    if (typeof child === "string") {
+      if (JWT_SHAPE.test(child) || (STORAGE_TOKEN_FIELD.test(field) && child.length >= MIN_ALIAS_TOKEN_LENGTH && OPAQUE_TOKEN.test(child))) {
+        out.push({ key: childPath, token: child });
+      }
+    } else {
+      scanStorageJson(child, childPath, depth + 1, out);
+    }
+  }
+}
+
+/** JWTs beat opaque strings; access tokens beat id tokens. */
+function storageTokenScore(token: StorageAuthToken): number {
+  return (token.jwt ? 2 : 0) + (/access/i.test(token.key) ? 1 : 0);
+}
+
+/**
+ * Tokens SPAs stash in localStorage/sessionStorage: JWT-shaped values under
+ * any key, opaque tokens under auth-looking keys, and token fields inside
+ * JSON values (auth0, amplify, firebase caches). Refresh tokens are skipped —
+ * they are never valid as a bearer credential. Best candidates first.
+ */
+export function findStorageAuthTokens(
+  storage: StorageAuthToken["storage"],
+  entries: Record<string, string>,
+): StorageAuthToken[] {
+  const found: Array<{ key: string; token: string }> = [];
+  for (const [key, rawValue] of Object.entries(entries)) {
+    if (typeof rawValue !== "string") continue;
+    // Some stores JSON.stringify plain strings, leaving them quoted.
+    const value = rawValue.trim().replace(/^"(.*)"$/, "$1");
+    if (JWT_SHAPE.test(value)) {
+      found.push({ key, token: value });
+    } else if (value.startsWith("{") || value.startsWith("[")) {
+      try { scanStorageJson(JSON.parse(value), key, 0, found); } catch { /* not JSON */ }
+    } else if (STORAGE_AUTH_KEY.test(key) && value.length >= MIN_ALIAS_TOKEN_LENGTH && OPAQUE_TOKEN.test(value)) {
+      found.push({ key, token: value });
+    }
+  }
+  return found
+    .filter((t) => !/refresh/i.test(t.key))
+    .map((t) => ({ storage, ...t, jwt: JWT_SHAPE.test(t.token) }))
+    .sort((a, b) => storageTokenScore(b) - storageTokenScore(a));
+}
+
+/** `authorization: Bearer <token>` from the best storage token, or nothing. */
+export function storageBearerHeader(tokens: StorageAuthToken[]): Record<string, string> {
+  const best = [...tokens].sort((a, b) => storageTokenScore(b) - storageTokenScore(a))[0];
+  return best ? { authorization: `Bearer ${best.token}` } : {};
+}
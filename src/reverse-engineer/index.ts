@@ -1,19 +1,38 @@
 import type { RawRequest, CapturedWsMessage } from "../capture/index.js";
-import type { CsrfPlan, EndpointDescriptor, WsMessage } from "../types/index.js";
+import type { BodyFormat, CaptureStats, CsrfPlan, EndpointDescriptor, QueryParamInfo, RequestSigningInfo, ResponseSchema, StatusFilter, WsMessage } from "../types/index.js";
 import { inferSchema } from "../transform/index.js";
-import { getRegistrableDomain } from "../domain.js";
-import { nanoid } from "nanoid";
+import { isStreamingContentType, parseStreamBody } from "../transform/stream.js";
+import { describeGrpcWeb, parseGrpcWebFrames } from "../transform/grpc-web.js";
+import { getRegistrableDomain, isDomainMatch } from "../domain.js";
+import { createHash } from "node:crypto";
 import { inferEndpointSemantic } from "../graph/index.js";
 import { writeDebugTrace } from "../debug-trace.js";
+import { isLogLevelEnabled, logDebug } from "../logger.js";
 import { buildQueryBindingMap } from "../template-params.js";
 import { buildDescriptionPrompt, groundedDescription, extractResponseKeys } from "./description-prompt.js";
 import { isRscPayload, extractRscDataEndpoints } from "../capture/rsc.js";
+import { parseRateLimitHeaders } from "../execution/retry.js";
+import { isDeviceIdentifierHeader, isSessionCookieName, loginRedirectTarget, scrubAuthHeaders, type AuthHeaderScrubOptions, type DeviceIdentifierOptions } from "./auth-detect.js";
 const SKIP_EXTENSIONS = /\.(js|mjs|css|png|jpg|jpeg|gif|svg|ico|woff|woff2|ttf|map|webp|html|avif)([?#]|$)/i;
 const SKIP_JS_BUNDLES = /\/(boq-|_\/mss\/|og\/_\/js\/|_\/scs\/)/i;
 const SKIP_PATHS = /\/_next\/static\/|\/_next\/data\/|\/_next\/image|\/static\/chunks\/|\/static\/media\/|\/cdn-cgi\//i;
 
-// Known infrastructure/auth hosts — never useful as skill endpoints
-const SKIP_HOSTS = /(cloudflare\.com|google-analytics\.com|doubleclick\.net|gstatic\.com|accounts\.google\.com|login\.microsoftonline\.com|auth0\.com|cognito-idp\.|appleid\.apple\.com|github\.com\/login|facebook\.com\/login|protechts\.net|demdex\.net|litms|platform-telemetry|datadoghq\.com|fullstory\.com|launchdarkly\.com|intercom\.io|privy\.io|mypinata\.cloud|sentry\.io|segment\.io|amplitude\.com|mixpanel\.com|hotjar\.com|clarity\.ms|googletagmanager\.com|walletconnect\.com|imagedelivery\.net|cloudflareinsights\.com)/i;
+// Known infrastructure/auth hosts — never useful as skill endpoints.
+// Matched on label boundaries so e.g. "notsentry.io" is not treated as "sentry.io".
+const SKIP_HOST_DOMAINS = [
+  "cloudflare.com", "google-analytics.com", "doubleclick.net", "gstatic.com", "accounts.google.com",
+  "login.microsoftonline.com", "auth0.com", "appleid.apple.com", "protechts.net", "demdex.net",
+  "datadoghq.com", "fullstory.com", "launchdarkly.com", "intercom.io", "privy.io", "mypinata.cloud",
+  "sentry.io", "segment.io", "amplitude.com", "mixpanel.com", "hotjar.com", "clarity.ms",
+  "googletagmanager.com", "walletconnect.com", "imagedelivery.net", "cloudflareinsights.com",
+];
+
+// Host fragments that can't be expressed as a domain suffix
+const SKIP_HOSTS = /(cognito-idp\.|litms|platform-telemetry)/i;
+
+function isSkippedHost(hostname: string): boolean {
+  return SKIP_HOST_DOMAINS.some((d) => isDomainMatch(d, hostname)) || SKIP_HOSTS.test(hostname);
+}
 
 // Google-specific telemetry, ads, and infrastructure subdomains (BUG-GC-004)
 const SKIP_TELEMETRY_HOSTS = /(waa-pa\.|signaler-pa\.|appsgrowthpromo-pa\.|ogads-pa\.|peoplestackwebexperiments-pa\.)/i;
@@ -24,6 +43,38 @@ const SKIP_TELEMETRY_PATHS = /\/(log|logging|telemetry|analytics|beacon|ping|hea
 // RPC/API path hints — tightened to avoid false positives (BUG-GC-004)
 const RPC_HINTS = /(\/$rpc\/|\/rpc\/|graphql|trending|search|feed|results|batchexecute|\/api\/)/i;
 
+/** Site-specific path heuristics layered over the built-in API hints and skip paths. */
+interface PathOverrides {
+  api: RegExp[];
+  skip: RegExp[];
+}
+
+const envPatternsWarned = new Set<string>();
+
+/** Comma-separated regexes from an env var; malformed entries are logged once and dropped. */
+function envPatterns(name: string): RegExp[] {
+  const raw = process.env[name];
+  if (!raw) return [];
+  return raw.split(",").map((p) => p.trim()).filter(Boolean).flatMap((p) => {
+    try {
+      return [new RegExp(p, "i")];
+    } catch (err) {
+      if (!envPatternsWarned.has(`${name}:${p}`)) {
+        logDebug("extract", `ignoring ${name} pattern ${p}: ${(err as Error).message}`);
+        envPatternsWarned.add(`${name}:${p}`);
+      }
+      return [];
+    }
+  });
+}
+
+function resolvePathOverrides(context?: ExtractionContext): PathOverrides {
+  return {
+    api: [...envPatterns("UNBROWSE_API_PATH_PATTERNS"), ...(context?.apiPathPatterns ?? [])],
+    skip: [...envPatterns("UNBROWSE_SKIP_PATH_PATTERNS"), ...(context?.skipPathPatterns ?? [])],
+  };
+}
+
 const ALLOWED_METHODS = new Set(["GET", "POST", "PUT", "PATCH", "DELETE"]);
 
 // Headers that must never be stored in skill manifests (BUG-GC-005)
@@ -84,9 +135,16 @@ const SENSITIVE_HEADER_PATTERN = /token|key|secret|credential|password|session/i
 // Query param names that likely contain credentials and must be stripped from URL templates
 const SENSITIVE_QUERY_PARAMS = /^(api[_-]?key|apikey|access[_-]?token|auth[_-]?token|secret|password|key|token|session[_-]?id|client[_-]?secret|private[_-]?key|bearer)$/i;
 
+// Body fields that carry credentials regardless of encoding (JSON, form, multipart)
+const SENSITIVE_BODY_FIELDS = /^(passwd|pass|refresh[_-]?token|id[_-]?token|otp|code[_-]?verifier|assertion|client[_-]?assertion)$/i;
+
 // Framework-internal query params — noise from Next.js RSC, cache busting, etc.
 const FRAMEWORK_QUERY_PARAMS = /^(_rsc|_next|__next|_t|_hash|__cf_chl_tk|nxtP\[.*\])$/i;
 
+// Cache-busters and asset hashes — never "required", whatever the capture shows
+const VOLATILE_QUERY_PARAMS = /^(_|t|ts|cb|v|ver|rnd|rand|random|nocache|cachebust(er)?|timestamp|nonce)$/i;
+const QUERY_PARAM_EXAMPLES = 3;
+
 // Ad/tracking hosts that slip through the main SKIP_HOSTS filter
 const AD_HOSTS = /buysellads\.com|carbonads\.com|ethicalads\.io|srv\.buysellads\.com|facet-futures\./i;
 
@@ -97,6 +155,7 @@ const AD_SCHEMA_KEYS = new Set([
   "orderid", "impressionurl", "clickurl", "customerid",
   "adunitid", "adslot", "adsize", "lineitemid",
 ]);
+const WS_SAMPLE_PER_DIRECTION = 10; // sent/received frames kept per WS endpoint
 const AD_SCHEMA_THRESHOLD = 3; // need at least this many ad-like keys to classify
 
 function singularize(word: string): string {
@@ -245,7 +304,7 @@ function isJsonResponseBody(body: string | undefined): boolean {
 }
 
 function hasAdmissibleParsedBody(body: string | undefined): boolean {
-  return isJsonResponseBody(body) || isHtmlResponseBody(body);
+  return isJsonResponseBody(body) || isHtmlResponseBody(body) || parseStreamBody(body) !== null;
 }
 
 /** Collect top-level + one-level-nested keys from an object/array */
@@ -339,7 +398,12 @@ function parseCookieHeader(header: string | undefined): Record<string, string> {
   return out;
 }
 
-function normalizeBodyBindingKey(path: string): string {
+function isSensitiveBodyField(path: string): boolean {
+  const leaf = path.replace(/\[\d+\]$/, "").split(".").pop() ?? "";
+  return SENSITIVE_QUERY_PARAMS.test(leaf) || SENSITIVE_BODY_FIELDS.test(leaf);
+}
+
+export function normalizeBodyBindingKey(path: string): string {
   const normalized = path
     .replace(/\.(\d+)\./g, "_$1_")
     .replace(/\[(\d+)\]/g, "_$1")
@@ -380,17 +444,31 @@ function shouldTemplateBodyValue(path: string, value: unknown, context?: Extract
   return false;
 }
 
+/** Values never templated, whatever the path: flags and long free text. */
+function isTemplatableBodyValue(value: unknown): boolean {
+  if (value == null || typeof value === "boolean") return false;
+  return typeof value !== "string" || value.trim().length <= 280;
+}
+
 function templatizeBodyObject(
   value: unknown,
   context?: ExtractionContext,
   path = "",
   bodyParams: Record<string, unknown> = {},
+  varying?: Set<string>,
 ): unknown {
   if (Array.isArray(value)) {
-    return value.map((entry, index) => templatizeBodyObject(entry, context, `${path}[${index}]`, bodyParams));
+    return value.map((entry, index) => templatizeBodyObject(entry, context, `${path}[${index}]`, bodyParams, varying));
   }
   if (!value || typeof value !== "object") {
-    if (!path || !shouldTemplateBodyValue(path, value, context)) return value;
+    // Credential-bearing fields (password, refresh_token, ...) are always
+    // templated and their captured value is never stored as a default —
+    // requiredBodyBindings lists them so execution asks for them.
+    if (path && typeof value === "string" && isSensitiveBodyField(path)) return `{${normalizeBodyBindingKey(path)}}`;
+    if (!path || value == null) return value;
+    // Varying across samples skips the name heuristics, not the value guards.
+    const varies = varying?.has(path) && isTemplatableBodyValue(value);
+    if (!varies && !shouldTemplateBodyValue(path, value, context)) return value;
     const binding = normalizeBodyBindingKey(path);
     if (!(binding in bodyParams)) bodyParams[binding] = value;
     return `{${binding}}`;
@@ -398,11 +476,114 @@ function templatizeBodyObject(
   return Object.fromEntries(
     Object.entries(value as Record<string, unknown>).map(([key, next]) => [
       key,
-      templatizeBodyObject(next, context, path ? `${path}.${key}` : key, bodyParams),
+      templatizeBodyObject(next, context, path ? `${path}.${key}` : key, bodyParams, varying),
     ]),
   );
 }
 
+/** `{binding}` placeholders in a templated body that have no captured default (credential fields). */
+function requiredBodyBindings(template: unknown, bodyParams: Record<string, unknown>): string[] {
+  const required = new Set<string>();
+  for (const json of flattenBodyLeaves(template).values()) {
+    const binding = json.match(/^"\{(\w+)\}"$/)?.[1];
+    if (binding && !(binding in bodyParams)) required.add(binding);
+  }
+  return [...required];
+}
+
+/** Leaf paths (templatizeBodyObject's `a.b[0]` form) → JSON of the value. */
+function flattenBodyLeaves(value: unknown, path = "", out = new Map<string, string>()): Map<string, string> {
+  if (Array.isArray(value)) value.forEach((entry, i) => flattenBodyLeaves(entry, `${path}[${i}]`, out));
+  else if (value && typeof value === "object") {
+    for (const [key, next] of Object.entries(value as Record<string, unknown>)) flattenBodyLeaves(next, path ? `${path}.${key}` : key, out);
+  } else if (path) out.set(path, JSON.stringify(value ?? null));
+  return out;
+}
+
+/** `operationName` of a GraphQL request body, if it has one. */
+function graphqlOperationName(body: unknown): string | undefined {
+  if (!body || typeof body !== "object" || Array.isArray(body)) return undefined;
+  const name = (body as Record<string, unknown>).operationName;
+  return typeof name === "string" ? name : undefined;
+}
+
+/**
+ * Leaf paths whose value differs across `bodies`. Only bodies for the same
+ * GraphQL operation as `reference` are compared — different operations
+ * posted to one endpoint differ everywhere without any of it being input.
+ * A path missing from some bodies counts as varying; empty with fewer than
+ * two comparable bodies.
+ */
+function varyingBodyPaths(allBodies: unknown[], reference: unknown = allBodies[0]): Set<string> {
+  const operation = graphqlOperationName(reference);
+  const bodies = allBodies.filter((b) => graphqlOperationName(b) === operation);
+  const varying = new Set<string>();
+  if (bodies.length < 2) return varying;
+  const leaves = bodies.map((b) => flattenBodyLeaves(b));
+  for (const path of new Set(leaves.flatMap((l) => [...l.keys()]))) {
+    const values = new Set(leaves.map((l) => l.get(path)));
+    if (values.size > 1) varying.add(path);
+  }
+  return varying;
+}
+
+export interface BodyTemplateParam {
+  /** Binding name used in the template's `{name}` placeholder */
+  name: string;
+  /** Dot/index path in the body */
+  path: string;
+  type: "string" | "number" | "boolean" | "null";
+  /** Captured value; absent for required credential fields, whose value is never kept */
+  example?: unknown;
+  /** True when the value differed across samples; false when only the name heuristics picked it */
+  varying: boolean;
+  /** No default — the caller must supply it (password, refresh_token, ...) */
+  required?: boolean;
+}
+
+export interface BodyTemplate {
+  template: unknown;
+  params: BodyTemplateParam[];
+  samples: number;
+}
+
+/**
+ * Infer a parameterized request body from captured bodies of one endpoint.
+ * Fields that differ between samples become `{binding}` placeholders; with
+ * a single sample (or for fields that happen to be equal) the name/shape
+ * heuristics still template ids, queries and similar. The first body is
+ * the shape; bodies that don't parse as objects are ignored.
+ */
+export function inferBodyTemplate(bodies: Array<string | Record<string, unknown>>, contentType?: string, context?: ExtractionContext): BodyTemplate {
+  const parsed = bodies
+    .map((b) => (typeof b === "string" ? parseRequestBody(b, contentType) : b))
+    .filter((b): b is Record<string, unknown> => !!b && typeof b === "object" && !Array.isArray(b));
+  if (parsed.length === 0) return { template: undefined, params: [], samples: 0 };
+  const varying = varyingBodyPaths(parsed);
+  const bodyParams: Record<string, unknown> = {};
+  const template = templatizeBodyObject(parsed[0], context, "", bodyParams, varying);
+  const params: BodyTemplateParam[] = [];
+  const required = new Set(requiredBodyBindings(template, bodyParams));
+  for (const [path, json] of flattenBodyLeaves(parsed[0])) {
+    const name = normalizeBodyBindingKey(path);
+    if (params.some((p) => p.name === name)) continue;
+    if (required.has(name)) {
+      params.push({ name, path, type: "string", varying: varying.has(path), required: true });
+      continue;
+    }
+    if (!(name in bodyParams)) continue;
+    const example = JSON.parse(json) as unknown;
+    params.push({
+      name,
+      path,
+      type: example === null ? "null" : typeof example as BodyTemplateParam["type"],
+      example,
+      varying: varying.has(path),
+    });
+  }
+  return { template, params, samples: parsed.length };
+}
+
 function inferCsrfPlan(req: RawRequest, parsedBody?: unknown): CsrfPlan | undefined {
   const headers = Object.fromEntries(
     Object.entries(req.request_headers).map(([key, value]) => [key.toLowerCase(), value]),
@@ -552,19 +733,113 @@ function isSemanticallyAdmissibleResponse(
 // on the site's own domain (not caught by SKIP_HOSTS since they're same-origin).
 const ON_DOMAIN_NOISE = /\/(recaptcha|captcha|update-recaptcha|csrf|consent|data-protection|badge|drawer|header-action|geolocation|onboarding|wana\/bids|prebid|bids\/request|ads\/|pixel|beacon|collect|impression|click-tracking|heartbeat|webConfig|config\.json|manifest\.json|service-worker|sw\.js|favicon|robots\.txt|sitemap|opensearch|partial\/[a-zA-Z]+\/mod-|logging|csp-report|gen_204|generate_204|sodar|__|devvit-|user-drawer|action-item)/i;
 
+// Fixed UUIDv5 namespace for endpoint ids — never change it, or every
+// re-capture stops matching the ids of earlier ones.
+const ENDPOINT_ID_NAMESPACE = "5b3f1c2e-8d4a-5e6f-9a7b-0c1d2e3f4a5b";
+
+/**
+ * Deterministic endpoint id: a UUIDv5 of method + URL template (+ occurrence
+ * index when one capture yields the same template twice). Re-extracting an
+ * unchanged capture produces identical ids, so diffs and caches line up.
+ */
+export function deterministicEndpointId(method: string, urlTemplate: string, occurrence = 0): string {
+  const name = `${method.toUpperCase()} ${urlTemplate}${occurrence > 0 ? `#${occurrence}` : ""}`;
+  const ns = Buffer.from(ENDPOINT_ID_NAMESPACE.replace(/-/g, ""), "hex");
+  const hash = createHash("sha1").update(ns).update(name, "utf8").digest();
+  hash[6] = (hash[6] & 0x0f) | 0x50;
+  hash[8] = (hash[8] & 0x3f) | 0x80;
+  const hex = hash.subarray(0, 16).toString("hex");
+  return `${hex.slice(0, 8)}-${hex.slice(8, 12)}-${hex.slice(12, 16)}-${hex.slice(16, 20)}-${hex.slice(20)}`;
+}
+
+// Binary RPC wire formats — clearly API traffic even though the body never parses.
+const BINARY_API_CONTENT_TYPE = /application\/(grpc(-web)?(-text)?(\+proto)?|x-protobuf|protobuf|vnd\.google\.protobuf|x-msgpack|msgpack|vnd\.msgpack)\b/i;
+
+function responseContentType(req: RawRequest): string {
+  return Object.entries(req.response_headers ?? {}).find(([k]) => k.toLowerCase() === "content-type")?.[1] ?? "";
+}
+
+/** Body format from a content-type, e.g. `application/grpc-web+proto` → "grpc". */
+export function classifyBodyFormat(contentType: string | undefined): BodyFormat {
+  const ct = (contentType ?? "").toLowerCase();
+  if (/application\/grpc/.test(ct)) return "grpc";
+  if (/text\/event-stream/.test(ct)) return "sse";
+  if (isStreamingContentType(ct)) return "ndjson";
+  if (/json/.test(ct) && !/protobuf/.test(ct)) return "json";
+  if (/protobuf/.test(ct)) return "protobuf";
+  if (/msgpack/.test(ct)) return "msgpack";
+  if (/x-www-form-urlencoded|multipart\/form-data/.test(ct)) return "form";
+  return "other";
+}
+
+function isBinaryApiResponse(req: RawRequest): boolean {
+  return BINARY_API_CONTENT_TYPE.test(responseContentType(req));
+}
+
+/**
+ * Request-side API signals, for APIs on generic edge domains whose response
+ * gave no content-type to go on (HEAD, 204, untracked headers): the client
+ * asked for JSON, or sent an Authorization header.
+ */
+function requestApiSignals(req: RawRequest): { acceptsJson: boolean; authorized: boolean } {
+  const accept = Object.entries(req.request_headers ?? {}).find(([k]) => k.toLowerCase() === "accept")?.[1] ?? "";
+  // `text/html, ..., */*` is a navigation, even though `*/*` admits JSON
+  const acceptsJson = /json/i.test(accept) && !/^\s*text\/html/i.test(accept);
+  const authorized = Object.entries(req.request_headers ?? {}).some(([k, v]) => k.toLowerCase() === "authorization" && !!v?.trim());
+  return { acceptsJson, authorized };
+}
+
+/** `name=value` pairs from a request's Cookie header. */
+function requestCookiePairs(req: RawRequest): string[] {
+  const header = Object.entries(req.request_headers ?? {}).find(([k]) => k.toLowerCase() === "cookie")?.[1] ?? "";
+  return header.split(";").map((c) => c.trim()).filter((c) => c.includes("=") && !c.endsWith("="));
+}
+
+/**
+ * Session cookies (`name=value`) the target's own hosts were sent — cookies
+ * with session-like names on requests to the affinity domains. Exported for
+ * testability.
+ */
+export function detectSessionCookies(requests: RawRequest[], affinityDomains: Set<string>): Set<string> {
+  const pairs = new Set<string>();
+  for (const req of requests) {
+    let domain: string;
+    try { domain = getRegistrableDomain(new URL(req.url).hostname); } catch { continue; }
+    if (!affinityDomains.has(domain)) continue;
+    for (const pair of requestCookiePairs(req)) {
+      if (isSessionCookieName(pair.slice(0, pair.indexOf("=")))) pairs.add(pair);
+    }
+  }
+  return pairs;
+}
+
+/** Rank penalty for same-root calls sent without the session or any Authorization. */
+const SESSIONLESS_SCORE_PENALTY = 3;
+
 // Score a request: higher = more likely to be a real data API (BUG-GC-004)
-function scoreRequest(req: RawRequest): number {
+function scoreRequest(req: RawRequest, overrides: PathOverrides = { api: [], skip: [] }): number {
   let score = 0;
   // GET is preferred — safe, idempotent, more useful for data retrieval
   if (req.method === "GET") score += 2;
-  if (RPC_HINTS.test(req.url)) score += 3;
+  const forcedApi = overrides.api.some((re) => re.test(req.url));
+  if (RPC_HINTS.test(req.url) || forcedApi) score += 3;
   if (SKIP_JS_BUNDLES.test(req.url)) score -= 10;
   const ct = req.response_headers?.["content-type"] ?? "";
   if (ct.includes("application/json") && !ct.includes("protobuf")) score += 4;
+  // gRPC-web / protobuf / msgpack are RPC responses, as API-like as JSON
+  else if (BINARY_API_CONTENT_TYPE.test(ct)) score += 4;
+  // NDJSON / SSE feeds carry the same data as JSON, one record per frame
+  else if (isStreamingContentType(ct)) score += 4;
   // Fallback: if response_headers is empty (common in tracked requests), check if body is JSON
   else if (!ct && req.response_body) {
     try { JSON.parse(stripJsonPrefix(req.response_body)); score += 4; } catch { /* not JSON */ }
   }
+  else if (!/html|javascript|css|image\//i.test(ct)) {
+    // No response-side signal (HEAD, 204, missing content-type) — fall back to what the request asked for.
+    const signals = requestApiSignals(req);
+    if (signals.acceptsJson) score += 3;
+    if (signals.authorized) score += 2;
+  }
   // Protobuf responses are not parseable — score neutral, don't reward (BUG-GC-006)
   if (ct.includes("x-protobuf") || ct.includes("json+protobuf")) score += 0;
   // Penalise long URLs — but only the path, not query params (GraphQL endpoints
@@ -578,7 +853,7 @@ function scoreRequest(req: RawRequest): number {
   // #227: Structural RSC body detection — catches payloads without URL/content-type hints
   if (isRscPayload(req.response_body ?? "")) score -= 15;
   // Penalise on-domain noise (framework plumbing, recaptcha, consent, ad bids)
-  try { if (ON_DOMAIN_NOISE.test(new URL(req.url).pathname)) score -= 15; } catch {}
+  try { if (!forcedApi && ON_DOMAIN_NOISE.test(new URL(req.url).pathname)) score -= 15; } catch {}
   // Reward rich JSON responses (data endpoints have deep objects, noise has shallow)
   if (req.response_body) {
     try {
@@ -601,12 +876,158 @@ export interface ExtractionContext {
   finalUrl?: string;
   /** The user's intent string */
   intent?: string;
+  /**
+   * Extra URL patterns that mark a request as API-like (e.g. a site serving
+   * JSON from `/_data/` or `/x/` paths): they add the RPC-hint score and
+   * bypass the path-based skip rules. Merged with UNBROWSE_API_PATH_PATTERNS.
+   */
+  apiPathPatterns?: RegExp[];
+  /** Extra URL patterns to reject as non-API. Merged with UNBROWSE_SKIP_PATH_PATTERNS. */
+  skipPathPatterns?: RegExp[];
+  /**
+   * Collapse endpoints with the same method and response schema (A/B
+   * variants, CDN mirrors) into the most-called one. Off by default since
+   * distinct endpoints can share a shape; UNBROWSE_DEDUPE_BY_SHAPE=1 also enables it.
+   */
+  dedupeByShape?: boolean;
+  /**
+   * Surface endpoints whose CORS preflight was seen but whose real request
+   * never was (the browser blocked it) as `blocked_by: "cors"` endpoints.
+   * UNBROWSE_REPORT_CORS_BLOCKED=1 also enables it.
+   */
+  reportCorsBlocked?: boolean;
+  /**
+   * Keep only (or drop) endpoints with these methods, e.g. `{ only: ["GET"] }`
+   * for a read-only skill. Merged with UNBROWSE_ONLY_METHODS /
+   * UNBROWSE_EXCLUDE_METHODS. Default keeps every method.
+   */
+  methods?: MethodsFilter;
+  /**
+   * Keep only calls whose response status is in range, e.g.
+   * `{ include_errors: false }` so a skill doesn't advertise endpoints that
+   * only ever returned 404/500. Merged with UNBROWSE_STATUS_RANGES
+   * ("200-399,401"). Default keeps every status.
+   */
+  statuses?: StatusFilter;
+  /**
+   * Drop 3xx responses that bounce to a login page instead of documenting
+   * them as endpoints (see summarizeLoginRedirects for the session-level
+   * signal). UNBROWSE_EXCLUDE_LOGIN_REDIRECTS=1 also enables it.
+   */
+  excludeLoginRedirects?: boolean;
+  /**
+   * Group calls whose paths differ only in case (`/API/Users` and
+   * `/api/users`) into one endpoint. Off by default since some APIs are
+   * case-sensitive; UNBROWSE_CASE_INSENSITIVE_PATHS=1 also enables it.
+   * Trailing-slash variants are always grouped.
+   */
+  caseInsensitivePaths?: boolean;
+  /**
+   * Judge first-party by the target's session cookie instead of the host
+   * alone: a request to an unrelated domain that carries the seed domain's
+   * session cookie (an API on a separate CDN domain) is kept, and same-root
+   * requests sending neither that cookie nor an Authorization header rank
+   * lower. UNBROWSE_SESSION_FIRST_PARTY=1 also enables it.
+   */
+  sessionFirstParty?: boolean;
+}
+
+export type { StatusFilter };
+
+const SUCCESS_STATUS_RANGE = { min: 200, max: 399 };
+
+function envStatusRanges(): Array<{ min: number; max: number }> {
+  return (process.env.UNBROWSE_STATUS_RANGES ?? "").split(",").map((r) => r.trim()).filter(Boolean).flatMap((r) => {
+    const [min, max = min] = r.split("-").map((n) => Number(n.trim()));
+    return Number.isFinite(min) && Number.isFinite(max) ? [{ min, max }] : [];
+  });
+}
+
+/** Whether a response status passes a StatusFilter (and UNBROWSE_STATUS_RANGES). */
+export function statusAllowed(status: number, filter: StatusFilter | undefined): boolean {
+  const ranges = [
+    ...envStatusRanges(),
+    ...(filter?.ranges ?? []),
+    ...(filter?.include_errors === false ? [SUCCESS_STATUS_RANGE] : []),
+  ];
+  return ranges.length === 0 || ranges.some((r) => status >= r.min && status <= r.max);
+}
+
+export interface MethodsFilter {
+  /** Allowlist; when set, any other method is dropped */
+  only?: string[];
+  /** Denylist; wins over `only` */
+  exclude?: string[];
+}
+
+function envMethods(name: string): string[] {
+  return (process.env[name] ?? "").split(",").map((m) => m.trim().toUpperCase()).filter(Boolean);
+}
+
+function resolveMethodsFilter(context?: ExtractionContext): { only: Set<string> | null; exclude: Set<string> } {
+  const only = [...envMethods("UNBROWSE_ONLY_METHODS"), ...(context?.methods?.only ?? []).map((m) => m.toUpperCase())];
+  const exclude = [...envMethods("UNBROWSE_EXCLUDE_METHODS"), ...(context?.methods?.exclude ?? []).map((m) => m.toUpperCase())];
+  return { only: only.length > 0 ? new Set(only) : null, exclude: new Set(exclude) };
+}
+
+/** Whether a method passes a MethodsFilter (case-insensitive). Exported for testability. */
+export function methodAllowed(method: string, filter: MethodsFilter | undefined): boolean {
+  const { only, exclude } = resolveMethodsFilter({ methods: filter });
+  const upper = method.toUpperCase();
+  if (exclude.has(upper)) return false;
+  return !only || only.has(upper);
+}
+
+const METHOD_OVERRIDE_HEADERS = ["x-http-method-override", "x-http-method", "x-method-override"];
+const OVERRIDABLE_METHODS = new Set(["GET", "PUT", "PATCH", "DELETE"]);
+
+/**
+ * The method a POST is tunnelling, from `X-HTTP-Method-Override` (and
+ * variants) or a `_method` form/JSON field, as older frameworks do.
+ * Null when the request isn't tunnelled.
+ */
+export function overriddenMethod(req: RawRequest): string | null {
+  if (req.method.toUpperCase() !== "POST") return null;
+  let candidate: string | undefined;
+  for (const [k, v] of Object.entries(req.request_headers ?? {})) {
+    if (METHOD_OVERRIDE_HEADERS.includes(k.toLowerCase())) candidate = v;
+  }
+  if (!candidate && req.request_body) {
+    const body = req.request_body.trim();
+    if (body.startsWith("{")) {
+      try {
+        const parsed = JSON.parse(body) as Record<string, unknown>;
+        if (typeof parsed._method === "string") candidate = parsed._method;
+      } catch { /* not JSON */ }
+    } else if (/(^|&)_method=/.test(body)) {
+      candidate = new URLSearchParams(body).get("_method") ?? undefined;
+    }
+  }
+  const method = candidate?.trim().toUpperCase();
+  return method && OVERRIDABLE_METHODS.has(method) ? method : null;
 }
 
 export function extractEndpoints(requests: RawRequest[], wsMessages?: CapturedWsMessage[], context?: ExtractionContext): EndpointDescriptor[] {
+  // Group and classify tunnelled requests by their effective method; the
+  // wire method is kept on the endpoint for replay.
+  const wireMethods = new Map<RawRequest, string>();
+  requests = requests.map((req) => {
+    const method = overriddenMethod(req);
+    if (!method) return req;
+    const effective = { ...req, method };
+    wireMethods.set(effective, req.method.toUpperCase());
+    return effective;
+  });
   const seen = new Set<string>();
   const endpoints: EndpointDescriptor[] = [];
   const traceRows: Array<Record<string, unknown>> = [];
+  const idOccurrences = new Map<string, number>();
+  const nextEndpointId = (method: string, urlTemplate: string): string => {
+    const key = `${method} ${urlTemplate}`;
+    const occurrence = idOccurrences.get(key) ?? 0;
+    idOccurrences.set(key, occurrence + 1);
+    return deterministicEndpointId(method, urlTemplate, occurrence);
+  };
 
   // Extract the registrable domain(s) for affinity filtering.
   // Include both pageUrl and finalUrl domains to handle redirects
@@ -618,21 +1039,47 @@ export function extractEndpoints(requests: RawRequest[], wsMessages?: CapturedWs
   }
 
   const scored: Array<{ req: RawRequest; score: number }> = [];
+  const polled: Array<{ req: RawRequest; score: number }> = [];
+  const foldCase = context?.caseInsensitivePaths ?? process.env.UNBROWSE_CASE_INSENSITIVE_PATHS === "1";
+  const pollingKeys = detectPollingKeys(requests, foldCase);
+  const signingByKey = detectRequestSigning(requests, foldCase);
+  const pathOverrides = resolvePathOverrides(context);
+  const sessionCookies = (context?.sessionFirstParty ?? process.env.UNBROWSE_SESSION_FIRST_PARTY === "1") && affinityDomains.size > 0
+    ? detectSessionCookies(requests, affinityDomains)
+    : new Set<string>();
   for (const req of requests) {
-    const score = scoreRequest(req);
-    if (!isApiLike(req)) {
-      traceRows.push({ url: req.url, method: req.method, score, kept: false, reason: "not_api_like" });
+    if (!methodAllowed(req.method, context?.methods)) {
+      traceRows.push({ url: req.url, method: req.method, kept: false, reason: "method_filtered" });
+      continue;
+    }
+    if ((context?.excludeLoginRedirects ?? process.env.UNBROWSE_EXCLUDE_LOGIN_REDIRECTS === "1") && loginRedirectTarget(req)) {
+      traceRows.push({ url: req.url, method: req.method, status: req.response_status, kept: false, reason: "login_redirect" });
+      continue;
+    }
+    if (!statusAllowed(req.response_status, context?.statuses)) {
+      traceRows.push({ url: req.url, method: req.method, status: req.response_status, kept: false, reason: "status_filtered" });
+      continue;
+    }
+    const score = scoreRequest(req, pathOverrides);
+    const rejection = apiLikeRejection(req, pathOverrides);
+    if (rejection) {
+      traceRows.push({ url: req.url, method: req.method, score, kept: false, reason: "not_api_like", rule: rejection });
       continue;
     }
     if (score <= 0) {
       traceRows.push({ url: req.url, method: req.method, score, kept: false, reason: "score_non_positive" });
       continue;
     }
-    if (!hasAdmissibleParsedBody(req.response_body)) {
+    // A truncated body never parses — content-type alone vouches for it.
+    const truncatedJson = req.response_body_truncated === true
+      && /json/i.test(Object.entries(req.response_headers).find(([k]) => k.toLowerCase() === "content-type")?.[1] ?? "");
+    if (!truncatedJson && !isBinaryApiResponse(req) && !hasAdmissibleParsedBody(req.response_body)) {
       // API endpoints may have large/truncated/missing response bodies.
       // Admit them anyway if the URL pattern is clearly an API endpoint.
       const urlPath = (() => { try { return new URL(req.url).pathname; } catch { return ""; } })();
-      const isApiUrl = /\/(api|graphql)\b/i.test(urlPath) || /\.(json)(\?|$)/.test(req.url);
+      const signals = requestApiSignals(req);
+      const isApiUrl = /\/(api|graphql)\b/i.test(urlPath) || /\.(json)(\?|$)/.test(req.url)
+        || signals.acceptsJson || signals.authorized;
 
       // For GraphQL: extract operationName from request body or URL
       let graphqlOpName: string | undefined;
@@ -671,22 +1118,35 @@ export function extractEndpoints(requests: RawRequest[], wsMessages?: CapturedWs
       traceRows.push({ url: req.url, method: req.method, score, kept: false, reason: "rsc_payload", rsc_embedded_urls: rscUrls.length > 0 ? rscUrls : undefined });
       continue;
     }
+    const carriesSession = sessionCookies.size > 0 && requestCookiePairs(req).some((pair) => sessionCookies.has(pair));
+    let rankedScore = score;
     if (affinityDomains.size > 0) {
       try {
         const reqHost = new URL(req.url).hostname;
         const reqDomain = getRegistrableDomain(reqHost);
-        if (!affinityDomains.has(reqDomain)) {
+        if (!affinityDomains.has(reqDomain) && !carriesSession) {
           traceRows.push({ url: req.url, method: req.method, score, kept: false, reason: "domain_mismatch" });
           continue;
         }
+        if (sessionCookies.size > 0 && affinityDomains.has(reqDomain) && !carriesSession && !requestApiSignals(req).authorized) {
+          rankedScore -= SESSIONLESS_SCORE_PENALTY;
+        }
       } catch {
         traceRows.push({ url: req.url, method: req.method, score, kept: false, reason: "bad_url" });
         continue;
       }
     }
-    traceRows.push({ url: req.url, method: req.method, score, kept: true, reason: "candidate" });
-    scored.push({ req, score });
+    if (pollingKeys.has(endpointGroupKey(req.method, req.url, foldCase))) {
+      traceRows.push({ url: req.url, method: req.method, score, kept: false, reason: "background_polling" });
+      polled.push({ req, score: rankedScore });
+      continue;
+    }
+    const sessionNote = sessionCookies.size > 0 ? { session: carriesSession ? "carried" : "missing" } : {};
+    traceRows.push({ url: req.url, method: req.method, score: rankedScore, kept: true, reason: "candidate", ...sessionNote });
+    scored.push({ req, score: rankedScore });
   }
+  // Polling is only dropped from the primary flow — if it's all we saw, keep it.
+  if (scored.length === 0) scored.push(...polled);
   scored.sort((a, b) => b.score - a.score);
 
   // For passive captures (no context page URL), pre-compute path templates across
@@ -699,9 +1159,26 @@ export function extractEndpoints(requests: RawRequest[], wsMessages?: CapturedWs
     : new Map<string, string>();
 
 
+  // Every call per endpoint, not just the representative one, so query params
+  // can be classified required vs optional and body fields that vary between
+  // calls can be templated.
+  const urlsByKey = new Map<string, string[]>();
+  const bodiesByKey = new Map<string, string[]>();
+  const statsByKey = new Map<string, CaptureStats>();
+  for (const { req } of scored) {
+    const key = endpointGroupKey(req.method, req.url, foldCase);
+    urlsByKey.set(key, [...(urlsByKey.get(key) ?? []), req.url]);
+    if (req.request_body) bodiesByKey.set(key, [...(bodiesByKey.get(key) ?? []), req.request_body]);
+    const stats = statsByKey.get(key) ?? { calls: 0, ok_calls: 0, json: false };
+    stats.calls++;
+    if (req.response_status >= 200 && req.response_status < 300) stats.ok_calls++;
+    if (/json/i.test(responseContentType(req))) stats.json = true;
+    statsByKey.set(key, stats);
+  }
+
   for (const { req } of scored) {
     const normalized = normalizeUrl(req.url);
-    const key = `${req.method}:${normalized}`;
+    const key = endpointGroupKey(req.method, req.url, foldCase);
     if (seen.has(key)) continue;
     seen.add(key);
 
@@ -717,18 +1194,25 @@ export function extractEndpoints(requests: RawRequest[], wsMessages?: CapturedWs
       continue;
     }
 
-    // BUG-GC-006: Skip protobuf-only endpoints — we can't parse their bodies
-    const ct = req.response_headers?.["content-type"] ?? "";
-    if ((ct.includes("x-protobuf") || ct.includes("json+protobuf")) && !isJsonParseable(req.response_body)) {
-      traceRows.push({ url: req.url, method: req.method, kept: false, reason: "protobuf_unparseable" });
-      continue;
+    // BUG-GC-006: binary bodies (protobuf, gRPC-web, msgpack) can't be parsed —
+    // keep the endpoint so the service isn't invisible, but skip shape analysis.
+    const ct = responseContentType(req);
+    const stream = parseStreamBody(req.response_body, ct);
+    const bodyFormat = ct ? classifyBodyFormat(ct) : isJsonParseable(req.response_body) ? "json" : stream?.format ?? "other";
+    const binaryBody = isBinaryApiResponse(req) || (bodyFormat === "protobuf" && !isJsonParseable(req.response_body));
+    const grpcWeb = bodyFormat === "grpc" ? parseGrpcWebFrames(req.response_body, ct, req.response_headers) : null;
+    if (grpcWeb?.grpc_status) {
+      logDebug("reverse-engineer", `${req.method} ${req.url}: ${describeGrpcWeb(grpcWeb)}${grpcWeb.grpc_message ? ` ${grpcWeb.grpc_message}` : ""}`);
     }
 
     const isGet = req.method === "GET";
+    // Query and body are read as sent: a tunnelled GET is a POST on the wire
+    // and its parameters live in the body.
+    const wireGet = (wireMethods.get(req) ?? req.method) === "GET";
 
     // Infer response schema from captured body
-    let response_schema = undefined;
-    if (req.response_body) {
+    let response_schema: ResponseSchema | undefined = undefined;
+    if (req.response_body && !req.response_body_truncated && !binaryBody) {
       try {
         const cleaned = stripJsonPrefix(req.response_body);
         const parsed = JSON.parse(cleaned);
@@ -737,6 +1221,17 @@ export function extractEndpoints(requests: RawRequest[], wsMessages?: CapturedWs
         // not valid JSON — skip schema inference
       }
     }
+    // Streams are shaped per line / frame; a capped (truncated) read still
+    // holds whole records up to the cut.
+    if (!response_schema && stream && stream.items.length > 0) {
+      response_schema = {
+        type: "array",
+        items: inferSchema(stream.items),
+        inferred_from_samples: stream.items.length,
+        stream: stream.format,
+        ...(stream.events ? { stream_events: stream.events } : {}),
+      };
+    }
 
     // BUG-008: mark endpoints with no response body as potentially CF-blocked
     const verificationStatus = req.response_body ? "unverified" as const : "pending" as const;
@@ -750,7 +1245,7 @@ export function extractEndpoints(requests: RawRequest[], wsMessages?: CapturedWs
     // Build url_template with templatized query params so callers know what to pass.
     // normalizeUrl strips the query string; we rebuild it with {param} placeholders.
     // endpoint.query stores the captured defaults for execution-time fallback.
-    const sanitizedQParams = isGet ? sanitizeQueryParams(extractQueryParams(req.url)) : undefined;
+    const sanitizedQParams = wireGet ? sanitizeQueryParams(extractQueryParams(req.url)) : undefined;
     let pathTemplate = sanitizeUrlTemplate(normalized);
     const qBindings = sanitizedQParams ? buildQueryBindingMap(Object.keys(sanitizedQParams)) : {};
     const qTemplateStr = sanitizedQParams && Object.keys(sanitizedQParams).length > 0
@@ -761,33 +1256,55 @@ export function extractEndpoints(requests: RawRequest[], wsMessages?: CapturedWs
     const { url: templatizedPath, pathParams } = templatizePathSegments(pathTemplate, req.url, context);
     pathTemplate = templatizedPath;
 
-    const parsedRequestBody = !isGet && req.request_body ? tryParseBody(req.request_body) : undefined;
+    const requestContentType = Object.entries(req.request_headers).find(([k]) => k.toLowerCase() === "content-type")?.[1];
+    const parsedRequestBody = !wireGet && req.request_body ? parseRequestBody(req.request_body, requestContentType) : undefined;
     const bodyParams: Record<string, unknown> = {};
-    const templatedRequestBody = !isGet && parsedRequestBody && typeof parsedRequestBody === "object" && !Array.isArray(parsedRequestBody)
-      ? templatizeBodyObject(parsedRequestBody, context, "", bodyParams) as Record<string, unknown>
+    const templatedRequestBody = !wireGet && parsedRequestBody && typeof parsedRequestBody === "object" && !Array.isArray(parsedRequestBody)
+      ? templatizeBodyObject(
+        parsedRequestBody,
+        context,
+        "",
+        bodyParams,
+        varyingBodyPaths((bodiesByKey.get(key) ?? []).map((b) => parseRequestBody(b, requestContentType)).filter(Boolean), parsedRequestBody),
+      ) as Record<string, unknown>
       : parsedRequestBody;
-    const sampleResponse = req.response_body ? tryParseBody(req.response_body) : undefined;
+    const sampleResponse = req.response_body && !req.response_body_truncated && !binaryBody ? tryParseBody(req.response_body) : undefined;
     const sampleRequest = flattenRequestExample({
       path_params: Object.keys(pathParams).length > 0 ? pathParams : undefined,
       query: sanitizedQParams,
       body: templatedRequestBody,
     });
     const csrfPlan = inferCsrfPlan(req, parsedRequestBody);
+    const signing = signingByKey.get(key);
+    const rateLimit = parseRateLimitHeaders(req.response_headers);
+    const queryParams = aggregateQueryParams(urlsByKey.get(key) ?? [req.url]);
+    const requiredBodyParams = templatedRequestBody ? requiredBodyBindings(templatedRequestBody, bodyParams) : [];
 
+    const urlTemplate = qTemplateStr ? `${pathTemplate}?${qTemplateStr}` : pathTemplate;
     const endpoint: EndpointDescriptor = {
-      endpoint_id: nanoid(),
+      endpoint_id: nextEndpointId(req.method, urlTemplate),
       method: req.method as EndpointDescriptor["method"],
-      url_template: qTemplateStr ? `${pathTemplate}?${qTemplateStr}` : pathTemplate,
+      url_template: urlTemplate,
       description: buildEndpointDescription(req, sampleRequest, sampleResponse),
-      headers_template: sanitizeHeaders(req.request_headers),
+      headers_template: signing?.location === "header"
+        ? omitHeader(sanitizeHeaders(req.request_headers), signing.signature_name)
+        : sanitizeHeaders(req.request_headers),
       query: sanitizedQParams,
+      ...(queryParams.length > 0 ? { query_params: queryParams } : {}),
       path_params: Object.keys(pathParams).length > 0 ? pathParams : undefined,
       ...(Object.keys(bodyParams).length > 0 ? { body_params: bodyParams } : {}),
+      ...(requiredBodyParams.length > 0 ? { required_body_params: requiredBodyParams } : {}),
       ...(templatedRequestBody && typeof templatedRequestBody === "object" && !Array.isArray(templatedRequestBody) ? { body: templatedRequestBody as Record<string, unknown> } : {}),
       ...(csrfPlan ? { csrf_plan: csrfPlan } : {}),
+      ...(signing ? { request_signing: signing } : {}),
+      ...(rateLimit ? { rate_limit: rateLimit } : {}),
+      body_format: bodyFormat,
+      ...(grpcWeb ? { grpc_web: grpcWeb } : {}),
       idempotency: isGet ? "safe" : "unsafe",
       verification_status: verificationStatus,
       reliability_score: 0.5,
+      ...(statsByKey.has(key) ? { capture_stats: statsByKey.get(key) } : {}),
+      ...(wireMethods.has(req) ? { wire_method: wireMethods.get(req) } : {}),
       response_schema,
       // Record which page triggered this API call — used for trigger-and-intercept execution
       trigger_url: context?.pageUrl,
@@ -807,6 +1324,7 @@ export function extractEndpoints(requests: RawRequest[], wsMessages?: CapturedWs
       };
     }
     endpoint.description = endpoint.semantic?.description_out ?? endpoint.description;
+    if (grpcWeb) endpoint.description = `${endpoint.description ?? ""} (${describeGrpcWeb(grpcWeb)})`.trim();
     const admission = isSemanticallyAdmissibleResponse(req, sampleResponse, sampleRequest, context);
     if (!admission.ok) {
       traceRows.push({
@@ -838,12 +1356,31 @@ export function extractEndpoints(requests: RawRequest[], wsMessages?: CapturedWs
 
   // Collapse sibling endpoints into templatized ones
   // e.g. /ticker-sentiment/MSFT + /ticker-sentiment/NVDA → /ticker-sentiment/{ticker}
-  const deduped = collapseEndpoints(endpoints);
+  let deduped = collapseEndpoints(endpoints);
+  if (context?.dedupeByShape ?? process.env.UNBROWSE_DEDUPE_BY_SHAPE === "1") {
+    deduped = dedupeEndpointsByShape(deduped);
+  }
   endpoints.length = 0;
   endpoints.push(...deduped);
 
+  if (context?.reportCorsBlocked ?? process.env.UNBROWSE_REPORT_CORS_BLOCKED === "1") {
+    for (const req of findCorsBlockedRequests(requests)) {
+      if (!methodAllowed(req.method, context?.methods)) continue;
+      const rejection = apiLikeRejection(req, pathOverrides);
+      let domainOk = true;
+      try { domainOk = affinityDomains.size === 0 || affinityDomains.has(getRegistrableDomain(new URL(req.url).hostname)); } catch { domainOk = false; }
+      if (rejection || !domainOk) {
+        traceRows.push({ url: req.url, method: req.method, kept: false, reason: rejection ? "not_api_like" : "domain_mismatch", cors_blocked: true });
+        continue;
+      }
+      const endpoint = corsBlockedEndpoint(req, context, nextEndpointId);
+      traceRows.push({ url: req.url, method: req.method, kept: true, reason: "cors_blocked", endpoint_id: endpoint.endpoint_id });
+      endpoints.push(endpoint);
+    }
+  }
+
   // Create endpoints from WebSocket messages
-  if (wsMessages && wsMessages.length > 0) {
+  if (wsMessages && wsMessages.length > 0 && methodAllowed("WS", context?.methods)) {
     const wsByUrl = new Map<string, CapturedWsMessage[]>();
     for (const msg of wsMessages) {
       const arr = wsByUrl.get(msg.url) ?? [];
@@ -853,7 +1390,12 @@ export function extractEndpoints(requests: RawRequest[], wsMessages?: CapturedWs
 
     for (const [wsUrl, msgs] of wsByUrl) {
       const received = msgs.filter((m) => m.direction === "received");
-      const wsMsgList: WsMessage[] = msgs.map((m) => ({
+      // Keep a sample of each direction so long-lived feeds don't bloat the manifest
+      const sample = [
+        ...msgs.filter((m) => m.direction === "sent").slice(0, WS_SAMPLE_PER_DIRECTION),
+        ...received.slice(0, WS_SAMPLE_PER_DIRECTION),
+      ].sort((a, b) => a.timestamp.localeCompare(b.timestamp));
+      const wsMsgList: WsMessage[] = sample.map((m) => ({
         direction: m.direction,
         data: m.data,
         timestamp: m.timestamp,
@@ -871,8 +1413,9 @@ export function extractEndpoints(requests: RawRequest[], wsMessages?: CapturedWs
         response_schema = inferSchema(jsonSamples);
       }
 
+      const subprotocol = msgs.find((m) => m.protocol)?.protocol;
       const endpoint: EndpointDescriptor = {
-        endpoint_id: nanoid(),
+        endpoint_id: nextEndpointId("WS", wsUrl),
         method: "WS",
         url_template: wsUrl,
         idempotency: "safe",
@@ -880,6 +1423,7 @@ export function extractEndpoints(requests: RawRequest[], wsMessages?: CapturedWs
         reliability_score: jsonSamples.length > 0 ? 0.7 : 0.3,
         response_schema,
         ws_messages: wsMsgList,
+        ...(subprotocol ? { ws_subprotocol: subprotocol } : {}),
       };
       endpoint.semantic = inferEndpointSemantic(endpoint, {
         sampleResponse: jsonSamples[0],
@@ -891,6 +1435,13 @@ export function extractEndpoints(requests: RawRequest[], wsMessages?: CapturedWs
     }
   }
 
+  if (isLogLevelEnabled("debug")) {
+    for (const row of traceRows) {
+      if (row.kept) continue;
+      logDebug("reverse-engineer", `dropped ${row.method} ${row.url}: ${row.reason}${row.rule ? ` (${row.rule})` : ""}`);
+    }
+  }
+
   writeDebugTrace("generation", {
     page_url: context?.pageUrl ?? null,
     final_url: context?.finalUrl ?? null,
@@ -911,29 +1462,242 @@ export function extractEndpoints(requests: RawRequest[], wsMessages?: CapturedWs
   return endpoints;
 }
 
-function isApiLike(req: RawRequest): boolean {
-  if (!ALLOWED_METHODS.has(req.method.toUpperCase())) return false;
-  if (SKIP_EXTENSIONS.test(req.url)) return false;
-  if (SKIP_JS_BUNDLES.test(req.url)) return false;
-  if (SKIP_PATHS.test(req.url)) return false;
+function headerValue(headers: Record<string, string>, name: string): string | undefined {
+  return Object.entries(headers).find(([k]) => k.toLowerCase() === name)?.[1];
+}
+
+/**
+ * CORS preflights whose real request never appears in the capture — the
+ * browser refused to send it, so without this the endpoint silently
+ * vanishes. Each comes back as the intended request: method from
+ * Access-Control-Request-Method, no body, no response (status 0).
+ */
+export function findCorsBlockedRequests(requests: RawRequest[]): RawRequest[] {
+  const sent = new Set(requests
+    .filter((r) => r.method.toUpperCase() !== "OPTIONS")
+    .map((r) => endpointGroupKey(r.method.toUpperCase(), r.url)));
+  const blocked = new Map<string, RawRequest>();
+  for (const req of requests) {
+    if (req.method.toUpperCase() !== "OPTIONS") continue;
+    const method = headerValue(req.request_headers, "access-control-request-method")?.trim().toUpperCase();
+    if (!method) continue; // plain OPTIONS, not a preflight
+    const key = endpointGroupKey(method, req.url);
+    if (sent.has(key) || blocked.has(key)) continue;
+    blocked.set(key, {
+      url: req.url,
+      method,
+      request_headers: {},
+      response_status: 0,
+      response_headers: {},
+      timestamp: req.timestamp,
+    });
+  }
+  return [...blocked.values()];
+}
+
+/**
+ * Endpoint for a CORS-blocked request. It has no response to learn from, so
+ * it stays `pending` with a low score; server-side execution isn't subject
+ * to CORS and may still reach it.
+ */
+function corsBlockedEndpoint(
+  req: RawRequest,
+  context: ExtractionContext | undefined,
+  nextEndpointId: (method: string, urlTemplate: string) => string,
+): EndpointDescriptor {
+  const isGet = req.method === "GET";
+  const query = isGet ? sanitizeQueryParams(extractQueryParams(req.url)) : {};
+  const bindings = buildQueryBindingMap(Object.keys(query));
+  const { url: pathTemplate, pathParams } = templatizePathSegments(sanitizeUrlTemplate(normalizeUrl(req.url)), req.url, context);
+  const urlTemplate = Object.keys(query).length > 0
+    ? `${pathTemplate}?${Object.keys(query).map((k) => `${encodeURIComponent(k)}={${bindings[k] ?? k}}`).join("&")}`
+    : pathTemplate;
+  const endpoint: EndpointDescriptor = {
+    endpoint_id: nextEndpointId(req.method, urlTemplate),
+    method: req.method as EndpointDescriptor["method"],
+    url_template: urlTemplate,
+    headers_template: {},
+    ...(Object.keys(query).length > 0 ? { query } : {}),
+    ...(Object.keys(pathParams).length > 0 ? { path_params: pathParams } : {}),
+    idempotency: isGet ? "safe" : "unsafe",
+    verification_status: "pending",
+    reliability_score: 0.2,
+    blocked_by: "cors",
+    trigger_url: context?.pageUrl,
+  };
+  endpoint.semantic = inferEndpointSemantic(endpoint, { observedAt: req.timestamp, sampleRequestUrl: req.url });
+  endpoint.description = `${endpoint.semantic?.description_out ?? `${req.method} ${pathTemplate}`} (blocked by CORS in the browser)`;
+  return endpoint;
+}
+
+/**
+ * Name of the filter rule that rejects a request, or null when it looks like
+ * an API call. Configured API patterns skip the path rules (host rules still
+ * apply); framework path prefixes only count as static when the response is
+ * not JSON, so loader routes like `/_next/data/` survive.
+ */
+function apiLikeRejection(req: RawRequest, overrides: PathOverrides = { api: [], skip: [] }): string | null {
+  if (!ALLOWED_METHODS.has(req.method.toUpperCase())) return "method";
+  if (overrides.skip.some((re) => re.test(req.url))) return "custom_skip_path";
+  const forcedApi = overrides.api.some((re) => re.test(req.url));
+  const jsonResponse = /json/i.test(responseContentType(req));
+  if (!forcedApi) {
+    if (SKIP_EXTENSIONS.test(req.url)) return "static_asset";
+    if (!jsonResponse && SKIP_JS_BUNDLES.test(req.url)) return "js_bundle";
+    if (!jsonResponse && SKIP_PATHS.test(req.url)) return "skipped_path";
+  }
   try {
     const { hostname, pathname } = new URL(req.url);
-    if (SKIP_HOSTS.test(hostname)) return false;
-    if (SKIP_TELEMETRY_HOSTS.test(hostname)) return false;  // BUG-GC-004
-    if (SKIP_TELEMETRY_PATHS.test(pathname)) return false;  // BUG-GC-004
-    if (AD_HOSTS.test(hostname)) return false;
+    if (isSkippedHost(hostname)) return "skipped_domain";
+    if (SKIP_TELEMETRY_HOSTS.test(hostname)) return "telemetry_host";  // BUG-GC-004
+    if (!forcedApi && SKIP_TELEMETRY_PATHS.test(pathname)) return "telemetry_path";  // BUG-GC-004
+    if (AD_HOSTS.test(hostname)) return "ad_host";
     // play.google.com/log is telemetry, not calendar data
-    if (hostname === "play.google.com" && pathname.startsWith("/log")) return false;
+    if (hostname === "play.google.com" && pathname.startsWith("/log")) return "telemetry_path";
+    if (forcedApi) return req.response_body && req.response_body.length < 20 ? "tiny_response" : null;
     // Skip image CDN paths (coin images, avatars, etc.)
-    if (/\/(coin-image|avatar|profile-image)\//.test(pathname)) return false;
+    if (/\/(coin-image|avatar|profile-image)\//.test(pathname)) return "image_path";
     // Hard-skip on-domain noise that's never useful data
-    if (/\/(recaptcha|update-recaptcha|captcha|wana\/bids|prebid|bids\/request|pixel[s]?\/|beacon\/|csp-report|service-worker|sw\.js$|favicon|robots\.txt$|sitemap|opensearch)/.test(pathname)) return false;
+    if (/\/(recaptcha|update-recaptcha|captcha|wana\/bids|prebid|bids\/request|pixel[s]?\/|beacon\/|csp-report|service-worker|sw\.js$|favicon|robots\.txt$|sitemap|opensearch)/.test(pathname)) return "noise_path";
   } catch {
-    return false;
+    return "bad_url";
   }
   // Skip tiny responses — config/status/empty endpoints, not data
-  if (req.response_body && req.response_body.length < 20) return false;
-  return true;
+  if (req.response_body && req.response_body.length < 20) return "tiny_response";
+  return null;
+}
+
+const SIGNATURE_NAME = /(^|[-_])(signature|sign|sig|hmac)$/i;
+const SIGNING_TIMESTAMP_NAME = /(^|[-_])(timestamp|ts|time|request[-_]?time)$/i;
+const SIGNING_NONCE_NAME = /(^|[-_])nonce$/i;
+
+function omitHeader(headers: Record<string, string>, name: string): Record<string, string> {
+  return Object.fromEntries(Object.entries(headers).filter(([k]) => k.toLowerCase() !== name.toLowerCase()));
+}
+
+/** Classify a value as a hex/base64 HMAC digest and guess the hash from its length. */
+function classifyDigest(value: string): Pick<RequestSigningInfo, "encoding" | "digest_guess"> | null {
+  const v = value.trim();
+  const byBytes: Record<number, RequestSigningInfo["digest_guess"]> = { 20: "sha1", 32: "sha256", 48: "sha384", 64: "sha512" };
+  if (/^[0-9a-f]+$/i.test(v) && v.length % 2 === 0 && v.length >= 40) {
+    return { encoding: "hex", digest_guess: byBytes[v.length / 2] ?? "unknown" };
+  }
+  if (/^[A-Za-z0-9+/_-]+={0,2}$/.test(v) && v.length >= 27) {
+    const bytes = Math.floor((v.replace(/=+$/, "").length * 3) / 4);
+    return { encoding: "base64", digest_guess: byBytes[bytes] ?? "unknown" };
+  }
+  return null;
+}
+
+function looksLikeRequestTimestamp(value: string): boolean {
+  return requestTimestampFormat(value) !== undefined;
+}
+
+function requestTimestampFormat(value: string): RequestSigningInfo["timestamp_format"] {
+  const v = value.trim();
+  if (/^\d{13}$/.test(v)) return "epoch_ms";
+  if (/^\d{10}$/.test(v)) return "epoch_s";
+  if (/\d{4}-\d{2}-\d{2}T/.test(v) && !Number.isNaN(Date.parse(v))) return "iso";
+  return undefined;
+}
+
+/**
+ * Detect HMAC-signed requests: a signature-named header/query param whose
+ * value looks like a digest, next to a timestamp/nonce that changes between
+ * requests (or, with a single sample, looks like an epoch/ISO timestamp).
+ * Returns signing info keyed by endpointGroupKey. Exported for testability.
+ */
+export function detectRequestSigning(requests: RawRequest[], foldCase = false): Map<string, RequestSigningInfo> {
+  const groups = new Map<string, RawRequest[]>();
+  for (const req of requests) {
+    const key = endpointGroupKey(req.method, req.url, foldCase);
+    const arr = groups.get(key) ?? [];
+    arr.push(req);
+    groups.set(key, arr);
+  }
+
+  const result = new Map<string, RequestSigningInfo>();
+  for (const [key, reqs] of groups) {
+    const fields: Array<{ location: "header" | "query"; name: string; values: string[] }> = [];
+    const headerNames = new Set(reqs.flatMap((r) => Object.keys(r.request_headers).map((k) => k.toLowerCase())));
+    for (const name of headerNames) {
+      fields.push({
+        location: "header",
+        name,
+        values: reqs.map((r) => Object.entries(r.request_headers).find(([k]) => k.toLowerCase() === name)?.[1]).filter((v): v is string => !!v),
+      });
+    }
+    const queryNames = new Set(reqs.flatMap((r) => Object.keys(extractQueryParams(r.url))));
+    for (const name of queryNames) {
+      fields.push({
+        location: "query",
+        name,
+        values: reqs.map((r) => extractQueryParams(r.url)[name]).filter((v): v is string => !!v),
+      });
+    }
+
+    const signature = fields.find((f) => SIGNATURE_NAME.test(f.name) && f.values.length > 0 && classifyDigest(f.values[0]));
+    if (!signature) continue;
+    const timestamp = fields.find((f) =>
+      f !== signature &&
+      SIGNING_TIMESTAMP_NAME.test(f.name) &&
+      f.values.length > 0 &&
+      (new Set(f.values).size > 1 || (reqs.length === 1 && looksLikeRequestTimestamp(f.values[0]))),
+    );
+    const nonce = fields.find((f) =>
+      f !== signature &&
+      SIGNING_NONCE_NAME.test(f.name) &&
+      f.values.length > 0 &&
+      (new Set(f.values).size > 1 || (reqs.length === 1 && f.values[0].trim().length >= 8)),
+    );
+    // Without a moving timestamp or nonce, a rotating signature still proves per-request signing.
+    if (!timestamp && !nonce && new Set(signature.values).size <= 1) continue;
+
+    const timestampFormat = timestamp ? requestTimestampFormat(timestamp.values[0]) : undefined;
+    result.set(key, {
+      location: signature.location,
+      signature_name: signature.name,
+      ...(timestamp ? { timestamp_name: timestamp.name, timestamp_location: timestamp.location } : {}),
+      ...(timestampFormat ? { timestamp_format: timestampFormat } : {}),
+      ...(nonce ? { nonce_name: nonce.name, nonce_location: nonce.location } : {}),
+      ...classifyDigest(signature.values[0])!,
+      // Best-effort: the common `method\npath\ntimestamp\nbody` layout; the secret is needed to confirm it.
+      canonical_fields: ["method", "path", ...(timestamp ? ["timestamp" as const] : []), ...(nonce ? ["nonce" as const] : []), "body"],
+    });
+  }
+  return result;
+}
+
+const POLLING_MIN_HITS = 4;
+const POLLING_MIN_INTERVAL_MS = 1000;
+const POLLING_MAX_JITTER = 0.25; // max coefficient of variation between intervals
+
+/**
+ * Find background polling: the same method + normalized URL hit at least
+ * POLLING_MIN_HITS times at a near-constant interval. Returns the
+ * `${method}:${normalizedUrl}` keys. Exported for testability.
+ */
+export function detectPollingKeys(requests: RawRequest[], foldCase = false): Set<string> {
+  const times = new Map<string, number[]>();
+  for (const req of requests) {
+    const ts = Date.parse(req.timestamp);
+    if (Number.isNaN(ts)) continue;
+    const key = endpointGroupKey(req.method, req.url, foldCase);
+    const arr = times.get(key) ?? [];
+    arr.push(ts);
+    times.set(key, arr);
+  }
+  const polling = new Set<string>();
+  for (const [key, stamps] of times) {
+    if (stamps.length < POLLING_MIN_HITS) continue;
+    stamps.sort((a, b) => a - b);
+    const intervals = stamps.slice(1).map((t, i) => t - stamps[i]);
+    const mean = intervals.reduce((a, b) => a + b, 0) / intervals.length;
+    if (mean < POLLING_MIN_INTERVAL_MS) continue;
+    const variance = intervals.reduce((a, b) => a + (b - mean) ** 2, 0) / intervals.length;
+    if (Math.sqrt(variance) / mean <= POLLING_MAX_JITTER) polling.add(key);
+  }
+  return polling;
 }
 
 function normalizeUrl(rawUrl: string): string {
@@ -958,6 +1722,71 @@ function normalizeUrl(rawUrl: string): string {
   }
 }
 
+/**
+ * Grouping key for calls to the same endpoint: method plus normalizeUrl,
+ * with trailing slashes collapsed (`/api/users/` is `/api/users`; the root
+ * stays `/`) and, with `foldCase`, the path lowercased for case-insensitive
+ * servers. Only the key is folded — templates keep the representative
+ * call's own path.
+ */
+function endpointGroupKey(method: string, url: string, foldCase = false): string {
+  const normalized = normalizeUrl(url);
+  const queryAt = normalized.indexOf("?");
+  let base = queryAt === -1 ? normalized : normalized.slice(0, queryAt);
+  const rest = queryAt === -1 ? "" : normalized.slice(queryAt);
+  try {
+    const { origin, pathname } = new URL(base);
+    const path = pathname.length > 1 ? pathname.replace(/\/+$/, "") || "/" : pathname;
+    base = `${origin}${foldCase ? path.toLowerCase() : path}`;
+  } catch { /* not a URL — group as-is */ }
+  return `${method}:${base}${rest}`;
+}
+
+/** Epoch seconds/ms within a year of now — a per-call timestamp. */
+function isVolatileQueryValue(value: string): boolean {
+  if (/^\d{10}(\d{3})?$/.test(value)) {
+    const ms = value.length === 13 ? Number(value) : Number(value) * 1000;
+    return Math.abs(ms - Date.now()) < 365 * 24 * 3600 * 1000;
+  }
+  return false;
+}
+
+/**
+ * Aggregate the query strings of every call to one endpoint. A param is
+ * required when every call carried it; cache-buster names and per-call
+ * timestamp values are flagged volatile and left optional. `role` diffs the
+ * calls: a param carried everywhere with one value is constant, one whose
+ * value changes is a variable input.
+ */
+export function aggregateQueryParams(urls: string[]): QueryParamInfo[] {
+  const byName = new Map<string, { seen: number; values: string[]; volatileValues: number }>();
+  for (const url of urls) {
+    for (const [name, value] of Object.entries(sanitizeQueryParams(extractQueryParams(url)))) {
+      const entry = byName.get(name) ?? { seen: 0, values: [], volatileValues: 0 };
+      entry.seen++;
+      if (isVolatileQueryValue(value)) entry.volatileValues++;
+      if (!entry.values.includes(value)) entry.values.push(value);
+      byName.set(name, entry);
+    }
+  }
+  return [...byName.entries()].map(([name, entry]): QueryParamInfo => {
+    const volatile = VOLATILE_QUERY_PARAMS.test(name) || (entry.volatileValues === entry.seen && entry.values.length > 1);
+    const everyCall = entry.seen === urls.length;
+    const role: QueryParamInfo["role"] = volatile ? "volatile"
+      : !everyCall ? "optional"
+      : entry.values.length === 1 && urls.length > 1 ? "constant"
+      : "variable";
+    return {
+      name,
+      required: !volatile && everyCall,
+      examples: entry.values.slice(0, QUERY_PARAM_EXAMPLES),
+      seen: entry.seen,
+      ...(volatile ? { volatile: true } : {}),
+      role,
+    };
+  });
+}
+
 function extractQueryParams(rawUrl: string): Record<string, string> {
   try {
     const u = new URL(rawUrl);
@@ -970,7 +1799,7 @@ function extractQueryParams(rawUrl: string): Record<string, string> {
 }
 
 /** Returns true if a header name is sensitive and should be stripped from skill manifests. */
-function isSensitiveHeader(name: string): boolean {
+export function isSensitiveHeader(name: string): boolean {
   const lower = name.toLowerCase();
   if (lower === "cookie" || lower === "content-length" || lower === "host") return false; // handled separately
   if (STRIP_HEADERS.has(lower)) return true;
@@ -981,6 +1810,39 @@ function isSensitiveHeader(name: string): boolean {
   return false;
 }
 
+// API versioning: `X-API-Version`, `Api-Version`, `Stripe-Version`, `GitHub-Api-Version`, ...
+const VERSION_HEADER_NAME = /(^|[-_])(api[-_]?)?version$/i;
+
+/** True for headers that select an API version — `*-version` names, or Accept carrying `vnd.`/`version=`. */
+export function isVersionHeader(name: string, value: string): boolean {
+  const lower = name.toLowerCase();
+  if (lower === "accept") return /vnd\.|version\s*=/i.test(value);
+  if (lower === "x-restli-protocol-version") return true;
+  return VERSION_HEADER_NAME.test(lower) && !isSensitiveHeader(lower);
+}
+
+/**
+ * Collect versioning headers across a capture, most frequent value per name.
+ * These must be replayed verbatim to get the same response shape as captured.
+ */
+export function extractVersionHeaders(requests: RawRequest[]): Record<string, string> {
+  const counts = new Map<string, Map<string, number>>();
+  for (const req of requests) {
+    for (const [k, v] of Object.entries(req.request_headers ?? {})) {
+      if (typeof v !== "string" || !v.trim() || !isVersionHeader(k, v)) continue;
+      const lower = k.toLowerCase();
+      const byValue = counts.get(lower) ?? new Map<string, number>();
+      byValue.set(v.trim(), (byValue.get(v.trim()) ?? 0) + 1);
+      counts.set(lower, byValue);
+    }
+  }
+  const versionHeaders: Record<string, string> = {};
+  for (const [name, byValue] of counts) {
+    versionHeaders[name] = [...byValue.entries()].sort((a, b) => b[1] - a[1])[0][0];
+  }
+  return versionHeaders;
+}
+
 function isReplayCriticalHeader(name: string, value: string): boolean {
   const lower = name.toLowerCase();
   if (REPLAY_HEADER_EXACT.has(lower)) {
@@ -995,28 +1857,137 @@ function sanitizeHeaders(headers: Record<string, string>): Record<string, string
     Object.entries(headers ?? {}).filter(([k]) => {
       const lower = k.toLowerCase();
       if (lower === "cookie" || lower === "content-length" || lower === "host") return false;
+      // Per-install values — replayed from the vault, never published.
+      if (isDeviceIdentifierHeader(lower)) return false;
       return !isSensitiveHeader(k);
     })
   );
 }
 
+/** Requests in capture order by timestamp; unparseable timestamps keep their position. */
+function chronological(requests: RawRequest[]): RawRequest[] {
+  return requests
+    .map((req, index) => ({ req, index, at: Date.parse(req.timestamp) }))
+    .sort((a, b) => (Number.isNaN(a.at) || Number.isNaN(b.at) ? 0 : a.at - b.at) || a.index - b.index)
+    .map(({ req }) => req);
+}
+
+/** Auth-bearing request headers, each with the distinct values it took in capture order. */
+function authHeaderHistory(requests: RawRequest[], scrub?: AuthHeaderScrubOptions): Map<string, Array<{ value: string; seen_at: string }>> {
+  const history = new Map<string, Array<{ value: string; seen_at: string }>>();
+  for (const req of chronological(requests)) {
+    for (const [k, v] of Object.entries(req.request_headers)) {
+      const lower = k.toLowerCase();
+      if (lower === "cookie" || lower === "content-length" || lower === "host") continue;
+      if (isDeviceIdentifierHeader(lower)) continue; // see extractDeviceIdentifiers
+      if (!isSensitiveHeader(k) && !isReplayCriticalHeader(k, v)) continue;
+      // Per-request tracing IDs would otherwise read as a rotation.
+      if (!(lower in scrubAuthHeaders({ [lower]: v }, scrub))) continue;
+      const values = history.get(lower) ?? [];
+      if (values[values.length - 1]?.value !== v) values.push({ value: v, seen_at: req.timestamp });
+      history.set(lower, values);
+    }
+  }
+  return history;
+}
+
 /**
  * Extract auth-sensitive headers from captured requests — the inverse of sanitizeHeaders.
  * These are stored in the vault (not the skill manifest) so server-fetch can reconstruct
  * the full header set without launching a browser. This is what makes the 2nd call fast.
+ * When a key or token was rotated mid-capture the temporally last value wins, so
+ * replay never uses the pre-rotation one.
  */
-export function extractAuthHeaders(requests: RawRequest[]): Record<string, string> {
+export function extractAuthHeaders(requests: RawRequest[], scrub?: AuthHeaderScrubOptions): Record<string, string> {
   const authHeaders: Record<string, string> = {};
-  for (const req of requests) {
+  for (const [name, values] of authHeaderHistory(requests, scrub)) {
+    authHeaders[name] = values[values.length - 1].value;
+  }
+  return authHeaders;
+}
+
+/**
+ * Device / installation identifier headers (`x-device-id`, `x-install-id`,
+ * ...) from the capture, latest value per name. They travel with the auth
+ * bundle as context for the rotating token but are not credentials, so
+ * extractAuthHeaders leaves them out.
+ */
+export function extractDeviceIdentifiers(requests: RawRequest[], options: DeviceIdentifierOptions = {}): Record<string, string> {
+  const ids: Record<string, string> = {};
+  for (const req of chronological(requests)) {
     for (const [k, v] of Object.entries(req.request_headers)) {
-      const lower = k.toLowerCase();
-      if (lower === "cookie" || lower === "content-length" || lower === "host") continue;
-      if ((isSensitiveHeader(k) || isReplayCriticalHeader(k, v)) && !authHeaders[lower]) {
-        authHeaders[lower] = v;
-      }
+      if (v?.trim() && isDeviceIdentifierHeader(k, options)) ids[k.toLowerCase()] = v;
     }
   }
-  return authHeaders;
+  return ids;
+}
+
+/** How strongly a capture points at one registrable domain as its target service. */
+export interface DomainScore {
+  domain: string;
+  requests: number;
+  /** Distinct API-like, non-polling method+URL pairs */
+  api_endpoints: number;
+  /** Requests that sent an auth header (Authorization, API keys, ...) */
+  auth_requests: number;
+  score: number;
+}
+
+/** Bonus for carrying auth headers: the service the user is logged into is the one they meant. */
+const AUTH_DOMAIN_BONUS = 10;
+
+/**
+ * Rank the domains in a capture that has no seed URL. Raw request counts
+ * are dominated by polling and telemetry, so a domain scores by its distinct
+ * API-like endpoints plus a flat bonus when it carries auth headers; the
+ * request count only breaks ties. Skipped infrastructure hosts never score.
+ */
+export function scoreCaptureDomains(requests: RawRequest[]): DomainScore[] {
+  const pollingKeys = detectPollingKeys(requests);
+  const byDomain = new Map<string, { requests: number; endpoints: Set<string>; auth: number }>();
+  for (const req of requests) {
+    let domain: string;
+    try {
+      const host = new URL(req.url).hostname;
+      if (isSkippedHost(host)) continue;
+      domain = getRegistrableDomain(host);
+    } catch { continue; }
+    const entry = byDomain.get(domain) ?? { requests: 0, endpoints: new Set<string>(), auth: 0 };
+    entry.requests++;
+    const key = endpointGroupKey(req.method, req.url);
+    if (!apiLikeRejection(req) && !pollingKeys.has(key)) entry.endpoints.add(key);
+    const hasAuth = Object.entries(req.request_headers ?? {})
+      .some(([k, v]) => !!v?.trim() && isSensitiveHeader(k) && !isDeviceIdentifierHeader(k));
+    if (hasAuth) entry.auth++;
+    byDomain.set(domain, entry);
+  }
+  return [...byDomain.entries()]
+    .map(([domain, e]) => ({
+      domain,
+      requests: e.requests,
+      api_endpoints: e.endpoints.size,
+      auth_requests: e.auth,
+      score: e.endpoints.size + (e.auth > 0 ? AUTH_DOMAIN_BONUS : 0) + Math.min(e.requests, 100) / 1000,
+    }))
+    .sort((a, b) => b.score - a.score);
+}
+
+/** The most likely target domain of a seedless capture, or null when nothing scored. */
+export function inferTargetDomain(requests: RawRequest[]): string | null {
+  const [best] = scoreCaptureDomains(requests);
+  return best && best.score >= 1 ? best.domain : null;
+}
+
+/**
+ * Auth headers that carried more than one distinct value during the capture,
+ * with each value and when it was first sent (oldest first).
+ */
+export function detectAuthHeaderRotations(requests: RawRequest[], scrub?: AuthHeaderScrubOptions): Record<string, Array<{ value: string; seen_at: string }>> {
+  const rotations: Record<string, Array<{ value: string; seen_at: string }>> = {};
+  for (const [name, values] of authHeaderHistory(requests, scrub)) {
+    if (new Set(values.map((v) => v.value)).size > 1) rotations[name] = values;
+  }
+  return rotations;
 }
 
 function sanitizeQueryParams(params: Record<string, string>): Record<string, string> {
@@ -1192,6 +2163,45 @@ function stripJsonPrefix(body: string): string {
   return body.replace(/^\)?\]?\}?'?\s*\n/, "");
 }
 
+/**
+ * Parse a request body according to its content-type. Handles JSON,
+ * x-www-form-urlencoded and multipart/form-data (file parts are reduced to
+ * their filename). Falls back to sniffing when the header is missing.
+ * Exported for testability.
+ */
+export function parseRequestBody(body: string, contentType?: string): Record<string, unknown> | undefined {
+  const ct = (contentType ?? "").toLowerCase();
+  if (ct.includes("multipart/form-data")) {
+    const boundary = contentType!.match(/boundary=(?:"([^"]+)"|([^;\s]+))/i);
+    const fields = boundary ? parseMultipartBody(body, boundary[1] ?? boundary[2]) : undefined;
+    if (fields && Object.keys(fields).length > 0) return fields;
+  } else if (ct.includes("application/x-www-form-urlencoded")) {
+    const result: Record<string, unknown> = {};
+    new URLSearchParams(body).forEach((v, k) => { result[k] = v; });
+    if (Object.keys(result).length > 0) return result;
+  } else if (ct.includes("json")) {
+    try { return JSON.parse(stripJsonPrefix(body)) as Record<string, unknown>; } catch {}
+  }
+  return tryParseBody(body);
+}
+
+/** Split a multipart/form-data body into field name → value. */
+function parseMultipartBody(body: string, boundary: string): Record<string, string> {
+  const fields: Record<string, string> = {};
+  for (const part of body.split(`--${boundary}`)) {
+    if (!part.trim() || part.trim() === "--") continue;
+    const sep = part.match(/\r?\n\r?\n/);
+    if (!sep || sep.index == null) continue;
+    const head = part.slice(0, sep.index);
+    const name = head.match(/content-disposition:[^\n]*\bname="([^"]*)"/i)?.[1];
+    if (!name) continue;
+    const filename = head.match(/\bfilename="([^"]*)"/i)?.[1];
+    const value = part.slice(sep.index + sep[0].length).replace(/\r?\n$/, "");
+    if (!(name in fields)) fields[name] = filename != null ? filename : value;
+  }
+  return fields;
+}
+
 function tryParseBody(body: string): Record<string, unknown> | undefined {
   // Try JSON first
   try {
@@ -1345,6 +2355,50 @@ export function minePathTemplates(
   return result;
 }
 
+/**
+ * Canonical form of a response schema for shape comparison: key order,
+ * sample counts and descriptions are ignored. Null for schemas too thin to
+ * tell endpoints apart (scalars, empty objects).
+ */
+function schemaShapeKey(schema: ResponseSchema | undefined): string | null {
+  if (!schema) return null;
+  const canonical = (s: ResponseSchema): unknown => ({
+    type: s.type,
+    ...(s.properties ? { properties: Object.keys(s.properties).sort().map((k) => [k, canonical(s.properties![k])]) } : {}),
+    ...(s.items ? { items: canonical(s.items) } : {}),
+    ...(s.anyOf ? { anyOf: s.anyOf.map(canonical) } : {}),
+    ...(s.stream ? { stream: s.stream } : {}),
+  });
+  const fields = schema.properties ?? schema.items?.properties;
+  if (!fields || Object.keys(fields).length === 0) return null;
+  return JSON.stringify(canonical(schema));
+}
+
+/**
+ * Fold endpoints that share a method and response shape into the one the
+ * page called most (capture order breaks ties); the others' URL templates
+ * are kept as `shape_aliases`.
+ */
+export function dedupeEndpointsByShape(endpoints: EndpointDescriptor[]): EndpointDescriptor[] {
+  const groups = new Map<string, EndpointDescriptor[]>();
+  for (const ep of endpoints) {
+    const shape = ep.method === "WS" ? null : schemaShapeKey(ep.response_schema);
+    if (!shape) continue;
+    const key = `${ep.method} ${shape}`;
+    groups.set(key, [...(groups.get(key) ?? []), ep]);
+  }
+  const dropped = new Set<EndpointDescriptor>();
+  for (const group of groups.values()) {
+    if (group.length < 2) continue;
+    const keep = group.reduce((best, ep) => (ep.capture_stats?.calls ?? 0) > (best.capture_stats?.calls ?? 0) ? ep : best);
+    const aliases = group.filter((ep) => ep !== keep);
+    for (const ep of aliases) dropped.add(ep);
+    keep.shape_aliases = [...new Set([...(keep.shape_aliases ?? []), ...aliases.flatMap((ep) => [ep.url_template, ...(ep.shape_aliases ?? [])])])];
+    logDebug("reverse-engineer", `shape dedupe: kept ${keep.method} ${keep.url_template}, folded ${aliases.map((ep) => ep.url_template).join(", ")}`);
+  }
+  return endpoints.filter((ep) => !dropped.has(ep));
+}
+
 function collapseEndpoints(endpoints: EndpointDescriptor[]): EndpointDescriptor[] {
   // Group by method + origin + all-but-last path segment
   const groups = new Map<string, EndpointDescriptor[]>();
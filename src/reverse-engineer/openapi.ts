@@ -0,0 +1,236 @@
+/**
+ * OpenAPI 3 import — seed a skill from a service's published spec.
+ *
+ * Spec endpoints carry the authoritative structure (methods, path params,
+ * content types, declared security) but no real traffic, so they are marked
+ * `from_spec: true` and left unverified. Captures merged on top via
+ * mergeEndpoints supply the examples and flip `from_spec` to false.
+ */
+
+import type { EndpointDescriptor, QueryParamInfo, ResponseSchema } from "../types/index.js";
+import { inferEndpointSemantic } from "../graph/index.js";
+import { unbrowseError } from "../errors.js";
+import { deterministicEndpointId } from "./index.js";
+
+type OpenApiSchema = {
+  $ref?: string;
+  type?: string | string[];
+  description?: string;
+  properties?: Record<string, OpenApiSchema>;
+  items?: OpenApiSchema;
+  required?: string[];
+  anyOf?: OpenApiSchema[];
+  oneOf?: OpenApiSchema[];
+  allOf?: OpenApiSchema[];
+  example?: unknown;
+  default?: unknown;
+  enum?: unknown[];
+};
+type OpenApiParameter = { $ref?: string; name: string; in: string; required?: boolean; example?: unknown; schema?: OpenApiSchema };
+type OpenApiMedia = { schema?: OpenApiSchema; example?: unknown };
+type OpenApiOperation = {
+  operationId?: string;
+  summary?: string;
+  description?: string;
+  parameters?: OpenApiParameter[];
+  requestBody?: { $ref?: string; content?: Record<string, OpenApiMedia> };
+  responses?: Record<string, { $ref?: string; content?: Record<string, OpenApiMedia> }>;
+  security?: Array<Record<string, string[]>>;
+  deprecated?: boolean;
+};
+type SecurityScheme = { type?: string; scheme?: string; in?: string; name?: string };
+type OpenApiDocument = {
+  openapi?: string;
+  servers?: Array<{ url?: string }>;
+  paths?: Record<string, Record<string, unknown>>;
+  components?: {
+    schemas?: Record<string, OpenApiSchema>;
+    parameters?: Record<string, OpenApiParameter>;
+    requestBodies?: Record<string, OpenApiOperation["requestBody"]>;
+    responses?: Record<string, { content?: Record<string, OpenApiMedia> }>;
+    securitySchemes?: Record<string, SecurityScheme>;
+  };
+  security?: Array<Record<string, string[]>>;
+};
+
+export interface OpenApiImport {
+  endpoints: EndpointDescriptor[];
+  /** Declared security scheme, in guessAuthMethod's vocabulary */
+  auth_method?: string;
+  /** Base URL the templates were built against */
+  base_url: string;
+}
+
+const HTTP_METHODS = ["get", "post", "put", "patch", "delete", "head", "options"] as const;
+const MAX_SCHEMA_DEPTH = 8;
+
+function resolveRef<T>(doc: OpenApiDocument, value: T & { $ref?: string }, seen = new Set<string>()): T {
+  if (!value?.$ref) return value;
+  if (seen.has(value.$ref) || !value.$ref.startsWith("#/")) return {} as T;
+  seen.add(value.$ref);
+  let target: unknown = doc;
+  for (const part of value.$ref.slice(2).split("/")) {
+    target = (target as Record<string, unknown> | undefined)?.[part.replace(/~1/g, "/").replace(/~0/g, "~")];
+  }
+  return target ? resolveRef(doc, target as T & { $ref?: string }, seen) : {} as T;
+}
+
+function toResponseSchema(doc: OpenApiDocument, raw: OpenApiSchema | undefined, depth = 0): ResponseSchema {
+  const schema = raw ? resolveRef(doc, raw) : {};
+  if (depth >= MAX_SCHEMA_DEPTH) return { type: "object", inferred_from_samples: 0 };
+  if (schema.allOf?.length) {
+    // Flatten composition into one object — enough for projection and drift.
+    const parts = schema.allOf.map((s) => toResponseSchema(doc, s, depth + 1));
+    return {
+      type: "object",
+      properties: Object.assign({}, ...parts.map((p) => p.properties ?? {})),
+      inferred_from_samples: 0,
+    };
+  }
+  const variants = schema.anyOf ?? schema.oneOf;
+  if (variants?.length) return { type: "anyOf", anyOf: variants.map((s) => toResponseSchema(doc, s, depth + 1)), inferred_from_samples: 0 };
+  const type = Array.isArray(schema.type) ? schema.type.find((t) => t !== "null") ?? "null" : schema.type ?? (schema.properties ? "object" : "unknown");
+  return {
+    type,
+    ...(schema.description ? { description: schema.description } : {}),
+    ...(schema.properties ? { properties: Object.fromEntries(Object.entries(schema.properties).map(([k, v]) => [k, toResponseSchema(doc, v, depth + 1)])) } : {}),
+    ...(schema.items ? { items: toResponseSchema(doc, schema.items, depth + 1) } : {}),
+    ...(schema.required?.length ? { required: schema.required } : {}),
+    inferred_from_samples: 0,
+  };
+}
+
+/** Example value for a parameter or schema: declared example, default, first enum. */
+function exampleOf(doc: OpenApiDocument, param: { example?: unknown; schema?: OpenApiSchema }): unknown {
+  if (param.example !== undefined) return param.example;
+  const schema = param.schema ? resolveRef(doc, param.schema) : undefined;
+  return schema?.example ?? schema?.default ?? schema?.enum?.[0];
+}
+
+/** Build a JSON body skeleton from a schema's examples, for `body` / body templating. */
+function bodySkeleton(doc: OpenApiDocument, raw: OpenApiSchema | undefined, depth = 0): unknown {
+  const schema = raw ? resolveRef(doc, raw) : undefined;
+  if (!schema || depth >= MAX_SCHEMA_DEPTH) return undefined;
+  if (schema.example !== undefined) return schema.example;
+  if (schema.properties) {
+    return Object.fromEntries(Object.entries(schema.properties)
+      .map(([k, v]) => [k, bodySkeleton(doc, v, depth + 1)] as const)
+      .filter(([, v]) => v !== undefined));
+  }
+  return schema.default ?? schema.enum?.[0];
+}
+
+function authMethodForSecurity(doc: OpenApiDocument, security: Array<Record<string, string[]>> | undefined): string | undefined {
+  for (const requirement of security ?? []) {
+    for (const name of Object.keys(requirement)) {
+      const scheme = doc.components?.securitySchemes?.[name];
+      if (!scheme) continue;
+      const httpScheme = scheme.scheme?.toLowerCase();
+      if (scheme.type === "http" && httpScheme === "bearer") return "Bearer Token";
+      if (scheme.type === "http" && httpScheme === "basic") return "Basic Auth";
+      if (scheme.type === "http" && httpScheme === "digest") return "Digest Auth";
+      if (scheme.type === "apiKey") return scheme.in === "cookie" ? "Cookie" : `API Key (${scheme.name ?? "key"})`;
+      if (scheme.type === "oauth2" || scheme.type === "openIdConnect") return "Bearer Token";
+    }
+  }
+  return undefined;
+}
+
+function resolveBaseUrl(doc: OpenApiDocument, baseUrl: string | undefined): string {
+  const server = doc.servers?.[0]?.url;
+  const raw = baseUrl || server;
+  if (!raw) throw unbrowseError("invalid_input", "OpenAPI document has no servers[] URL; pass base_url");
+  try {
+    // Relative server URLs ("/v2") resolve against base_url when both are given.
+    const resolved = baseUrl && server && !/^https?:/i.test(server) ? new URL(server, baseUrl).href : new URL(raw).href;
+    return resolved.replace(/\/+$/, "");
+  } catch {
+    throw unbrowseError("invalid_input", `Invalid OpenAPI base URL: ${raw}`);
+  }
+}
+
+/**
+ * Parse an OpenAPI 3 document (object or JSON string) into endpoint
+ * descriptors. `baseUrl` overrides `servers[0].url`. Deprecated operations
+ * are skipped.
+ */
+export function endpointsFromOpenApi(spec: unknown, baseUrl?: string): OpenApiImport {
+  let doc: OpenApiDocument;
+  try {
+    doc = (typeof spec === "string" ? JSON.parse(spec) : spec) as OpenApiDocument;
+  } catch (err) {
+    throw unbrowseError("invalid_input", `OpenAPI spec is not valid JSON: ${(err as Error).message}`);
+  }
+  if (!doc || typeof doc !== "object" || !String(doc.openapi ?? "").startsWith("3")) {
+    throw unbrowseError("invalid_input", "Only OpenAPI 3.x documents are supported");
+  }
+  const base = resolveBaseUrl(doc, baseUrl);
+  const endpoints: EndpointDescriptor[] = [];
+  let authMethod = authMethodForSecurity(doc, doc.security);
+
+  for (const [path, item] of Object.entries(doc.paths ?? {})) {
+    const shared = (item.parameters as OpenApiParameter[] | undefined) ?? [];
+    for (const method of HTTP_METHODS) {
+      const op = item[method] as OpenApiOperation | undefined;
+      if (!op || op.deprecated) continue;
+
+      // Operation parameters override path-level ones with the same name+location.
+      const params = new Map<string, OpenApiParameter>();
+      for (const p of [...shared, ...(op.parameters ?? [])].map((p) => resolveRef(doc, p))) {
+        if (p?.name) params.set(`${p.in}:${p.name}`, p);
+      }
+      const pathParams: Record<string, string> = {};
+      const query: Record<string, unknown> = {};
+      const queryParams: QueryParamInfo[] = [];
+      const headers: Record<string, string> = {};
+      for (const p of params.values()) {
+        const example = exampleOf(doc, p);
+        if (p.in === "path" && example !== undefined) pathParams[p.name] = String(example);
+        if (p.in === "query") {
+          // Optional params without an example/default/enum stay out of the
+          // defaults, or replay would send `?name=` on every call.
+          if (p.required || example !== undefined) query[p.name] = example ?? "";
+          queryParams.push({ name: p.name, required: !!p.required, examples: example !== undefined ? [String(example)] : [], seen: 0 });
+        }
+      }
+
+      const requestBody = op.requestBody ? resolveRef(doc, op.requestBody) : undefined;
+      const [requestType, requestMedia] = Object.entries(requestBody?.content ?? {})[0] ?? [];
+      if (requestType) headers["content-type"] = requestType;
+      const body = requestMedia ? requestMedia.example ?? bodySkeleton(doc, requestMedia.schema) : undefined;
+
+      const success = Object.entries(op.responses ?? {}).find(([code]) => /^2/.test(code) || code === "default")?.[1];
+      const responses = success ? resolveRef(doc, success).content ?? {} : {};
+      const responseTypes = Object.keys(responses);
+      const jsonType = responseTypes.find((t) => /json/i.test(t));
+      if (responseTypes.length > 0) headers.accept = jsonType ?? responseTypes[0];
+      const responseSchema = jsonType && responses[jsonType].schema ? toResponseSchema(doc, responses[jsonType].schema) : undefined;
+
+      const upper = method.toUpperCase() as EndpointDescriptor["method"];
+      const urlTemplate = `${base}${path.startsWith("/") ? path : `/${path}`}`;
+      const isGet = upper === "GET" || upper === "HEAD";
+      const endpoint: EndpointDescriptor = {
+        endpoint_id: deterministicEndpointId(upper, urlTemplate),
+        method: upper,
+        url_template: urlTemplate,
+        ...(op.summary || op.description ? { description: (op.summary ?? op.description)!.trim() } : {}),
+        headers_template: headers,
+        ...(Object.keys(query).length > 0 ? { query } : {}),
+        ...(queryParams.length > 0 ? { query_params: queryParams } : {}),
+        ...(Object.keys(pathParams).length > 0 ? { path_params: pathParams } : {}),
+        ...(body && typeof body === "object" && !Array.isArray(body) ? { body: body as Record<string, unknown> } : {}),
+        idempotency: isGet ? "safe" : "unsafe",
+        verification_status: "unverified",
+        reliability_score: 0.5,
+        from_spec: true,
+        ...(responseSchema ? { response_schema: responseSchema } : {}),
+      };
+      endpoint.semantic = inferEndpointSemantic(endpoint, { sampleRequestUrl: urlTemplate });
+      if (!endpoint.description) endpoint.description = endpoint.semantic?.description_out;
+      endpoints.push(endpoint);
+      authMethod ??= authMethodForSecurity(doc, op.security);
+    }
+  }
+
+  return { endpoints, base_url: base, ...(authMethod ? { auth_method: authMethod } : {}) };
+}
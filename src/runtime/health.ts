@@ -0,0 +1,85 @@
+/**
+ * Aggregate dependency health for `unbrowse health`.
+ *
+ * Each probe is independent and never throws — a failing component is
+ * reported as `error` (or `warn` when unbrowse still works without it), so
+ * one dead dependency doesn't hide the state of the others.
+ */
+
+import { existsSync } from "node:fs";
+import * as kuri from "../kuri/client.js";
+import { checkVaultAvailable } from "../vault/index.js";
+import { resolveChromiumCookiesPath } from "../auth/browser-cookies.js";
+import { getBackendUrl, isLocalOnlyMode } from "../client/index.js";
+import { checkWalletConfigured } from "../payments/wallet.js";
+
+export type HealthStatus = "ok" | "warn" | "error";
+
+export interface ComponentHealth {
+  status: HealthStatus;
+  message: string;
+}
+
+export interface HealthReport {
+  /** False when any component is in `error` */
+  ok: boolean;
+  components: {
+    browser: ComponentHealth;
+    vault: ComponentHealth;
+    chrome_cookies: ComponentHealth;
+    marketplace: ComponentHealth;
+    wallet: ComponentHealth;
+  };
+}
+
+const MARKETPLACE_TIMEOUT_MS = 3_000;
+
+async function probeBrowser(): Promise<ComponentHealth> {
+  const result = await kuri.health();
+  if (!result.ok) return { status: "error", message: `browser control not reachable on port ${kuri.getPort()}` };
+  return { status: "ok", message: result.tabs != null ? `browser control up (${result.tabs} tabs)` : "browser control up" };
+}
+
+async function probeVault(): Promise<ComponentHealth> {
+  const vault = await checkVaultAvailable();
+  return { status: vault.ok ? "ok" : "error", message: vault.message };
+}
+
+function probeChromeCookies(): ComponentHealth {
+  const cookiePath = resolveChromiumCookiesPath();
+  if (cookiePath && existsSync(cookiePath)) return { status: "ok", message: cookiePath };
+  // Only needed for browser-cookie auth extraction; everything else works without it.
+  return { status: "warn", message: "Chrome cookie database not found" };
+}
+
+async function probeMarketplace(): Promise<ComponentHealth> {
+  if (isLocalOnlyMode()) return { status: "warn", message: "local-only mode; marketplace disabled" };
+  const url = getBackendUrl();
+  try {
+    // Any HTTP response means the backend is reachable; only network failures count.
+    const res = await fetch(url, { method: "HEAD", signal: AbortSignal.timeout(MARKETPLACE_TIMEOUT_MS) });
+    return { status: "ok", message: `${url} (HTTP ${res.status})` };
+  } catch (err) {
+    const timedOut = (err as Error).name === "TimeoutError" || (err as Error).name === "AbortError";
+    return { status: "error", message: timedOut ? `${url} timed out after ${MARKETPLACE_TIMEOUT_MS}ms` : `${url} unreachable: ${(err as Error).message}` };
+  }
+}
+
+function probeWallet(): ComponentHealth {
+  const wallet = checkWalletConfigured();
+  if (!wallet.configured) return { status: "warn", message: "no wallet configured; paid skills unavailable" };
+  return { status: "ok", message: `${wallet.provider}${wallet.profile ? ` (profile ${wallet.profile})` : ""}` };
+}
+
+/** Probe every external dependency concurrently. */
+export async function healthCheck(): Promise<HealthReport> {
+  const [browser, vault, marketplace] = await Promise.all([probeBrowser(), probeVault(), probeMarketplace()]);
+  const components = {
+    browser,
+    vault,
+    chrome_cookies: probeChromeCookies(),
+    marketplace,
+    wallet: probeWallet(),
+  };
+  return { ok: Object.values(components).every((c) => c.status !== "error"), components };
+}
@@ -0,0 +1,69 @@
+/**
+ * Outbound request identity.
+ *
+ * Marketplace and robots.txt requests identify as `unbrowse/<version>`
+ * unless UNBROWSE_USER_AGENT overrides it. UNBROWSE_DEFAULT_HEADERS (a JSON
+ * object, e.g. `{"Accept-Language":"de-DE"}`) adds headers to every replayed
+ * endpoint call so responses match what the capturing browser saw.
+ */
+
+import { readFileSync } from "node:fs";
+import path from "node:path";
+import { getPackageRoot } from "./paths.js";
+import { log } from "../logger.js";
+
+function readPackageVersion(): string {
+  try {
+    const pkg = JSON.parse(readFileSync(path.join(getPackageRoot(import.meta.url), "package.json"), "utf-8")) as { version?: string };
+    return pkg.version ?? "unknown";
+  } catch {
+    return "unknown";
+  }
+}
+
+const PACKAGE_VERSION = readPackageVersion();
+
+/** Explicit UNBROWSE_USER_AGENT override, if any. */
+export function getUserAgentOverride(): string | undefined {
+  return process.env.UNBROWSE_USER_AGENT?.trim() || undefined;
+}
+
+/** `unbrowse/<version>` or the UNBROWSE_USER_AGENT override. */
+export function getUnbrowseUserAgent(): string {
+  return getUserAgentOverride() ?? `unbrowse/${PACKAGE_VERSION}`;
+}
+
+/**
+ * `headers` with the UNBROWSE_USER_AGENT override applied on top, replacing
+ * a user-agent in any case — captured templates keep the browser's UA, and
+ * the override must win over it. Unchanged when no override is set.
+ */
+export function applyUserAgentOverride(headers: Record<string, string>): Record<string, string> {
+  const override = getUserAgentOverride();
+  if (!override) return headers;
+  const rest = Object.fromEntries(Object.entries(headers).filter(([k]) => k.toLowerCase() !== "user-agent"));
+  return { ...rest, "user-agent": override };
+}
+
+let defaultHeadersWarned = false;
+
+/** Lowercased headers from UNBROWSE_DEFAULT_HEADERS; empty when unset or malformed. */
+export function getDefaultRequestHeaders(): Record<string, string> {
+  const raw = process.env.UNBROWSE_DEFAULT_HEADERS;
+  if (!raw) return {};
+  try {
+    const parsed = JSON.parse(raw) as unknown;
+    if (!parsed || typeof parsed !== "object" || Array.isArray(parsed)) throw new Error("expected a JSON object");
+    return Object.fromEntries(
+      Object.entries(parsed as Record<string, unknown>)
+        .filter(([, v]) => typeof v === "string")
+        .map(([k, v]) => [k.toLowerCase(), v as string]),
+    );
+  } catch (err) {
+    if (!defaultHeadersWarned) {
+      log("http", `ignoring UNBROWSE_DEFAULT_HEADERS: ${(err as Error).message}`);
+      defaultHeadersWarned = true;
+    }
+    return {};
+  }
+}
@@ -9,6 +9,7 @@ import { schedulePeriodicVerification } from "./verification/index.js";
 import { ensureRegistered } from "./client/index.js";
 import { shutdownAllBrowsers } from "./capture/index.js";
 import * as kuri from "./kuri/client.js";
+import { installProxyFetch } from "./runtime/proxy.js";
 
 type StartServerOptions = {
   host?: string;
@@ -16,6 +17,8 @@ type StartServerOptions = {
   logger?: boolean;
   pidFile?: string;
   scheduleVerification?: boolean;
+  /** Outbound proxy URL (credentials allowed); overrides UNBROWSE_PROXY / HTTPS_PROXY */
+  proxy?: string;
 };
 
 export type RunningUnbrowseServer = {
@@ -54,6 +57,7 @@ export async function startUnbrowseServer(options: StartServerOptions = {}): Pro
   const pidFile = options.pidFile ?? process.env.UNBROWSE_PID_FILE;
 
   updatePidFile(pidFile, host, port);
+  installProxyFetch(options.proxy);
 
   try {
     execSync("pkill -f chrome-headless-shell", { stdio: "ignore" });
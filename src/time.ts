@@ -0,0 +1,43 @@
+/**
+ * Shared timestamp conversions for captured data.
+ *
+ * Captures mix three clocks: Chrome's cookie store (microseconds since
+ * 1601-01-01, with 0 meaning "session cookie"), JS epoch milliseconds, and
+ * HAR `startedDateTime` strings of varying precision and offset. Everything
+ * is normalized to ISO 8601 UTC here so the 1601 offset lives in one place.
+ */
+
+/** Microseconds between 1601-01-01 (Windows/Chrome epoch) and 1970-01-01. */
+export const CHROME_EPOCH_OFFSET_MICROS = 11_644_473_600_000_000;
+
+/**
+ * Chrome `expires_utc` to Unix seconds, as cookie APIs expect. 0 (and
+ * anything at or before the Unix epoch) is a session cookie: -1.
+ */
+export function chromeTimestampToEpochSeconds(micros: number | string): number {
+  const value = Number(micros);
+  if (!Number.isFinite(value) || value <= CHROME_EPOCH_OFFSET_MICROS) return -1;
+  return Math.floor((value - CHROME_EPOCH_OFFSET_MICROS) / 1_000_000);
+}
+
+/** Chrome timestamp as ISO 8601, or null for a session cookie (0). */
+export function chromeTimestampToIso(micros: number | string): string | null {
+  const seconds = chromeTimestampToEpochSeconds(micros);
+  return seconds < 0 ? null : epochMsToIso(seconds * 1000);
+}
+
+/** Epoch milliseconds as ISO 8601; an invalid value falls back to now. */
+export function epochMsToIso(ms: number): string {
+  const date = new Date(ms);
+  return Number.isNaN(date.getTime()) ? new Date().toISOString() : date.toISOString();
+}
+
+/**
+ * HAR `startedDateTime` normalized to ISO 8601 UTC (HAR allows any offset
+ * and precision). Missing or unparseable values become `fallback`, default now.
+ */
+export function parseHarDateTime(value: string | undefined | null, fallback?: string): string {
+  const ms = value ? Date.parse(value) : Number.NaN;
+  if (!Number.isNaN(ms)) return new Date(ms).toISOString();
+  return fallback ?? new Date().toISOString();
+}
@@ -30,7 +30,11 @@ function collectPaths(schema: ResponseSchema, prefix = ""): Map<string, string>
  * Returns added/removed fields and type changes.
  */
 export function detectSchemaDrift(existing: ResponseSchema, newSample: unknown): DriftResult {
-  const newSchema = inferSchema([newSample]);
+  return diffSchemas(existing, inferSchema([newSample]));
+}
+
+/** Field-level difference between two inferred schemas. */
+export function diffSchemas(existing: ResponseSchema, newSchema: ResponseSchema): DriftResult {
   const oldPaths = collectPaths(existing);
   const newPaths = collectPaths(newSchema);
 
@@ -0,0 +1,89 @@
+/**
+ * gRPC-web response framing. Without the .proto the message payloads stay
+ * opaque, but the framing is standard: each frame is a flag byte (bit 0 =
+ * compressed, bit 7 = trailer) plus a 4-byte big-endian length. Counting
+ * data frames and reading the trailer frame's `grpc-status` tells whether
+ * a captured call actually succeeded — gRPC reports errors with HTTP 200.
+ */
+
+import type { GrpcWebSummary } from "../types/index.js";
+
+const TRAILER_FLAG = 0x80;
+const HEADER_BYTES = 5;
+
+/**
+ * Bytes of a captured body. `grpc-web-text` is base64 on the wire; binary
+ * `grpc-web` arrives either base64-encoded (HAR content encoding) or as a
+ * byte-per-char string.
+ */
+function bodyBytes(body: string, contentType: string): Buffer {
+  const compact = body.replace(/\s+/g, "");
+  const looksBase64 = compact.length > 0 && compact.length % 4 === 0 && /^[A-Za-z0-9+/]+={0,2}$/.test(compact);
+  if (/grpc-web-text/i.test(contentType) || looksBase64) {
+    // grpc-web-text may concatenate independently padded base64 chunks
+    return Buffer.concat((compact.match(/[^=]+(?:=+|$)/g) ?? []).map((chunk) => Buffer.from(chunk, "base64")));
+  }
+  return Buffer.from(body, "latin1");
+}
+
+function parseTrailers(text: string): Record<string, string> {
+  const trailers: Record<string, string> = {};
+  for (const line of text.split(/\r?\n/)) {
+    const idx = line.indexOf(":");
+    if (idx > 0) trailers[line.slice(0, idx).trim().toLowerCase()] = line.slice(idx + 1).trim();
+  }
+  return trailers;
+}
+
+/** grpc-message is percent-encoded; keep the raw text if it isn't valid encoding. */
+function safeDecode(value: string): string {
+  try { return decodeURIComponent(value); } catch { return value; }
+}
+
+/**
+ * Parse gRPC-web framing. Falls back to `grpc-status` / `grpc-message`
+ * response headers (trailers-only responses send them there). Null when the
+ * body isn't valid framing and no status header is present.
+ */
+export function parseGrpcWebFrames(body: string | undefined, contentType = "", responseHeaders: Record<string, string> = {}): GrpcWebSummary | null {
+  let frames = 0;
+  let compressed = false;
+  let trailers: Record<string, string> = {};
+  let valid = false;
+  if (body) {
+    const bytes = bodyBytes(body, contentType);
+    let offset = 0;
+    valid = bytes.length >= HEADER_BYTES;
+    while (valid && offset + HEADER_BYTES <= bytes.length) {
+      const flag = bytes[offset];
+      const length = bytes.readUInt32BE(offset + 1);
+      const end = offset + HEADER_BYTES + length;
+      if (end > bytes.length || (flag & ~(TRAILER_FLAG | 0x01)) !== 0) { valid = false; break; }
+      if (flag & TRAILER_FLAG) {
+        trailers = { ...trailers, ...parseTrailers(bytes.subarray(offset + HEADER_BYTES, end).toString("utf8")) };
+      } else {
+        frames++;
+        if (flag & 0x01) compressed = true;
+      }
+      offset = end;
+    }
+    if (offset !== bytes.length) valid = false;
+  }
+
+  const header = (name: string) => Object.entries(responseHeaders).find(([k]) => k.toLowerCase() === name)?.[1];
+  const status = trailers["grpc-status"] ?? header("grpc-status");
+  const message = trailers["grpc-message"] ?? header("grpc-message");
+  if (!valid && status === undefined) return null;
+  return {
+    frames: valid ? frames : 0,
+    ...(compressed ? { compressed } : {}),
+    ...(status !== undefined && /^\d+$/.test(status) ? { grpc_status: Number(status) } : {}),
+    ...(message ? { grpc_message: safeDecode(message) } : {}),
+  };
+}
+
+/** Short label for descriptions and logs, e.g. `grpc-web[3 frames]` or `grpc-web[0 frames, status 7]`. */
+export function describeGrpcWeb(summary: GrpcWebSummary): string {
+  const status = summary.grpc_status !== undefined && summary.grpc_status !== 0 ? `, status ${summary.grpc_status}` : "";
+  return `grpc-web[${summary.frames} frame${summary.frames === 1 ? "" : "s"}${status}]`;
+}
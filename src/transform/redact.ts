@@ -0,0 +1,171 @@
+/**
+ * PII redaction for captured bodies that leave the machine.
+ *
+ * Example responses and requests travel into published skills. Locally they
+ * are the user's own data; in the marketplace they are someone else's email
+ * and account number. This masks values under PII-named keys and values that
+ * look like emails, card numbers or JWTs with "<redacted>", keeping the JSON
+ * structure intact so the example still shows the shape.
+ */
+
+import { normalizeBodyBindingKey } from "../reverse-engineer/index.js";
+import type { EndpointDescriptor } from "../types/index.js";
+
+export const REDACTED = "<redacted>";
+
+/** Key words (snake/camel/kebab-split, lowercased) whose values are PII. */
+const PII_KEY_WORDS = new Set([
+  "email", "emails", "phone", "mobile", "ssn", "password", "passwd", "token", "address", "iban", "dob",
+]);
+/** Multi-word names, matched after separators are stripped. */
+const PII_KEY_JOINED = /accountnumber|cardnumber|creditcard|dateofbirth|socialsecurity|emailaddress|phonenumber/;
+
+const EMAIL = /[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}/g;
+const JWT = /eyJ[\w-]{5,}\.eyJ[\w-]{5,}\.[\w-]*/g;
+const CARD_CANDIDATE = /\b(?:\d[ -]?){13,19}\b/g;
+const TEMPLATE_PLACEHOLDER = /^\{[A-Za-z_][\w.\-\[\]]*\}$/;
+
+export interface PiiRedactionOptions {
+  /** Extra key names (any case, exact after separator stripping) to mask */
+  keys?: string[];
+  /** Extra value patterns; matches are replaced in place */
+  valuePatterns?: RegExp[];
+}
+
+/** Extra PII key names from UNBROWSE_PII_KEYS (comma-separated). */
+function envPiiKeys(): string[] {
+  return (process.env.UNBROWSE_PII_KEYS ?? "").split(",").map((k) => k.trim()).filter(Boolean);
+}
+
+function normalizeKey(key: string): string {
+  return key.toLowerCase().replace(/[^a-z0-9]/g, "");
+}
+
+function isPiiKey(key: string, extraKeys: Set<string>): boolean {
+  if (!key) return false;
+  const joined = normalizeKey(key);
+  if (extraKeys.has(joined) || PII_KEY_JOINED.test(joined)) return true;
+  const words = key.replace(/([a-z0-9])([A-Z])/g, "$1 $2").toLowerCase().split(/[^a-z0-9]+/);
+  return words.some((w) => PII_KEY_WORDS.has(w));
+}
+
+/** Luhn check so order ids and timestamps aren't taken for card numbers. */
+function isLuhn(digits: string): boolean {
+  let sum = 0;
+  for (let i = 0; i < digits.length; i++) {
+    let d = Number(digits[digits.length - 1 - i]);
+    if (i % 2 === 1) { d *= 2; if (d > 9) d -= 9; }
+    sum += d;
+  }
+  return sum % 10 === 0;
+}
+
+function redactText(text: string, extraPatterns: RegExp[]): string {
+  let out = text.replace(EMAIL, REDACTED).replace(JWT, REDACTED).replace(CARD_CANDIDATE, (match) => {
+    const digits = match.replace(/\D/g, "");
+    return digits.length >= 13 && digits.length <= 19 && isLuhn(digits) ? REDACTED : match;
+  });
+  for (const pattern of extraPatterns) {
+    out = out.replace(pattern.global ? pattern : new RegExp(pattern.source, `${pattern.flags}g`), REDACTED);
+  }
+  return out;
+}
+
+function extraPiiKeys(options: PiiRedactionOptions): Set<string> {
+  return new Set([...envPiiKeys(), ...(options.keys ?? [])].map(normalizeKey));
+}
+
+/**
+ * Mask PII in a parsed JSON value. Only leaves are masked: an array or object
+ * under a PII-named key (`emails: [...]`, `address: { city }`) keeps its shape
+ * with each leaf inside redacted. Returns a new value; the input is not mutated.
+ */
+export function redactPii(value: unknown, options: PiiRedactionOptions = {}): unknown {
+  const extraKeys = extraPiiKeys(options);
+  const extraPatterns = options.valuePatterns ?? [];
+  const walk = (node: unknown, pii: boolean): unknown => {
+    if (node === null || node === undefined) return node;
+    if (typeof node === "string" && TEMPLATE_PLACEHOLDER.test(node)) return node; // `{email}` is a slot, not data
+    if (Array.isArray(node)) return node.map((item) => walk(item, pii));
+    if (typeof node === "object") {
+      return Object.fromEntries(Object.entries(node as Record<string, unknown>).map(([k, v]) => [k, walk(v, pii || isPiiKey(k, extraKeys))]));
+    }
+    if (pii && typeof node !== "boolean") return REDACTED;
+    if (typeof node === "string") return redactText(node, extraPatterns);
+    return node;
+  };
+  return walk(value, false);
+}
+
+/**
+ * Replace the PII leaves of a request body template with `{binding}`
+ * placeholders (named like reverse-engineer's body bindings), so the
+ * published body still replays once the caller supplies them. Bindings
+ * created here are added to `required`.
+ */
+function templatePiiBody(body: Record<string, unknown>, extraKeys: Set<string>, required: Set<string>): Record<string, unknown> {
+  const walk = (node: unknown, path: string, pii: boolean): unknown => {
+    if (Array.isArray(node)) return node.map((item, i) => walk(item, `${path}[${i}]`, pii));
+    if (node && typeof node === "object") {
+      return Object.fromEntries(Object.entries(node as Record<string, unknown>).map(([k, v]) => [
+        k,
+        walk(v, path ? `${path}.${k}` : k, pii || isPiiKey(k, extraKeys)),
+      ]));
+    }
+    if (!pii || node === null || node === undefined || typeof node === "boolean") return node;
+    if (typeof node === "string" && TEMPLATE_PLACEHOLDER.test(node)) return node;
+    const binding = normalizeBodyBindingKey(path);
+    required.add(binding);
+    return `{${binding}}`;
+  };
+  return walk(body, "", false) as Record<string, unknown>;
+}
+
+/**
+ * Redact a raw response body. JSON bodies are walked key by key; anything
+ * else (or JSON that doesn't parse) gets the value-pattern pass only.
+ */
+export function redactResponseBody(body: string, contentType = "", options: PiiRedactionOptions = {}): string {
+  const trimmed = body.trimStart();
+  if (/json/i.test(contentType) || trimmed.startsWith("{") || trimmed.startsWith("[")) {
+    try {
+      return JSON.stringify(redactPii(JSON.parse(body), options));
+    } catch { /* fall through to text redaction */ }
+  }
+  return redactText(body, options.valuePatterns ?? []);
+}
+
+/**
+ * Endpoint with its captured examples redacted. Semantic examples are masked;
+ * the request body is a replay template, so its PII fields become required
+ * `{binding}` inputs instead, and captured PII defaults in body_params are
+ * dropped.
+ */
+export function redactEndpointExamples(ep: EndpointDescriptor, options: PiiRedactionOptions = {}): EndpointDescriptor {
+  const extraKeys = extraPiiKeys(options);
+  const required = new Set(ep.required_body_params ?? []);
+  const body = ep.body ? templatePiiBody(ep.body, extraKeys, required) : undefined;
+  let bodyParams = ep.body_params;
+  if (bodyParams) {
+    bodyParams = Object.fromEntries(Object.entries(bodyParams).filter(([binding]) => {
+      if (!isPiiKey(binding, extraKeys) && !required.has(binding)) return true;
+      required.add(binding);
+      return false;
+    }));
+  }
+  const semantic = ep.semantic
+    ? {
+      ...ep.semantic,
+      ...(ep.semantic.example_response_compact !== undefined ? { example_response_compact: redactPii(ep.semantic.example_response_compact, options) } : {}),
+      ...(ep.semantic.example_request !== undefined ? { example_request: redactPii(ep.semantic.example_request, options) } : {}),
+    }
+    : undefined;
+  const { body_params: _bodyParams, required_body_params: _required, ...rest } = ep;
+  return {
+    ...rest,
+    ...(body ? { body } : {}),
+    ...(bodyParams && Object.keys(bodyParams).length > 0 ? { body_params: bodyParams } : {}),
+    ...(required.size > 0 ? { required_body_params: [...required] } : {}),
+    ...(semantic ? { semantic } : {}),
+  };
+}
@@ -0,0 +1,122 @@
+/**
+ * Streaming response bodies: NDJSON (one JSON value per line) and SSE
+ * (`text/event-stream` frames). A plain JSON.parse fails on both, so without
+ * this they were recorded as opaque text with no schema.
+ */
+
+export type StreamFormat = "ndjson" | "sse";
+
+export interface ParsedStream {
+  format: StreamFormat;
+  /** Parsed values — NDJSON lines or SSE `data:` payloads (JSON when it parses) */
+  items: unknown[];
+  /** Distinct SSE `event:` names, in first-seen order */
+  events?: string[];
+}
+
+/** Lines / frames parsed per body — enough to infer a shape. */
+const MAX_STREAM_ITEMS = 50;
+/** Stop reading a streaming response after this many bytes... */
+export const MAX_STREAM_BYTES = 256 * 1024;
+/** ...or after this long, so an idle infinite feed can't hang a call. */
+export const MAX_STREAM_MS = 5000;
+
+const STREAM_CONTENT_TYPE = /text\/event-stream|ndjson|jsonl|json-seq|x-json-stream/i;
+
+export function isStreamingContentType(contentType: string | undefined | null): boolean {
+  return STREAM_CONTENT_TYPE.test(contentType ?? "");
+}
+
+function parseJsonish(value: string): unknown {
+  try { return JSON.parse(value); } catch { return value; }
+}
+
+function parseNdjson(text: string): ParsedStream | null {
+  const lines = text.split(/\r?\n/).map((l) => l.replace(/^\x1e/, "").trim()).filter(Boolean);
+  if (lines.length === 0) return null;
+  const items: unknown[] = [];
+  for (let i = 0; i < Math.min(lines.length, MAX_STREAM_ITEMS); i++) {
+    try {
+      items.push(JSON.parse(lines[i]));
+    } catch {
+      // A cut-off final line is expected when the read was capped.
+      if (i > 0 && i === lines.length - 1) break;
+      return null;
+    }
+  }
+  return { format: "ndjson", items };
+}
+
+function parseSse(text: string): ParsedStream | null {
+  const items: unknown[] = [];
+  const events: string[] = [];
+  for (const frame of text.split(/\r?\n\r?\n/)) {
+    let event: string | undefined;
+    const data: string[] = [];
+    for (const line of frame.split(/\r?\n/)) {
+      if (line.startsWith(":")) continue; // comment / keep-alive
+      const colon = line.indexOf(":");
+      const field = colon === -1 ? line : line.slice(0, colon);
+      const value = colon === -1 ? "" : line.slice(colon + 1).replace(/^ /, "");
+      if (field === "event") event = value;
+      else if (field === "data") data.push(value);
+    }
+    if (event && !events.includes(event)) events.push(event);
+    if (data.length === 0) continue;
+    const payload = data.join("\n");
+    if (payload === "[DONE]") continue;
+    items.push(parseJsonish(payload));
+    if (items.length >= MAX_STREAM_ITEMS) break;
+  }
+  if (items.length === 0 && events.length === 0) return null;
+  return { format: "sse", items, ...(events.length > 0 ? { events } : {}) };
+}
+
+const SSE_SHAPE = /^(?::[^\n]*\n)*(event|data|id|retry):/;
+
+/**
+ * Parse a streaming body. The content-type decides when present; otherwise
+ * SSE is recognised by its leading field lines and NDJSON by two or more
+ * lines that each parse as JSON (a single JSON document is not a stream).
+ */
+export function parseStreamBody(text: string | undefined, contentType?: string): ParsedStream | null {
+  if (!text) return null;
+  const ct = (contentType ?? "").toLowerCase();
+  if (ct.includes("event-stream")) return parseSse(text);
+  if (STREAM_CONTENT_TYPE.test(ct)) return parseNdjson(text);
+  if (SSE_SHAPE.test(text.trimStart())) return parseSse(text);
+  if (text.trim().split(/\r?\n/).filter((l) => l.trim()).length < 2) return null;
+  try { JSON.parse(text); return null; } catch { /* not one document — maybe NDJSON */ }
+  return parseNdjson(text);
+}
+
+/**
+ * Read a fetch Response as text. Streaming content-types are read
+ * incrementally and cut at MAX_STREAM_BYTES / MAX_STREAM_MS; everything
+ * else is read whole.
+ */
+export async function readResponseText(res: Response): Promise<string> {
+  if (!isStreamingContentType(res.headers.get("content-type")) || !res.body) return res.text();
+  const reader = res.body.getReader();
+  const decoder = new TextDecoder();
+  const deadline = Date.now() + MAX_STREAM_MS;
+  let text = "";
+  let bytes = 0;
+  try {
+    while (bytes < MAX_STREAM_BYTES) {
+      const remaining = deadline - Date.now();
+      if (remaining <= 0) break;
+      let timer: ReturnType<typeof setTimeout> | undefined;
+      const chunk = await Promise.race([
+        reader.read(),
+        new Promise<{ done: true; value?: undefined }>((resolve) => { timer = setTimeout(() => resolve({ done: true }), remaining); }),
+      ]).finally(() => clearTimeout(timer));
+      if (chunk.done || !chunk.value) break;
+      bytes += chunk.value.byteLength;
+      text += decoder.decode(chunk.value, { stream: true });
+    }
+  } finally {
+    reader.cancel().catch(() => {});
+  }
+  return text + decoder.decode();
+}
@@ -12,6 +12,29 @@ export interface AuthProfile {
   storage_hint: string;
 }
 
+/**
+ * Token refresh endpoint for skills whose access token expires mid-session.
+ * The refresh token lives in the vault, never in the manifest.
+ */
+export interface AuthRefreshConfig {
+  url: string;
+  method?: "POST" | "GET";
+  /** Request body; string values may contain `{{refresh_token}}` */
+  body?: Record<string, unknown>;
+  /** Body encoding (default json) */
+  body_format?: "json" | "form";
+  /** Vault account holding the refresh token */
+  refresh_token_ref: string;
+  /** Dot path to the new access token in the refresh response, e.g. "access_token" */
+  token_path: string;
+  /** Dot path to a rotated refresh token, when the server issues one */
+  refresh_token_path?: string;
+  /** Header the access token is sent in (default "authorization") */
+  header?: string;
+  /** Scheme prefix for the header value (default "Bearer"; "" for raw tokens) */
+  scheme?: string;
+}
+
 export interface CsrfPlan {
   source: "header" | "cookie" | "form";
   param_name: string;
@@ -19,6 +42,82 @@ export interface CsrfPlan {
   extractor_sequence: string[];
 }
 
+/**
+ * Per-request HMAC signing detected at capture time. The captured signature
+ * is useless on replay — it must be recomputed over a fresh timestamp.
+ */
+export interface RequestSigningInfo {
+  /** Where the signature travels */
+  location: "header" | "query";
+  signature_name: string;
+  /** Header or query param carrying the per-request timestamp */
+  timestamp_name?: string;
+  timestamp_location?: "header" | "query";
+  /** How the captured timestamp was written; fresh ones are generated the same way */
+  timestamp_format?: "epoch_s" | "epoch_ms" | "iso";
+  /** Header or query param carrying a per-request random nonce */
+  nonce_name?: string;
+  nonce_location?: "header" | "query";
+  encoding: "hex" | "base64";
+  digest_guess: "sha1" | "sha256" | "sha384" | "sha512" | "unknown";
+  /**
+   * Fields of the string-to-sign, in order, joined by `canonical_separator`
+   * (default "\n"). Inferred at capture as a best guess — edit it when the
+   * site's client code signs something else.
+   */
+  canonical_fields?: Array<"method" | "path" | "query" | "timestamp" | "nonce" | "body">;
+  canonical_separator?: string;
+}
+
+/** Auth scheme classified from a capture (classifyAuth); auth_method is its display label. */
+export type AuthScheme =
+  | "bearer_token"
+  | "api_key"
+  | "jwt"
+  | "basic_auth"
+  | "digest_auth"
+  | "session_cookie"
+  | "aws_sigv4"
+  | "oauth"
+  | "token_auth"
+  | "hoba"
+  | "custom_header"
+  /** Public API: calls succeeded with no credentials sent */
+  | "none"
+  | "unknown";
+
+/** Coarse endpoint grouping for docs and exports (classifyEndpoint). */
+export type EndpointCategory = "auth" | "search" | "list" | "detail" | "create" | "update" | "delete" | "other";
+
+export type BodyFormat = "json" | "ndjson" | "sse" | "protobuf" | "grpc" | "msgpack" | "form" | "other";
+
+/** Frame structure of a captured gRPC-web response (payloads stay opaque without the .proto). */
+export interface GrpcWebSummary {
+  /** Data (non-trailer) frames */
+  frames: number;
+  /** At least one data frame had the compression flag set */
+  compressed?: boolean;
+  /** From the trailer frame or grpc-status header; non-zero means the call failed despite HTTP 200 */
+  grpc_status?: number;
+  grpc_message?: string;
+}
+
+/**
+ * Throttling advertised by the server in response headers. `scheme` records
+ * which header family was seen: GitHub-style `X-RateLimit-*`, the IETF draft
+ * `RateLimit-*` / `RateLimit`, or a bare `Retry-After`.
+ */
+export interface RateLimitInfo {
+  scheme: "x-ratelimit" | "ratelimit" | "retry-after";
+  limit?: number;
+  remaining?: number;
+  /** Absolute time the window resets, ISO 8601 */
+  reset_at?: string;
+  /** Window length from RateLimit-Policy (`100;w=60`), seconds */
+  window_seconds?: number;
+  retry_after_ms?: number;
+}
+
 export interface OAuthPlan {
   grant_type: string;
   token_url?: string;
@@ -42,6 +141,35 @@ export interface Transform {
   };
 }
 
+/** Query parameter observed across all captured calls to one endpoint. */
+export interface QueryParamInfo {
+  name: string;
+  /** Present in every observed call (never true for volatile params) */
+  required: boolean;
+  /** Distinct captured values, first few only */
+  examples: string[];
+  /** Number of observed calls carrying the param */
+  seen: number;
+  /** Cache-buster / version-hash style param that changes per request */
+  volatile?: boolean;
+  /**
+   * What diffing the calls says about the param: `constant` (same value on
+   * every call — sent from the default, not asked for), `variable` (on every
+   * call, value changes — a required input), `optional` (only some calls),
+   * or `volatile`. A single observed call makes every param `variable`.
+   */
+  role?: "constant" | "variable" | "optional" | "volatile";
+}
+
+/** What capture observed for one endpoint across all of its calls. */
+export interface CaptureStats {
+  calls: number;
+  /** Calls that answered 2xx */
+  ok_calls: number;
+  /** Any call returned a JSON content-type */
+  json: boolean;
+}
+
 export interface WsMessage {
   direction: "sent" | "received";
   data: string;
@@ -79,24 +207,55 @@ export interface EndpointSemanticDescriptor {
 export interface EndpointDescriptor {
   endpoint_id: string;
   method: "GET" | "POST" | "PUT" | "PATCH" | "DELETE" | "HEAD" | "OPTIONS" | "WS";
+  /** HTTP method actually sent when `method` is tunnelled (POST + X-HTTP-Method-Override / `_method`) */
+  wire_method?: string;
   url_template: string;
   /** LLM-generated description of what this endpoint returns, for semantic matching */
   description?: string;
   ws_messages?: WsMessage[];
+  /** Negotiated Sec-WebSocket-Protocol for WS endpoints */
+  ws_subprotocol?: string;
   headers_template?: Record<string, string>;
   query?: Record<string, unknown>;
+  /** Query params aggregated over every captured call: required vs optional, examples */
+  query_params?: QueryParamInfo[];
   /** Default values for templatized path segments (e.g. {symbol} → "SPY,QQQ") */
   path_params?: Record<string, string>;
   /** Default values for templatized request-body placeholders */
   body_params?: Record<string, unknown>;
+  /** Body placeholders with no default (password, refresh_token, ...) — execution refuses to run without them */
+  required_body_params?: string[];
   body?: Record<string, unknown>;
   csrf_plan?: CsrfPlan;
+  /** Set when requests carry a per-request HMAC signature (not statically replayable) */
+  request_signing?: RequestSigningInfo;
   oauth_plan?: OAuthPlan;
   transform_ref?: string;
   idempotency: Idempotency;
   verification_status: VerificationStatus;
   reliability_score: number;
   last_verified_at?: string;
+  /** Call counts and outcomes seen during capture (evidence ranking) */
+  capture_stats?: CaptureStats;
+  /** Seeded from an OpenAPI document and not yet seen in a capture; false once a capture merged on top */
+  from_spec?: boolean;
+  /** URL templates of same-method, same-response-shape endpoints folded into
+   *  this one by shape dedupe (ExtractionContext.dedupeByShape) */
+  shape_aliases?: string[];
+  /** Seen only as a failed CORS preflight — the browser never sent the real
+   *  request, so there is no response or schema (ExtractionContext.reportCorsBlocked) */
+  blocked_by?: "cors";
+  /** Wire format of the captured response body. Binary formats (protobuf,
+   *  grpc, msgpack) are kept as endpoints but never shape-analyzed. */
+  body_format?: BodyFormat;
+  /** Set for gRPC-web responses: frame count and trailer status */
+  grpc_web?: GrpcWebSummary;
+  /** Most recent rate-limit headers seen for this endpoint (capture or verification) */
+  rate_limit?: RateLimitInfo;
+  /** Set by verification: the server answered a conditional re-request
+   *  (If-None-Match / If-Modified-Since) with 304. Undefined when the
+   *  response carried no ETag or Last-Modified to test with. */
+  supports_conditional?: boolean;
   signature?: string;
   response_schema?: ResponseSchema;
   /** When set, endpoint returns HTML — apply DOM extraction with this config */
@@ -202,6 +361,17 @@ export interface AgentSkillChunkView {
   available_operations: AgentAvailableOperation[];
 }
 
+/** A `WWW-Authenticate` challenge from a 401 response, e.g. `Bearer realm="api", error="invalid_token"`. */
+export interface AuthChallenge {
+  /** Lowercased scheme token: "bearer", "basic", "digest", or a custom scheme */
+  scheme: string;
+  realm?: string;
+  scope?: string;
+  error?: string;
+  /** Every auth-param as sent (lowercased names); `token68` for the bare-token form */
+  params: Record<string, string>;
+}
+
 export interface SkillManifest {
   skill_id: string;
   version: string;
@@ -214,6 +384,22 @@ export interface SkillManifest {
   owner_type: OwnerType;
   execution_type: ExecutionType;
   auth_profile_ref?: string;
+  /** How to mint a fresh access token after a 401/403 */
+  auth_refresh?: AuthRefreshConfig;
+  /** Auth method inferred from captured headers/cookies, e.g. "Bearer Token" */
+  auth_method?: string;
+  /** Machine-readable form of auth_method, for branching without parsing the label */
+  auth_scheme?: AuthScheme;
+  /** Requests are signed one by one (e.g. AWS SigV4); replaying a captured Authorization value will fail */
+  signed_requests?: boolean;
+  /** First WWW-Authenticate challenge seen on a 401 during capture */
+  auth_challenge?: AuthChallenge;
+  /** Auth headers whose value changed mid-capture (key rotation); the stored bundle holds the latest */
+  rotated_auth_headers?: string[];
+  /** Device / installation id headers the API expects alongside the token; values live in the auth bundle's `context.device_identifiers` */
+  device_identifier_headers?: string[];
+  /** API versioning headers seen at capture (Accept vnd., X-API-Version, ...); sent on every replay */
+  version_headers?: Record<string, string>;
   endpoints: EndpointDescriptor[];
   transform_ref?: string;
   lifecycle: SkillLifecycle;
@@ -268,6 +454,8 @@ export interface ExecutionTrace {
   tokens_saved_pct?: number;
   /** Code version hash + git SHA — tracks which code produced this trace */
   trace_version?: string;
+  /** A 401/403 triggered a successful credential refresh; the call itself was not retried */
+  auth_refreshed?: boolean;
 }
 
 export interface DiscoveryCandidate {
@@ -288,6 +476,10 @@ export interface ResponseSchema {
   required?: string[];
   anyOf?: ResponseSchema[];
   inferred_from_samples: number;
+  /** Streamed response: `items` describes one NDJSON line or SSE data frame */
+  stream?: "ndjson" | "sse";
+  /** SSE event names seen during capture */
+  stream_events?: string[];
 }
 
 export interface ProjectionOptions {
@@ -317,6 +509,14 @@ export interface EndpointStats {
   last_success_at?: string;
 }
 
+/** Response-status ranges endpoint extraction keeps (ExtractionContext.statuses) */
+export interface StatusFilter {
+  /** Allowed inclusive ranges; a call outside all of them is dropped */
+  ranges?: Array<{ min: number; max: number }>;
+  /** false is shorthand for ranges `[{ min: 200, max: 399 }]`; default true */
+  include_errors?: boolean;
+}
+
 export interface ExecutionOptions {
   confirm_unsafe?: boolean;
   dry_run?: boolean;
@@ -332,6 +532,19 @@ export interface ExecutionOptions {
   payment_verified?: boolean;
   /** Skip robots.txt compliance check (e.g. for testing or trusted internal domains) */
   skip_robots_check?: boolean;
+  /** Request-scoped headers layered over the endpoint template (e.g. a workflow's Idempotency-Key) */
+  extra_headers?: Record<string, string>;
+  /**
+   * Applied last, over auth headers and cookies — precedence is
+   * template < extra_headers < auth < header_overrides < remove_headers.
+   */
+  header_overrides?: Record<string, string>;
+  /** Header names (any case) to leave off this request, auth included — e.g. `authorization` for a public call a stale token would 401 */
+  remove_headers?: string[];
+  /** Aborts the in-flight server fetch (workflow timeout / cancellation) */
+  signal?: AbortSignal;
+  /** Live capture only: response statuses whose endpoints are kept, e.g. `{ include_errors: false }` */
+  statuses?: StatusFilter;
 }
 
 export interface ValidationResult {
@@ -1,13 +1,17 @@
-import { createCipheriv, createDecipheriv, randomBytes } from "crypto";
-import { existsSync, mkdirSync, readFileSync, writeFileSync } from "fs";
+import * as nodeCrypto from "crypto";
+import { createCipheriv, createDecipheriv, randomBytes, scryptSync } from "crypto";
+import { closeSync, copyFileSync, existsSync, linkSync, mkdirSync, openSync, readFileSync, renameSync, statSync, unlinkSync, writeFileSync, writeSync } from "fs";
 import { join } from "path";
 import { homedir } from "os";
-import { log } from "../logger.js";
+import { log, logError } from "../logger.js";
+import { unbrowseError } from "../errors.js";
+import { getKeychainTimeoutMs, isKeychainDenial, keychainDeniedError } from "../auth/keychain.js";
 
 type KeytarClient = {
   setPassword: (service: string, account: string, password: string) => Promise<unknown>;
   getPassword: (service: string, account: string) => Promise<string | null>;
   deletePassword: (service: string, account: string) => Promise<boolean>;
+  findCredentials?: (service: string) => Promise<Array<{ account: string; password: string }>>;
 };
 
 const KEYTAR_UNAVAILABLE = Symbol("KEYTAR_UNAVAILABLE");
@@ -54,12 +58,23 @@ function disableKeytar(error: unknown): void {
 
 async function callKeytar<T>(op: (client: KeytarClient) => Promise<T>): Promise<T | typeof KEYTAR_UNAVAILABLE> {
   if (!keytar) return KEYTAR_UNAVAILABLE;
+  let timer: ReturnType<typeof setTimeout> | undefined;
   try {
-    return await op(keytar);
+    // An unanswered macOS "allow access" prompt would otherwise block forever.
+    const timeout = new Promise<never>((_, reject) => {
+      timer = setTimeout(() => reject(keychainDeniedError(SERVICE, "timeout")), getKeychainTimeoutMs());
+    });
+    return await Promise.race([op(keytar), timeout]);
   } catch (error) {
-    if (!isKeytarBindingError(error)) throw error;
+    if ((error as { code?: unknown }).code === "keychain_access_denied") throw error;
+    if (isKeychainDenial(error)) throw keychainDeniedError(SERVICE, "denied", (error as Error).message);
+    if (!isKeytarBindingError(error)) {
+      throw unbrowseError("keychain_unavailable", `keychain access failed: ${error instanceof Error ? error.message : String(error)}`);
+    }
     disableKeytar(error);
     return KEYTAR_UNAVAILABLE;
+  } finally {
+    clearTimeout(timer);
   }
 }
 
@@ -78,6 +93,18 @@ export interface StoredCredential {
   stored_at: string;
   expires_at?: string;
   max_age_ms?: number;
+  /** Page the credential was captured from (provenance for "why is this token wrong") */
+  source_url?: string;
+  /** When the capture ran — can predate stored_at when a bundle is re-merged */
+  captured_at?: string;
+}
+
+/** Provenance and expiry for storeCredential. */
+export interface StoreCredentialOptions {
+  expires_at?: string;
+  max_age_ms?: number;
+  source_url?: string;
+  captured_at?: string;
 }
 
 const SERVICE = "unbrowse";
@@ -85,59 +112,235 @@ const SERVICE = "unbrowse";
 const VAULT_DIR = join(homedir(), ".unbrowse", "vault");
 const VAULT_FILE = join(VAULT_DIR, "credentials.enc");
 const KEY_FILE = join(VAULT_DIR, ".key");
+const LOCK_FILE = join(VAULT_DIR, "credentials.lock");
+const DEFAULT_VAULT_BUSY_TIMEOUT_MS = 5_000;
+/** A lock with no readable pid older than this is from a writer that crashed mid-create — writes take milliseconds */
+const STALE_LOCK_MS = 30_000;
 
 function getOrCreateKey(): Buffer {
   if (!existsSync(VAULT_DIR)) mkdirSync(VAULT_DIR, { recursive: true, mode: 0o700 });
   if (existsSync(KEY_FILE)) return readFileSync(KEY_FILE);
   const key = randomBytes(32);
-  writeFileSync(KEY_FILE, key, { mode: 0o600 });
+  try {
+    // Exclusive create: two processes creating the vault at once must agree on one key.
+    writeFileSync(KEY_FILE, key, { mode: 0o600, flag: "wx" });
+  } catch (error) {
+    if ((error as NodeJS.ErrnoException).code !== "EEXIST") throw error;
+    return readFileSync(KEY_FILE);
+  }
   return key;
 }
 
-// Async mutex to prevent concurrent read-modify-write races
+function getVaultBusyTimeoutMs(): number {
+  const configured = Number(process.env.UNBROWSE_VAULT_BUSY_TIMEOUT_MS);
+  return Number.isFinite(configured) && configured > 0 ? configured : DEFAULT_VAULT_BUSY_TIMEOUT_MS;
+}
+
+/** Holder pid of a lock file ("<pid> <token>"), or null while it is still being written. */
+function lockHolderPid(file: string): number | null {
+  const pid = parseInt(readFileSync(file, "utf8"), 10);
+  return Number.isInteger(pid) && pid > 0 ? pid : null;
+}
+
+/**
+ * Stale when the holder has died. Liveness is checked first so a slow but
+ * live writer (a keychain prompt, a large vault) keeps its lock; the age
+ * limit only applies when no pid can be read.
+ */
+function isStaleLock(file: string): boolean {
+  try {
+    const pid = lockHolderPid(file);
+    if (pid === null) return Date.now() - statSync(file).mtimeMs > STALE_LOCK_MS;
+    // Our own in-process mutex serializes holders, so our pid here is a leaked lock.
+    if (pid === process.pid) return true;
+    try { process.kill(pid, 0); return false; } catch (error) { return (error as NodeJS.ErrnoException).code === "ESRCH"; }
+  } catch {
+    return false;
+  }
+}
+
+/**
+ * Take a stale lock out of the way. The lock is first renamed to a name only
+ * we know, so of several processes that saw it stale exactly one gets it, and
+ * a fresh lock someone created meanwhile is never the one deleted. If what we
+ * moved turns out to be live after all it is put back (link won't overwrite
+ * a newer lock).
+ */
+function removeStaleLock(): void {
+  const moved = `${LOCK_FILE}.${process.pid}.${randomBytes(4).toString("hex")}`;
+  try { renameSync(LOCK_FILE, moved); } catch { return; /* another process got there first */ }
+  try {
+    if (isStaleLock(moved)) {
+      log("vault", `removed stale vault lock ${LOCK_FILE}`);
+      return;
+    }
+    try { linkSync(moved, LOCK_FILE); } catch { /* a newer lock took its place */ }
+  } finally {
+    try { unlinkSync(moved); } catch { /* already gone */ }
+  }
+}
+
+/**
+ * Cross-process write lock on credentials.enc: an exclusively created lock
+ * file holding our pid, retried with backoff for the busy timeout
+ * (UNBROWSE_VAULT_BUSY_TIMEOUT_MS, default 5s). Returns the release, which
+ * only removes the lock while it is still ours.
+ */
+async function acquireVaultFileLock(): Promise<() => void> {
+  if (!existsSync(VAULT_DIR)) mkdirSync(VAULT_DIR, { recursive: true, mode: 0o700 });
+  const timeoutMs = getVaultBusyTimeoutMs();
+  const deadline = Date.now() + timeoutMs;
+  const owner = `${process.pid} ${randomBytes(8).toString("hex")}`;
+  for (let delay = 10; ; delay = Math.min(delay * 2, 200)) {
+    try {
+      const fd = openSync(LOCK_FILE, "wx", 0o600);
+      try { writeSync(fd, owner); } finally { closeSync(fd); }
+      return () => {
+        try { if (readFileSync(LOCK_FILE, "utf8") === owner) unlinkSync(LOCK_FILE); } catch { /* already gone */ }
+      };
+    } catch (error) {
+      if ((error as NodeJS.ErrnoException).code !== "EEXIST") throw error;
+    }
+    if (isStaleLock(LOCK_FILE)) {
+      removeStaleLock();
+      continue;
+    }
+    if (Date.now() >= deadline) {
+      throw unbrowseError("vault_locked", `vault is locked by another unbrowse process (waited ${timeoutMs}ms for ${LOCK_FILE})`, { lock_file: LOCK_FILE });
+    }
+    await new Promise((r) => setTimeout(r, delay));
+  }
+}
+
+// Async mutex to prevent concurrent read-modify-write races within this
+// process; the lock file extends it to other processes (parallel captures,
+// CLI next to the server), so each read-modify-write is one transaction.
 let vaultLock: Promise<void> = Promise.resolve();
 function withVaultLock<T>(fn: () => T | Promise<T>): Promise<T> {
   const prev = vaultLock;
   let release: () => void;
   vaultLock = new Promise<void>((r) => { release = r; });
-  return prev.then(fn).finally(() => release!());
+  return prev
+    .then(async () => {
+      const releaseFile = await acquireVaultFileLock();
+      try { return await fn(); } finally { releaseFile(); }
+    })
+    .finally(() => release!());
+}
+
+// --- credentials.enc record format ---
+//
+//   [0..4)  magic "UBV\0"
+//   [4]     format version (VAULT_FORMAT_VERSION)
+//   [5]     cipher id
+//   [6..]   cipher payload — AES-256-CBC: 16-byte iv, then ciphertext
+//
+// Files written before the header existed are a bare iv + ciphertext and
+// are read as version 0 (same cipher). A version or cipher this build
+// doesn't know is refused rather than read as empty, so an older unbrowse
+// never overwrites a vault written by a newer one.
+
+const VAULT_MAGIC = Buffer.from("UBV\0", "latin1");
+const VAULT_FORMAT_VERSION = 1;
+const CIPHER_AES_256_CBC = 1;
+const VAULT_HEADER_LEN = 6;
+
+type VaultBlob = { version: number; cipher: number; iv: Buffer; body: Buffer };
+
+/** Split credentials.enc into header fields and payload. Throws for an unknown format. */
+function decodeVaultBlob(raw: Buffer): VaultBlob {
+  if (raw.length < VAULT_HEADER_LEN || !raw.subarray(0, VAULT_MAGIC.length).equals(VAULT_MAGIC)) {
+    return { version: 0, cipher: CIPHER_AES_256_CBC, iv: raw.subarray(0, 16), body: raw.subarray(16) };
+  }
+  const version = raw[4];
+  const cipher = raw[5];
+  if (version !== VAULT_FORMAT_VERSION) {
+    throw unbrowseError("decryption_failed", `vault file format v${version} is not supported by this version of unbrowse (expected v${VAULT_FORMAT_VERSION})`, { format_version: version });
+  }
+  if (cipher !== CIPHER_AES_256_CBC) {
+    throw unbrowseError("decryption_failed", `vault file uses unknown cipher id ${cipher}`, { format_version: version, cipher_id: cipher });
+  }
+  const payload = raw.subarray(VAULT_HEADER_LEN);
+  return { version, cipher, iv: payload.subarray(0, 16), body: payload.subarray(16) };
+}
+
+function decryptVaultBlob(key: Buffer, blob: VaultBlob): Record<string, string> {
+  const decipher = createDecipheriv("aes-256-cbc", key, blob.iv);
+  const dec = Buffer.concat([decipher.update(blob.body), decipher.final()]);
+  return JSON.parse(dec.toString("utf8")) as Record<string, string>;
 }
 
 function readVaultFile(): Record<string, string> {
   if (!existsSync(VAULT_FILE)) return {};
+  let blob: VaultBlob;
+  let key: Buffer;
   try {
-    const key = getOrCreateKey();
-    const raw = readFileSync(VAULT_FILE);
-    const iv = raw.subarray(0, 16);
-    const enc = raw.subarray(16);
-    const decipher = createDecipheriv("aes-256-cbc", key, iv);
-    const dec = Buffer.concat([decipher.update(enc), decipher.final()]);
-    return JSON.parse(dec.toString("utf8")) as Record<string, string>;
-  } catch {
-    return {};
+    key = getOrCreateKey();
+    blob = decodeVaultBlob(readFileSync(VAULT_FILE));
+  } catch (error) {
+    // Unknown format: fail loudly — returning {} would let the next write clobber it.
+    logError("vault", `decryption_failed: ${VAULT_FILE}: ${(error as Error).message}`);
+    throw error;
+  }
+  try {
+    return decryptVaultBlob(key, blob);
+  } catch (error) {
+    // Same as an unknown format: returning {} would let the next write clobber every entry.
+    logError("vault", `decryption_failed: could not decrypt ${VAULT_FILE} (${(error as Error).message})`);
+    throw unbrowseError("decryption_failed", `could not decrypt ${VAULT_FILE} — run \`unbrowse vault verify\` or \`unbrowse vault repair\``, { format_version: blob.version });
   }
 }
 
-function writeVaultFile(data: Record<string, string>): void {
+function writeVaultFile(data: Record<string, string>, file = VAULT_FILE): void {
   const key = getOrCreateKey();
   const iv = randomBytes(16);
   const cipher = createCipheriv("aes-256-cbc", key, iv);
   const enc = Buffer.concat([cipher.update(JSON.stringify(data), "utf8"), cipher.final()]);
-  writeFileSync(VAULT_FILE, Buffer.concat([iv, enc]), { mode: 0o600 });
+  const header = Buffer.concat([VAULT_MAGIC, Buffer.from([VAULT_FORMAT_VERSION, CIPHER_AES_256_CBC])]);
+  // Write-then-rename: a concurrent reader sees the old file or the new one,
+  // never a half-written blob that fails to decrypt.
+  const tmp = `${file}.${process.pid}.tmp`;
+  writeFileSync(tmp, Buffer.concat([header, iv, enc]), { mode: 0o600 });
+  renameSync(tmp, file);
+}
+
+/**
+ * Probe the credential backend without touching any stored entry: the OS
+ * keychain when keytar loads, otherwise the encrypted-file key (created on
+ * first use, same as a real write would).
+ */
+export async function checkVaultAvailable(): Promise<{ backend: "keychain" | "file"; ok: boolean; message: string }> {
+  try {
+    const keytarResult = await callKeytar((client) => client.getPassword(SERVICE, "__health_probe__"));
+    if (keytarResult !== KEYTAR_UNAVAILABLE) return { backend: "keychain", ok: true, message: "OS keychain reachable" };
+  } catch (error) {
+    return { backend: "keychain", ok: false, message: (error as Error).message };
+  }
+  try {
+    getOrCreateKey();
+    return { backend: "file", ok: true, message: `encrypted file vault (${VAULT_DIR})` };
+  } catch (error) {
+    return { backend: "file", ok: false, message: `vault key unavailable: ${(error as Error).message}` };
+  }
 }
 
 export async function storeCredential(
   account: string,
   value: string,
-  opts?: { expires_at?: string; max_age_ms?: number }
+  opts?: StoreCredentialOptions
 ): Promise<void> {
+  const storedAt = new Date().toISOString();
   const wrapped: StoredCredential = {
     value,
-    stored_at: new Date().toISOString(),
+    stored_at: storedAt,
     expires_at: opts?.expires_at,
     max_age_ms: opts?.max_age_ms,
+    ...(opts?.source_url ? { source_url: opts.source_url, captured_at: opts.captured_at ?? storedAt } : {}),
   };
-  const serialized = JSON.stringify(wrapped);
+  await storeSerialized(account, JSON.stringify(wrapped));
+}
+
+async function storeSerialized(account: string, serialized: string): Promise<void> {
   const keytarResult = await callKeytar((client) => client.setPassword(SERVICE, account, serialized));
   if (keytarResult !== KEYTAR_UNAVAILABLE) return;
   await withVaultLock(() => {
@@ -157,15 +360,14 @@ function isExpired(cred: StoredCredential): boolean {
   return false;
 }
 
-export async function getCredential(account: string): Promise<string | null> {
-  let raw: string | null;
+async function readSerialized(account: string): Promise<string | null> {
   const keytarResult = await callKeytar((client) => client.getPassword(SERVICE, account));
-  if (keytarResult !== KEYTAR_UNAVAILABLE) {
-    raw = keytarResult;
-  } else {
-    const data = readVaultFile();
-    raw = data[account] ?? null;
-  }
+  if (keytarResult !== KEYTAR_UNAVAILABLE) return keytarResult;
+  return readVaultFile()[account] ?? null;
+}
+
+export async function getCredential(account: string): Promise<string | null> {
+  const raw = await readSerialized(account);
   if (!raw) return null;
 
   // Try to parse as StoredCredential; backward-compat: raw strings are legacy (no expiry)
@@ -185,6 +387,24 @@ export async function getCredential(account: string): Promise<string | null> {
   return raw;
 }
 
+/**
+ * Stored metadata for `account` — stored_at, expiry and capture provenance —
+ * without the value. Legacy raw-string entries have none of it. Unlike
+ * getCredential, an expired entry is reported (`expired: true`), not deleted.
+ */
+export async function getCredentialInfo(account: string): Promise<(Omit<StoredCredential, "value" | "stored_at"> & { account: string; stored_at?: string; expired: boolean }) | null> {
+  const raw = await readSerialized(account);
+  if (!raw) return null;
+  try {
+    const parsed = JSON.parse(raw) as StoredCredential;
+    if (parsed && typeof parsed === "object" && parsed.value && parsed.stored_at) {
+      const { value: _value, ...meta } = parsed;
+      return { account, ...meta, expired: isExpired(parsed) };
+    }
+  } catch { /* legacy raw string */ }
+  return { account, expired: false };
+}
+
 export async function deleteCredential(account: string): Promise<void> {
   const keytarResult = await callKeytar((client) => client.deletePassword(SERVICE, account));
   if (keytarResult !== KEYTAR_UNAVAILABLE) return;
@@ -194,3 +414,442 @@ export async function deleteCredential(account: string): Promise<void> {
     writeVaultFile(data);
   });
 }
+
+// --- Integrity check and recovery ---
+
+export type VaultEntryStatus = "ok" | "expired" | "corrupt";
+
+export interface VaultEntryHealth {
+  account: string;
+  backend: "keychain" | "file";
+  status: VaultEntryStatus;
+  error?: string;
+}
+
+export interface VaultHealthReport {
+  /**
+   * Whole-file state of credentials.enc; "corrupt" means it no longer
+   * decrypts as a whole, "unsupported" that it was written in a record
+   * format (version or cipher) this build doesn't know — left untouched
+   */
+  file: "ok" | "missing" | "corrupt" | "unsupported";
+  file_error?: string;
+  /** Record format version of credentials.enc (0 = written before the versioned header) */
+  format_version?: number;
+  /** For a corrupt file: entries still readable from the ciphertext and listed below */
+  salvageable?: number;
+  entries: VaultEntryHealth[];
+}
+
+export interface VaultRepairResult {
+  dropped: string[];
+  /** Entries rewritten from a corrupt file */
+  recovered: string[];
+  backup_path?: string;
+}
+
+function classifySerialized(serialized: unknown): { status: VaultEntryStatus; error?: string } {
+  if (typeof serialized !== "string") return { status: "corrupt", error: "entry is not a string" };
+  let parsed: unknown;
+  try { parsed = JSON.parse(serialized); } catch { return { status: "ok" }; /* legacy raw string */ }
+  if (!parsed || typeof parsed !== "object" || !("stored_at" in parsed)) return { status: "ok" };
+  const cred = parsed as Partial<StoredCredential>;
+  if (typeof cred.value !== "string") return { status: "corrupt", error: "wrapped credential has no value" };
+  if (Number.isNaN(new Date(cred.stored_at as string).getTime())) return { status: "corrupt", error: "invalid stored_at" };
+  return isExpired(cred as StoredCredential) ? { status: "expired" } : { status: "ok" };
+}
+
+type VaultFileRead =
+  | { state: "missing"; data: Record<string, string> }
+  | { state: "ok"; data: Record<string, string>; version: number }
+  | { state: "corrupt"; data: Record<string, string>; error: string; version?: number }
+  | { state: "unsupported"; data: Record<string, string>; error: string };
+
+/**
+ * Like readVaultFile, but a file that no longer decrypts is reported rather
+ * than read as empty, and whatever entries survive are salvaged: CBC only
+ * garbles the blocks around damage, so entries outside it are still intact
+ * JSON in the unpadded plaintext.
+ */
+function readVaultFileStrict(): VaultFileRead {
+  if (!existsSync(VAULT_FILE)) return { state: "missing", data: {} };
+  let key: Buffer;
+  let raw: Buffer;
+  try {
+    key = getOrCreateKey();
+    raw = readFileSync(VAULT_FILE);
+  } catch (error) {
+    return { state: "corrupt", data: {}, error: (error as Error).message };
+  }
+  let blob: VaultBlob;
+  try {
+    blob = decodeVaultBlob(raw);
+  } catch (error) {
+    return { state: "unsupported", data: {}, error: (error as Error).message };
+  }
+  try {
+    const data = decryptVaultBlob(key, blob) as unknown;
+    if (!data || typeof data !== "object" || Array.isArray(data)) throw new Error("vault root is not an object");
+    return { state: "ok", data: data as Record<string, string>, version: blob.version };
+  } catch (error) {
+    return { state: "corrupt", data: salvageVaultPlaintext(key, blob), error: (error as Error).message, version: blob.version };
+  }
+}
+
+const SALVAGE_PAIR = /"((?:[^"\\]|\\.)*)":"((?:[^"\\]|\\.)*)"(?=[,}])/g;
+
+function salvageVaultPlaintext(key: Buffer, blob: VaultBlob): Record<string, string> {
+  const body = blob.body.subarray(0, Math.floor(blob.body.length / 16) * 16);
+  if (blob.iv.length < 16 || body.length === 0) return {};
+  let text: string;
+  try {
+    const decipher = createDecipheriv("aes-256-cbc", key, blob.iv);
+    decipher.setAutoPadding(false);
+    text = Buffer.concat([decipher.update(body), decipher.final()]).toString("utf8");
+  } catch {
+    return {};
+  }
+  const salvaged: Record<string, string> = {};
+  for (const match of text.matchAll(SALVAGE_PAIR)) {
+    try {
+      const account = JSON.parse(`"${match[1]}"`) as string;
+      const serialized = JSON.parse(`"${match[2]}"`) as string;
+      // A match that starts inside garbled bytes can misalign; only keep
+      // pairs that still look like a real entry.
+      if (!/^[\w:.@\-]+$/.test(account) || classifySerialized(serialized).status === "corrupt") continue;
+      salvaged[account] ??= serialized;
+    } catch {
+      continue;
+    }
+  }
+  return salvaged;
+}
+
+/**
+ * Check every stored credential without stopping at the first bad one:
+ * keychain entries and file-vault entries are each classified ok / expired /
+ * corrupt, and a file that no longer decrypts as a whole is reported with
+ * the number of entries repairVault could still recover from it.
+ */
+export async function verifyVault(): Promise<VaultHealthReport> {
+  const entries: VaultEntryHealth[] = [];
+  const fromKeytar = await callKeytar(async (client) => client.findCredentials ? client.findCredentials(SERVICE) : []);
+  if (fromKeytar !== KEYTAR_UNAVAILABLE) {
+    for (const { account, password } of fromKeytar) entries.push({ account, backend: "keychain", ...classifySerialized(password) });
+  }
+  const file = await withVaultLock(() => readVaultFileStrict());
+  for (const [account, serialized] of Object.entries(file.data)) {
+    entries.push({ account, backend: "file", ...classifySerialized(serialized) });
+  }
+  return {
+    file: file.state,
+    ...("version" in file && file.version !== undefined ? { format_version: file.version } : {}),
+    ...(file.state === "corrupt" ? { file_error: file.error, salvageable: Object.keys(file.data).length } : {}),
+    ...(file.state === "unsupported" ? { file_error: file.error } : {}),
+    entries,
+  };
+}
+
+/**
+ * Drop only the entries verifyVault reports as corrupt, keeping everything
+ * else. A file vault that no longer decrypts is rewritten from its salvaged
+ * entries; when none survive it is left untouched and repair throws
+ * `decryption_failed`, since a wrong key looks the same. With `backup`, the original credentials.enc and any dropped
+ * keychain entries are first written (encrypted with the vault key) next to
+ * the vault, so nothing is lost that was still there before repair.
+ */
+export async function repairVault(opts: { backup?: boolean } = {}): Promise<VaultRepairResult> {
+  const stamp = new Date().toISOString().replace(/[:.]/g, "-");
+  const result: VaultRepairResult = { dropped: [], recovered: [] };
+
+  const droppedKeychain: Record<string, string> = {};
+  const fromKeytar = await callKeytar(async (client) => client.findCredentials ? client.findCredentials(SERVICE) : []);
+  if (fromKeytar !== KEYTAR_UNAVAILABLE) {
+    for (const { account, password } of fromKeytar) {
+      if (classifySerialized(password).status === "corrupt") droppedKeychain[account] = String(password);
+    }
+  }
+
+  await withVaultLock(() => {
+    const file = readVaultFileStrict();
+    // Nothing decrypts — most likely .key was regenerated rather than the
+    // file being damaged. Rewriting would replace every credential with an
+    // empty vault, so leave the file for the original key to read.
+    if (file.state === "corrupt" && Object.keys(file.data).length === 0) {
+      throw unbrowseError(
+        "decryption_failed",
+        `no entry in ${VAULT_FILE} decrypts with the current key (${file.error}); refusing to rewrite it — restore the original ${KEY_FILE} or move the file aside`,
+        { file_error: file.error },
+      );
+    }
+    if (opts.backup && (file.state === "corrupt" || Object.keys(droppedKeychain).length > 0 ||
+        Object.values(file.data).some((v) => classifySerialized(v).status === "corrupt"))) {
+      result.backup_path = join(VAULT_DIR, `credentials.enc.bak-${stamp}`);
+      if (existsSync(VAULT_FILE)) copyFileSync(VAULT_FILE, result.backup_path);
+      if (Object.keys(droppedKeychain).length > 0) {
+        writeVaultFile(droppedKeychain, join(VAULT_DIR, `keychain.enc.bak-${stamp}`));
+      }
+    }
+    if (file.state === "missing" || file.state === "unsupported") return;
+    const kept: Record<string, string> = {};
+    for (const [account, serialized] of Object.entries(file.data)) {
+      if (classifySerialized(serialized).status === "corrupt") result.dropped.push(account);
+      else kept[account] = serialized;
+    }
+    if (file.state === "corrupt") result.recovered.push(...Object.keys(kept));
+    if (file.state === "corrupt" || result.dropped.length > 0) writeVaultFile(kept);
+  });
+
+  for (const account of Object.keys(droppedKeychain)) {
+    await callKeytar((client) => client.deletePassword(SERVICE, account));
+    result.dropped.push(account);
+  }
+  log("vault", `repair: dropped ${result.dropped.length}, recovered ${result.recovered.length}${result.backup_path ? `, backup ${result.backup_path}` : ""}`);
+  return result;
+}
+
+// --- Portable encrypted backups ---
+//
+// Blob layout (base64 of):
+//   [0]      format version (EXPORT_VERSION)
+//   [1]      kdf id (1 = argon2id, 2 = scrypt where the runtime lacks argon2)
+//   [2..14)  kdf params, u32 BE x3 — argon2id: memory KiB, passes, parallelism; scrypt: N, r, p
+//   [14..30) salt
+//   [30..42) AES-256-GCM iv
+//   [42..58) GCM auth tag
+//   [58..]   ciphertext of { exported_at, entries: { account: serialized StoredCredential } }
+// The header is bound as GCM AAD, so tampering with params fails decryption.
+
+const EXPORT_VERSION = 1;
+const KDF_ARGON2ID = 1;
+const KDF_SCRYPT = 2;
+const EXPORT_HEADER_LEN = 30;
+const ARGON2_DEFAULTS = { memory: 64 * 1024, passes: 3, parallelism: 4 };
+const SCRYPT_DEFAULTS = { N: 1 << 15, r: 8, p: 1 };
+// Import reads params from an untrusted header; anything past these would let
+// a crafted blob pin the CPU or exhaust memory before the GCM tag is checked.
+const ARGON2_LIMITS = { memory: 1024 * 1024, passes: 10, parallelism: 16 };
+const SCRYPT_LIMITS = { N: 1 << 20, r: 8, p: 16 };
+
+type Argon2Sync = (algorithm: string, params: {
+  message: Buffer | string;
+  nonce: Buffer;
+  parallelism: number;
+  tagLength: number;
+  memory: number;
+  passes: number;
+}) => Buffer;
+
+/** node:crypto ships argon2 from Node 24.7; older runtimes fall back to scrypt. */
+function getArgon2(): Argon2Sync | null {
+  const fn = (nodeCrypto as unknown as { argon2Sync?: Argon2Sync }).argon2Sync;
+  return typeof fn === "function" ? fn : null;
+}
+
+function deriveExportKey(passphrase: string, kdf: number, params: [number, number, number], salt: Buffer): Buffer {
+  if (kdf === KDF_ARGON2ID) {
+    const argon2 = getArgon2();
+    if (!argon2) throw unbrowseError("decryption_failed", "vault backup uses argon2id, which this runtime does not support");
+    const [memory, passes, parallelism] = params;
+    return argon2("argon2id", { message: passphrase, nonce: salt, memory, passes, parallelism, tagLength: 32 });
+  }
+  if (kdf === KDF_SCRYPT) {
+    const [N, r, p] = params;
+    return scryptSync(passphrase, salt, 32, { N, r, p, maxmem: 256 * N * r + 1024 * 1024 });
+  }
+  throw unbrowseError("decryption_failed", `unknown vault backup kdf ${kdf}`);
+}
+
+/** Reject KDF params from an imported header that fall outside the fixed ceilings. */
+function assertExportKdfParams(kdf: number, params: [number, number, number]): void {
+  if (kdf === KDF_ARGON2ID) {
+    const [memory, passes, parallelism] = params;
+    if (parallelism < 1 || parallelism > ARGON2_LIMITS.parallelism
+      || passes < 1 || passes > ARGON2_LIMITS.passes
+      || memory < 8 * parallelism || memory > ARGON2_LIMITS.memory) {
+      throw unbrowseError("invalid_input", `vault backup argon2id params out of range (memory ${memory} KiB, passes ${passes}, parallelism ${parallelism})`);
+    }
+    return;
+  }
+  if (kdf === KDF_SCRYPT) {
+    const [N, r, p] = params;
+    if (N < 2 || N > SCRYPT_LIMITS.N || (N & (N - 1)) !== 0
+      || r < 1 || r > SCRYPT_LIMITS.r
+      || p < 1 || p > SCRYPT_LIMITS.p) {
+      throw unbrowseError("invalid_input", `vault backup scrypt params out of range (N ${N}, r ${r}, p ${p})`);
+    }
+    return;
+  }
+  throw unbrowseError("invalid_input", `unknown vault backup kdf ${kdf}`);
+}
+
+async function listSerializedCredentials(): Promise<Record<string, string>> {
+  const entries: Record<string, string> = {};
+  const fromKeytar = await callKeytar(async (client) => client.findCredentials ? client.findCredentials(SERVICE) : []);
+  if (fromKeytar !== KEYTAR_UNAVAILABLE) {
+    for (const { account, password } of fromKeytar) entries[account] = password;
+  }
+  // Credentials written while keytar was unavailable live in the file vault.
+  await withVaultLock(() => {
+    for (const [account, serialized] of Object.entries(readVaultFile())) {
+      entries[account] ??= serialized;
+    }
+  });
+  return entries;
+}
+
+/** Names of all stored credentials (keychain entries plus the file vault). */
+export async function listCredentialAccounts(): Promise<string[]> {
+  return Object.keys(await listSerializedCredentials());
+}
+
+/** Number of stored credentials. */
+export async function countCredentials(): Promise<number> {
+  return (await listCredentialAccounts()).length;
+}
+
+/**
+ * Accounts stored (or re-stored) at or after `since` (ISO 8601). Legacy
+ * raw entries carry no timestamp and are never included.
+ */
+export async function credentialsUpdatedSince(since: string): Promise<string[]> {
+  const cutoff = Date.parse(since);
+  if (Number.isNaN(cutoff)) throw unbrowseError("invalid_input", `Invalid timestamp: ${since}`);
+  return Object.entries(await listSerializedCredentials())
+    .filter(([, serialized]) => {
+      try {
+        const storedAt = Date.parse((JSON.parse(serialized) as StoredCredential).stored_at);
+        return !Number.isNaN(storedAt) && storedAt >= cutoff;
+      } catch {
+        return false;
+      }
+    })
+    .map(([account]) => account)
+    .sort();
+}
+
+/** `*` / `?` glob over account names; a pattern without wildcards is a prefix. */
+function accountMatcher(pattern: string): (account: string) => boolean {
+  if (!/[*?]/.test(pattern)) return (account) => account.startsWith(pattern);
+  const body = pattern.replace(/[.+^${}()|[\]\\]/g, "\\$&").replace(/\*/g, ".*").replace(/\?/g, ".");
+  const re = new RegExp(`^${body}$`);
+  return (account) => re.test(account);
+}
+
+/** Remove accounts from both backends — entries can sit in the file vault while keytar works. */
+async function deleteAccounts(accounts: string[]): Promise<void> {
+  for (const account of accounts) {
+    await callKeytar((client) => client.deletePassword(SERVICE, account));
+  }
+  await withVaultLock(() => {
+    const data = readVaultFile();
+    let changed = false;
+    for (const account of accounts) {
+      if (account in data) { delete data[account]; changed = true; }
+    }
+    if (changed) writeVaultFile(data);
+  });
+}
+
+/**
+ * Delete every credential whose account matches `pattern` — a glob
+ * (`acme-*-session`) or, without wildcards, a prefix (`acme`). Returns the
+ * number deleted.
+ */
+export async function deleteCredentialsMatching(pattern: string): Promise<number> {
+  if (!pattern.trim() || /^\*+$/.test(pattern.trim())) {
+    throw unbrowseError("invalid_input", "pattern must name something; use clearVault to delete everything");
+  }
+  const matches = accountMatcher(pattern.trim());
+  const accounts = (await listCredentialAccounts()).filter(matches);
+  await deleteAccounts(accounts);
+  log("vault", `deleted ${accounts.length} credential(s) matching ${pattern}`);
+  return accounts.length;
+}
+
+/** Confirmation clearVault requires, so a stray call can't wipe the vault. */
+export const VAULT_CLEAR_CONFIRMATION = "delete-all-credentials";
+
+/** Delete every stored credential. `confirm` must be VAULT_CLEAR_CONFIRMATION. Returns the number deleted. */
+export async function clearVault(confirm: string): Promise<number> {
+  if (confirm !== VAULT_CLEAR_CONFIRMATION) {
+    throw unbrowseError("invalid_input", `Refusing to clear the vault without confirmation "${VAULT_CLEAR_CONFIRMATION}"`);
+  }
+  const accounts = await listCredentialAccounts();
+  await deleteAccounts(accounts);
+  log("vault", `cleared ${accounts.length} credential(s)`);
+  return accounts.length;
+}
+
+/**
+ * Re-encrypt every stored credential under a passphrase-derived key and
+ * return a portable base64 blob, independent of the OS keychain and the
+ * machine-local vault key.
+ */
+export async function exportVaultEncrypted(passphrase: string): Promise<string> {
+  if (!passphrase) throw unbrowseError("invalid_input", "passphrase is required");
+  const entries = await listSerializedCredentials();
+  const kdf = getArgon2() ? KDF_ARGON2ID : KDF_SCRYPT;
+  const params: [number, number, number] = kdf === KDF_ARGON2ID
+    ? [ARGON2_DEFAULTS.memory, ARGON2_DEFAULTS.passes, ARGON2_DEFAULTS.parallelism]
+    : [SCRYPT_DEFAULTS.N, SCRYPT_DEFAULTS.r, SCRYPT_DEFAULTS.p];
+  const salt = randomBytes(16);
+  const header = Buffer.alloc(EXPORT_HEADER_LEN);
+  header.writeUInt8(EXPORT_VERSION, 0);
+  header.writeUInt8(kdf, 1);
+  header.writeUInt32BE(params[0], 2);
+  header.writeUInt32BE(params[1], 6);
+  header.writeUInt32BE(params[2], 10);
+  salt.copy(header, 14);
+
+  const key = deriveExportKey(passphrase, kdf, params, salt);
+  const iv = randomBytes(12);
+  const cipher = createCipheriv("aes-256-gcm", key, iv);
+  cipher.setAAD(header);
+  const payload = JSON.stringify({ exported_at: new Date().toISOString(), entries });
+  const enc = Buffer.concat([cipher.update(payload, "utf8"), cipher.final()]);
+  log("vault", `exported ${Object.keys(entries).length} credential(s)`);
+  return Buffer.concat([header, iv, cipher.getAuthTag(), enc]).toString("base64");
+}
+
+/**
+ * Decrypt a blob from exportVaultEncrypted and store its credentials under
+ * the local vault. Existing accounts with the same name are overwritten.
+ * Returns the imported account names.
+ */
+export async function importVaultEncrypted(blob: string, passphrase: string): Promise<string[]> {
+  const raw = Buffer.from(blob.trim(), "base64");
+  if (raw.length < EXPORT_HEADER_LEN + 28) throw unbrowseError("invalid_input", "vault backup is truncated or not base64");
+  const version = raw.readUInt8(0);
+  if (version !== EXPORT_VERSION) throw unbrowseError("invalid_input", `unsupported vault backup version ${version}`);
+  const header = raw.subarray(0, EXPORT_HEADER_LEN);
+  const kdf = raw.readUInt8(1);
+  const params: [number, number, number] = [raw.readUInt32BE(2), raw.readUInt32BE(6), raw.readUInt32BE(10)];
+  assertExportKdfParams(kdf, params);
+  const salt = raw.subarray(14, EXPORT_HEADER_LEN);
+  const iv = raw.subarray(EXPORT_HEADER_LEN, EXPORT_HEADER_LEN + 12);
+  const tag = raw.subarray(EXPORT_HEADER_LEN + 12, EXPORT_HEADER_LEN + 28);
+  const enc = raw.subarray(EXPORT_HEADER_LEN + 28);
+
+  let entries: Record<string, string>;
+  try {
+    const key = deriveExportKey(passphrase, kdf, params, salt);
+    const decipher = createDecipheriv("aes-256-gcm", key, iv);
+    decipher.setAAD(header);
+    decipher.setAuthTag(tag);
+    const dec = Buffer.concat([decipher.update(enc), decipher.final()]);
+    entries = (JSON.parse(dec.toString("utf8")) as { entries?: Record<string, string> }).entries ?? {};
+  } catch (error) {
+    if ((error as { code?: unknown }).code === "decryption_failed") throw error;
+    throw unbrowseError("decryption_failed", "could not decrypt vault backup (wrong passphrase or corrupted blob)");
+  }
+
+  const imported: string[] = [];
+  for (const [account, serialized] of Object.entries(entries)) {
+    if (typeof serialized !== "string") continue;
+    await storeSerialized(account, serialized);
+    imported.push(account);
+  }
+  log("vault", `imported ${imported.length} credential(s)`);
+  return imported;
+}
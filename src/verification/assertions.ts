@@ -0,0 +1,80 @@
+/**
+ * Response assertions for endpoint verification.
+ *
+ * Status and schema checks pass an endpoint that answers 200 with
+ * `{"error": "..."}` or an empty list. An assertion names a path into the
+ * parsed body and a condition the data there must meet, so a test call
+ * only counts when the expected data actually came back.
+ */
+
+import { resolvePath } from "../transform/index.js";
+
+export type AssertionOp = "exists" | "not_empty" | "equals" | "gt";
+
+export interface ResponseAssertion {
+  /** Dot path with `[]` array expansion, as in field projection (`data.items[].id`). `$` or "" is the whole body. */
+  path: string;
+  op: AssertionOp;
+  /** Expected value for `equals`; threshold for `gt` (compared to a number, or to the length of an array/string) */
+  value?: unknown;
+}
+
+export interface AssertionResult {
+  path: string;
+  op: AssertionOp;
+  passed: boolean;
+  /** What the path resolved to (first match), for diagnosing failures */
+  actual?: unknown;
+}
+
+function valuesAt(body: unknown, path: string): unknown[] {
+  const trimmed = path.trim().replace(/^\$\.?/, "");
+  if (!trimmed) return [body];
+  return resolvePath(body, trimmed).filter((v) => v !== undefined && v !== null);
+}
+
+function isNonEmpty(value: unknown): boolean {
+  if (Array.isArray(value) || typeof value === "string") return value.length > 0;
+  if (value && typeof value === "object") return Object.keys(value).length > 0;
+  return value !== undefined && value !== null;
+}
+
+function measure(value: unknown): number | null {
+  if (typeof value === "number") return value;
+  if (Array.isArray(value) || typeof value === "string") return value.length;
+  return null;
+}
+
+/** Evaluate one assertion. With `[]` expansion the assertion holds if any matched value satisfies it. */
+export function evaluateAssertion(body: unknown, assertion: ResponseAssertion): AssertionResult {
+  const values = valuesAt(body, assertion.path);
+  let passed: boolean;
+  switch (assertion.op) {
+    case "exists":
+      passed = values.length > 0;
+      break;
+    case "not_empty":
+      passed = values.some(isNonEmpty);
+      break;
+    case "equals": {
+      const expected = JSON.stringify(assertion.value);
+      passed = values.some((v) => JSON.stringify(v) === expected);
+      break;
+    }
+    case "gt": {
+      const threshold = Number(assertion.value ?? 0);
+      passed = values.some((v) => {
+        const n = measure(v);
+        return n !== null && n > threshold;
+      });
+      break;
+    }
+    default:
+      passed = false;
+  }
+  return { path: assertion.path, op: assertion.op, passed, ...(values.length > 0 ? { actual: values[0] } : {}) };
+}
+
+export function evaluateAssertions(body: unknown, assertions: ResponseAssertion[]): AssertionResult[] {
+  return assertions.map((a) => evaluateAssertion(body, a));
+}
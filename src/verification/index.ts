@@ -1,37 +1,169 @@
 import { executeInBrowser } from "../capture/index.js";
 import { updateEndpointScore } from "../marketplace/index.js";
-import { listSkills, getSkill } from "../marketplace/index.js";
+import { listSkills } from "../marketplace/index.js";
 import { detectSchemaDrift } from "../transform/drift.js";
 import { computeVerificationCoverage, INITIAL_MATRIX } from "./matrix.js";
+import { evaluateAssertions, type AssertionResult, type ResponseAssertion } from "./assertions.js";
+import { parseRateLimitHeaders, parseRetryAfter, waitForHostSlot } from "../execution/retry.js";
+import { checkRobots } from "../execution/robots.js";
+import { log } from "../logger.js";
+import type { KuriCookie } from "../kuri/client.js";
 import type { VerificationMatrix } from "./matrix.js";
+export type { AssertionOp, AssertionResult, ResponseAssertion } from "./assertions.js";
 import type { EndpointDescriptor, SkillManifest, VerificationStatus } from "../types/index.js";
 
+export interface VerifyOptions {
+  /** Minimum spacing between requests to the same host. */
+  min_interval_ms?: number;
+  /** Skip (leave status unchanged) endpoints disallowed by robots.txt. */
+  respect_robots?: boolean;
+  /**
+   * Carry cookies set by one endpoint into the following ones, like a browser
+   * session — avoids false 401s when a later endpoint depends on a session an
+   * earlier call established. Only applies to verifySkill batches.
+   */
+  follow_cookies?: boolean;
+  /** Called when an endpoint's test call was redirected. */
+  on_redirect?: (endpointId: string, info: RedirectInfo) => void;
+  /**
+   * Response assertions per endpoint_id, checked against the parsed body of
+   * a 2xx test call. Any failure marks the endpoint failed — it answered,
+   * but not with the data it is supposed to return.
+   */
+  assertions?: Record<string, ResponseAssertion[]>;
+  /** Called with each endpoint's assertion results (only for endpoints that had assertions). */
+  on_assertions?: (endpointId: string, results: AssertionResult[]) => void;
+}
+
+/** Where a redirected test call ended up. */
+export interface RedirectInfo {
+  final_url: string;
+  /** Final host differs from the requested one — typically an SSO bounce */
+  cross_host: boolean;
+  /** Final path looks like a login page */
+  login_page: boolean;
+}
+
+const LOGIN_PATHS = /\/(login|signin|sign-in|sso|auth|uas\/login|checkpoint|oauth)(?=\/|$|\?|\.)/i;
+
+/**
+ * Redirect details for a test call, or null when it wasn't redirected.
+ * A bounce to another host or a login page means the credentials were
+ * rejected, even though the final hop answered 200.
+ */
+export function classifyRedirect(requestedUrl: string, result: { final_url?: string; redirected?: boolean }): RedirectInfo | null {
+  if (!result.final_url || (!result.redirected && result.final_url === requestedUrl)) return null;
+  try {
+    const requested = new URL(requestedUrl);
+    const final = new URL(result.final_url);
+    if (final.href === requested.href) return null;
+    return {
+      final_url: final.href,
+      cross_host: final.hostname !== requested.hostname,
+      login_page: LOGIN_PATHS.test(final.pathname) && !LOGIN_PATHS.test(requested.pathname),
+    };
+  } catch {
+    return null;
+  }
+}
+
+/** Cookies collected across a verification batch, keyed by name + domain + path. */
+type CookieJar = Map<string, KuriCookie>;
+
+function storeCookies(jar: CookieJar, cookies: KuriCookie[] | undefined): void {
+  const now = Date.now() / 1000;
+  for (const c of cookies ?? []) {
+    const key = `${c.name}|${c.domain}|${c.path ?? "/"}`;
+    if (c.expires != null && c.expires > 0 && c.expires < now) jar.delete(key);
+    else jar.set(key, c);
+  }
+}
+
+const DEFAULT_RETRY_AFTER_MS = 1000;
+const MAX_RETRY_AFTER_MS = 30_000;
+
 /**
  * Verify a single endpoint by test-executing safe (GET) endpoints.
- * Returns the new verification status.
+ * A 429 is retried once after the server's Retry-After delay.
+ * With a cookie jar, its cookies are sent and whatever the call sets is
+ * stored back into it. Returns the new verification status; what the call
+ * learned (last_verified_at, supports_conditional, rate_limit) is written
+ * onto `endpoint` for the caller to persist.
  */
 export async function verifyEndpoint(
   skill: SkillManifest,
-  endpoint: EndpointDescriptor
+  endpoint: EndpointDescriptor,
+  opts: VerifyOptions = {},
+  jar?: CookieJar,
 ): Promise<VerificationStatus> {
   // Only verify safe (GET) endpoints automatically
   if (endpoint.method !== "GET") return endpoint.verification_status;
 
+  if (opts.respect_robots) {
+    let allowed = true;
+    try {
+      const target = new URL(endpoint.url_template);
+      allowed = await checkRobots(target.origin, target.pathname);
+    } catch { /* unparseable template — let execution decide */ }
+    if (!allowed) {
+      log("verify", `robots.txt disallows ${endpoint.url_template} — skipping`);
+      return endpoint.verification_status;
+    }
+  }
+
   try {
-    const { status, data } = await executeInBrowser(
-      endpoint.url_template,
-      endpoint.method,
-      endpoint.headers_template ?? {},
-      undefined,
-      undefined,
-      undefined
-    );
+    const run = async (extraHeaders: Record<string, string> = {}) => {
+      await waitForHostSlot(endpoint.url_template, opts.min_interval_ms ?? 0);
+      // A tunnelled GET goes out as its wire method, with the body carrying `_method`.
+      const result = await executeInBrowser(
+        endpoint.url_template,
+        endpoint.wire_method ?? endpoint.method,
+        { ...(endpoint.headers_template ?? {}), ...extraHeaders },
+        endpoint.wire_method ? endpoint.body : undefined,
+        undefined,
+        jar && jar.size > 0 ? [...jar.values()] : undefined,
+        jar !== undefined,
+      );
+      if (jar) storeCookies(jar, result.cookies);
+      return result;
+    };
+    let result = await run();
+    if (result.status === 429) {
+      const delay = Math.min(parseRetryAfter(result.retry_after) ?? DEFAULT_RETRY_AFTER_MS, MAX_RETRY_AFTER_MS);
+      log("verify", `429 from ${endpoint.url_template} — retrying once in ${delay}ms`);
+      await new Promise((r) => setTimeout(r, delay));
+      result = await run();
+    }
+    const { status, data } = result;
+    // Recorded whatever the outcome — a failing endpoint's limits are still its limits.
+    const rateLimit = parseRateLimitHeaders(result.rate_limit_headers);
+    if (rateLimit) endpoint.rate_limit = rateLimit;
+
+    const redirect = classifyRedirect(endpoint.url_template, result);
+    if (redirect) opts.on_redirect?.(endpoint.endpoint_id, redirect);
+    if (redirect && (redirect.cross_host || redirect.login_page)) {
+      log("verify", `${endpoint.url_template} redirected to ${redirect.final_url} — credentials likely invalid`);
+      await updateEndpointScore(skill.skill_id, endpoint.endpoint_id, endpoint.reliability_score, "failed");
+      return "failed";
+    }
 
     if (status < 200 || status >= 300) {
       await updateEndpointScore(skill.skill_id, endpoint.endpoint_id, endpoint.reliability_score, "failed");
       return "failed";
     }
 
+    const assertions = opts.assertions?.[endpoint.endpoint_id];
+    if (assertions?.length) {
+      const outcomes = evaluateAssertions(data, assertions);
+      opts.on_assertions?.(endpoint.endpoint_id, outcomes);
+      const failed = outcomes.filter((o) => !o.passed);
+      if (failed.length > 0) {
+        log("verify", `${endpoint.url_template} failed ${failed.length}/${outcomes.length} assertions (${failed.map((f) => `${f.path} ${f.op}`).join(", ")})`);
+        await updateEndpointScore(skill.skill_id, endpoint.endpoint_id, endpoint.reliability_score, "failed");
+        return "failed";
+      }
+    }
+
     // Check for schema drift if we have a response schema
     let hasCriticalDrift = false;
     if (endpoint.response_schema && data != null) {
@@ -41,18 +173,17 @@ export async function verifyEndpoint(
       }
     }
 
+    const supportsConditional = await probeConditional(result, run);
+
     const newStatus: VerificationStatus = hasCriticalDrift ? "pending" : "verified";
     // Reset score for recovered disabled endpoints so they become usable again
     const newScore = endpoint.verification_status === "disabled" && newStatus === "verified"
       ? 0.5
       : endpoint.reliability_score;
     await updateEndpointScore(skill.skill_id, endpoint.endpoint_id, newScore, newStatus);
-    // Update last_verified_at
-    const fullSkill = await getSkill(skill.skill_id);
-    if (fullSkill) {
-      const ep = fullSkill.endpoints.find((e) => e.endpoint_id === endpoint.endpoint_id);
-      if (ep) ep.last_verified_at = new Date().toISOString();
-    }
+    // Recorded on the caller's endpoint — the caller persists the skill.
+    endpoint.last_verified_at = new Date().toISOString();
+    if (supportsConditional !== undefined) endpoint.supports_conditional = supportsConditional;
     return newStatus;
   } catch {
     await updateEndpointScore(skill.skill_id, endpoint.endpoint_id, endpoint.reliability_score, "failed");
@@ -60,16 +191,39 @@ export async function verifyEndpoint(
   }
 }
 
+/**
+ * Re-request with the first response's validators and report whether the
+ * server answers 304. One extra request; skipped (undefined) when the
+ * response had neither ETag nor Last-Modified.
+ */
+async function probeConditional(
+  first: { etag?: string; last_modified?: string },
+  run: (extraHeaders: Record<string, string>) => Promise<{ status: number }>,
+): Promise<boolean | undefined> {
+  if (!first.etag && !first.last_modified) return undefined;
+  try {
+    const { status } = await run({
+      ...(first.etag ? { "If-None-Match": first.etag } : {}),
+      ...(first.last_modified ? { "If-Modified-Since": first.last_modified } : {}),
+    });
+    return status === 304;
+  } catch {
+    return undefined;
+  }
+}
+
 /**
  * Verify all safe endpoints in a skill.
  * Returns a map of endpoint_id -> verification status.
  */
 export async function verifySkill(
-  skill: SkillManifest
+  skill: SkillManifest,
+  opts: VerifyOptions = {},
 ): Promise<Record<string, VerificationStatus>> {
   const results: Record<string, VerificationStatus> = {};
+  const jar: CookieJar | undefined = opts.follow_cookies ? new Map() : undefined;
   for (const endpoint of skill.endpoints) {
-    results[endpoint.endpoint_id] = await verifyEndpoint(skill, endpoint);
+    results[endpoint.endpoint_id] = await verifyEndpoint(skill, endpoint, opts, jar);
   }
   return results;
 }
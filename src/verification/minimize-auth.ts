@@ -0,0 +1,120 @@
+/**
+ * Auth minimization — which captured credentials does the server check?
+ *
+ * A capture stores every auth-like header and cookie the browser happened
+ * to send. minimizeAuth replays a safe request with the full set, then
+ * greedily drops one header or cookie at a time, keeping each drop whose
+ * probe still answers 2xx. What survives is a subset the endpoint still
+ * accepts — usually one token and a session cookie instead of a dozen.
+ */
+
+import { log } from "../logger.js";
+import { parseRetryAfter, waitForHostSlot } from "../execution/retry.js";
+
+export interface MinimizeAuthOptions {
+  /** Safe method to probe with (default GET) */
+  method?: string;
+  /** Upper bound on requests, baseline included (default 20) */
+  max_probes?: number;
+  /** Minimum spacing between probes to the host (default 250ms) */
+  min_interval_ms?: number;
+  /** Non-auth headers sent on every probe (accept, version headers, ...) */
+  base_headers?: Record<string, string>;
+}
+
+export interface MinimizeAuthResult {
+  /** Status with every header and cookie sent */
+  baseline_status: number;
+  /** The full set didn't get a 2xx, so nothing was dropped */
+  baseline_failed: boolean;
+  /** Headers the server still needed */
+  headers: Record<string, string>;
+  /** Cookie names the server still needed */
+  cookies: string[];
+  /** Header names (`header:`) and cookie names (`cookie:`) that made no difference */
+  dropped: string[];
+  probes: number;
+  /** Ran out of probes (or hit a persistent 429) before every candidate was tried */
+  truncated: boolean;
+}
+
+type Cookie = { name: string; value: string };
+
+const DEFAULT_MAX_PROBES = 20;
+const DEFAULT_MIN_INTERVAL_MS = 250;
+const MAX_RETRY_AFTER_MS = 30_000;
+
+/**
+ * Probe `url` to find which of `headers` and `cookies` it actually checks.
+ * Candidates are tried in order (headers, then cookies); each probe sends
+ * the current set minus one candidate, so the result is minimal for that
+ * order. Probes are spaced per host and a 429 is waited out once.
+ */
+export async function minimizeAuth(
+  url: string,
+  headers: Record<string, string>,
+  cookies: Cookie[] = [],
+  options: MinimizeAuthOptions = {},
+): Promise<MinimizeAuthResult> {
+  const method = (options.method ?? "GET").toUpperCase();
+  const maxProbes = Math.max(1, options.max_probes ?? DEFAULT_MAX_PROBES);
+  const minInterval = options.min_interval_ms ?? DEFAULT_MIN_INTERVAL_MS;
+  let probes = 0;
+  let rateLimited = false;
+
+  const send = async (keptHeaders: Record<string, string>, keptCookies: Cookie[]): Promise<number> => {
+    const requestHeaders: Record<string, string> = { ...(options.base_headers ?? {}), ...keptHeaders };
+    if (keptCookies.length > 0) requestHeaders.cookie = keptCookies.map((c) => `${c.name}=${c.value}`).join("; ");
+    for (let attempt = 0; ; attempt++) {
+      await waitForHostSlot(url, minInterval);
+      probes++;
+      // A login bounce must count as rejection, not as the login page's 200.
+      const res = await fetch(url, { method, headers: requestHeaders, redirect: "manual" });
+      await res.body?.cancel().catch(() => {});
+      if (res.status !== 429 || attempt > 0 || probes >= maxProbes) {
+        if (res.status === 429) rateLimited = true;
+        return res.status;
+      }
+      const delay = Math.min(parseRetryAfter(res.headers.get("retry-after")) ?? 1000, MAX_RETRY_AFTER_MS);
+      log("verify", `429 while minimizing auth for ${url} — waiting ${delay}ms`);
+      await new Promise((r) => setTimeout(r, delay));
+    }
+  };
+  const ok = (status: number) => status >= 200 && status < 300;
+
+  const keptHeaders = { ...headers };
+  let keptCookies = [...cookies];
+  const baseline = await send(keptHeaders, keptCookies);
+  const result = (truncated: boolean, dropped: string[]): MinimizeAuthResult => ({
+    baseline_status: baseline,
+    baseline_failed: !ok(baseline),
+    headers: keptHeaders,
+    cookies: keptCookies.map((c) => c.name),
+    dropped,
+    probes,
+    truncated,
+  });
+  if (!ok(baseline)) return result(false, []);
+
+  const candidates = [
+    ...Object.keys(headers).map((name) => ({ kind: "header" as const, name })),
+    ...cookies.map((c) => ({ kind: "cookie" as const, name: c.name })),
+  ];
+  const dropped: string[] = [];
+  for (const [i, candidate] of candidates.entries()) {
+    if (probes >= maxProbes || rateLimited) {
+      log("verify", `auth minimization for ${url} stopped after ${probes} probes; ${candidates.length - i} candidate(s) untried`);
+      return result(true, dropped);
+    }
+    const trialHeaders = { ...keptHeaders };
+    let trialCookies = keptCookies;
+    if (candidate.kind === "header") delete trialHeaders[candidate.name];
+    else trialCookies = keptCookies.filter((c) => c.name !== candidate.name);
+    if (!ok(await send(trialHeaders, trialCookies))) continue;
+    if (candidate.kind === "header") delete keptHeaders[candidate.name];
+    else keptCookies = trialCookies;
+    dropped.push(`${candidate.kind}:${candidate.name}`);
+  }
+  log("verify", `auth for ${url}: kept ${Object.keys(keptHeaders).length} header(s), ${keptCookies.length} cookie(s); dropped ${dropped.length} in ${probes} probes`);
+  return result(false, dropped);
+}
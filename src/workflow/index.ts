@@ -0,0 +1,521 @@
+/**
+ * Sequential workflows composed of skill executions.
+ *
+ * A workflow is an ordered list of steps sharing one variable map. Step
+ * inputs may reference variables as `{{name}}`; each step's `extract` copies
+ * values out of its result (dot paths, see transform/resolvePath) back into
+ * the map for later steps. Execution stops at the first failed step.
+ *
+ * A step that fails with 401/403 after executeEndpoint refreshed credentials
+ * (see SkillManifest.auth_refresh) is retried once; both attempts are kept in
+ * step_results.
+ *
+ * Calls to unsafe (mutating) endpoints carry an `Idempotency-Key` header so
+ * that retry, and re-running the same workflow with the same inputs, can be
+ * deduplicated. Only servers that honor the header dedupe; it is sent anyway.
+ *
+ * Skill-call steps may set `headers` / `header_overrides` and
+ * `remove_headers`; they apply over the endpoint's auth, so one step can
+ * swap or drop a stored credential without affecting the others.
+ *
+ * `http_request` steps send a literal request instead of calling a skill —
+ * used for credential-acquisition flows (see workflow/login.ts). Cookies set
+ * by one http_request step are sent by the later ones in the same run, to
+ * hosts and paths matching their Domain/Path like a browser would.
+ *
+ * A run can be bounded by `timeout_ms` and cancelled through
+ * WorkflowOptions.signal; either aborts the in-flight step, which is recorded
+ * as the interrupted step, and sets WorkflowResult.status accordingly.
+ */
+
+import { createHash } from "node:crypto";
+
+import { getRecentLocalSkill, getSkill } from "../client/index.js";
+import { executeSkill } from "../execution/index.js";
+import { checkPaymentRequirement } from "../payments/index.js";
+import { checkWalletConfigured, getWalletBalance } from "../payments/wallet.js";
+import { resolvePath } from "../transform/index.js";
+import { isDomainMatch } from "../domain.js";
+import { getErrorCode, type UnbrowseErrorCode } from "../errors.js";
+import type { SkillManifest } from "../types/index.js";
+
+/** Run an endpoint of an already-published (or locally cached) skill. */
+export interface SkillCallStep {
+  type: "skill_call";
+  skill_id: string;
+  endpoint_id: string;
+  /** Endpoint params; string values may reference `{{variable}}` */
+  inputs?: Record<string, unknown>;
+  /** variable name → dot path into the step result */
+  extract?: Record<string, string>;
+  /** Required for unsafe (mutating) endpoints */
+  confirm_unsafe?: boolean;
+  /** Idempotency-Key for unsafe endpoints; may reference `{{variable}}`.
+   *  Defaults to a hash of the step position, target, and resolved inputs. */
+  idempotency_key?: string;
+  /**
+   * Per-step headers; values may reference `{{variable}}`. Precedence is
+   * auth headers < `headers` < `header_overrides` < `remove_headers`, so a
+   * step can replace or drop a stored credential without a second auth map.
+   */
+  headers?: Record<string, string>;
+  header_overrides?: Record<string, string>;
+  /** Header names to leave off this call, auth included (e.g. `authorization` for a public endpoint) */
+  remove_headers?: string[];
+}
+
+/** Send one literal HTTP request. url, headers, and body may reference `{{variable}}`. */
+export interface HttpRequestStep {
+  type: "http_request";
+  method: string;
+  url: string;
+  headers?: Record<string, string>;
+  /** Objects are form-encoded when content-type says so, JSON otherwise */
+  body?: unknown;
+  /**
+   * variable name → source: `cookie:<name>` (Set-Cookie or jar), `header:<name>`,
+   * `regex:<pattern>` (first capture group over the body text), or a dot path
+   * into the JSON body.
+   */
+  extract?: Record<string, string>;
+  /**
+   * Status the recorded call returned. A different status is reported as a
+   * divergence (WorkflowStepResult.diverged); it only fails the step when
+   * the new status is itself an error.
+   */
+  expect_status?: number;
+}
+
+export type WorkflowStep = SkillCallStep | HttpRequestStep;
+
+export interface WorkflowDefinition {
+  name?: string;
+  steps: WorkflowStep[];
+  /** Initial variable map */
+  variables?: Record<string, unknown>;
+  /** Overall limit for the whole run, including the in-flight step */
+  timeout_ms?: number;
+}
+
+export interface WorkflowStepResult {
+  step_index: number;
+  type: WorkflowStep["type"];
+  ok: boolean;
+  /** http_request steps */
+  url?: string;
+  skill_id?: string;
+  endpoint_id?: string;
+  status_code?: number;
+  trace_id?: string;
+  error?: string;
+  error_code?: UnbrowseErrorCode;
+  result?: unknown;
+  /** 1 for the first call; 2 for the retry after a 401/403 credential refresh */
+  attempt?: number;
+  /** Idempotency-Key sent with the call (unsafe endpoints only) */
+  idempotency_key?: string;
+  /** http_request steps with expect_status */
+  expected_status?: number;
+  /** status_code differs from expected_status */
+  diverged?: boolean;
+}
+
+export type WorkflowStatus = "completed" | "failed" | "timed_out" | "cancelled";
+
+export interface WorkflowResult {
+  ok: boolean;
+  status: WorkflowStatus;
+  variables: Record<string, unknown>;
+  step_results: WorkflowStepResult[];
+  /** Step that was running when the run timed out or was cancelled */
+  interrupted_step?: number;
+  /** First step whose status differed from the recorded one (expect_status) */
+  diverged_step?: number;
+}
+
+export interface WorkflowOptions {
+  client_scope?: string;
+  /** Cancels the run (e.g. the HTTP client went away) */
+  signal?: AbortSignal;
+}
+
+/** JSON with object keys sorted, so equal inputs hash equally. */
+function stableStringify(value: unknown): string {
+  if (Array.isArray(value)) return `[${value.map(stableStringify).join(",")}]`;
+  if (value && typeof value === "object") {
+    const entries = Object.entries(value as Record<string, unknown>)
+      .filter(([, v]) => v !== undefined)
+      .sort(([a], [b]) => (a < b ? -1 : a > b ? 1 : 0));
+    return `{${entries.map(([k, v]) => `${JSON.stringify(k)}:${stableStringify(v)}`).join(",")}}`;
+  }
+  return JSON.stringify(value) ?? "null";
+}
+
+/** Deterministic key: same workflow position + target + inputs → same key across runs. */
+export function defaultIdempotencyKey(
+  workflowName: string | undefined,
+  stepIndex: number,
+  step: SkillCallStep,
+  inputs: Record<string, unknown>,
+): string {
+  const material = stableStringify({
+    workflow: workflowName ?? "",
+    step: stepIndex,
+    skill_id: step.skill_id,
+    endpoint_id: step.endpoint_id,
+    inputs,
+  });
+  return createHash("sha256").update(material).digest("hex").slice(0, 32);
+}
+
+const WHOLE_VAR = /^\{\{\s*([\w.-]+)\s*\}\}$/;
+const INLINE_VAR = /\{\{\s*([\w.-]+)\s*\}\}/g;
+
+/**
+ * Substitute `{{var}}` references. A string that is exactly one reference
+ * keeps the variable's type; embedded references are stringified.
+ */
+export function substituteWorkflowVariables(value: unknown, variables: Record<string, unknown>): unknown {
+  if (typeof value === "string") {
+    const whole = value.match(WHOLE_VAR);
+    if (whole) return whole[1] in variables ? variables[whole[1]] : value;
+    return value.replace(INLINE_VAR, (match, name: string) => {
+      if (!(name in variables)) return match;
+      const v = variables[name];
+      return typeof v === "string" ? v : JSON.stringify(v);
+    });
+  }
+  if (Array.isArray(value)) return value.map((item) => substituteWorkflowVariables(item, variables));
+  if (value && typeof value === "object") {
+    return Object.fromEntries(
+      Object.entries(value as Record<string, unknown>).map(([k, v]) => [k, substituteWorkflowVariables(v, variables)]),
+    );
+  }
+  return value;
+}
+
+function extractVariables(result: unknown, extract: Record<string, string> | undefined, variables: Record<string, unknown>): void {
+  for (const [name, path] of Object.entries(extract ?? {})) {
+    const values = resolvePath(result, path);
+    if (values.length === 0) continue;
+    variables[name] = path.includes("[]") ? values : values[0];
+  }
+}
+
+async function loadSkill(skillId: string, clientScope?: string): Promise<SkillManifest | null> {
+  return getRecentLocalSkill(skillId, clientScope) ?? await getSkill(skillId, clientScope);
+}
+
+/** Payment gate for paid marketplace skills; returns a message when the step cannot proceed. */
+async function checkSkillPayment(skill: SkillManifest, endpointId: string): Promise<string | null> {
+  if (!skill.base_price_usd || skill.base_price_usd <= 0) return null;
+  const walletCheck = checkWalletConfigured();
+  const usdcBalance = walletCheck.configured
+    ? await getWalletBalance().then((b) => b.usdc).catch(() => undefined)
+    : undefined;
+  const gate = await checkPaymentRequirement(skill.skill_id, endpointId, {
+    price_usd: String(skill.base_price_usd),
+    wallet_configured: walletCheck.configured,
+    usdc_balance: usdcBalance,
+  });
+  if (gate.status === "free" || gate.status === "paid") return null;
+  return gate.next_step ? `${gate.message} ${gate.next_step}` : gate.message;
+}
+
+async function runSkillCall(
+  step: SkillCallStep,
+  stepIndex: number,
+  variables: Record<string, unknown>,
+  options: WorkflowOptions,
+  signal: AbortSignal,
+  workflowName?: string,
+): Promise<WorkflowStepResult[]> {
+  const base = { step_index: stepIndex, type: step.type, skill_id: step.skill_id, endpoint_id: step.endpoint_id };
+  const skill = await loadSkill(step.skill_id, options.client_scope);
+  if (!skill) return [{ ...base, ok: false, error: `Skill ${step.skill_id} not found`, error_code: "invalid_input" }];
+  const endpoint = skill.endpoints.find((ep) => ep.endpoint_id === step.endpoint_id);
+  if (!endpoint) {
+    return [{ ...base, ok: false, error: `Endpoint ${step.endpoint_id} not found in skill ${step.skill_id}`, error_code: "invalid_input" }];
+  }
+
+  const paymentBlock = await checkSkillPayment(skill, step.endpoint_id);
+  if (paymentBlock) {
+    return [{ ...base, ok: false, error: `Payment required for ${skill.skill_id}: ${paymentBlock}`, error_code: "payment_required" }];
+  }
+
+  const inputs = substituteWorkflowVariables(step.inputs ?? {}, variables) as Record<string, unknown>;
+  // Computed once so the auth-refresh retry below reuses the same key.
+  const idempotencyKey = endpoint.idempotency === "unsafe"
+    ? (step.idempotency_key != null
+        ? String(substituteWorkflowVariables(step.idempotency_key, variables))
+        : defaultIdempotencyKey(workflowName, stepIndex, step, inputs))
+    : undefined;
+  const headerOverrides = substituteWorkflowVariables({ ...(step.headers ?? {}), ...(step.header_overrides ?? {}) }, variables) as Record<string, string>;
+  const results: WorkflowStepResult[] = [];
+  for (let attempt = 1; attempt <= 2; attempt++) {
+    const { trace, result } = await executeSkill(
+      skill,
+      { ...inputs, endpoint_id: step.endpoint_id },
+      undefined,
+      {
+        client_scope: options.client_scope,
+        confirm_unsafe: step.confirm_unsafe,
+        signal,
+        ...(idempotencyKey ? { extra_headers: { "Idempotency-Key": idempotencyKey } } : {}),
+        ...(Object.keys(headerOverrides).length > 0 ? { header_overrides: headerOverrides } : {}),
+        ...(step.remove_headers?.length ? { remove_headers: step.remove_headers } : {}),
+      },
+    );
+    const stepResult: WorkflowStepResult = {
+      ...base,
+      ok: trace.success,
+      status_code: trace.status_code,
+      trace_id: trace.trace_id,
+      result,
+      attempt,
+      ...(idempotencyKey ? { idempotency_key: idempotencyKey } : {}),
+    };
+    if (trace.status_code === 402) {
+      results.push({ ...stepResult, ok: false, error: `Payment required by ${skill.domain} (HTTP 402)`, error_code: "payment_required" });
+      return results;
+    }
+    if (trace.success) {
+      extractVariables(result, step.extract, variables);
+      results.push(stepResult);
+      return results;
+    }
+    results.push({ ...stepResult, error: trace.error ?? `HTTP ${trace.status_code ?? "error"}`, error_code: "api_error" });
+    // executeEndpoint already refreshed credentials (auth_refresh token grant,
+    // session refresh, or browser re-extract) — retry the call once with them.
+    const authExpired = trace.status_code === 401 || trace.status_code === 403;
+    if (!authExpired || !trace.auth_refreshed) return results;
+  }
+  return results;
+}
+
+/** A Set-Cookie from an http_request step, scoped the way a browser would scope it. */
+interface JarCookie {
+  name: string;
+  value: string;
+  domain: string;
+  /** No Domain attribute: sent back to the exact host only */
+  host_only: boolean;
+  path: string;
+  secure: boolean;
+}
+
+/** Keyed by domain + path + name, so same-named cookies from different hosts don't collide. */
+type WorkflowCookieJar = Map<string, JarCookie>;
+
+function defaultCookiePath(pathname: string): string {
+  const slash = pathname.lastIndexOf("/");
+  return slash <= 0 ? "/" : pathname.slice(0, slash);
+}
+
+/** Store one Set-Cookie line from a response to `url`; expired cookies are removed. */
+function storeSetCookie(jar: WorkflowCookieJar, url: URL, line: string): void {
+  const [pair, ...attrs] = line.split(";");
+  const eq = pair.indexOf("=");
+  if (eq <= 0) return;
+  const cookie: JarCookie = {
+    name: pair.slice(0, eq).trim(),
+    value: pair.slice(eq + 1).trim(),
+    domain: url.hostname.toLowerCase(),
+    host_only: true,
+    path: defaultCookiePath(url.pathname),
+    secure: false,
+  };
+  let expired = false;
+  for (const attr of attrs) {
+    const at = attr.indexOf("=");
+    const key = (at === -1 ? attr : attr.slice(0, at)).trim().toLowerCase();
+    const value = at === -1 ? "" : attr.slice(at + 1).trim();
+    if (key === "domain" && value) {
+      const domain = value.replace(/^\./, "").toLowerCase();
+      // A response may only scope a cookie to its own host or a parent of it.
+      if (!isDomainMatch(domain, cookie.domain)) return;
+      cookie.domain = domain;
+      cookie.host_only = false;
+    } else if (key === "path" && value.startsWith("/")) {
+      cookie.path = value;
+    } else if (key === "secure") {
+      cookie.secure = true;
+    } else if (key === "max-age" && Number(value) <= 0) {
+      expired = true;
+    } else if (key === "expires" && Date.parse(value) <= Date.now()) {
+      expired = true;
+    }
+  }
+  const id = `${cookie.domain}|${cookie.path}|${cookie.name}`;
+  if (expired) jar.delete(id);
+  else jar.set(id, cookie);
+}
+
+function cookieMatches(cookie: JarCookie, url: URL): boolean {
+  const host = url.hostname.toLowerCase();
+  if (cookie.host_only ? host !== cookie.domain : !isDomainMatch(cookie.domain, host)) return false;
+  if (cookie.secure && url.protocol !== "https:") return false;
+  const path = url.pathname || "/";
+  return path === cookie.path
+    || (path.startsWith(cookie.path) && (cookie.path.endsWith("/") || path[cookie.path.length] === "/"));
+}
+
+/** Cookie header for `url` — only cookies whose domain, path and Secure flag match. */
+function cookieHeaderFor(jar: WorkflowCookieJar, url: URL): string {
+  return [...jar.values()]
+    .filter((cookie) => cookieMatches(cookie, url))
+    .sort((a, b) => b.path.length - a.path.length)
+    .map((cookie) => `${cookie.name}=${cookie.value}`)
+    .join("; ");
+}
+
+/** Value of the named cookie, preferring one that would be sent to `url`. */
+function jarCookieValue(jar: WorkflowCookieJar, name: string, url: URL): string | undefined {
+  const named = [...jar.values()].filter((cookie) => cookie.name === name);
+  return (named.find((cookie) => cookieMatches(cookie, url)) ?? named[named.length - 1])?.value;
+}
+
+function encodeStepBody(body: unknown, headers: Record<string, string>): string | undefined {
+  if (body == null) return undefined;
+  if (typeof body === "string") return body;
+  const contentType = Object.entries(headers).find(([k]) => k.toLowerCase() === "content-type")?.[1] ?? "";
+  if (/x-www-form-urlencoded/i.test(contentType) && typeof body === "object" && !Array.isArray(body)) {
+    return new URLSearchParams(
+      Object.entries(body as Record<string, unknown>).map(([k, v]) => [k, typeof v === "string" ? v : JSON.stringify(v)]),
+    ).toString();
+  }
+  if (!contentType) headers["content-type"] = "application/json";
+  return JSON.stringify(body);
+}
+
+async function runHttpRequest(
+  step: HttpRequestStep,
+  stepIndex: number,
+  variables: Record<string, unknown>,
+  cookieJar: WorkflowCookieJar,
+  signal: AbortSignal,
+): Promise<WorkflowStepResult[]> {
+  const url = String(substituteWorkflowVariables(step.url, variables));
+  const base = { step_index: stepIndex, type: step.type, url };
+  let target: URL;
+  try {
+    target = new URL(url);
+  } catch {
+    return [{ ...base, ok: false, error: `Invalid URL: ${url}`, error_code: "invalid_input" }];
+  }
+  const headers = substituteWorkflowVariables(step.headers ?? {}, variables) as Record<string, string>;
+  const body = encodeStepBody(substituteWorkflowVariables(step.body, variables), headers);
+  if (!Object.keys(headers).some((k) => k.toLowerCase() === "cookie")) {
+    const cookieHeader = cookieHeaderFor(cookieJar, target);
+    if (cookieHeader) headers.cookie = cookieHeader;
+  }
+
+  // Manual redirects: login responses set the session cookie on the 302 itself.
+  const res = await fetch(url, { method: step.method, headers, body, redirect: "manual", signal: AbortSignal.any([signal, AbortSignal.timeout(30_000)]) });
+  const expected = step.expect_status === undefined
+    ? {}
+    : { expected_status: step.expect_status, ...(res.status !== step.expect_status ? { diverged: true } : {}) };
+  const setCookies = res.headers.getSetCookie?.() ?? [];
+  for (const line of setCookies) storeSetCookie(cookieJar, target, line);
+  const text = await res.text();
+  let json: unknown;
+  try { json = JSON.parse(text); } catch { /* not JSON */ }
+  const ok = res.status < 400;
+  const result = json ?? text;
+  if (!ok) return [{ ...base, ...expected, ok, status_code: res.status, result, error: `HTTP ${res.status}`, error_code: "api_error" }];
+
+  const missing: string[] = [];
+  for (const [name, source] of Object.entries(step.extract ?? {})) {
+    let value: unknown;
+    if (source.startsWith("cookie:")) {
+      value = jarCookieValue(cookieJar, source.slice("cookie:".length), target);
+    } else if (source.startsWith("header:")) {
+      value = res.headers.get(source.slice("header:".length)) ?? undefined;
+    } else if (source.startsWith("regex:")) {
+      value = text.match(new RegExp(source.slice("regex:".length)))?.[1];
+    } else {
+      const values = json !== undefined ? resolvePath(json, source) : [];
+      value = source.includes("[]") ? (values.length > 0 ? values : undefined) : values[0];
+    }
+    if (value === undefined) missing.push(name);
+    else variables[name] = value;
+  }
+  if (missing.length > 0) {
+    return [{ ...base, ...expected, ok: false, status_code: res.status, result, error: `Could not extract ${missing.join(", ")} from ${url}`, error_code: "api_error" }];
+  }
+  return [{ ...base, ...expected, ok, status_code: res.status, result }];
+}
+
+type AbortReason = { code: "timeout" | "cancelled"; message: string };
+
+/** `result` with diverged_step set from its step results, when any diverged. */
+function withDivergence(result: WorkflowResult): WorkflowResult {
+  const diverged = result.step_results.find((r) => r.diverged);
+  return diverged ? { ...result, diverged_step: diverged.step_index } : result;
+}
+
+/** Reject as soon as `signal` aborts — browser-backed steps can't be interrupted otherwise. */
+function raceAbort<T>(promise: Promise<T>, signal: AbortSignal): Promise<T> {
+  if (signal.aborted) return Promise.reject(signal.reason);
+  return new Promise<T>((resolve, reject) => {
+    const onAbort = () => reject(signal.reason);
+    signal.addEventListener("abort", onAbort, { once: true });
+    promise.then(resolve, reject).finally(() => signal.removeEventListener("abort", onAbort));
+  });
+}
+
+/**
+ * Run steps in order against a shared variable map, stopping at the first
+ * failure, when `timeout_ms` elapses, or when `options.signal` aborts.
+ */
+export async function executeWorkflow(workflow: WorkflowDefinition, options: WorkflowOptions = {}): Promise<WorkflowResult> {
+  const variables: Record<string, unknown> = { ...(workflow.variables ?? {}) };
+  const stepResults: WorkflowStepResult[] = [];
+  const cookieJar: WorkflowCookieJar = new Map();
+
+  const controller = new AbortController();
+  const abort = (reason: AbortReason) => { if (!controller.signal.aborted) controller.abort(reason); };
+  const onCancel = () => abort({ code: "cancelled", message: "Workflow cancelled" });
+  if (options.signal?.aborted) onCancel();
+  options.signal?.addEventListener("abort", onCancel, { once: true });
+  const timer = workflow.timeout_ms && workflow.timeout_ms > 0
+    ? setTimeout(() => abort({ code: "timeout", message: `Workflow timed out after ${workflow.timeout_ms}ms` }), workflow.timeout_ms)
+    : undefined;
+
+  try {
+    for (const [index, step] of workflow.steps.entries()) {
+      let results: WorkflowStepResult[];
+      try {
+        switch (step.type) {
+          case "skill_call":
+            results = await raceAbort(runSkillCall(step, index, variables, options, controller.signal, workflow.name), controller.signal);
+            break;
+          case "http_request":
+            results = await raceAbort(runHttpRequest(step, index, variables, cookieJar, controller.signal), controller.signal);
+            break;
+          default:
+            results = [{ step_index: index, type: (step as WorkflowStep).type, ok: false, error: `Unknown step type: ${(step as { type?: string }).type}`, error_code: "invalid_input" }];
+        }
+      } catch (err) {
+        if (controller.signal.aborted) {
+          const reason = controller.signal.reason as AbortReason;
+          stepResults.push({ step_index: index, type: step.type, ok: false, error: reason.message, error_code: reason.code });
+          return withDivergence({
+            ok: false,
+            status: reason.code === "timeout" ? "timed_out" : "cancelled",
+            variables,
+            step_results: stepResults,
+            interrupted_step: index,
+          });
+        }
+        results = [{ step_index: index, type: step.type, ok: false, error: (err as Error).message, error_code: getErrorCode(err) }];
+      }
+      stepResults.push(...results);
+      if (!results[results.length - 1].ok) return withDivergence({ ok: false, status: "failed", variables, step_results: stepResults });
+    }
+    return withDivergence({ ok: true, status: "completed", variables, step_results: stepResults });
+  } finally {
+    clearTimeout(timer);
+    options.signal?.removeEventListener("abort", onCancel);
+  }
+}
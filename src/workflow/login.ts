@@ -0,0 +1,283 @@
+/**
+ * Replayable login workflows built from a captured HAR.
+ *
+ * Finds the credentials POST (a form or JSON body with username- and
+ * password-like fields), the earlier response that handed out its CSRF token,
+ * and the cookie the login response sets. The result is a workflow of
+ * http_request steps: fetch the CSRF page, extract the token, then POST
+ * `{{username}}` / `{{password}}` with it and extract the session cookie as
+ * `session_cookie`. Run it with executeWorkflow and those two variables set,
+ * or record → learn → replay in one call with replayLoginHar.
+ *
+ * Each step carries the status the recorded call returned (expect_status),
+ * so a replay that answers differently — a 200 error page where the login
+ * redirected — is flagged as diverged at that step.
+ */
+
+import { isDomainMatch } from "../domain.js";
+import { unbrowseError } from "../errors.js";
+import { harPostDataText, harResponseBody, type KuriHarEntry } from "../kuri/client.js";
+import { inferTargetDomain } from "../reverse-engineer/index.js";
+import { executeWorkflow, type HttpRequestStep, type WorkflowDefinition, type WorkflowOptions, type WorkflowResult } from "./index.js";
+
+// Matched against whole field names (see credentialFieldName), so
+// `login_challenge`, `account_type`, `bypass` and `user_agent` are left alone.
+const PASSWORD_FIELD = /^(user|login|account|current)?(pass|password|passwd|pwd)$/;
+const USERNAME_FIELD = /^(user|username|userid|useremail|login|loginid|loginname|loginemail|email|emailaddress|identifier|account|accountname|handle)$/;
+const CSRF_FIELD = /^(csrf|csrf_?token|_csrf|csrfmiddlewaretoken|authenticity_token|xsrf|_xsrf|_token|__RequestVerificationToken)$/i;
+const CSRF_HEADER = /^(x-csrf-token|x-xsrf-token|x-csrftoken|csrf-token)$/i;
+const SESSION_COOKIE = /sess|sid|auth|token|jwt|login/i;
+/** Headers worth replaying on the login POST; cookies come from the workflow jar. */
+const REPLAY_HEADERS = /^(accept|content-type|origin|referer|x-requested-with)$/i;
+
+type BodyShape = { kind: "form"; fields: Record<string, string> } | { kind: "json"; fields: Record<string, unknown> };
+
+function headerValue(headers: Array<{ name: string; value: string }> | undefined, name: string): string | undefined {
+  return (headers ?? []).find((h) => h.name.toLowerCase() === name)?.value;
+}
+
+function setCookiePairs(entry: KuriHarEntry): Array<[string, string]> {
+  return entry.response.headers
+    .filter((h) => h.name.toLowerCase() === "set-cookie")
+    .flatMap((h) => h.value.split("\n"))
+    .map((line) => line.split(";")[0])
+    .map((pair): [string, string] => [pair.slice(0, pair.indexOf("=")).trim(), pair.slice(pair.indexOf("=") + 1).trim()])
+    .filter(([name]) => name.length > 0);
+}
+
+function parseLoginBody(entry: KuriHarEntry): BodyShape | null {
+  const text = harPostDataText(entry.request.postData);
+  if (!text) return null;
+  const contentType = entry.request.postData?.mimeType ?? headerValue(entry.request.headers, "content-type") ?? "";
+  if (/json/i.test(contentType) || text.trimStart().startsWith("{")) {
+    try {
+      const parsed = JSON.parse(text) as unknown;
+      return parsed && typeof parsed === "object" && !Array.isArray(parsed) ? { kind: "json", fields: parsed as Record<string, unknown> } : null;
+    } catch {
+      return null;
+    }
+  }
+  return { kind: "form", fields: Object.fromEntries(new URLSearchParams(text)) };
+}
+
+/** `user[email]` → "email", `user_name`/`userName` → "username" — the form credential regexes match. */
+function credentialFieldName(key: string): string {
+  return key.replace(/^.*\[([^\]]+)\]$/, "$1").replace(/[-_.\s]/g, "").toLowerCase();
+}
+
+const isPasswordField = (key: string) => PASSWORD_FIELD.test(credentialFieldName(key));
+const isUsernameField = (key: string) => USERNAME_FIELD.test(credentialFieldName(key));
+
+/** Leaf field names of a body, one nesting level deep for JSON (`{ user: { email, password } }`). */
+function fieldNames(body: BodyShape): string[] {
+  return Object.entries(body.fields).flatMap(([key, value]) =>
+    value && typeof value === "object" && !Array.isArray(value) ? Object.keys(value) : [key]);
+}
+
+function isLoginPost(entry: KuriHarEntry): BodyShape | null {
+  if (entry.request.method.toUpperCase() !== "POST") return null;
+  const body = parseLoginBody(entry);
+  if (!body) return null;
+  const names = fieldNames(body);
+  const hasPassword = names.some(isPasswordField);
+  const hasUsername = names.some((n) => !isPasswordField(n) && isUsernameField(n));
+  return hasPassword && hasUsername ? body : null;
+}
+
+/** Replace credential and CSRF values with workflow placeholders. */
+function templateFields(fields: Record<string, unknown>, csrfField: string | undefined): Record<string, unknown> {
+  return Object.fromEntries(Object.entries(fields).map(([key, value]) => {
+    if (value && typeof value === "object" && !Array.isArray(value)) return [key, templateFields(value as Record<string, unknown>, csrfField)];
+    if (key === csrfField) return [key, "{{csrf_token}}"];
+    if (isPasswordField(key)) return [key, "{{password}}"];
+    if (isUsernameField(key)) return [key, "{{username}}"];
+    return [key, value];
+  }));
+}
+
+function findCsrfField(fields: Record<string, unknown>): { field: string; token: string } | null {
+  for (const [key, value] of Object.entries(fields)) {
+    if (typeof value === "string" && CSRF_FIELD.test(key) && value) return { field: key, token: value };
+    if (value && typeof value === "object" && !Array.isArray(value)) {
+      const nested = findCsrfField(value as Record<string, unknown>);
+      if (nested) return nested;
+    }
+  }
+  return null;
+}
+
+function escapeRegex(value: string): string {
+  return value.replace(/[.*+?^${}()|[\]\\]/g, "\\$&");
+}
+
+/** Dot path to the first string equal to `token` inside a JSON value. */
+function jsonPathTo(value: unknown, token: string, path = ""): string | null {
+  if (!value || typeof value !== "object" || Array.isArray(value)) return null;
+  for (const [key, child] of Object.entries(value as Record<string, unknown>)) {
+    const childPath = path ? `${path}.${key}` : key;
+    if (child === token) return childPath;
+    const nested = jsonPathTo(child, token, childPath);
+    if (nested) return nested;
+  }
+  return null;
+}
+
+/** HTML extraction patterns for a named token: hidden inputs (either attribute order) and meta tags. */
+function htmlTokenPatterns(name: string): string[] {
+  const n = escapeRegex(name);
+  return [
+    `<input[^>]*name=["']${n}["'][^>]*value=["']([^"']*)["']`,
+    `<input[^>]*value=["']([^"']*)["'][^>]*name=["']${n}["']`,
+    `<meta[^>]*name=["']${n}["'][^>]*content=["']([^"']*)["']`,
+    `<meta[^>]*name=["'](?:csrf-token|csrf_token|_csrf)["'][^>]*content=["']([^"']*)["']`,
+  ];
+}
+
+/** How an earlier response exposed `token`: a Set-Cookie, a JSON field, or an HTML attribute. */
+function tokenSource(entry: KuriHarEntry, token: string, name: string): string | null {
+  const cookie = setCookiePairs(entry).find(([, value]) => {
+    try { return value === token || decodeURIComponent(value) === token; } catch { return false; }
+  });
+  if (cookie) return `cookie:${cookie[0]}`;
+  const text = harResponseBody(entry.response.content).response_body;
+  if (!text || !text.includes(token)) return null;
+  try {
+    const path = jsonPathTo(JSON.parse(text), token);
+    if (path) return path;
+  } catch { /* not JSON */ }
+  for (const pattern of htmlTokenPatterns(name)) {
+    if (text.match(new RegExp(pattern))?.[1] === token) return `regex:${pattern}`;
+  }
+  return null;
+}
+
+function sameSite(url: string, seedHost: string): boolean {
+  try {
+    const host = new URL(url).hostname;
+    return isDomainMatch(seedHost, host) || isDomainMatch(host, seedHost);
+  } catch {
+    return false;
+  }
+}
+
+/**
+ * Target site of a HAR recorded without a seed URL: the highest-scoring
+ * domain (see scoreCaptureDomains), as the page that sent its login POST —
+ * the POST's Referer, else its origin.
+ */
+function inferSeedUrl(entries: KuriHarEntry[]): string | null {
+  const domain = inferTargetDomain(entries.filter((e) => e?.request && e?.response).map((e) => ({
+    url: e.request.url,
+    method: e.request.method.toUpperCase(),
+    request_headers: Object.fromEntries((e.request.headers ?? []).map((h) => [h.name.toLowerCase(), h.value])),
+    response_status: e.response.status,
+    response_headers: {},
+    timestamp: e.startedDateTime,
+  })));
+  if (!domain) return null;
+  const login = entries.find((e) => e?.request && e?.response && sameSite(e.request.url, domain) && isLoginPost(e));
+  if (!login) return `https://${domain}/`;
+  const referer = headerValue(login.request.headers, "referer");
+  if (referer && sameSite(referer, domain)) return referer;
+  return `${new URL(login.request.url).origin}/`;
+}
+
+/**
+ * Build a credential-acquisition workflow from a HAR (object or JSON text)
+ * that recorded a login on `seedUrl`'s site. Without a seed URL the target
+ * site is inferred from the HAR. Throws har_parse for an unreadable HAR and
+ * invalid_input when no login POST is found.
+ */
+export function buildLoginWorkflow(har: unknown, seedUrl?: string): WorkflowDefinition {
+  let parsed = har;
+  if (typeof har === "string") {
+    try { parsed = JSON.parse(har); } catch (err) {
+      throw unbrowseError("har_parse", `Invalid HAR JSON: ${(err as Error).message}`);
+    }
+  }
+  const entries = (parsed as { log?: { entries?: KuriHarEntry[] } } | null)?.log?.entries;
+  if (!Array.isArray(entries)) throw unbrowseError("har_parse", "HAR has no log.entries");
+
+  if (!seedUrl) {
+    const inferred = inferSeedUrl(entries);
+    if (!inferred) throw unbrowseError("invalid_input", "Could not infer the target site from the HAR; pass seed_url");
+    seedUrl = inferred;
+  }
+  let seedHost: string;
+  try { seedHost = new URL(seedUrl).hostname; } catch {
+    throw unbrowseError("invalid_input", `Invalid seed URL: ${seedUrl}`);
+  }
+
+  const loginIndex = entries.findIndex((e) => e?.request && e?.response && sameSite(e.request.url, seedHost) && isLoginPost(e));
+  if (loginIndex === -1) throw unbrowseError("invalid_input", `No login POST with username/password fields found for ${seedHost}`);
+  const login = entries[loginIndex];
+  const body = isLoginPost(login)!;
+
+  // The token travels in the body (form field) or a header (SPA double-submit).
+  const csrfBody = findCsrfField(body.fields);
+  const csrfHeader = login.request.headers.find((h) => CSRF_HEADER.test(h.name) && h.value);
+  const csrf = csrfBody
+    ? { ...csrfBody, header: undefined as string | undefined }
+    : csrfHeader ? { field: csrfHeader.name, token: csrfHeader.value, header: csrfHeader.name } : null;
+
+  const steps: HttpRequestStep[] = [];
+  if (csrf) {
+    let source: { url: string; extract: string; status?: number } | null = null;
+    for (let i = loginIndex - 1; i >= 0 && !source; i--) {
+      const entry = entries[i];
+      if (!entry?.request || !entry.response || entry.request.method.toUpperCase() !== "GET") continue;
+      const found = tokenSource(entry, csrf.token, csrf.field);
+      if (found) source = { url: entry.request.url, extract: found, status: entry.response.status };
+    }
+    // Token never seen in an earlier response — fall back to the login form on the seed page.
+    source ??= { url: seedUrl, extract: `regex:${htmlTokenPatterns(csrf.field)[0]}` };
+    steps.push({
+      type: "http_request",
+      method: "GET",
+      url: source.url,
+      extract: { csrf_token: source.extract },
+      ...(source.status ? { expect_status: source.status } : {}),
+    });
+  } else {
+    // Still load the seed page first so pre-login cookies land in the jar.
+    steps.push({ type: "http_request", method: "GET", url: seedUrl });
+  }
+
+  const headers = Object.fromEntries(
+    login.request.headers
+      .filter((h) => REPLAY_HEADERS.test(h.name))
+      .map((h) => [h.name.toLowerCase(), h.value]),
+  );
+  if (csrf?.header) headers[csrf.header.toLowerCase()] = "{{csrf_token}}";
+  if (!headers["content-type"] && login.request.postData?.mimeType) headers["content-type"] = login.request.postData.mimeType;
+
+  const loginCookies = setCookiePairs(login).map(([name]) => name);
+  const sessionCookie = loginCookies.find((name) => SESSION_COOKIE.test(name)) ?? loginCookies[0];
+  steps.push({
+    type: "http_request",
+    method: "POST",
+    url: login.request.url,
+    headers,
+    body: templateFields(body.fields, csrfBody?.field),
+    ...(sessionCookie ? { extract: { session_cookie: `cookie:${sessionCookie}` } } : {}),
+    ...(login.response.status ? { expect_status: login.response.status } : {}),
+  });
+
+  return { name: `login:${seedHost}`, steps };
+}
+
+/**
+ * Build the login workflow for `har` and run it straight away with
+ * `variables` (username, password). Catches a workflow that learned wrong
+ * before it is stored: check result.ok, and result.diverged_step for the
+ * first call that answered differently from the recording.
+ */
+export async function replayLoginHar(
+  har: unknown,
+  variables: Record<string, unknown>,
+  options: WorkflowOptions & { seed_url?: string } = {},
+): Promise<{ workflow: WorkflowDefinition; result: WorkflowResult }> {
+  const workflow = buildLoginWorkflow(har, options.seed_url);
+  const result = await executeWorkflow({ ...workflow, variables: { ...(workflow.variables ?? {}), ...variables } }, options);
+  return { workflow, result };
+}
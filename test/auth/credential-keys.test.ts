@@ -0,0 +1,36 @@
+import { describe, expect, test } from "bun:test";
+import { findDomainCredentialKeys } from "../../src/auth/index.js";
+
+const session = (host: string) => `${host}-session`;
+const stored = ["login.example.com-session", "auth:login.example.com", "login.other.com-session", "example.com.evil.net-session"];
+
+describe("findDomainCredentialKeys", () => {
+  test("walks parents up to the registrable domain", () => {
+    expect(findDomainCredentialKeys("api.app.example.com", session)).toEqual([
+      "api.app.example.com-session",
+      "www.api.app.example.com-session",
+      "app.example.com-session",
+      "example.com-session",
+    ]);
+  });
+
+  test("credentials stored for login. are found when capturing app.", () => {
+    const keys = findDomainCredentialKeys("app.example.com", session, {}, stored);
+    expect(keys).toContain("login.example.com-session");
+    expect(keys.indexOf("login.example.com-session")).toBeGreaterThan(keys.indexOf("example.com-session"));
+    expect(keys).not.toContain("login.other.com-session");
+    expect(keys).not.toContain("example.com.evil.net-session");
+    expect(keys).not.toContain("auth:login.example.com");
+  });
+
+  test("the sibling pass follows the key format", () => {
+    expect(findDomainCredentialKeys("app.example.com", (host) => `auth:${host}`, {}, stored)).toContain("auth:login.example.com");
+  });
+
+  test("strict opts out of parents and siblings", () => {
+    expect(findDomainCredentialKeys("app.example.com", session, { strict: true }, stored)).toEqual([
+      "app.example.com-session",
+      "www.app.example.com-session",
+    ]);
+  });
+});
@@ -0,0 +1,22 @@
+import { describe, expect, test } from "bun:test";
+import { mergeAuthBundle } from "../../src/auth/index.js";
+
+describe("mergeAuthBundle — signing_secret", () => {
+  test("a re-capture keeps the stored secret", () => {
+    const merged = mergeAuthBundle(
+      { cookies: [], headers: { authorization: "Bearer old" }, signing_secret: "s3cret" },
+      { cookies: [], headers: { authorization: "Bearer new" } },
+    );
+    expect(merged.signing_secret).toBe("s3cret");
+    expect(merged.headers).toEqual({ authorization: "Bearer new" });
+  });
+
+  test("a new secret replaces the stored one and keeps the session", () => {
+    const merged = mergeAuthBundle(
+      { cookies: [{ name: "sid", value: "1", domain: "example.com" }], signing_secret: "old" },
+      { cookies: [], signing_secret: "new" },
+    );
+    expect(merged.signing_secret).toBe("new");
+    expect(merged.cookies).toEqual([{ name: "sid", value: "1", domain: "example.com" }]);
+  });
+});
@@ -0,0 +1,31 @@
+import { describe, expect, test } from "bun:test";
+import { getRegistrableDomain, isPublicSuffix, isSameRegistrableDomain } from "../src/domain.js";
+
+describe("getRegistrableDomain", () => {
+  test("multi-part public suffixes", () => {
+    expect(getRegistrableDomain("api.example.co.uk")).toBe("example.co.uk");
+    expect(getRegistrableDomain("www.example.com.sg")).toBe("example.com.sg");
+    expect(getRegistrableDomain("shop.example.com.au")).toBe("example.com.au");
+    expect(getRegistrableDomain("sub.example.com")).toBe("example.com");
+  });
+
+  test("co.xx is only a suffix where the PSL lists it", () => {
+    // co.de is not a public suffix, so co.de itself is the registrable domain.
+    expect(getRegistrableDomain("shop.co.de")).toBe("co.de");
+    expect(isPublicSuffix("co.de")).toBe(false);
+    expect(isSameRegistrableDomain("alice.co.de", "bob.co.de")).toBe(true);
+    expect(isPublicSuffix("co.uk")).toBe(true);
+  });
+
+  test("shared-hosting tenants are separate sites", () => {
+    expect(getRegistrableDomain("docs.alice.github.io")).toBe("alice.github.io");
+    expect(isSameRegistrableDomain("alice.github.io", "bob.github.io")).toBe(false);
+    expect(getRegistrableDomain("bucket.s3.amazonaws.com")).toBe("bucket.s3.amazonaws.com");
+  });
+
+  test("hosts without a registrable part come back as-is", () => {
+    expect(getRegistrableDomain("localhost")).toBe("localhost");
+    expect(getRegistrableDomain("127.0.0.1")).toBe("127.0.0.1");
+    expect(getRegistrableDomain("Example.COM.")).toBe("example.com");
+  });
+});
@@ -0,0 +1,27 @@
+import { describe, expect, test } from "bun:test";
+import { generatePostmanCollection } from "../../src/export/postman.js";
+import type { SkillManifest } from "../../src/types/index.js";
+
+describe("generatePostmanCollection", () => {
+  test("sends tunnelled endpoints as their wire method with the body", () => {
+    const skill = {
+      name: "Example",
+      domain: "api.example.com",
+      endpoints: [{
+        endpoint_id: "search",
+        method: "GET",
+        wire_method: "POST",
+        url_template: "https://api.example.com/v1/search",
+        headers_template: { "X-HTTP-Method-Override": "GET", "Content-Type": "application/json" },
+        body: { q: "{q}" },
+        body_params: { q: "shoes" },
+      }],
+    } as unknown as SkillManifest;
+
+    const collection = JSON.parse(generatePostmanCollection(skill));
+    const [request] = collection.item[0].item.flatMap((folder: { item: Array<{ request: unknown }> }) => folder.item.map((item) => item.request));
+    expect(request.method).toBe("POST");
+    expect(JSON.parse(request.body.raw)).toEqual({ q: "{{q}}" });
+    expect(request.header).toContainEqual({ key: "X-HTTP-Method-Override", value: "GET" });
+  });
+});
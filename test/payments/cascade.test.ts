@@ -0,0 +1,61 @@
+import { describe, expect, test } from "bun:test";
+import bs58 from "bs58";
+import { ensureCascadeSplitForSkill } from "../../src/payments/cascade.js";
+
+// RFC 8032 §7.1, test 1
+const SEED = Buffer.from("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60", "hex");
+const SIGNER_ADDRESS = bs58.encode(Buffer.from("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a", "hex"));
+const USER_WALLET = bs58.encode(new Uint8Array(32).fill(7));
+
+const skill = {
+  skill_id: "skill-1",
+  contributors: [
+    { agent_id: "a", wallet_address: bs58.encode(new Uint8Array(32).fill(1)), share: 45 },
+    { agent_id: "b", wallet_address: bs58.encode(new Uint8Array(32).fill(2)), share: 45 },
+  ],
+} as Parameters<typeof ensureCascadeSplitForSkill>[0];
+
+const env = {
+  UNBROWSE_CASCADE_PLATFORM_WALLET: bs58.encode(new Uint8Array(32).fill(3)),
+  UNBROWSE_CASCADE_SIGNER_SECRET_KEY: JSON.stringify([...SEED]),
+  UNBROWSE_CASCADE_RPC_URL: "https://rpc.example.org",
+  UNBROWSE_CASCADE_RPC_WS_URL: "wss://rpc.example.org",
+  // The user's agent wallet — a different key from the platform signer.
+  AGENT_WALLET_ADDRESS: USER_WALLET,
+  LOBSTER_WALLET_ADDRESS: USER_WALLET,
+};
+
+const deps = {
+  loadSdk: async () => ({
+    createSplitsClient: () => ({ ensureSplit: async () => ({ status: "created" as const, splitConfig: "split-1" }) }),
+    labelToSeed: (label: string) => label,
+  }),
+  loadKit: async () => ({
+    createSolanaRpc: (url: string) => url,
+    createSolanaRpcSubscriptions: (url: string) => url,
+    createKeyPairSignerFromBytes: async (bytes: Uint8Array) => bytes,
+  }),
+};
+
+describe("ensureCascadeSplitForSkill signer", () => {
+  test("a user wallet different from the platform signer doesn't block the split", async () => {
+    const saved = { AGENT_WALLET_ADDRESS: process.env.AGENT_WALLET_ADDRESS, LOBSTER_WALLET_ADDRESS: process.env.LOBSTER_WALLET_ADDRESS };
+    process.env.AGENT_WALLET_ADDRESS = USER_WALLET;
+    process.env.LOBSTER_WALLET_ADDRESS = USER_WALLET;
+    try {
+      expect(await ensureCascadeSplitForSkill(skill, { ...deps, env })).toEqual({ split_config: "split-1", source: "sdk" });
+    } finally {
+      for (const [key, value] of Object.entries(saved)) {
+        if (value === undefined) delete process.env[key];
+        else process.env[key] = value;
+      }
+    }
+  });
+
+  test("UNBROWSE_CASCADE_SIGNER_ADDRESS pins the signer", async () => {
+    expect(await ensureCascadeSplitForSkill(skill, { ...deps, env: { ...env, UNBROWSE_CASCADE_SIGNER_ADDRESS: SIGNER_ADDRESS } }))
+      .toEqual({ split_config: "split-1", source: "sdk" });
+    await expect(ensureCascadeSplitForSkill(skill, { ...deps, env: { ...env, UNBROWSE_CASCADE_SIGNER_ADDRESS: USER_WALLET } }))
+      .rejects.toThrow(`signer key is for ${SIGNER_ADDRESS}`);
+  });
+});
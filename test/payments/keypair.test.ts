@@ -0,0 +1,34 @@
+import { describe, expect, test } from "bun:test";
+import bs58 from "bs58";
+import { toKeypairBytes } from "../../src/payments/keypair.js";
+
+// RFC 8032 §7.1, test 1
+const SEED = Uint8Array.from(Buffer.from("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60", "hex"));
+const PUBLIC_KEY = Uint8Array.from(Buffer.from("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a", "hex"));
+const ADDRESS = bs58.encode(PUBLIC_KEY);
+const KEYPAIR = Uint8Array.from([...SEED, ...PUBLIC_KEY]);
+
+describe("toKeypairBytes", () => {
+  test("appends the derived public key to a 32-byte seed", () => {
+    expect(toKeypairBytes(SEED)).toEqual(KEYPAIR);
+  });
+
+  test("accepts a consistent 64-byte keypair", () => {
+    expect(toKeypairBytes(KEYPAIR, ADDRESS)).toEqual(KEYPAIR);
+  });
+
+  test("rejects a 64-byte keypair whose public half doesn't match its seed", () => {
+    const spliced = Uint8Array.from(KEYPAIR);
+    spliced[63] ^= 0xff;
+    expect(() => toKeypairBytes(spliced)).toThrow(/inconsistent/);
+  });
+
+  test("rejects a key for a different address than the configured wallet", () => {
+    const other = bs58.encode(new Uint8Array(32).fill(7));
+    expect(() => toKeypairBytes(SEED, other)).toThrow(`signer key is for ${ADDRESS}, but the configured wallet is ${other}`);
+  });
+
+  test("rejects secrets that are neither 32 nor 64 bytes", () => {
+    expect(() => toKeypairBytes(new Uint8Array(48))).toThrow(/got 48 bytes/);
+  });
+});
@@ -0,0 +1,57 @@
+import { describe, expect, test } from "bun:test";
+import { unbrowseError } from "../../src/errors.js";
+import { classifyMarketplaceError, parseX402Challenge } from "../../src/payments/x402.js";
+
+const RECIPIENT = "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin";
+
+// Body of a marketplace 402, as the backend sends it.
+const PAYMENT_REQUIRED_BODY = {
+  error: "This skill requires payment",
+  terms: {
+    x402Version: 1,
+    accepts: [
+      { scheme: "exact", network: "solana-devnet", maxAmountRequired: "2500", payTo: RECIPIENT, asset: "USDC", resource: "/v1/skills/s1" },
+      { scheme: "exact", network: "base-sepolia", maxAmountRequired: "2500", payTo: "0xabc" },
+    ],
+  },
+};
+
+describe("parseX402Challenge", () => {
+  test("reads price, recipient and accepts from a 402 body", () => {
+    const challenge = parseX402Challenge(PAYMENT_REQUIRED_BODY.terms);
+    expect(challenge.price_usdc).toBe(0.0025);
+    expect(challenge.recipient).toBe(RECIPIENT);
+    expect(challenge.network).toBe("solana-devnet");
+    expect(challenge.accepts).toEqual([
+      { scheme: "exact", network: "solana-devnet", max_amount_required: "2500", pay_to: RECIPIENT, asset: "USDC", resource: "/v1/skills/s1" },
+      { scheme: "exact", network: "base-sepolia", max_amount_required: "2500", pay_to: "0xabc" },
+    ]);
+  });
+
+  test("falls back to a WWW-Authenticate x402 challenge", () => {
+    const challenge = parseX402Challenge(undefined, `x402 amount="1000000", recipient="${RECIPIENT}", network=solana`);
+    expect(challenge).toMatchObject({ price_usdc: 1, recipient: RECIPIENT, network: "solana" });
+  });
+});
+
+describe("classifyMarketplaceError", () => {
+  test("a 402 error becomes payment_required with its terms", () => {
+    const err = unbrowseError("payment_required", `Payment required: ${PAYMENT_REQUIRED_BODY.error}`, {
+      x402: true,
+      status: 402,
+      terms: PAYMENT_REQUIRED_BODY.terms,
+      server_error: PAYMENT_REQUIRED_BODY.error,
+    });
+    expect(classifyMarketplaceError(err)).toMatchObject({ kind: "payment_required", price_usdc: 0.0025, recipient: RECIPIENT });
+  });
+
+  test("a bounced payment is insufficient_funds", () => {
+    const err = unbrowseError("payment_required", "Payment required: insufficient_funds", { x402: true, status: 402, terms: PAYMENT_REQUIRED_BODY.terms });
+    expect(classifyMarketplaceError(err).kind).toBe("insufficient_funds");
+  });
+
+  test("already purchased is already_owned", () => {
+    const err = unbrowseError("api_error", "Skill already purchased", { status: 409, server_error: "already_purchased" });
+    expect(classifyMarketplaceError(err).kind).toBe("already_owned");
+  });
+});
@@ -0,0 +1,50 @@
+import { describe, expect, test } from "bun:test";
+import type { RawRequest } from "../../src/capture/index.js";
+import { detectPollingKeys } from "../../src/reverse-engineer/index.js";
+
+const START = Date.parse("2026-01-01T00:00:00Z");
+
+/** Calls 5s apart, cycling through `urls` — enough hits for detectPollingKeys to group them. */
+function calls(urls: string[], count = 4): RawRequest[] {
+  return Array.from({ length: count }, (_, i) => ({
+    url: urls[i % urls.length],
+    method: "GET",
+    request_headers: {},
+    response_status: 200,
+    response_headers: { "content-type": "application/json" },
+    response_body: "{}",
+    timestamp: new Date(START + i * 5000).toISOString(),
+  }));
+}
+
+describe("endpoint grouping", () => {
+  test("trailing-slash variants are one endpoint", () => {
+    const keys = detectPollingKeys(calls(["https://api.example.org/v1/feed", "https://api.example.org/v1/feed/"]));
+    expect([...keys]).toEqual(["GET:https://api.example.org/v1/feed"]);
+  });
+
+  test("repeated trailing slashes collapse too", () => {
+    const keys = detectPollingKeys(calls(["https://api.example.org/v1/feed//", "https://api.example.org/v1/feed"]));
+    expect([...keys]).toEqual(["GET:https://api.example.org/v1/feed"]);
+  });
+
+  test("the root path keeps its slash", () => {
+    const keys = detectPollingKeys(calls(["https://api.example.org/"]));
+    expect([...keys]).toEqual(["GET:https://api.example.org/"]);
+  });
+
+  test("case variants stay separate by default", () => {
+    const keys = detectPollingKeys(calls(["https://api.example.org/V1/Feed", "https://api.example.org/v1/feed"]));
+    expect(keys.size).toBe(0);
+  });
+
+  test("case variants are one endpoint with foldCase", () => {
+    const keys = detectPollingKeys(calls(["https://api.example.org/V1/Feed", "https://api.example.org/v1/feed/"]), true);
+    expect([...keys]).toEqual(["GET:https://api.example.org/v1/feed"]);
+  });
+
+  test("the host is never case-folded into a different origin", () => {
+    const keys = detectPollingKeys(calls(["https://API.example.org/v1/feed", "https://api.example.org/v1/feed"]));
+    expect([...keys]).toEqual(["GET:https://api.example.org/v1/feed"]);
+  });
+});
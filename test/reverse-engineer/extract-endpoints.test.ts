@@ -0,0 +1,170 @@
+import { describe, expect, test } from "bun:test";
+import { mergePassiveCaptureData, type RawRequest } from "../../src/capture/index.js";
+import { extractEndpoints, findCorsBlockedRequests } from "../../src/reverse-engineer/index.js";
+
+function request(overrides: Partial<RawRequest> & { url: string }): RawRequest {
+  return {
+    method: "GET",
+    request_headers: {},
+    response_status: 200,
+    response_headers: {},
+    timestamp: "2026-01-01T00:00:00.000Z",
+    ...overrides,
+  };
+}
+
+/** A gRPC-web frame: flag byte, 4-byte big-endian length, payload. */
+function frame(flag: number, payload: Buffer): Buffer {
+  const header = Buffer.alloc(5);
+  header[0] = flag;
+  header.writeUInt32BE(payload.length, 1);
+  return Buffer.concat([header, payload]);
+}
+
+describe("extractEndpoints — binary API responses", () => {
+  const grpcBody = Buffer.concat([
+    frame(0x00, Buffer.from("0a0b68656c6c6f2d776f726c641001", "hex")),
+    frame(0x00, Buffer.from("0a057365636f6e64", "hex")),
+    frame(0x80, Buffer.from("grpc-status:0\r\ngrpc-message:OK\r\n")),
+  ]).toString("base64");
+
+  test("a grpc-web call is kept with its frames counted", () => {
+    const [endpoint] = extractEndpoints([request({
+      url: "https://api.example.com/example.v1.Catalog/ListItems",
+      method: "POST",
+      request_headers: { "content-type": "application/grpc-web+proto" },
+      request_body: "AAAAAAA=",
+      response_headers: { "content-type": "application/grpc-web+proto" },
+      response_body: grpcBody,
+    })]);
+    expect(endpoint).toBeDefined();
+    expect(endpoint.body_format).toBe("grpc");
+    expect(endpoint.grpc_web).toEqual({ frames: 2, grpc_status: 0, grpc_message: "OK" });
+    expect(endpoint.response_schema).toBeUndefined();
+  });
+
+  test("a grpc-web error reported with HTTP 200 keeps its status", () => {
+    const [endpoint] = extractEndpoints([request({
+      url: "https://api.example.com/example.v1.Catalog/GetItem",
+      method: "POST",
+      response_headers: { "content-type": "application/grpc-web-text" },
+      response_body: frame(0x80, Buffer.from("grpc-status:7\r\ngrpc-message:permission%20denied\r\n")).toString("base64"),
+    })]);
+    expect(endpoint.grpc_web).toEqual({ frames: 0, grpc_status: 7, grpc_message: "permission denied" });
+  });
+
+  test("protobuf and msgpack responses are kept without shape analysis", () => {
+    const endpoints = extractEndpoints([
+      request({
+        url: "https://api.example.com/catalog/items",
+        response_headers: { "content-type": "application/x-protobuf" },
+        response_body: "\n\u000bhello-world\u0010\u0001\n\u0006second-item",
+      }),
+      request({
+        url: "https://api.example.com/v2/account",
+        response_headers: { "content-type": "application/msgpack" },
+        response_body: "\u0082¤name¥alice¥email±alice@example.org",
+      }),
+    ]);
+    const byPath = Object.fromEntries(endpoints.map((ep) => [new URL(ep.url_template).pathname, ep]));
+    expect(byPath["/catalog/items"]?.body_format).toBe("protobuf");
+    expect(byPath["/v2/account"]?.body_format).toBe("msgpack");
+    expect(byPath["/catalog/items"]?.response_schema).toBeUndefined();
+  });
+});
+
+describe("extractEndpoints — framework loader routes", () => {
+  const posts = JSON.stringify({ posts: [{ id: 1, title: "Hello" }, { id: 2, title: "World" }] });
+  const json = { "content-type": "application/json; charset=utf-8" };
+  const paths = (endpoints: ReturnType<typeof extractEndpoints>) => endpoints.map((ep) => new URL(ep.url_template).pathname);
+
+  test("a Remix /_data/ loader returning JSON is kept", () => {
+    const endpoints = extractEndpoints([request({ url: "https://remix.example.com/_data/routes/posts", response_headers: json, response_body: posts })]);
+    expect(paths(endpoints)).toEqual(["/_data/routes/posts"]);
+  });
+
+  test("a Remix ?_data= loader returning JSON is kept", () => {
+    const endpoints = extractEndpoints([request({ url: "https://remix.example.com/posts?_data=routes%2Fposts", response_headers: json, response_body: posts })]);
+    expect(paths(endpoints)).toEqual(["/posts"]);
+  });
+
+  test("skipped framework prefixes only drop non-JSON responses", () => {
+    const endpoints = extractEndpoints([
+      request({ url: "https://next.example.com/_next/data/build-1/posts.json", response_headers: json, response_body: posts }),
+      request({ url: "https://next.example.com/_next/data/build-1/about", response_headers: { "content-type": "text/plain" }, response_body: "about page, rendered on the server" }),
+    ]);
+    expect(paths(endpoints)).toEqual(["/_next/data/build-1/posts.json"]);
+  });
+
+  test("configured patterns force and skip paths", () => {
+    const requests = () => [
+      request({ url: "https://app.example.com/__loader/posts", response_headers: json, response_body: posts }),
+      request({ url: "https://app.example.com/api/internal/reports", response_headers: json, response_body: posts }),
+    ];
+    expect(paths(extractEndpoints(requests()))).toEqual(["/api/internal/reports"]);
+    const configured = extractEndpoints(requests(), undefined, { apiPathPatterns: [/\/__loader\//], skipPathPatterns: [/\/internal\//] });
+    expect(paths(configured)).toEqual(["/__loader/posts"]);
+  });
+});
+
+describe("CORS preflights", () => {
+  const preflight = (url: string, method: string) => request({
+    url,
+    method: "OPTIONS",
+    request_headers: { "Access-Control-Request-Method": method, origin: "https://app.example.com" },
+    response_status: 403,
+  });
+
+  test("an OPTIONS whose GET is missing comes back as the blocked GET", () => {
+    expect(findCorsBlockedRequests([preflight("https://api.example.com/v1/orders?page=2", "get")])).toEqual([{
+      url: "https://api.example.com/v1/orders?page=2",
+      method: "GET",
+      request_headers: {},
+      response_status: 0,
+      response_headers: {},
+      timestamp: "2026-01-01T00:00:00.000Z",
+    }]);
+  });
+
+  test("a preflight followed by its real request is not blocked", () => {
+    expect(findCorsBlockedRequests([
+      preflight("https://api.example.com/v1/orders", "GET"),
+      request({ url: "https://api.example.com/v1/orders", response_headers: { "content-type": "application/json" }, response_body: "[]" }),
+    ])).toEqual([]);
+  });
+
+  test("a plain OPTIONS is not a preflight", () => {
+    expect(findCorsBlockedRequests([request({ url: "https://api.example.com/v1/orders", method: "OPTIONS" })])).toEqual([]);
+  });
+
+  test("a blocked POST to a URL whose GET went through survives the passive merge", () => {
+    const url = "https://api.example.com/v1/orders";
+    const merged = mergePassiveCaptureData([], [
+      {
+        request: { method: "GET", url, headers: [] },
+        response: { status: 200, headers: [{ name: "content-type", value: "application/json" }], content: { size: 2, mimeType: "application/json", text: "[]" } },
+        startedDateTime: "2026-01-01T00:00:00.000Z",
+      },
+      {
+        request: { method: "OPTIONS", url, headers: [{ name: "Access-Control-Request-Method", value: "POST" }] },
+        response: { status: 403, headers: [] },
+        startedDateTime: "2026-01-01T00:00:01.000Z",
+      },
+      {
+        request: { method: "OPTIONS", url, headers: [{ name: "Access-Control-Request-Method", value: "GET" }] },
+        response: { status: 204, headers: [] },
+        startedDateTime: "2026-01-01T00:00:02.000Z",
+      },
+    ], [], new Map());
+    expect(merged.map((r) => r.method)).toEqual(["GET", "OPTIONS"]);
+    expect(findCorsBlockedRequests(merged).map((r) => `${r.method} ${r.url}`)).toEqual([`POST ${url}`]);
+  });
+
+  test("reportCorsBlocked surfaces the endpoint as blocked_by cors", () => {
+    const requests = [preflight("https://api.example.com/v1/orders?page=2", "GET")];
+    expect(extractEndpoints(requests)).toEqual([]);
+    const [endpoint] = extractEndpoints(requests, undefined, { reportCorsBlocked: true });
+    expect(endpoint).toMatchObject({ method: "GET", blocked_by: "cors", verification_status: "pending", query: { page: "2" } });
+    expect(new URL(endpoint.url_template).pathname).toBe("/v1/orders");
+  });
+});
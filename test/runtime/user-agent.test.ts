@@ -0,0 +1,22 @@
+import { afterEach, describe, expect, test } from "bun:test";
+import { applyUserAgentOverride } from "../../src/runtime/user-agent.js";
+
+const saved = process.env.UNBROWSE_USER_AGENT;
+afterEach(() => {
+  if (saved === undefined) delete process.env.UNBROWSE_USER_AGENT;
+  else process.env.UNBROWSE_USER_AGENT = saved;
+});
+
+describe("applyUserAgentOverride", () => {
+  const captured = { "User-Agent": "Mozilla/5.0 (captured browser)", accept: "application/json" };
+
+  test("the override replaces a captured template's user-agent", () => {
+    process.env.UNBROWSE_USER_AGENT = "my-agent/1.0";
+    expect(applyUserAgentOverride(captured)).toEqual({ accept: "application/json", "user-agent": "my-agent/1.0" });
+  });
+
+  test("without an override the captured user-agent is kept", () => {
+    delete process.env.UNBROWSE_USER_AGENT;
+    expect(applyUserAgentOverride(captured)).toEqual(captured);
+  });
+});
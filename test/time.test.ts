@@ -0,0 +1,65 @@
+import { describe, expect, test } from "bun:test";
+import {
+  CHROME_EPOCH_OFFSET_MICROS,
+  chromeTimestampToEpochSeconds,
+  chromeTimestampToIso,
+  epochMsToIso,
+  parseHarDateTime,
+} from "../src/time.js";
+
+/** 2020-01-01T00:00:00Z in Chrome's microseconds-since-1601. */
+const NEW_YEAR_2020 = 13_222_310_400_000_000;
+
+describe("chrome timestamps", () => {
+  test("the offset is 1601-01-01 to 1970-01-01", () => {
+    expect(CHROME_EPOCH_OFFSET_MICROS / 1000).toBe(-Date.UTC(1601, 0, 1));
+  });
+
+  test("microseconds since 1601 to Unix seconds and ISO", () => {
+    expect(chromeTimestampToEpochSeconds(NEW_YEAR_2020)).toBe(1_577_836_800);
+    expect(chromeTimestampToEpochSeconds(NEW_YEAR_2020 + 999_998)).toBe(1_577_836_800);
+    expect(chromeTimestampToIso(NEW_YEAR_2020)).toBe("2020-01-01T00:00:00.000Z");
+  });
+
+  test("string values from sqlite are accepted", () => {
+    expect(chromeTimestampToEpochSeconds("13222310400000000")).toBe(1_577_836_800);
+  });
+
+  test("expires_utc 0 is a session cookie", () => {
+    expect(chromeTimestampToEpochSeconds(0)).toBe(-1);
+    expect(chromeTimestampToIso(0)).toBeNull();
+    expect(chromeTimestampToIso("0")).toBeNull();
+  });
+
+  test("values at or before the Unix epoch, or garbage, are session cookies too", () => {
+    expect(chromeTimestampToEpochSeconds(CHROME_EPOCH_OFFSET_MICROS)).toBe(-1);
+    expect(chromeTimestampToEpochSeconds(CHROME_EPOCH_OFFSET_MICROS + 1_000_000)).toBe(1);
+    expect(chromeTimestampToEpochSeconds("not a number")).toBe(-1);
+  });
+});
+
+describe("epochMsToIso", () => {
+  test("epoch milliseconds to ISO", () => {
+    expect(epochMsToIso(0)).toBe("1970-01-01T00:00:00.000Z");
+    expect(epochMsToIso(1_577_836_800_123)).toBe("2020-01-01T00:00:00.123Z");
+  });
+
+  test("an invalid value falls back to now", () => {
+    const before = Date.now();
+    const parsed = Date.parse(epochMsToIso(Number.NaN));
+    expect(parsed).toBeGreaterThanOrEqual(before);
+    expect(parsed).toBeLessThanOrEqual(Date.now());
+  });
+});
+
+describe("parseHarDateTime", () => {
+  test("offsets and precision normalize to UTC milliseconds", () => {
+    expect(parseHarDateTime("2020-01-01T02:00:00.5+02:00")).toBe("2020-01-01T00:00:00.500Z");
+    expect(parseHarDateTime("2020-01-01T00:00:00Z")).toBe("2020-01-01T00:00:00.000Z");
+  });
+
+  test("missing or unparseable values use the fallback", () => {
+    expect(parseHarDateTime(undefined, "2021-06-01T00:00:00.000Z")).toBe("2021-06-01T00:00:00.000Z");
+    expect(parseHarDateTime("yesterday", "2021-06-01T00:00:00.000Z")).toBe("2021-06-01T00:00:00.000Z");
+  });
+});
@@ -0,0 +1,63 @@
+import { describe, expect, test } from "bun:test";
+import { REDACTED, redactEndpointExamples, redactPii } from "../../src/transform/redact.js";
+import type { EndpointDescriptor } from "../../src/types/index.js";
+
+function endpoint(overrides: Partial<EndpointDescriptor>): EndpointDescriptor {
+  return {
+    endpoint_id: "ep1",
+    method: "POST",
+    url_template: "https://api.example.org/v1/contacts",
+    idempotency: "unsafe",
+    verification_status: "unverified",
+    reliability_score: 0.5,
+    ...overrides,
+  };
+}
+
+describe("redactPii", () => {
+  test("redacts each leaf of a PII-named array instead of collapsing it", () => {
+    expect(redactPii({ emails: ["a@x.io", "b@x.io"], count: 2 })).toEqual({ emails: [REDACTED, REDACTED], count: 2 });
+  });
+
+  test("keeps the shape of a PII-named object", () => {
+    expect(redactPii({ address: { city: "Oslo", zip: 1234, verified: true } })).toEqual({
+      address: { city: REDACTED, zip: REDACTED, verified: true },
+    });
+  });
+
+  test("leaves template placeholders and non-PII values alone", () => {
+    expect(redactPii({ email: "{email}", name: "widget" })).toEqual({ email: "{email}", name: "widget" });
+  });
+
+  test("masks emails in free text under any key", () => {
+    expect(redactPii({ note: "mail me at a@x.io" })).toEqual({ note: `mail me at ${REDACTED}` });
+  });
+});
+
+describe("redactEndpointExamples", () => {
+  test("templates PII body fields as required inputs instead of overwriting them", () => {
+    const ep = redactEndpointExamples(endpoint({
+      body: { contact: { email: "a@x.io", phones: ["+4712345678"] }, source: "web" },
+    }));
+    expect(ep.body).toEqual({ contact: { email: "{contact_email}", phones: ["{contact_phones_0}"] }, source: "web" });
+    expect(ep.required_body_params).toEqual(["contact_email", "contact_phones_0"]);
+  });
+
+  test("drops captured PII defaults from body_params and requires them", () => {
+    const ep = redactEndpointExamples(endpoint({
+      body: { email: "{email}", page: "{page}" },
+      body_params: { email: "a@x.io", page: 2 },
+    }));
+    expect(ep.body).toEqual({ email: "{email}", page: "{page}" });
+    expect(ep.body_params).toEqual({ page: 2 });
+    expect(ep.required_body_params).toEqual(["email"]);
+  });
+
+  test("masks semantic examples", () => {
+    const ep = redactEndpointExamples(endpoint({
+      semantic: { example_request: { email: "a@x.io" }, example_response_compact: { id: 1, email: "a@x.io" } } as EndpointDescriptor["semantic"],
+    }));
+    expect(ep.semantic?.example_request).toEqual({ email: REDACTED });
+    expect(ep.semantic?.example_response_compact).toEqual({ id: 1, email: REDACTED });
+  });
+});
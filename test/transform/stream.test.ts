@@ -0,0 +1,60 @@
+import { describe, expect, test } from "bun:test";
+import { MAX_STREAM_BYTES, parseStreamBody, readResponseText } from "../../src/transform/stream.js";
+
+describe("parseStreamBody", () => {
+  test("NDJSON lines parse one record each", () => {
+    const body = '{"id":1,"name":"a"}\n{"id":2,"name":"b"}\n';
+    expect(parseStreamBody(body, "application/x-ndjson")).toEqual({
+      format: "ndjson",
+      items: [{ id: 1, name: "a" }, { id: 2, name: "b" }],
+    });
+  });
+
+  test("NDJSON is recognised without a content-type, a single document is not", () => {
+    expect(parseStreamBody('{"id":1}\n{"id":2}')?.format).toBe("ndjson");
+    expect(parseStreamBody('{\n  "id": 1\n}')).toBeNull();
+  });
+
+  test("a cut-off final NDJSON line is dropped", () => {
+    expect(parseStreamBody('{"id":1}\n{"id":2}\n{"id":', "application/x-ndjson")?.items).toEqual([{ id: 1 }, { id: 2 }]);
+  });
+
+  test("SSE data frames and event names", () => {
+    const body = [
+      ": keep-alive",
+      "",
+      "event: price",
+      'data: {"symbol":"ABC","price":1.5}',
+      "",
+      "event: price",
+      'data: {"symbol":"XYZ",',
+      'data: "price":2}',
+      "",
+      "data: [DONE]",
+      "",
+    ].join("\n");
+    expect(parseStreamBody(body, "text/event-stream")).toEqual({
+      format: "sse",
+      items: [{ symbol: "ABC", price: 1.5 }, { symbol: "XYZ", price: 2 }],
+      events: ["price"],
+    });
+  });
+
+  test("SSE is recognised by its field lines", () => {
+    expect(parseStreamBody("data: hello\n\n")).toEqual({ format: "sse", items: ["hello"] });
+  });
+});
+
+describe("readResponseText", () => {
+  test("an endless stream is cut at MAX_STREAM_BYTES", async () => {
+    const chunk = new TextEncoder().encode(`${JSON.stringify({ tick: "x".repeat(1000) })}\n`);
+    const endless = new ReadableStream<Uint8Array>({ pull: (controller) => controller.enqueue(chunk) });
+    const text = await readResponseText(new Response(endless, { headers: { "content-type": "application/x-ndjson" } }));
+    expect(text.length).toBeGreaterThanOrEqual(MAX_STREAM_BYTES);
+    expect(text.length).toBeLessThan(MAX_STREAM_BYTES + chunk.byteLength);
+  });
+
+  test("other content-types are read whole", async () => {
+    expect(await readResponseText(new Response('{"ok":true}', { headers: { "content-type": "application/json" } }))).toBe('{"ok":true}');
+  });
+});
@@ -0,0 +1,51 @@
+import { afterAll, describe, expect, test } from "bun:test";
+import { mkdtempSync, rmSync } from "node:fs";
+import { tmpdir } from "node:os";
+import { join } from "node:path";
+
+// The vault lives under ~/.unbrowse, resolved when the module loads.
+const home = mkdtempSync(join(tmpdir(), "unbrowse-vault-"));
+process.env.HOME = home;
+const vault = await import("../../src/vault/index.js");
+vault.setKeytarClientForTests(null);
+
+afterAll(() => rmSync(home, { recursive: true, force: true }));
+
+/** Re-encode `blob` with its header KDF params replaced (the GCM tag is never reached). */
+function withParams(blob: string, kdf: number, params: [number, number, number]): string {
+  const raw = Buffer.from(blob, "base64");
+  raw.writeUInt8(kdf, 1);
+  raw.writeUInt32BE(params[0], 2);
+  raw.writeUInt32BE(params[1], 6);
+  raw.writeUInt32BE(params[2], 10);
+  return raw.toString("base64");
+}
+
+describe("vault encrypted export", () => {
+  test("round-trips credentials through a passphrase", async () => {
+    await vault.storeCredential("export-a", "value-a");
+    const blob = await vault.exportVaultEncrypted("correct horse");
+    await vault.clearVault(vault.VAULT_CLEAR_CONFIRMATION);
+    expect(await vault.importVaultEncrypted(blob, "correct horse")).toEqual(["export-a"]);
+    expect(await vault.getCredential("export-a")).toBe("value-a");
+  });
+
+  test("rejects KDF params above the ceilings before deriving a key", async () => {
+    const blob = await vault.exportVaultEncrypted("correct horse");
+    const oversized: Array<[number, [number, number, number]]> = [
+      [1, [4 * 1024 * 1024, 3, 4]],
+      [1, [64 * 1024, 0xffffffff, 4]],
+      [1, [64 * 1024, 3, 255]],
+      [2, [1 << 24, 8, 1]],
+      [2, [1 << 15, 1024, 1]],
+      [2, [1 << 15, 8, 1 << 20]],
+      [2, [3000, 8, 1]],
+    ];
+    for (const [kdf, params] of oversized) {
+      const started = Date.now();
+      await expect(vault.importVaultEncrypted(withParams(blob, kdf, params), "correct horse"))
+        .rejects.toMatchObject({ code: "invalid_input" });
+      expect(Date.now() - started).toBeLessThan(1000);
+    }
+  });
+});
@@ -0,0 +1,35 @@
+import { afterAll, describe, expect, test } from "bun:test";
+import { mkdtempSync, rmSync } from "node:fs";
+import { tmpdir } from "node:os";
+import { join } from "node:path";
+
+// The vault lives under ~/.unbrowse, resolved when the module loads.
+const home = mkdtempSync(join(tmpdir(), "unbrowse-vault-"));
+process.env.HOME = home;
+const vault = await import("../../src/vault/index.js");
+vault.setKeytarClientForTests(null);
+
+const VAULT_MODULE = join(import.meta.dir, "../../src/vault/index.ts");
+
+afterAll(() => rmSync(home, { recursive: true, force: true }));
+
+describe("vault file lock", () => {
+  test("concurrent stores in one process are all persisted", async () => {
+    await Promise.all(Array.from({ length: 10 }, (_, i) => vault.storeCredential(`local-${i}`, `value-${i}`)));
+    for (let i = 0; i < 10; i++) expect(await vault.getCredential(`local-${i}`)).toBe(`value-${i}`);
+  });
+
+  test("concurrent stores from several processes are all persisted", async () => {
+    const writers = Array.from({ length: 4 }, (_, p) => Bun.spawn([
+      process.execPath,
+      "-e",
+      `const v = await import(${JSON.stringify(VAULT_MODULE)});
+       v.setKeytarClientForTests(null);
+       for (let i = 0; i < 5; i++) await v.storeCredential("proc-${p}-" + i, "value-${p}-" + i);`,
+    ], { env: { ...process.env, HOME: home }, stderr: "inherit" }));
+    expect(await Promise.all(writers.map((w) => w.exited))).toEqual([0, 0, 0, 0]);
+    for (let p = 0; p < 4; p++) {
+      for (let i = 0; i < 5; i++) expect(await vault.getCredential(`proc-${p}-${i}`)).toBe(`value-${p}-${i}`);
+    }
+  });
+});